@@ -15,6 +15,7 @@ use defmt::info;
 use defmt_rtt as _;
 use device_kit::{
     Error, Result,
+    animation::{AnimationSource, AnimationStream},
     button::{Button, PressDuration, PressedTo},
     clock::{Clock, ClockStatic, ONE_DAY, ONE_MINUTE, ONE_SECOND, h12_m_s},
     led_strips,
@@ -22,6 +23,7 @@ use device_kit::{
     led_layout::LedLayout,
     led_strip::{Current, colors, gamma::Gamma},
     led2d,
+    led2d::compose::Transition,
     time_sync::{TimeSync, TimeSyncEvent, TimeSyncStatic},
     wifi_auto::{
         WifiAuto, WifiAutoEvent,
@@ -30,7 +32,7 @@ use device_kit::{
 };
 use embassy_executor::Spawner;
 use embassy_futures::select::{Either, select};
-use embassy_time::Duration;
+use embassy_time::{Duration, Timer};
 use heapless::String;
 use panic_probe as _;
 use smart_leds::RGB8;
@@ -199,7 +201,7 @@ impl State {
     ) -> Result<Self> {
         clock.set_speed(speed).await;
         let (hours, minutes, _) = h12_m_s(&clock.now_local());
-        show_hours_minutes(led_8x12, hours, minutes).await?;
+        let mut shown_frame = show_hours_minutes(led_8x12, hours, minutes).await?;
         clock.set_tick_interval(Some(ONE_MINUTE)).await;
         let mut button_press = pin!(button.wait_for_press_duration());
         loop {
@@ -234,7 +236,12 @@ impl State {
                 // Clock tick
                 Either::Second(Either::First(time_event)) => {
                     let (hours, minutes, _) = h12_m_s(&time_event);
-                    show_hours_minutes(led_8x12, hours, minutes).await?;
+                    // Fade between minutes at normal speed.
+                    shown_frame = if is_normal_speed(speed) {
+                        crossfade_hours_minutes(led_8x12, &shown_frame, hours, minutes).await?
+                    } else {
+                        show_hours_minutes(led_8x12, hours, minutes).await?
+                    };
                 }
                 // Time sync events
                 Either::Second(Either::Second(TimeSyncEvent::Success { unix_seconds })) => {
@@ -375,7 +382,7 @@ async fn show_portal_ready(led_8x12: &Led8x12) -> Result<()> {
 
 async fn show_connecting(led_8x12: &Led8x12, try_index: u8, _try_count: u8) -> Result<()> {
     // Delay animation start to avoid wifi initialization glitches
-    embassy_time::Timer::after(Duration::from_secs(1)).await;
+    Timer::after(Duration::from_secs(1)).await;
 
     let clockwise = try_index % 2 == 0;
     const FRAME_DURATION: Duration = Duration::from_millis(90);
@@ -391,11 +398,83 @@ async fn show_connection_failed(led_8x12: &Led8x12) -> Result<()> {
     led_8x12.write_text("FA\nIL", &DIGIT_COLORS).await
 }
 
-async fn show_hours_minutes(led_8x12: &Led8x12, hours: u8, minutes: u8) -> Result<()> {
+async fn show_hours_minutes(led_8x12: &Led8x12, hours: u8, minutes: u8) -> Result<Led8x12Frame> {
+    let frame = hours_minutes_frame(led_8x12, hours, minutes)?;
+    led_8x12.write_frame(frame).await?;
+    Ok(frame)
+}
+
+async fn crossfade_hours_minutes(
+    led_8x12: &Led8x12,
+    from: &Led8x12Frame,
+    hours: u8,
+    minutes: u8,
+) -> Result<Led8x12Frame> {
+    static CROSSFADE_STREAM: AnimationStream<Crossfade, Led8x12Frame> =
+        AnimationStream::new(Crossfade::new(), Led8x12::new_frame());
+    let to = hours_minutes_frame(led_8x12, hours, minutes)?;
+    CROSSFADE_STREAM
+        .with_source(|crossfade| crossfade.restart(*from, to))
+        .await;
+    // The fade plays in the display task, so the next command interrupts it.
+    led_8x12.animate_stream(&CROSSFADE_STREAM).await?;
+    Ok(to)
+}
+
+/// Fades from one frame to another in [`Crossfade::STEPS`] steps, then stops on the new one.
+struct Crossfade {
+    from: Led8x12Frame,
+    to: Led8x12Frame,
+    step: u8,
+}
+
+impl Crossfade {
+    const STEPS: u8 = 10;
+    const STEP_DURATION: Duration = Duration::from_millis(50);
+
+    const fn new() -> Self {
+        Self {
+            from: Led8x12::new_frame(),
+            to: Led8x12::new_frame(),
+            step: 0,
+        }
+    }
+
+    const fn restart(&mut self, from: Led8x12Frame, to: Led8x12Frame) {
+        self.from = from;
+        self.to = to;
+        self.step = 0;
+    }
+}
+
+impl AnimationSource<Led8x12Frame> for Crossfade {
+    fn next_frame(&mut self, frame: &mut Led8x12Frame) -> Duration {
+        self.step = self.step.saturating_add(1).min(Self::STEPS);
+        *frame = Transition::Crossfade.render(
+            &self.from,
+            &self.to,
+            usize::from(self.step),
+            usize::from(Self::STEPS),
+        );
+        if self.step == Self::STEPS {
+            // A zero duration ends the stream with the new frame on display.
+            Duration::from_ticks(0)
+        } else {
+            Self::STEP_DURATION
+        }
+    }
+}
+
+/// Whether the clock runs in real time; fast mode ticks too often to fade.
+fn is_normal_speed(speed: f32) -> bool {
+    (speed - 1.0).abs() <= f32::EPSILON
+}
+
+fn hours_minutes_frame(led_8x12: &Led8x12, hours: u8, minutes: u8) -> Result<Led8x12Frame> {
     let (hours_tens, hours_ones) = hours_digits(hours);
     let (minutes_tens, minutes_ones) = two_digit_chars(minutes);
     let text = two_line_text([hours_tens, hours_ones], [minutes_tens, minutes_ones]);
-    led_8x12.write_text(text.as_str(), &DIGIT_COLORS).await
+    text_frame(led_8x12, text.as_str(), &DIGIT_COLORS)
 }
 
 async fn show_hours_minutes_indicator(led_8x12: &Led8x12, hours: u8, minutes: u8) -> Result<()> {
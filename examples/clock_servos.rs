@@ -18,8 +18,8 @@ use defmt_rtt as _;
 use device_kit::button::{Button, PressDuration, PressedTo};
 use device_kit::clock::{Clock, ClockStatic, ONE_DAY, ONE_MINUTE, ONE_SECOND, h12_m_s};
 use device_kit::flash_array::{FlashArray, FlashArrayStatic};
-use device_kit::servo_animate::{
-    ServoAnimate, ServoAnimateStatic, Step, concat_steps, linear, servo_even,
+use device_kit::servo_group::{
+    ServoGroup, ServoGroupStatic, Step, concat_steps, linear, servo_even,
 };
 use device_kit::time_sync::{TimeSync, TimeSyncEvent, TimeSyncStatic};
 use device_kit::wifi_auto::fields::{TimezoneField, TimezoneFieldStatic};
@@ -42,10 +42,15 @@ async fn inner_main(spawner: Spawner) -> Result<Infallible> {
     info!("Starting Wi-Fi servo clock (WifiAuto)");
     let p = embassy_rp::init(Default::default());
 
-    // Use two blocks of flash storage: Wi-Fi credentials + timezone
-    static FLASH_STATIC: FlashArrayStatic = FlashArray::<2>::new_static();
-    let [wifi_credentials_flash_block, timezone_flash_block] =
-        FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+    // Use four blocks of flash storage: Wi-Fi credentials, timezone, and each servo's
+    // calibration.
+    static FLASH_STATIC: FlashArrayStatic = FlashArray::<4>::new_static();
+    let [
+        wifi_credentials_flash_block,
+        timezone_flash_block,
+        mut bottom_calibration,
+        mut top_calibration,
+    ] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
 
     // Define HTML to ask for timezone on the captive portal.
     static TIMEZONE_FIELD_STATIC: TimezoneFieldStatic = TimezoneField::new_static();
@@ -67,21 +72,16 @@ async fn inner_main(spawner: Spawner) -> Result<Infallible> {
         spawner,
     )?;
 
-    // Configure two servos for the display.
-    static LEFT_SERVO_ANIMATE_STATIC: ServoAnimateStatic = ServoAnimate::new_static();
-    static RIGHT_SERVO_ANIMATE_STATIC: ServoAnimateStatic = ServoAnimate::new_static();
-    let servo_display = ServoClockDisplay::new(
-        ServoAnimate::new(
-            &LEFT_SERVO_ANIMATE_STATIC,
-            servo_even!(p.PIN_0, p.PWM_SLICE0, 500, 2500),
-            spawner,
-        )?,
-        ServoAnimate::new(
-            &RIGHT_SERVO_ANIMATE_STATIC,
-            servo_even!(p.PIN_2, p.PWM_SLICE1, 500, 2500),
-            spawner,
-        )?,
-    );
+    // Configure two servos for the display; they move in lock-step as one group.
+    static SERVO_GROUP_STATIC: ServoGroupStatic = ServoGroup::<2>::new_static();
+    let servo_display = ServoClockDisplay::new(ServoGroup::new(
+        &SERVO_GROUP_STATIC,
+        [
+            servo_even!(p.PIN_0, p.PWM_SLICE0, 500, 2500, &mut bottom_calibration)?, // bottom
+            servo_even!(p.PIN_2, p.PWM_SLICE1, 500, 2500, &mut top_calibration)?,    // top
+        ],
+        spawner,
+    )?);
 
     // Connect Wi-Fi, using the servos for status indications.
     let servo_display_ref = &servo_display;
@@ -279,7 +279,7 @@ impl State {
                 duration: Duration::from_millis(250),
             },
         ];
-        servo_display.bottom.animate(&WIGGLE).await;
+        servo_display.wiggle_bottom(&WIGGLE).await;
 
         // Get the current offset minutes from clock (source of truth)
         let mut offset_minutes = clock.offset_minutes();
@@ -309,7 +309,7 @@ impl State {
                     servo_display
                         .show_hours_minutes_indicator(hours, minutes)
                         .await;
-                    servo_display.bottom.animate(&WIGGLE).await;
+                    servo_display.wiggle_bottom(&WIGGLE).await;
                 }
                 PressDuration::Long => {
                     info!("Long press detected - saving and exiting edit mode");
@@ -323,32 +323,34 @@ impl State {
     }
 }
 
+/// Servos in a [`ServoGroup`], in order: bottom, top.
 struct ServoClockDisplay {
-    bottom: ServoAnimate,
-    top: ServoAnimate,
+    servos: ServoGroup<2>,
 }
 
 impl ServoClockDisplay {
-    fn new(bottom: ServoAnimate, top: ServoAnimate) -> Self {
-        Self { bottom, top }
+    fn new(servos: ServoGroup<2>) -> Self {
+        Self { servos }
     }
 
     async fn show_portal_ready(&self) {
-        self.bottom.set(90).await;
-        self.top.set(90).await;
+        self.servos.set([90, 90]).await;
     }
 
     async fn show_connecting(&self) {
-        // Keep bottom servo fixed; animate top servo through a two-phase sweep.
-        self.bottom.set(0).await;
+        // Sweep both servos through a two-phase cycle, half a cycle apart.
         // cmk understand if we really want this to have 11 steps and a sleep after each.
         const FIVE_SECONDS: Duration = Duration::from_secs(5);
         let clockwise = linear::<10>(180 - 18, 0, FIVE_SECONDS);
         let and_back = linear::<2>(0, 180, FIVE_SECONDS);
         let top_sequence = concat_steps::<16>(&[&clockwise, &and_back]);
-        self.top.animate(&top_sequence).await;
         let bottom_sequence = concat_steps::<16>(&[&and_back, &clockwise]);
-        self.bottom.animate(&bottom_sequence).await;
+        self.servos.animate([&bottom_sequence, &top_sequence]).await;
+    }
+
+    /// Wiggle the bottom servo while the top servo holds its position.
+    async fn wiggle_bottom(&self, steps: &[Step]) {
+        self.servos.animate([steps, &[]]).await;
     }
 
     async fn show_hours_minutes(&self, hours: u8, minutes: u8) {
@@ -377,8 +379,7 @@ impl ServoClockDisplay {
             u16::try_from(physical_left).expect("servo angles must be between 0 and 180 degrees");
         let right_angle =
             u16::try_from(physical_right).expect("servo angles must be between 0 and 180 degrees");
-        self.bottom.set(left_angle).await;
-        self.top.set(right_angle).await;
+        self.servos.set([left_angle, right_angle]).await;
     }
 }
 
@@ -9,14 +9,16 @@ use defmt_rtt as _;
 use device_kit::Result;
 use device_kit::led_layout::LedLayout;
 use device_kit::led_strip::led_strips;
-use device_kit::led_strip::{Frame, Rgb, colors};
+use device_kit::led_strip::{Current, Frame, Rgb, colors};
 use device_kit::led2d::led2d_from_strip;
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 use heapless::Vec;
 use panic_probe as _;
 
+// All three strips run from one supply, so cap their combined current.
 led_strips! {
+    total_current: Current::Milliamps(1000),
     LedStrips {
         gpio0: { pin: PIN_0, len: 8},
         gpio3: { pin: PIN_3, len: 48},
@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+
+use core::convert::Infallible;
+
+use defmt::info;
+use device_kit::Result;
+use device_kit::led_strip::clocked::{ClockedProtocol, clocked_led_strip};
+use device_kit::led_strip::{Current, Frame, colors};
+use embassy_executor::Spawner;
+use embassy_time::{Duration, Timer};
+use {defmt_rtt as _, panic_probe as _};
+
+clocked_led_strip! {
+    DotStar {
+        protocol: ClockedProtocol::Apa102,
+        clk: PIN_18,
+        mosi: PIN_19,
+        len: 8,
+        max_current: Current::Milliamps(100),
+    }
+}
+
+#[embassy_executor::main]
+async fn main(spawner: Spawner) -> ! {
+    let err = inner_main(spawner).await.unwrap_err();
+    core::panic!("{err}");
+}
+
+async fn inner_main(spawner: Spawner) -> Result<Infallible> {
+    let p = embassy_rp::init(Default::default());
+
+    let dot_star = DotStar::new(p.SPI0, p.PIN_18, p.PIN_19, p.DMA_CH0, spawner)?;
+
+    info!("APA102 strip initialized with {} LEDs", DotStar::LEN);
+
+    dot_star
+        .write_frame(Frame::from([
+            colors::RED,
+            colors::ORANGE,
+            colors::YELLOW,
+            colors::GREEN,
+            colors::CYAN,
+            colors::BLUE,
+            colors::PURPLE,
+            colors::MAGENTA,
+        ]))
+        .await?;
+
+    // Fade down to a glow and back; the global brightness field keeps the colors smooth.
+    const FADE: Duration = Duration::from_secs(3);
+    loop {
+        dot_star.fade_brightness(4, FADE);
+        Timer::after(FADE).await;
+        dot_star.fade_brightness(255, FADE);
+        Timer::after(FADE).await;
+    }
+}
@@ -4,8 +4,10 @@
 use defmt::info;
 use defmt_rtt as _;
 use device_kit::Result;
+use device_kit::animation::AnimationStream;
+use device_kit::effects::TheaterChase;
 use device_kit::led_strip::led_strips;
-use device_kit::led_strip::{Frame, Rgb, colors};
+use device_kit::led_strip::{Frame, colors};
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 use panic_probe as _;
@@ -13,8 +15,8 @@ use panic_probe as _;
 // Two WS2812B 4x12 LED matrices (48 pixels each) sharing PIO0
 led_strips! {
     LedStrips {
-        gpio3: { pin: PIN_3, len: 48, max_frames: 48 },
-        gpio4: { pin: PIN_4, len: 48, max_frames: 48 }
+        gpio3: { pin: PIN_3, len: 48 },
+        gpio4: { pin: PIN_4, len: 48 }
     }
 }
 
@@ -40,33 +42,23 @@ async fn inner_main(spawner: Spawner) -> Result<()> {
         100
     );
 
+    // Broadway-style chase: every fourth LED dark, marching along the strip.
     const FRAME_DURATION: Duration = Duration::from_millis(300);
-    const BRIGHT: Rgb = colors::WHITE;
-    const GAP: Rgb = colors::BLACK;
     const GAP_SPACING: usize = 4;
-    const FRAME_COUNT: usize = GAP_SPACING;
+    const CHASE: TheaterChase =
+        TheaterChase::new(colors::BLACK, colors::WHITE, GAP_SPACING, FRAME_DURATION).reversed();
 
-    let mut frames =
-        heapless::Vec::<(Frame<{ Gpio3LedStrip::LEN }>, Duration), FRAME_COUNT>::new();
+    // Each strip renders from its own stream, so they need not stay in step.
+    static GPIO3_CHASE: AnimationStream<TheaterChase, Frame<{ Gpio3LedStrip::LEN }>> =
+        AnimationStream::new(CHASE, Frame::new());
+    static GPIO4_CHASE: AnimationStream<TheaterChase, Frame<{ Gpio4LedStrip::LEN }>> =
+        AnimationStream::new(CHASE, Frame::new());
 
-    for frame_offset in 0..FRAME_COUNT {
-        let mut frame = Frame::<{ Gpio3LedStrip::LEN }>::filled(BRIGHT);
-        for pixel_index in 0..Gpio3LedStrip::LEN {
-            if (pixel_index + frame_offset) % GAP_SPACING == 0 {
-                frame[pixel_index] = GAP;
-            }
-        }
-        frames.push((frame, FRAME_DURATION)).ok();
-    }
-
-    info!(
-        "Starting Broadway-style animation with {} frames per strip",
-        frames.len()
-    );
+    info!("Starting Broadway-style animation");
 
     // Start the animation loop on both strips - they will run forever in the background
-    gpio3_led_strip.animate(frames.clone()).await?;
-    gpio4_led_strip.animate(frames).await?;
+    gpio3_led_strip.animate_stream(&GPIO3_CHASE).await?;
+    gpio4_led_strip.animate_stream(&GPIO4_CHASE).await?;
 
     info!("Snake animations started, entering idle loop");
 
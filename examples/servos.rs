@@ -1,16 +1,17 @@
 //! Dual servo control example.
 //! Moves two servos in opposite directions for 2 seconds.
-//! Connect servos to GPIO 0 and GPIO 2.
+//! Connect servos to GPIO 0 and GPIO 2. Each servo uses its saved calibration, if any.
 
 #![no_std]
 #![no_main]
 
 use defmt::info;
 use defmt_rtt as _;
+use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+use device_kit::servo::servo_even;
 use embassy_executor::Spawner;
 use embassy_time::{Duration, Timer};
 use panic_probe as _;
-use device_kit::servo::servo_even;
 
 #[embassy_executor::main]
 pub async fn main(_spawner: Spawner) -> ! {
@@ -18,11 +19,18 @@ pub async fn main(_spawner: Spawner) -> ! {
 
     info!("Starting dual servo example");
 
+    // One block of flash per servo for its calibration.
+    static FLASH_STATIC: FlashArrayStatic = FlashArray::<2>::new_static();
+    let [mut calibration0, mut calibration2] =
+        FlashArray::new(&FLASH_STATIC, p.FLASH).expect("flash");
+
     // Create servos on GPIO 0 and GPIO 2 (both even pins)
     // GPIO 0 → (0/2) % 8 = 0 → PWM_SLICE0
     // GPIO 2 → (2/2) % 8 = 1 → PWM_SLICE1
-    let mut servo0 = servo_even!(p.PIN_0, p.PWM_SLICE0, 500, 2500);
-    let mut servo2 = servo_even!(p.PIN_2, p.PWM_SLICE1, 500, 2500);
+    let mut servo0 =
+        servo_even!(p.PIN_0, p.PWM_SLICE0, 500, 2500, &mut calibration0).expect("servo 0");
+    let mut servo2 =
+        servo_even!(p.PIN_2, p.PWM_SLICE1, 500, 2500, &mut calibration2).expect("servo 2");
 
     info!("Moving servos in opposite directions for 2 seconds");
 
@@ -55,6 +55,18 @@
 //! - Extract frames at 10 FPS
 //! - Scale to 12x8 pixels
 //! - Convert to embedded Rust arrays
+//!
+//! ## Compressed Videos
+//!
+//! Frame arrays cost 288 bytes per frame and are limited by `max_frames`. For longer videos,
+//! compress to the DKV1 format instead and play it with `animate_video`:
+//! ```bash
+//! cargo xtask video-compress cat.mp4 cat.dkv --width 12 --height 8 --flip-v
+//! ```
+//! ```rust,ignore
+//! static CAT: Video = Video::new(include_bytes!("../cat.dkv"));
+//! led_12x8.animate_video(CAT).await?;
+//! ```
 
 #![no_std]
 #![no_main]
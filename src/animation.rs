@@ -0,0 +1,156 @@
+//! Animations whose frames are generated on demand instead of stored up front.
+//!
+//! `LedStrip::animate` and `Led2d::animate` copy every frame before playing, so an animation
+//! can be at most `MAX_FRAMES` long. An [`AnimationSource`] instead renders each frame just
+//! before it is shown, inside the device's animation loop. Procedural effects (plasma,
+//! Conway's life) and long videos then run indefinitely in constant memory. Like any
+//! animation, a stream keeps playing until the next command (such as `write_frame`)
+//! interrupts it.
+//!
+//! Wrap a source in a `static` [`AnimationStream`] and pass it to `animate_stream`. The
+//! stream owns the source's state and the current frame, so the same stream can be started
+//! again later and resumes where it left off. The device renders from the stream while it
+//! plays, so a stream should be playing on only one device at a time. A frame duration of
+//! zero ends the stream, leaving its last frame on display.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::animation::{AnimationSource, AnimationStream};
+//! use device_kit::led_strip::{Current, Frame, colors, led_strip};
+//! use embassy_executor::Spawner;
+//! use embassy_time::Duration;
+//!
+//! led_strip! {
+//!     Gpio0LedStrip {
+//!         pin: PIN_0,
+//!         len: 8,
+//!         max_current: Current::Milliamps(50),
+//!     }
+//! }
+//!
+//! /// A single lit pixel that bounces back and forth forever.
+//! struct Bounce {
+//!     position: usize,
+//!     forward: bool,
+//! }
+//!
+//! impl AnimationSource<Frame<8>> for Bounce {
+//!     fn next_frame(&mut self, frame: &mut Frame<8>) -> Duration {
+//!         *frame = Frame::new();
+//!         frame[self.position] = colors::ORANGE;
+//!         self.forward = match self.position {
+//!             0 => true,
+//!             7 => false,
+//!             _ => self.forward,
+//!         };
+//!         self.position = if self.forward { self.position + 1 } else { self.position - 1 };
+//!         Duration::from_millis(50)
+//!     }
+//! }
+//!
+//! static BOUNCE: AnimationStream<Bounce, Frame<8>> = AnimationStream::new(
+//!     Bounce {
+//!         position: 0,
+//!         forward: true,
+//!     },
+//!     Frame::new(),
+//! );
+//!
+//! #[embassy_executor::main]
+//! async fn main(spawner: Spawner) {
+//!     let p = embassy_rp::init(Default::default());
+//!     let strip = Gpio0LedStrip::new(p.PIO0, p.DMA_CH0, p.PIN_0, spawner).unwrap();
+//!     strip.animate_stream(&BOUNCE).await.unwrap();
+//! }
+//! ```
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::mutex::Mutex;
+use embassy_sync::signal::Signal;
+use embassy_time::Duration;
+
+/// Renders animation frames on demand.
+///
+/// `F` is the frame type: [`led_strip::Frame`](crate::led_strip::Frame) for strips or
+/// [`led2d::Frame`](crate::led2d::Frame) for 2D displays. Closures and `fn` items with the
+/// signature `FnMut(&mut F) -> Duration` are sources too.
+pub trait AnimationSource<F> {
+    /// Render the next frame into `frame` and return how long to show it.
+    ///
+    /// `frame` still holds the previously rendered frame, so sources can update just the
+    /// pixels that change. A zero duration ends the stream with this frame on display.
+    ///
+    /// This runs in the display's task without an `await` point, so keep it to one frame's
+    /// worth of work.
+    fn next_frame(&mut self, frame: &mut F) -> Duration;
+}
+
+impl<F, T> AnimationSource<F> for T
+where
+    T: FnMut(&mut F) -> Duration,
+{
+    fn next_frame(&mut self, frame: &mut F) -> Duration {
+        self(frame)
+    }
+}
+
+/// Static storage for an [`AnimationSource`] and its current frame, ready to pass to
+/// `animate_stream`.
+///
+/// See the [module documentation](self) for an example.
+pub struct AnimationStream<S, F> {
+    // An async mutex disables interrupts only to take and release the lock, not while a
+    // frame renders.
+    state: Mutex<CriticalSectionRawMutex, (S, F)>,
+    // Signaled when `with_source` releases the lock, so a device waiting to render wakes.
+    unlocked: UnlockSignal,
+}
+
+/// Wakes a device that found its stream locked by [`AnimationStream::with_source`].
+#[doc(hidden)] // Required pub for the type-erased stream traits
+pub type UnlockSignal = Signal<CriticalSectionRawMutex, ()>;
+
+impl<S, F> AnimationStream<S, F> {
+    /// Create a stream. `initial_frame` is what the source sees as the previous frame the
+    /// first time it renders; usually a blank `Frame::new()`.
+    #[must_use]
+    pub const fn new(source: S, initial_frame: F) -> Self {
+        Self {
+            state: Mutex::new((source, initial_frame)),
+            unlocked: Signal::new(),
+        }
+    }
+
+    /// Access the source, for example to change its parameters or reset it.
+    ///
+    /// Changes take effect from the next frame, even while the stream is playing. Waits if
+    /// a frame is rendering on another core.
+    pub async fn with_source<R>(&self, f: impl FnOnce(&mut S) -> R) -> R {
+        let result = f(&mut self.state.lock().await.0);
+        self.unlocked.signal(());
+        result
+    }
+
+    /// Render the next frame and return a copy of it, with how long to show it, or `None`
+    /// if [`with_source`](Self::with_source) holds the stream. Then wait on
+    /// [`unlocked`](Self::unlocked) and try again.
+    pub(crate) fn try_advance(&self) -> Option<(F, Duration)>
+    where
+        S: AnimationSource<F>,
+        F: Copy,
+    {
+        let mut state = self.state.try_lock().ok()?;
+        let (source, frame) = &mut *state;
+        let duration = source.next_frame(frame);
+        Some((*frame, duration))
+    }
+
+    /// Signaled each time [`with_source`](Self::with_source) finishes.
+    pub(crate) const fn unlocked(&self) -> &UnlockSignal {
+        &self.unlocked
+    }
+}
@@ -1,4 +1,4 @@
-//! A device abstraction for buttons with debouncing and press duration detection.
+//! A device abstraction for buttons with debouncing, press duration detection, and gestures.
 //!
 //! See [`Button`] for usage example.
 // cmk check this now that it works connected to both ground and voltage
@@ -7,7 +7,7 @@ use defmt::info;
 use embassy_futures::select::{Either, select};
 use embassy_rp::Peri;
 use embassy_rp::gpio::{Input, Pull};
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
 
 // ============================================================================
 // Constants
@@ -19,6 +19,9 @@ const BUTTON_DEBOUNCE_DELAY: Duration = Duration::from_millis(10);
 /// Duration representing a long button press.
 const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
 
+/// Most clicks [`ButtonGestures`] counts before reporting without waiting for more.
+const MAX_CLICKS: u8 = 3;
+
 // ============================================================================
 // PressedTo - How the button is wired
 // ============================================================================
@@ -189,3 +192,206 @@ impl<'a> Button<'a> {
         self
     }
 }
+
+// ============================================================================
+// Gestures - clicks, long press, and hold-repeat
+// ============================================================================
+
+/// A gesture recognized by [`ButtonGestures`].
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, defmt::Format)]
+pub enum Gesture {
+    /// One short press, with no second press within the multi-click gap.
+    SingleClick,
+    /// Two short presses in quick succession.
+    DoubleClick,
+    /// Three short presses in quick succession (reported as soon as the third is released).
+    TripleClick,
+    /// The button has been held for the long-press time. Fires while still held.
+    LongPressStart,
+    /// The button is still held; `count` starts at 1 and increases every repeat interval.
+    HoldRepeat { count: u16 },
+    /// The button was released after a [`Gesture::LongPressStart`].
+    Release { held: Duration },
+}
+
+/// Timing used by [`ButtonGestures`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub struct GestureTiming {
+    /// Longest wait after a release for the next click of a double/triple click.
+    pub multi_click_gap: Duration,
+    /// How long the button must be held to count as a long press.
+    pub long_press: Duration,
+    /// Delay after [`Gesture::LongPressStart`] before the first [`Gesture::HoldRepeat`].
+    pub repeat_delay: Duration,
+    /// Interval between [`Gesture::HoldRepeat`] ticks.
+    pub repeat_interval: Duration,
+}
+
+impl GestureTiming {
+    /// Default timing: 300 ms click gap, 500 ms long press, repeats every 200 ms after 500 ms.
+    pub const DEFAULT: Self = Self {
+        multi_click_gap: Duration::from_millis(300),
+        long_press: LONG_PRESS_DURATION,
+        repeat_delay: Duration::from_millis(500),
+        repeat_interval: Duration::from_millis(200),
+    };
+}
+
+impl Default for GestureTiming {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Tracks a held button between calls to [`ButtonGestures::wait_for_gesture`].
+#[derive(Clone, Copy)]
+enum GestureState {
+    Idle,
+    Holding {
+        pressed_at: Instant,
+        next_repeat: Instant,
+        repeat_count: u16,
+    },
+}
+
+/// Recognizes clicks, multi-clicks, long presses, and hold-repeat on a single [`Button`].
+///
+/// Use this when one button must drive a menu: for example, single-click to advance,
+/// double-click to go back, and hold to scroll a value quickly.
+///
+/// A single click is only reported once the multi-click gap has passed without another
+/// press, so prefer [`Button::wait_for_press_duration`] when double-clicks aren't needed.
+/// A long press that follows one or more quick clicks is reported as
+/// [`Gesture::LongPressStart`]; the earlier clicks are dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::button::{Button, ButtonGestures, Gesture, GestureTiming, PressedTo};
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+///
+/// async fn example(p: embassy_rp::Peripherals) {
+///     let button = Button::new(p.PIN_13, PressedTo::Ground);
+///     let mut gestures = ButtonGestures::new(button, GestureTiming::default());
+///
+///     let mut value: i32 = 0;
+///     loop {
+///         match gestures.wait_for_gesture().await {
+///             Gesture::SingleClick => value += 1,
+///             Gesture::DoubleClick => value -= 1,
+///             Gesture::TripleClick => value = 0,
+///             Gesture::LongPressStart => value += 10,
+///             Gesture::HoldRepeat { .. } => value += 10,
+///             Gesture::Release { .. } => defmt::info!("value = {}", value),
+///         }
+///     }
+/// }
+/// ```
+pub struct ButtonGestures<'a> {
+    button: Button<'a>,
+    timing: GestureTiming,
+    state: GestureState,
+}
+
+impl<'a> ButtonGestures<'a> {
+    /// Wrap a [`Button`] with gesture recognition using the given timing.
+    #[must_use]
+    pub const fn new(button: Button<'a>, timing: GestureTiming) -> Self {
+        Self {
+            button,
+            timing,
+            state: GestureState::Idle,
+        }
+    }
+
+    /// Return the wrapped [`Button`].
+    #[must_use]
+    pub fn into_inner(self) -> Button<'a> {
+        self.button
+    }
+
+    /// Waits for the next gesture. See [`ButtonGestures`] for the gestures reported.
+    pub async fn wait_for_gesture(&mut self) -> Gesture {
+        let gesture = match self.state {
+            GestureState::Idle => self.wait_for_clicks_or_long_press().await,
+            GestureState::Holding {
+                pressed_at,
+                next_repeat,
+                repeat_count,
+            } => {
+                self.wait_for_repeat_or_release(pressed_at, next_repeat, repeat_count)
+                    .await
+            }
+        };
+        info!("Gesture: {:?}", gesture);
+        gesture
+    }
+
+    async fn wait_for_clicks_or_long_press(&mut self) -> Gesture {
+        self.button.wait_for_button_up().await;
+        Timer::after(BUTTON_DEBOUNCE_DELAY).await;
+        self.button.wait_for_button_down().await;
+        let mut clicks: u8 = 0;
+        loop {
+            let pressed_at = Instant::now();
+            Timer::after(BUTTON_DEBOUNCE_DELAY).await;
+            let long_press_at = pressed_at + self.timing.long_press;
+            if let Either::Second(()) =
+                select(self.button.wait_for_button_up(), Timer::at(long_press_at)).await
+            {
+                self.state = GestureState::Holding {
+                    pressed_at,
+                    next_repeat: long_press_at + self.timing.repeat_delay,
+                    repeat_count: 0,
+                };
+                return Gesture::LongPressStart;
+            }
+            Timer::after(BUTTON_DEBOUNCE_DELAY).await;
+            clicks += 1;
+            if clicks >= MAX_CLICKS {
+                break;
+            }
+            if let Either::Second(()) = select(
+                self.button.wait_for_button_down(),
+                Timer::after(self.timing.multi_click_gap),
+            )
+            .await
+            {
+                break;
+            }
+        }
+        match clicks {
+            1 => Gesture::SingleClick,
+            2 => Gesture::DoubleClick,
+            _ => Gesture::TripleClick,
+        }
+    }
+
+    async fn wait_for_repeat_or_release(
+        &mut self,
+        pressed_at: Instant,
+        next_repeat: Instant,
+        repeat_count: u16,
+    ) -> Gesture {
+        match select(self.button.wait_for_button_up(), Timer::at(next_repeat)).await {
+            Either::First(_) => {
+                self.state = GestureState::Idle;
+                let held = pressed_at.elapsed();
+                Timer::after(BUTTON_DEBOUNCE_DELAY).await;
+                Gesture::Release { held }
+            }
+            Either::Second(()) => {
+                let count = repeat_count.saturating_add(1);
+                self.state = GestureState::Holding {
+                    pressed_at,
+                    next_repeat: next_repeat + self.timing.repeat_interval,
+                    repeat_count: count,
+                };
+                Gesture::HoldRepeat { count }
+            }
+        }
+    }
+}
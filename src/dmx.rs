@@ -0,0 +1,527 @@
+//! A device abstraction for driving LED strips from lighting software over the network.
+//!
+//! Lighting and pixel-mapping programs (xLights, QLC+, Jinx!, Resolume, and many others) send
+//! LED colors as DMX universes over E1.31 (sACN) or Art-Net, or as raw pixel data over DDP.
+//! With the `wifi` feature, [`run_dmx_receiver`] listens for all three on their standard UDP
+//! ports and shows each complete frame on a strip.
+//!
+//! Pixels arrive in display order, three channels (red, green, blue) each. A [`DmxMapper`]
+//! places them with an [`LedLayout`], so row-major display order lands on the right LEDs of a
+//! serpentine panel, or in order along a plain strip. The mapping and the packet parsing work
+//! without hardware; see `tests/dmx.rs`.
+//!
+//! # Channels and universes
+//!
+//! A [`DmxConfig`] gives the first universe and the DMX start address (1-512) of the first
+//! pixel. Pixels never straddle universes: the first universe holds as many whole pixels as
+//! fit after the start address, and each following universe holds 170 pixels (channels 1-510).
+//! This matches the default "170 pixels per universe" patching of most pixel software. A frame
+//! is shown when the universe holding the last pixel arrives.
+//!
+//! DDP needs no configuration: its byte offsets address the pixels directly, and a frame is
+//! shown when a packet sets the push flag.
+//!
+//! Send to the device's own address (unicast); the receiver does not join E1.31 multicast
+//! groups.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # #[panic_handler]
+//! # fn panic(_: &core::panic::PanicInfo) -> ! { loop {} }
+//! use device_kit::dmx::{DmxConfig, DmxMapper, DmxPacket};
+//! use smart_leds::RGB8;
+//!
+//! # fn example() {
+//! // Three LEDs starting at address 4 of universe 1.
+//! let mut mapper = DmxMapper::<3>::linear(DmxConfig { universe: 1, start_address: 4 });
+//! let channels = [0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, 255];
+//! let packet = DmxPacket::Universe { universe: 1, channels: &channels };
+//! assert!(mapper.apply(&packet)); // The last pixel arrived, so the frame is ready.
+//! assert_eq!(mapper.frame()[2], RGB8::new(0, 0, 255));
+//! # }
+//! ```
+
+use smart_leds::RGB8;
+
+use crate::led_layout::LedLayout;
+
+/// UDP port for E1.31 (sACN).
+pub const E131_PORT: u16 = 5568;
+/// UDP port for Art-Net.
+pub const ART_NET_PORT: u16 = 6454;
+/// UDP port for DDP.
+pub const DDP_PORT: u16 = 4048;
+
+/// DMX channels in a universe.
+const UNIVERSE_CHANNELS: usize = 512;
+/// Whole RGB pixels in a universe that starts at address 1.
+const PIXELS_PER_UNIVERSE: usize = UNIVERSE_CHANNELS / 3;
+
+const E131_PACKET_IDENTIFIER: &[u8; 12] = b"ASC-E1.17\0\0\0";
+const E131_VECTOR_ROOT_DATA: u32 = 0x0000_0004;
+const E131_VECTOR_FRAMING_DATA: u32 = 0x0000_0002;
+const E131_VECTOR_DMP_SET_PROPERTY: u8 = 0x02;
+const E131_ADDRESS_AND_DATA_TYPE: u8 = 0xA1;
+const E131_OPTION_PREVIEW: u8 = 0x40;
+/// Offset of the DMX start code; channel values follow it.
+const E131_START_CODE_OFFSET: usize = 125;
+
+const ART_NET_ID: &[u8; 8] = b"Art-Net\0";
+const ART_NET_OP_DMX: u16 = 0x5000;
+const ART_NET_MIN_VERSION: u16 = 14;
+const ART_NET_HEADER_LEN: usize = 18;
+
+const DDP_HEADER_LEN: usize = 10;
+const DDP_VERSION_1: u8 = 0x40;
+const DDP_VERSION_MASK: u8 = 0xC0;
+const DDP_FLAG_PUSH: u8 = 0x01;
+const DDP_FLAG_QUERY: u8 = 0x08;
+const DDP_FLAG_REPLY: u8 = 0x04;
+const DDP_FLAG_TIMECODE: u8 = 0x10;
+const DDP_ID_DISPLAY: u8 = 1;
+
+/// Where a device's pixels start in the DMX universes it receives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DmxConfig {
+    /// Universe holding the first pixel. E1.31 numbers universes from 1; Art-Net from 0.
+    pub universe: u16,
+    /// DMX address (1-512) of the first pixel's red channel.
+    pub start_address: u16,
+}
+
+impl DmxConfig {
+    /// Universe 1, address 1.
+    pub const DEFAULT: Self = Self {
+        universe: 1,
+        start_address: 1,
+    };
+
+    /// Whether `start_address` is a DMX address, 1-512.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.start_address >= 1 && self.start_address as usize <= UNIVERSE_CHANNELS
+    }
+}
+
+impl Default for DmxConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// A network lighting protocol, identified by the UDP port it arrived on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmxProtocol {
+    /// E1.31, also called streaming ACN or sACN.
+    E131,
+    /// Art-Net (ArtDmx packets).
+    ArtNet,
+    /// Distributed Display Protocol.
+    Ddp,
+}
+
+impl DmxProtocol {
+    /// The protocol's standard UDP port.
+    #[must_use]
+    pub const fn port(self) -> u16 {
+        match self {
+            Self::E131 => E131_PORT,
+            Self::ArtNet => ART_NET_PORT,
+            Self::Ddp => DDP_PORT,
+        }
+    }
+}
+
+/// The LED data in one received packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DmxPacket<'a> {
+    /// Channel values for one DMX universe, starting at address 1 (E1.31 and Art-Net).
+    Universe { universe: u16, channels: &'a [u8] },
+    /// Pixel bytes starting `offset` bytes into the display (DDP).
+    Pixels {
+        offset: usize,
+        data: &'a [u8],
+        push: bool,
+    },
+}
+
+impl<'a> DmxPacket<'a> {
+    /// Parse a packet of `protocol`, returning `None` for malformed packets and for packets
+    /// that carry no LED data (such as polls, sync, or preview data).
+    #[must_use]
+    pub fn parse(protocol: DmxProtocol, bytes: &'a [u8]) -> Option<Self> {
+        match protocol {
+            DmxProtocol::E131 => Self::parse_e131(bytes),
+            DmxProtocol::ArtNet => Self::parse_art_net(bytes),
+            DmxProtocol::Ddp => Self::parse_ddp(bytes),
+        }
+    }
+
+    /// Parse an E1.31 data packet.
+    #[must_use]
+    pub fn parse_e131(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.get(4..16)? != E131_PACKET_IDENTIFIER
+            || be_u32(bytes, 18)? != E131_VECTOR_ROOT_DATA
+            || be_u32(bytes, 40)? != E131_VECTOR_FRAMING_DATA
+            || bytes.get(112)? & E131_OPTION_PREVIEW != 0
+            || *bytes.get(117)? != E131_VECTOR_DMP_SET_PROPERTY
+            || *bytes.get(118)? != E131_ADDRESS_AND_DATA_TYPE
+        {
+            return None;
+        }
+        let universe = be_u16(bytes, 113)?;
+        // The property values are a start code followed by the channels.
+        let value_count = usize::from(be_u16(bytes, 123)?);
+        let values = bytes.get(E131_START_CODE_OFFSET..)?;
+        let values = values.get(..value_count.min(values.len()))?;
+        let (&start_code, channels) = values.split_first()?;
+        (start_code == 0).then_some(Self::Universe { universe, channels })
+    }
+
+    /// Parse an Art-Net ArtDmx packet.
+    #[must_use]
+    pub fn parse_art_net(bytes: &'a [u8]) -> Option<Self> {
+        let op_code = u16::from_le_bytes([*bytes.get(8)?, *bytes.get(9)?]);
+        if bytes.get(..8)? != ART_NET_ID
+            || op_code != ART_NET_OP_DMX
+            || be_u16(bytes, 10)? < ART_NET_MIN_VERSION
+        {
+            return None;
+        }
+        // 15-bit port address: net, then sub-net and universe.
+        let universe = u16::from_be_bytes([bytes.get(15)? & 0x7F, *bytes.get(14)?]);
+        let length = usize::from(be_u16(bytes, 16)?);
+        let channels = bytes.get(ART_NET_HEADER_LEN..)?;
+        let channels = channels.get(..length.min(channels.len()))?;
+        Some(Self::Universe { universe, channels })
+    }
+
+    /// Parse a DDP data packet addressed to the display.
+    #[must_use]
+    pub fn parse_ddp(bytes: &'a [u8]) -> Option<Self> {
+        let flags = *bytes.first()?;
+        let destination = *bytes.get(3)?;
+        if flags & DDP_VERSION_MASK != DDP_VERSION_1
+            || flags & (DDP_FLAG_QUERY | DDP_FLAG_REPLY) != 0
+            || (destination != 0 && destination != DDP_ID_DISPLAY)
+        {
+            return None;
+        }
+        let header_len = if flags & DDP_FLAG_TIMECODE == 0 {
+            DDP_HEADER_LEN
+        } else {
+            DDP_HEADER_LEN + 4
+        };
+        let offset = usize::try_from(be_u32(bytes, 4)?).ok()?;
+        let length = usize::from(be_u16(bytes, 8)?);
+        let data = bytes.get(header_len..)?;
+        let data = data.get(..length.min(data.len()))?;
+        Some(Self::Pixels {
+            offset,
+            data,
+            push: flags & DDP_FLAG_PUSH != 0,
+        })
+    }
+}
+
+/// Assembles received packets into frames for `N` LEDs.
+///
+/// See the [module documentation](self) for how channels map to pixels.
+pub struct DmxMapper<const N: usize> {
+    config: DmxConfig,
+    mapping_by_xy: [u16; N],
+    frame: [RGB8; N],
+}
+
+impl<const N: usize> DmxMapper<N> {
+    /// Map pixels in row-major display order onto the LEDs of `led_layout`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.start_address` is not 1-512.
+    #[must_use]
+    pub const fn new<const W: usize, const H: usize>(
+        config: DmxConfig,
+        led_layout: &LedLayout<N, W, H>,
+    ) -> Self {
+        assert!(config.is_valid(), "DMX start address must be 1-512");
+        Self {
+            config,
+            mapping_by_xy: led_layout.mapping_by_xy(),
+            frame: [RGB8::new(0, 0, 0); N],
+        }
+    }
+
+    /// Map pixels in order along a strip.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config.start_address` is not 1-512.
+    #[must_use]
+    pub const fn linear(config: DmxConfig) -> Self {
+        Self::new(config, &LedLayout::<N, N, 1>::linear_h())
+    }
+
+    /// The configuration this mapper was created with.
+    #[must_use]
+    pub const fn config(&self) -> DmxConfig {
+        self.config
+    }
+
+    /// The frame assembled so far, in LED order.
+    #[must_use]
+    pub const fn frame(&self) -> &[RGB8; N] {
+        &self.frame
+    }
+
+    /// The last universe holding any of this device's pixels.
+    #[must_use]
+    pub const fn last_universe(&self) -> u16 {
+        let first_pixels = self.first_universe_pixels();
+        let extra_universes = if N <= first_pixels {
+            0
+        } else {
+            (N - first_pixels).div_ceil(PIXELS_PER_UNIVERSE)
+        };
+        self.config.universe.saturating_add(extra_universes as u16)
+    }
+
+    /// Copy a packet's pixels into the frame. Returns whether the frame is complete and
+    /// should be shown.
+    pub fn apply(&mut self, packet: &DmxPacket<'_>) -> bool {
+        match *packet {
+            DmxPacket::Universe { universe, channels } => self.apply_universe(universe, channels),
+            DmxPacket::Pixels { offset, data, push } => {
+                self.apply_pixels(offset, data);
+                push
+            }
+        }
+    }
+
+    const fn first_universe_pixels(&self) -> usize {
+        (UNIVERSE_CHANNELS - (self.config.start_address as usize - 1)) / 3
+    }
+
+    fn apply_universe(&mut self, universe: u16, channels: &[u8]) -> bool {
+        let Some(index) = universe.checked_sub(self.config.universe) else {
+            return false;
+        };
+        let first_pixels = self.first_universe_pixels();
+        let (first_pixel, capacity, channels) = if index == 0 {
+            let start = usize::from(self.config.start_address) - 1;
+            (0, first_pixels, channels.get(start..).unwrap_or_default())
+        } else {
+            let first_pixel = first_pixels + (usize::from(index) - 1) * PIXELS_PER_UNIVERSE;
+            (first_pixel, PIXELS_PER_UNIVERSE, channels)
+        };
+        if first_pixel >= N {
+            return false;
+        }
+        for (pixel, rgb) in (first_pixel..N)
+            .take(capacity)
+            .zip(channels.chunks_exact(3))
+        {
+            if let [r, g, b] = *rgb {
+                self.set_pixel(pixel, RGB8::new(r, g, b));
+            }
+        }
+        first_pixel + capacity >= N
+    }
+
+    fn apply_pixels(&mut self, offset: usize, data: &[u8]) {
+        for (byte_index, &value) in (offset..N * 3).zip(data) {
+            let pixel = byte_index / 3;
+            let Some(&led_index) = self.mapping_by_xy.get(pixel) else {
+                return;
+            };
+            if let Some(color) = self.frame.get_mut(usize::from(led_index)) {
+                match byte_index % 3 {
+                    0 => color.r = value,
+                    1 => color.g = value,
+                    _ => color.b = value,
+                }
+            }
+        }
+    }
+
+    fn set_pixel(&mut self, pixel: usize, color: RGB8) {
+        if let Some(&led_index) = self.mapping_by_xy.get(pixel) {
+            if let Some(slot) = self.frame.get_mut(usize::from(led_index)) {
+                *slot = color;
+            }
+        }
+    }
+}
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([
+        *bytes.get(offset)?,
+        *bytes.get(offset + 1)?,
+    ]))
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let word = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(word.try_into().ok()?))
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+mod wifi_impl {
+    use core::convert::Infallible;
+
+    use defmt::{info, warn};
+    use embassy_futures::select::{Either3, select3};
+    use embassy_net::Stack;
+    use embassy_net::udp::{PacketMetadata, UdpSocket};
+
+    use super::{DmxMapper, DmxPacket, DmxProtocol};
+    use crate::led_strip::Frame;
+    use crate::led2d::WriteFrame;
+    use crate::{Error, Result};
+
+    /// Largest E1.31 data packet: 125 header bytes, a start code, and 512 channels.
+    const E131_MAX_PACKET: usize = 638;
+    /// Largest ArtDmx packet: 18 header bytes and 512 channels.
+    const ART_NET_MAX_PACKET: usize = 530;
+    /// Largest DDP packet in one Ethernet frame: 14 header bytes and 1440 data bytes.
+    const DDP_MAX_PACKET: usize = 1454;
+    /// Packets each socket can queue while a frame is being written.
+    const QUEUED_PACKETS: usize = 4;
+
+    /// Receive E1.31, Art-Net, and DDP on their standard ports and show each complete frame
+    /// on `strip`.
+    ///
+    /// Runs until a frame can't be written. To drive a display made with
+    /// [`led2d_from_strip!`](crate::led2d::led2d_from_strip), pass the underlying strip and
+    /// build `mapper` from the display's [`LedLayout`](crate::led_layout::LedLayout).
+    ///
+    /// ```no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use panic_probe as _;
+    /// use device_kit::dmx::{DmxConfig, DmxMapper, run_dmx_receiver};
+    /// use device_kit::led_layout::LedLayout;
+    /// use device_kit::led_strip::{Current, led_strip};
+    ///
+    /// led_strip! {
+    ///     Panel {
+    ///         pin: PIN_0,
+    ///         len: 96,
+    ///         max_current: Current::Milliamps(1000),
+    ///     }
+    /// }
+    ///
+    /// // A 12x8 panel wired in a serpentine, fed row by row from the lighting software.
+    /// const LED_LAYOUT: LedLayout<96, 12, 8> = LedLayout::serpentine_column_major();
+    ///
+    /// async fn show_dmx(
+    ///     stack: &'static embassy_net::Stack<'static>,
+    ///     panel: &'static Panel,
+    /// ) -> device_kit::Result<core::convert::Infallible> {
+    ///     let config = DmxConfig { universe: 1, start_address: 1 };
+    ///     run_dmx_receiver(stack, DmxMapper::new(config, &LED_LAYOUT), panel).await
+    /// }
+    /// # #[embassy_executor::main]
+    /// # async fn main(_spawner: embassy_executor::Spawner) {}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a port can't be bound or `strip` fails to show a frame.
+    pub async fn run_dmx_receiver<const N: usize>(
+        stack: &'static Stack<'static>,
+        mut mapper: DmxMapper<N>,
+        strip: &impl WriteFrame<N>,
+    ) -> Result<Infallible> {
+        let mut e131_meta = [PacketMetadata::EMPTY; QUEUED_PACKETS];
+        let mut e131_rx = [0; E131_MAX_PACKET * QUEUED_PACKETS];
+        let mut e131_tx_meta = [PacketMetadata::EMPTY; 1];
+        let mut e131_tx = [0; 0];
+        let mut e131 = UdpSocket::new(
+            *stack,
+            &mut e131_meta,
+            &mut e131_rx,
+            &mut e131_tx_meta,
+            &mut e131_tx,
+        );
+        e131.bind(DmxProtocol::E131.port())
+            .map_err(Error::UdpBind)?;
+
+        let mut art_net_meta = [PacketMetadata::EMPTY; QUEUED_PACKETS];
+        let mut art_net_rx = [0; ART_NET_MAX_PACKET * QUEUED_PACKETS];
+        let mut art_net_tx_meta = [PacketMetadata::EMPTY; 1];
+        let mut art_net_tx = [0; 0];
+        let mut art_net = UdpSocket::new(
+            *stack,
+            &mut art_net_meta,
+            &mut art_net_rx,
+            &mut art_net_tx_meta,
+            &mut art_net_tx,
+        );
+        art_net
+            .bind(DmxProtocol::ArtNet.port())
+            .map_err(Error::UdpBind)?;
+
+        let mut ddp_meta = [PacketMetadata::EMPTY; QUEUED_PACKETS];
+        let mut ddp_rx = [0; DDP_MAX_PACKET * QUEUED_PACKETS];
+        let mut ddp_tx_meta = [PacketMetadata::EMPTY; 1];
+        let mut ddp_tx = [0; 0];
+        let mut ddp = UdpSocket::new(
+            *stack,
+            &mut ddp_meta,
+            &mut ddp_rx,
+            &mut ddp_tx_meta,
+            &mut ddp_tx,
+        );
+        ddp.bind(DmxProtocol::Ddp.port()).map_err(Error::UdpBind)?;
+
+        let config = mapper.config();
+        info!(
+            "DMX receiver listening: universes {}-{}, start address {}",
+            config.universe,
+            mapper.last_universe(),
+            config.start_address
+        );
+
+        let mut e131_packet = [0; E131_MAX_PACKET];
+        let mut art_net_packet = [0; ART_NET_MAX_PACKET];
+        let mut ddp_packet = [0; DDP_MAX_PACKET];
+        loop {
+            let (protocol, received) = match select3(
+                e131.recv_from(&mut e131_packet),
+                art_net.recv_from(&mut art_net_packet),
+                ddp.recv_from(&mut ddp_packet),
+            )
+            .await
+            {
+                Either3::First(received) => (DmxProtocol::E131, received),
+                Either3::Second(received) => (DmxProtocol::ArtNet, received),
+                Either3::Third(received) => (DmxProtocol::Ddp, received),
+            };
+            let Ok((len, _from)) = received else {
+                warn!("DMX receiver: dropped an oversized packet");
+                continue;
+            };
+            let buffer: &[u8] = match protocol {
+                DmxProtocol::E131 => &e131_packet,
+                DmxProtocol::ArtNet => &art_net_packet,
+                DmxProtocol::Ddp => &ddp_packet,
+            };
+            let Some(packet) = buffer
+                .get(..len)
+                .and_then(|bytes| DmxPacket::parse(protocol, bytes))
+            else {
+                continue;
+            };
+            if mapper.apply(&packet) {
+                strip.write_frame(Frame::from(*mapper.frame())).await?;
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+pub use wifi_impl::run_dmx_receiver;
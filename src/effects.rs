@@ -0,0 +1,683 @@
+//! Ready-made animation effects for LED strips and 2D LED displays.
+//!
+//! Every effect is an [`AnimationSource`], so it plays on any generated strip or display type
+//! through a `static` [`AnimationStream`](crate::animation::AnimationStream) and
+//! `animate_stream`, and stops when the next command (such as `write_frame`) arrives.
+//!
+//! - Effects along the LED order: [`RainbowCycle`], [`TheaterChase`], [`Comet`], [`Twinkle`],
+//!   and [`Breathing`]. They render into `led_strip::Frame<N>` and, in row-major order, into
+//!   `led2d::Frame<W, H>`.
+//! - 2D effects: [`Fire`], [`Plasma`], [`MatrixRain`], and [`Life`]. They render into
+//!   `led2d::Frame<W, H>`. To run one on a strip, wrap it in [`OnLayout`] with the strip's
+//!   [`LedLayout`](crate::led_layout::LedLayout).
+//!
+//! Each effect advances one step per frame, so `frame_duration` sets its speed. Effects that
+//! use randomness start from a fixed seed; change it with `with_seed`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::animation::AnimationStream;
+//! use device_kit::effects::{Comet, Fire, OnLayout};
+//! use device_kit::led_layout::LedLayout;
+//! use device_kit::led_strip::{Current, Frame, colors, led_strips};
+//! use embassy_executor::Spawner;
+//! use embassy_time::Duration;
+//!
+//! led_strips! {
+//!     LedStrips {
+//!         gpio0: { pin: PIN_0, len: 30, max_current: Current::Milliamps(250) },
+//!         gpio1: { pin: PIN_1, len: 48, max_current: Current::Milliamps(250) },
+//!     }
+//! }
+//!
+//! // A comet running along a 30-LED strip.
+//! static COMET: AnimationStream<Comet, Frame<30>> = AnimationStream::new(
+//!     Comet::new(colors::CYAN, 8, Duration::from_millis(30)),
+//!     Frame::new(),
+//! );
+//!
+//! // Fire on a 12x4 serpentine panel driven as a 48-LED strip.
+//! type PanelFire = OnLayout<Fire<12, 4>, 48, 12, 4>;
+//! static FIRE: AnimationStream<PanelFire, Frame<48>> = AnimationStream::new(
+//!     OnLayout::new(
+//!         Fire::new(55, 120, Duration::from_millis(40)),
+//!         LedLayout::serpentine_column_major(),
+//!     ),
+//!     Frame::new(),
+//! );
+//!
+//! #[embassy_executor::main]
+//! async fn main(spawner: Spawner) {
+//!     let p = embassy_rp::init(Default::default());
+//!     let (strip, panel) =
+//!         LedStrips::new(p.PIO0, p.DMA_CH0, p.PIN_0, p.DMA_CH1, p.PIN_1, spawner).unwrap();
+//!     strip.animate_stream(&COMET).await.unwrap();
+//!     panel.animate_stream(&FIRE).await.unwrap();
+//! }
+//! ```
+
+use embassy_time::Duration;
+use smart_leds::RGB8;
+
+use crate::animation::AnimationSource;
+#[cfg(not(feature = "host"))]
+use crate::led_layout::LedLayout;
+#[cfg(not(feature = "host"))]
+use crate::led_strip::Frame as StripFrame;
+use crate::led2d::Frame;
+
+const BLACK: RGB8 = RGB8::new(0, 0, 0);
+
+/// Seed used by effects that have not been given one with `with_seed`.
+const DEFAULT_SEED: u32 = 0x2545_F491;
+
+/// Implement [`AnimationSource`] for strip frames and (in row-major order) 2D frames in terms
+/// of the effect's `render(&mut self, pixels: &mut [RGB8]) -> Duration`.
+macro_rules! impl_linear_source {
+    ($effect:ty) => {
+        #[cfg(not(feature = "host"))]
+        impl<const N: usize> AnimationSource<StripFrame<N>> for $effect {
+            fn next_frame(&mut self, frame: &mut StripFrame<N>) -> Duration {
+                self.render(&mut frame[..])
+            }
+        }
+
+        impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for $effect {
+            fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+                self.render(frame.as_flattened_mut())
+            }
+        }
+    };
+}
+
+// ============================================================================
+// Color helpers
+// ============================================================================
+
+/// Fully saturated color at `hue` around the color wheel (0 = red, 85 = green, 170 = blue).
+#[must_use]
+pub const fn wheel(hue: u8) -> RGB8 {
+    let position = hue as u16 * 3;
+    let offset = (position % 255) as u8;
+    match position / 255 {
+        0 => RGB8::new(255 - offset, offset, 0),
+        1 => RGB8::new(0, 255 - offset, offset),
+        2 => RGB8::new(offset, 0, 255 - offset),
+        _ => RGB8::new(255, 0, 0),
+    }
+}
+
+/// Scale `color` by `level / 255`.
+#[must_use]
+pub const fn scale(color: RGB8, level: u8) -> RGB8 {
+    const fn channel(value: u8, level: u8) -> u8 {
+        (value as u16 * level as u16 / 255) as u8
+    }
+    RGB8::new(
+        channel(color.r, level),
+        channel(color.g, level),
+        channel(color.b, level),
+    )
+}
+
+/// Approximate `128 + 127 * sin(2π * theta / 256)` with one parabola per half wave.
+const fn sin8(theta: u8) -> u8 {
+    let x = (theta & 0x7F) as u32;
+    let y = (x * (128 - x) * 127 / (64 * 64)) as u8;
+    if theta < 128 { 128 + y } else { 128 - y }
+}
+
+/// Small xorshift generator; effects need variety, not statistical quality.
+#[derive(Clone, Copy, Debug)]
+struct Rng(u32);
+
+impl Rng {
+    const fn new(seed: u32) -> Self {
+        // Xorshift never leaves zero.
+        Self(if seed == 0 { DEFAULT_SEED } else { seed })
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    fn next_u8(&mut self) -> u8 {
+        (self.next_u32() >> 24) as u8
+    }
+
+    /// A value in `0..bound`.
+    fn below(&mut self, bound: u32) -> u32 {
+        ((u64::from(self.next_u32()) * u64::from(bound)) >> 32) as u32
+    }
+}
+
+// ============================================================================
+// Effects along the LED order
+// ============================================================================
+
+/// The whole color wheel spread along the LEDs, rotating one step per frame.
+#[derive(Clone, Copy, Debug)]
+pub struct RainbowCycle {
+    frame_duration: Duration,
+    hue: u8,
+}
+
+impl RainbowCycle {
+    /// Create a rainbow that completes one rotation every 256 frames.
+    #[must_use]
+    pub const fn new(frame_duration: Duration) -> Self {
+        Self {
+            frame_duration,
+            hue: 0,
+        }
+    }
+
+    fn render(&mut self, pixels: &mut [RGB8]) -> Duration {
+        let len = pixels.len();
+        for (index, pixel) in pixels.iter_mut().enumerate() {
+            *pixel = wheel(self.hue.wrapping_add((index * 256 / len) as u8));
+        }
+        self.hue = self.hue.wrapping_add(1);
+        self.frame_duration
+    }
+}
+
+impl_linear_source!(RainbowCycle);
+
+/// Every `spacing`-th LED lit, marching forward one LED per frame, like a theater marquee.
+#[derive(Clone, Copy, Debug)]
+pub struct TheaterChase {
+    color: RGB8,
+    background: RGB8,
+    spacing: usize,
+    frame_duration: Duration,
+    offset: usize,
+    reversed: bool,
+}
+
+impl TheaterChase {
+    /// Create a chase of `color` lights on `background`, `spacing` LEDs apart.
+    #[must_use]
+    pub const fn new(
+        color: RGB8,
+        background: RGB8,
+        spacing: usize,
+        frame_duration: Duration,
+    ) -> Self {
+        assert!(spacing > 0, "spacing must be positive");
+        Self {
+            color,
+            background,
+            spacing,
+            frame_duration,
+            offset: 0,
+            reversed: false,
+        }
+    }
+
+    /// March backward, toward the first LED, instead.
+    #[must_use]
+    pub const fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    fn render(&mut self, pixels: &mut [RGB8]) -> Duration {
+        let lit = if self.reversed {
+            (self.spacing - self.offset) % self.spacing
+        } else {
+            self.offset
+        };
+        for (index, pixel) in pixels.iter_mut().enumerate() {
+            *pixel = if (index + self.spacing - lit).is_multiple_of(self.spacing) {
+                self.color
+            } else {
+                self.background
+            };
+        }
+        self.offset = (self.offset + 1) % self.spacing;
+        self.frame_duration
+    }
+}
+
+impl_linear_source!(TheaterChase);
+
+/// A bright head with a fading tail, running along the LEDs and wrapping at the end.
+#[derive(Clone, Copy, Debug)]
+pub struct Comet {
+    color: RGB8,
+    tail_length: usize,
+    frame_duration: Duration,
+    position: usize,
+}
+
+impl Comet {
+    /// Create a comet whose tail fades to black over `tail_length` LEDs.
+    #[must_use]
+    pub const fn new(color: RGB8, tail_length: usize, frame_duration: Duration) -> Self {
+        Self {
+            color,
+            tail_length,
+            frame_duration,
+            position: 0,
+        }
+    }
+
+    fn render(&mut self, pixels: &mut [RGB8]) -> Duration {
+        let len = pixels.len();
+        if len == 0 {
+            return self.frame_duration;
+        }
+        let head = self.position % len;
+        pixels.fill(BLACK);
+        for distance in 0..=self.tail_length.min(len - 1) {
+            let level = 255 * (self.tail_length + 1 - distance) / (self.tail_length + 1);
+            pixels[(head + len - distance) % len] = scale(self.color, level as u8);
+        }
+        self.position = (head + 1) % len;
+        self.frame_duration
+    }
+}
+
+impl_linear_source!(Comet);
+
+/// LEDs that light at random and fade away.
+#[derive(Clone, Copy, Debug)]
+pub struct Twinkle {
+    color: RGB8,
+    density: u8,
+    frame_duration: Duration,
+    rng: Rng,
+}
+
+impl Twinkle {
+    /// How much each lit LED fades per frame, out of 255.
+    const FADE: u8 = 24;
+
+    /// Create twinkles of `color`. Each frame, each LED lights with probability
+    /// `density / 256`.
+    #[must_use]
+    pub const fn new(color: RGB8, density: u8, frame_duration: Duration) -> Self {
+        Self {
+            color,
+            density,
+            frame_duration,
+            rng: Rng::new(DEFAULT_SEED),
+        }
+    }
+
+    /// Use a different random sequence.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    fn render(&mut self, pixels: &mut [RGB8]) -> Duration {
+        for pixel in pixels.iter_mut() {
+            *pixel = if self.rng.next_u8() < self.density {
+                self.color
+            } else {
+                scale(*pixel, 255 - Self::FADE)
+            };
+        }
+        self.frame_duration
+    }
+}
+
+impl_linear_source!(Twinkle);
+
+/// Every LED slowly brightening and dimming together.
+#[derive(Clone, Copy, Debug)]
+pub struct Breathing {
+    color: RGB8,
+    frame_duration: Duration,
+    phase: u8,
+}
+
+impl Breathing {
+    /// Create a breathing `color` that takes 256 frames per breath, starting dark.
+    #[must_use]
+    pub const fn new(color: RGB8, frame_duration: Duration) -> Self {
+        Self {
+            color,
+            frame_duration,
+            phase: 0,
+        }
+    }
+
+    fn render(&mut self, pixels: &mut [RGB8]) -> Duration {
+        let level = sin8(self.phase.wrapping_sub(64));
+        // Squaring the level makes the dim end of the breath look smoother.
+        let level = (u16::from(level) * u16::from(level) / 255) as u8;
+        pixels.fill(scale(self.color, level));
+        self.phase = self.phase.wrapping_add(1);
+        self.frame_duration
+    }
+}
+
+impl_linear_source!(Breathing);
+
+// ============================================================================
+// 2D effects
+// ============================================================================
+
+/// Flames rising from the bottom row (the classic "Fire2012" simulation, one per column).
+#[derive(Clone, Copy, Debug)]
+pub struct Fire<const W: usize, const H: usize> {
+    heat: [[u8; W]; H],
+    cooling: u8,
+    sparking: u8,
+    frame_duration: Duration,
+    rng: Rng,
+}
+
+impl<const W: usize, const H: usize> Fire<W, H> {
+    /// Create a fire. Higher `cooling` (20..=100 works well) makes shorter flames; higher
+    /// `sparking` (50..=200) makes a more active fire.
+    #[must_use]
+    pub const fn new(cooling: u8, sparking: u8, frame_duration: Duration) -> Self {
+        Self {
+            heat: [[0; W]; H],
+            cooling,
+            sparking,
+            frame_duration,
+            rng: Rng::new(DEFAULT_SEED),
+        }
+    }
+
+    /// Use a different random sequence.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    /// Black through red and yellow to white.
+    const fn heat_color(heat: u8) -> RGB8 {
+        let scaled = (heat as u16 * 191 / 255) as u8;
+        let ramp = (scaled & 0x3F) << 2;
+        if scaled >= 128 {
+            RGB8::new(255, 255, ramp)
+        } else if scaled >= 64 {
+            RGB8::new(255, ramp, 0)
+        } else {
+            RGB8::new(ramp, 0, 0)
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for Fire<W, H> {
+    fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+        if W == 0 || H == 0 {
+            return self.frame_duration;
+        }
+        let max_cooling = u32::from(self.cooling) * 10 / H as u32 + 2;
+        for column in 0..W {
+            for row in 0..H {
+                let cooldown = self.rng.below(max_cooling) as u8;
+                self.heat[row][column] = self.heat[row][column].saturating_sub(cooldown);
+            }
+            // Heat drifts up: each cell takes from the two cells below it.
+            for row in 0..H.saturating_sub(2) {
+                let below = u16::from(self.heat[row + 1][column]);
+                let two_below = u16::from(self.heat[row + 2][column]);
+                self.heat[row][column] = ((below + 2 * two_below) / 3) as u8;
+            }
+            if self.rng.next_u8() < self.sparking {
+                let row = H - 1 - self.rng.below(H.min(3) as u32) as usize;
+                let spark = 160 + self.rng.below(96) as u8;
+                self.heat[row][column] = self.heat[row][column].saturating_add(spark);
+            }
+            for row in 0..H {
+                frame[row][column] = Self::heat_color(self.heat[row][column]);
+            }
+        }
+        self.frame_duration
+    }
+}
+
+/// Smoothly flowing color bands made from overlapping sine waves.
+#[derive(Clone, Copy, Debug)]
+pub struct Plasma {
+    frame_duration: Duration,
+    time: u8,
+}
+
+impl Plasma {
+    /// Create a plasma whose pattern repeats every 256 frames.
+    #[must_use]
+    pub const fn new(frame_duration: Duration) -> Self {
+        Self {
+            frame_duration,
+            time: 0,
+        }
+    }
+}
+
+impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for Plasma {
+    fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+        let time = self.time;
+        for (row, pixels) in frame.iter_mut().enumerate() {
+            for (column, pixel) in pixels.iter_mut().enumerate() {
+                let x = (column * 16) as u8;
+                let y = (row * 16) as u8;
+                let sum = u16::from(sin8(x.wrapping_add(time)))
+                    + u16::from(sin8(y.wrapping_sub(time.wrapping_mul(2))))
+                    + u16::from(sin8((x / 2).wrapping_add(y / 2).wrapping_add(time)));
+                *pixel = wheel(((sum / 3) as u8).wrapping_add(time));
+            }
+        }
+        self.time = self.time.wrapping_add(1);
+        self.frame_duration
+    }
+}
+
+/// Drops falling down columns, leaving fading trails ("digital rain").
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixRain<const W: usize> {
+    color: RGB8,
+    spawn_chance: u8,
+    frame_duration: Duration,
+    /// Row of each column's drop plus one, or 0 for no drop.
+    drops: [u8; W],
+    rng: Rng,
+}
+
+impl<const W: usize> MatrixRain<W> {
+    /// How much of its brightness a trail keeps each frame, out of 255.
+    const TRAIL: u8 = 170;
+
+    /// Create rain of `color`. Each frame, each empty column starts a drop with probability
+    /// `spawn_chance / 256`.
+    #[must_use]
+    pub const fn new(color: RGB8, spawn_chance: u8, frame_duration: Duration) -> Self {
+        Self {
+            color,
+            spawn_chance,
+            frame_duration,
+            drops: [0; W],
+            rng: Rng::new(DEFAULT_SEED),
+        }
+    }
+
+    /// Use a different random sequence.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+}
+
+impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for MatrixRain<W> {
+    fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+        assert!(H < 255, "matrix rain supports at most 254 rows");
+        if W == 0 || H == 0 {
+            return self.frame_duration;
+        }
+        for pixel in frame.as_flattened_mut() {
+            *pixel = scale(*pixel, Self::TRAIL);
+        }
+        for (column, drop) in self.drops.iter_mut().enumerate() {
+            if *drop == 0 {
+                if self.rng.next_u8() < self.spawn_chance {
+                    *drop = 1;
+                }
+            } else if usize::from(*drop) < H {
+                *drop += 1;
+            } else {
+                *drop = 0;
+            }
+            if *drop > 0 {
+                frame[usize::from(*drop) - 1][column] = self.color;
+            }
+        }
+        self.frame_duration
+    }
+}
+
+/// Conway's Game of Life, one generation per frame, on a board that wraps at the edges.
+///
+/// Any non-black pixel in the current frame is a live cell, so start a pattern by passing it
+/// as the stream's initial frame. When the board dies out or stops changing, it is reseeded
+/// at random.
+#[derive(Clone, Copy, Debug)]
+pub struct Life {
+    color: RGB8,
+    frame_duration: Duration,
+    rng: Rng,
+}
+
+impl Life {
+    /// Create a game drawing live cells in `color`.
+    #[must_use]
+    pub const fn new(color: RGB8, frame_duration: Duration) -> Self {
+        Self {
+            color,
+            frame_duration,
+            rng: Rng::new(DEFAULT_SEED),
+        }
+    }
+
+    /// Use a different random sequence.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u32) -> Self {
+        self.rng = Rng::new(seed);
+        self
+    }
+
+    fn is_alive<const W: usize, const H: usize>(
+        frame: &Frame<W, H>,
+        row: usize,
+        column: usize,
+    ) -> bool {
+        frame[row][column] != BLACK
+    }
+
+    fn live_neighbors<const W: usize, const H: usize>(
+        frame: &Frame<W, H>,
+        row: usize,
+        column: usize,
+    ) -> u8 {
+        let mut count = 0;
+        for row_offset in [H - 1, 0, 1] {
+            for column_offset in [W - 1, 0, 1] {
+                if row_offset == 0 && column_offset == 0 {
+                    continue;
+                }
+                let neighbor_row = (row + row_offset) % H;
+                let neighbor_column = (column + column_offset) % W;
+                if Self::is_alive(frame, neighbor_row, neighbor_column) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for Life {
+    fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+        let mut next = Frame::<W, H>::new();
+        let mut changed = false;
+        let mut any_alive = false;
+        for row in 0..H {
+            for column in 0..W {
+                let alive = Self::is_alive(frame, row, column);
+                let next_alive = matches!(
+                    (alive, Self::live_neighbors(frame, row, column)),
+                    (true, 2 | 3) | (false, 3)
+                );
+                if next_alive {
+                    next[row][column] = self.color;
+                }
+                changed |= next_alive != alive;
+                any_alive |= next_alive;
+            }
+        }
+
+        if !any_alive || !changed {
+            for pixel in next.as_flattened_mut() {
+                *pixel = if self.rng.next_u8() < 64 {
+                    self.color
+                } else {
+                    BLACK
+                };
+            }
+        }
+        *frame = next;
+        self.frame_duration
+    }
+}
+
+// ============================================================================
+// OnLayout
+// ============================================================================
+
+/// Runs a 2D effect on an LED strip, placing each LED according to a [`LedLayout`].
+///
+/// See the [module documentation](self) for an example.
+#[cfg(not(feature = "host"))]
+#[derive(Clone, Copy, Debug)]
+pub struct OnLayout<E, const N: usize, const W: usize, const H: usize> {
+    effect: E,
+    led_layout: LedLayout<N, W, H>,
+    frame: Frame<W, H>,
+}
+
+#[cfg(not(feature = "host"))]
+impl<E, const N: usize, const W: usize, const H: usize> OnLayout<E, N, W, H> {
+    /// Render `effect` on a `W` x `H` grid and show it on the LEDs of `led_layout`.
+    #[must_use]
+    pub const fn new(effect: E, led_layout: LedLayout<N, W, H>) -> Self {
+        Self {
+            effect,
+            led_layout,
+            frame: Frame::new(),
+        }
+    }
+}
+
+#[cfg(not(feature = "host"))]
+impl<E, const N: usize, const W: usize, const H: usize> AnimationSource<StripFrame<N>>
+    for OnLayout<E, N, W, H>
+where
+    E: AnimationSource<Frame<W, H>>,
+{
+    fn next_frame(&mut self, strip_frame: &mut StripFrame<N>) -> Duration {
+        let duration = self.effect.next_frame(&mut self.frame);
+        for (pixel, (column, row)) in strip_frame.iter_mut().zip(self.led_layout.map()) {
+            *pixel = self.frame[usize::from(*row)][usize::from(*column)];
+        }
+        duration
+    }
+}
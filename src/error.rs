@@ -44,6 +44,10 @@ pub enum Error {
 
     #[display("Storage is invalid or corrupted")]
     StorageCorrupted,
+
+    #[cfg(feature = "wifi")]
+    #[display("UDP socket bind failed: {_0:?}")]
+    UdpBind(#[error(not(source))] embassy_net::udp::BindError),
 }
 
 impl From<()> for Error {
@@ -109,6 +109,9 @@ pub struct FlashBlock {
 }
 
 impl FlashBlock {
+    /// Largest payload a block holds, in bytes, after postcard serialization.
+    pub const CAPACITY: usize = MAX_PAYLOAD_SIZE;
+
     /// Load data stored in this block.
     pub fn load<T>(&mut self) -> Result<Option<T>>
     where
@@ -135,6 +138,11 @@ impl FlashBlock {
     pub const fn block_id(&self) -> u32 {
         self.block
     }
+
+    /// Return the serialized size of the stored data, or `None` if the block is empty.
+    pub fn used_bytes(&mut self) -> Result<Option<usize>> {
+        used_block_bytes(self.manager, self.block)
+    }
 }
 
 /// Static type for constructing flash arrays.
@@ -153,6 +161,28 @@ impl FlashArrayStatic {
         }
     }
 
+    /// Return how many blocks have been reserved with [`FlashArray::new`].
+    #[must_use]
+    pub fn block_count(&'static self) -> u32 {
+        self.existing_manager()
+            .map_or(0, |manager| manager.next_block.load(Ordering::SeqCst))
+    }
+
+    /// Return the serialized size of the data in reserved block `block_id`, or `None` if
+    /// the block is empty. Lets status pages report usage without owning the
+    /// [`FlashBlock`].
+    pub fn used_bytes(&'static self, block_id: u32) -> Result<Option<usize>> {
+        if block_id >= self.block_count() {
+            return Err(Error::IndexOutOfBounds);
+        }
+        let manager = self.existing_manager().ok_or(Error::IndexOutOfBounds)?;
+        used_block_bytes(manager, block_id)
+    }
+
+    fn existing_manager(&'static self) -> Option<&'static FlashManager> {
+        self.manager_ref.lock(|slot_cell| *slot_cell.borrow())
+    }
+
     fn manager(&'static self, peripheral: Peri<'static, FLASH>) -> &'static FlashManager {
         self.manager_ref.lock(|slot_cell| {
             let mut slot = slot_cell.borrow_mut();
@@ -353,6 +383,27 @@ where
     Ok(Some(value))
 }
 
+/// Read only the header, so checking usage doesn't copy a whole block.
+fn used_block_bytes(manager: &'static FlashManager, block: u32) -> Result<Option<usize>> {
+    let mut header = [0u8; HEADER_SIZE];
+    manager.with_flash(|flash| {
+        flash
+            .blocking_read(block_offset(block), &mut header)
+            .map_err(Error::Flash)?;
+        Ok(())
+    })?;
+
+    let magic = u32::from_le_bytes(header[0..4].try_into().unwrap());
+    if magic != MAGIC {
+        return Ok(None);
+    }
+    let payload_len = u16::from_le_bytes(header[8..10].try_into().unwrap()) as usize;
+    if payload_len > MAX_PAYLOAD_SIZE {
+        return Err(Error::StorageCorrupted);
+    }
+    Ok(Some(payload_len))
+}
+
 fn clear_block(manager: &'static FlashManager, block: u32) -> Result<()> {
     let offset = block_offset(block);
     manager.with_flash(|flash| {
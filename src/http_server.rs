@@ -0,0 +1,412 @@
+//! A device abstraction for serving HTTP/1.1 requests over WiFi.
+//!
+//! [`run_http_server`] accepts connections on a network stack (typically from
+//! [`WifiAuto`](crate::wifi_auto::WifiAuto) in client mode) and passes each [`Request`] to a
+//! [`Handler`], which answers through a [`Response`]. Requests carry their query and body;
+//! [`Request::form`], [`Request::json`], and [`Request::route`] read form fields, JSON
+//! members, and `{name}` path segments. Each connection serves one request, then closes.
+//!
+//! The WiFi captive portal is built on the same server.
+//!
+//! # Example
+//!
+//! A small REST API: `GET /clock` reads the time and `POST /text` (with a JSON or form
+//! `text` field) shows text on a 4-digit display.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use core::fmt::Write as _;
+//!
+//! use device_kit::clock::Clock;
+//! use device_kit::http_server::{Handler, Method, Request, Response, Status, run_http_server};
+//! use device_kit::led4::{BlinkState, Led4};
+//! use embedded_io_async::Write;
+//!
+//! struct Api {
+//!     clock: &'static Clock,
+//!     led4: &'static Led4<'static>,
+//! }
+//!
+//! impl Handler for Api {
+//!     async fn handle<W: Write>(
+//!         &self,
+//!         request: &Request<'_>,
+//!         response: &mut Response<'_, W>,
+//!     ) -> device_kit::Result<()> {
+//!         if request.route(Method::Get, "/clock").is_some() {
+//!             let now = self.clock.now_local();
+//!             let mut body = heapless::String::<64>::new();
+//!             write!(body, r#"{{"hour":{},"minute":{}}}"#, now.hour(), now.minute())
+//!                 .map_err(|_| device_kit::Error::FormatError)?;
+//!             response.send(Status::Ok, "application/json", body.as_bytes()).await;
+//!         } else if request.route(Method::Post, "/text").is_some() {
+//!             let text = match request.json() {
+//!                 Ok(json) => json.get("text").and_then(|text| text.as_str()?.decode::<4>().ok()),
+//!                 Err(_) => request.form().get("text").and_then(|text| text.decode::<4>().ok()),
+//!             };
+//!             let Some(text) = text else {
+//!                 response.send(Status::BadRequest, "text/plain", b"need 1-4 characters").await;
+//!                 return Ok(());
+//!             };
+//!             let mut chars = [' '; 4];
+//!             for (cell, character) in chars.iter_mut().zip(text.chars()) {
+//!                 *cell = character;
+//!             }
+//!             self.led4.write_text(chars, BlinkState::Solid);
+//!             response.send(Status::NoContent, "text/plain", b"").await;
+//!         }
+//!         // Anything else gets 404 Not Found.
+//!         Ok(())
+//!     }
+//! }
+//!
+//! # #[allow(dead_code)]
+//! async fn serve_api(
+//!     stack: &'static embassy_net::Stack<'static>,
+//!     clock: &'static Clock,
+//!     led4: &'static Led4<'static>,
+//! ) -> ! {
+//!     run_http_server(stack, 80, &Api { clock, led4 }).await
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+#![allow(clippy::future_not_send, reason = "single-threaded")]
+
+pub mod json;
+mod request;
+
+use core::fmt::Write as _;
+use core::str::FromStr;
+
+use derive_more::derive::Display;
+use embedded_io_async::Write;
+use heapless::String;
+
+use crate::Result;
+
+pub use request::{Decoded, Params, PathParams, Request};
+
+/// An HTTP request method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Method {
+    Get,
+    Head,
+    Post,
+    Put,
+    Patch,
+    Delete,
+    Options,
+}
+
+impl FromStr for Method {
+    type Err = HttpError;
+
+    fn from_str(method: &str) -> core::result::Result<Self, HttpError> {
+        match method {
+            "GET" => Ok(Self::Get),
+            "HEAD" => Ok(Self::Head),
+            "POST" => Ok(Self::Post),
+            "PUT" => Ok(Self::Put),
+            "PATCH" => Ok(Self::Patch),
+            "DELETE" => Ok(Self::Delete),
+            "OPTIONS" => Ok(Self::Options),
+            _ => Err(HttpError::Unsupported),
+        }
+    }
+}
+
+/// Longest request, counting its line, headers, and body, that [`Request::parse`] accepts
+/// and a connection buffers.
+pub const MAX_REQUEST_LEN: usize = 2048;
+
+/// An HTTP response status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum Status {
+    Ok,
+    Created,
+    NoContent,
+    SeeOther,
+    BadRequest,
+    Forbidden,
+    NotFound,
+    MethodNotAllowed,
+    PayloadTooLarge,
+    TooManyRequests,
+    InternalServerError,
+    NotImplemented,
+}
+
+impl Status {
+    /// The numeric status code, such as 404.
+    #[must_use]
+    pub const fn code(self) -> u16 {
+        match self {
+            Self::Ok => 200,
+            Self::Created => 201,
+            Self::NoContent => 204,
+            Self::SeeOther => 303,
+            Self::BadRequest => 400,
+            Self::Forbidden => 403,
+            Self::NotFound => 404,
+            Self::MethodNotAllowed => 405,
+            Self::PayloadTooLarge => 413,
+            Self::TooManyRequests => 429,
+            Self::InternalServerError => 500,
+            Self::NotImplemented => 501,
+        }
+    }
+
+    /// The standard reason phrase, such as `Not Found`.
+    #[must_use]
+    pub const fn reason(self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Created => "Created",
+            Self::NoContent => "No Content",
+            Self::SeeOther => "See Other",
+            Self::BadRequest => "Bad Request",
+            Self::Forbidden => "Forbidden",
+            Self::NotFound => "Not Found",
+            Self::MethodNotAllowed => "Method Not Allowed",
+            Self::PayloadTooLarge => "Payload Too Large",
+            Self::TooManyRequests => "Too Many Requests",
+            Self::InternalServerError => "Internal Server Error",
+            Self::NotImplemented => "Not Implemented",
+        }
+    }
+}
+
+/// Why a request couldn't be read.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum HttpError {
+    #[display("malformed request")]
+    Malformed,
+    #[display("request or value too large")]
+    TooLarge,
+    #[display("unsupported method or transfer encoding")]
+    Unsupported,
+}
+
+impl HttpError {
+    /// The status to answer with.
+    #[must_use]
+    pub const fn status(self) -> Status {
+        match self {
+            Self::Malformed => Status::BadRequest,
+            Self::TooLarge => Status::PayloadTooLarge,
+            Self::Unsupported => Status::NotImplemented,
+        }
+    }
+}
+
+/// Answers HTTP requests. See the [module docs](self) for an example.
+pub trait Handler {
+    /// Answer `request` by calling [`Response::send`]. A request left unanswered gets
+    /// 404 Not Found; one whose handler returns an error gets 500 Internal Server Error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request couldn't be handled.
+    async fn handle<W: Write>(
+        &self,
+        request: &Request<'_>,
+        response: &mut Response<'_, W>,
+    ) -> Result<()>;
+}
+
+/// The answer to one request, written to the connection.
+pub struct Response<'a, W> {
+    writer: &'a mut W,
+    status: Option<Status>,
+    // Answering a HEAD request: send the headers, including `Content-Length`, but no body.
+    head_only: bool,
+}
+
+impl<'a, W: Write> Response<'a, W> {
+    /// A response written to `writer`.
+    pub const fn new(writer: &'a mut W) -> Self {
+        Self {
+            writer,
+            status: None,
+            head_only: false,
+        }
+    }
+
+    /// Send the status, headers, and `body`. Only the first call for a request sends
+    /// anything, and the body is left out when answering a HEAD request.
+    pub async fn send(&mut self, status: Status, content_type: &str, body: &[u8]) {
+        self.send_with_location(status, content_type, None, body)
+            .await;
+    }
+
+    /// Send `303 See Other`, pointing the client at `location`.
+    pub async fn redirect(&mut self, location: &str) {
+        self.send_with_location(Status::SeeOther, "text/plain", Some(location), b"")
+            .await;
+    }
+
+    /// The status sent, if any.
+    #[must_use]
+    pub const fn status(&self) -> Option<Status> {
+        self.status
+    }
+
+    async fn send_with_location(
+        &mut self,
+        status: Status,
+        content_type: &str,
+        location: Option<&str>,
+        body: &[u8],
+    ) {
+        if self.status.is_some() {
+            return;
+        }
+        self.status = Some(status);
+        let mut head = String::<256>::new();
+        let written = write!(
+            head,
+            "HTTP/1.1 {} {}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n",
+            status.code(),
+            status.reason(),
+            body.len()
+        )
+        .and_then(|()| match location {
+            Some(location) => write!(head, "Location: {location}\r\n"),
+            None => Ok(()),
+        })
+        .and_then(|()| head.write_str("Connection: close\r\n\r\n"));
+        if written.is_err() {
+            // Only an oversized content type or location gets here.
+            self.status = Some(Status::InternalServerError);
+            head.clear();
+            head.push_str(
+                "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            )
+            .ok();
+            self.writer.write_all(head.as_bytes()).await.ok();
+            return;
+        }
+        // The connection closes after the response, so a failed write has no one to tell.
+        if self.writer.write_all(head.as_bytes()).await.is_ok() && !self.head_only {
+            self.writer.write_all(body).await.ok();
+        }
+        self.writer.flush().await.ok();
+    }
+}
+
+/// Parse `bytes` as one request, pass it to `handler`, and write the response to `writer`.
+/// Answers with an error status if the request is malformed or incomplete; `bytes` should
+/// hold everything received, so an incomplete request is one too large to buffer.
+///
+/// [`run_http_server`] calls this for each connection; it is public for serving other
+/// transports and for testing handlers.
+pub async fn respond<W: Write>(handler: &impl Handler, bytes: &[u8], writer: &mut W) {
+    let mut response = Response::new(writer);
+    let status = match Request::parse(bytes) {
+        Ok(Some(request)) => {
+            response.head_only = request.method == Method::Head;
+            if handler.handle(&request, &mut response).await.is_ok() {
+                Status::NotFound
+            } else {
+                Status::InternalServerError
+            }
+        }
+        Ok(None) => HttpError::TooLarge.status(),
+        Err(err) => err.status(),
+    };
+    // Does nothing if the handler already answered.
+    response
+        .send(status, "text/plain", status.reason().as_bytes())
+        .await;
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+mod wifi_impl {
+    use defmt::{info, warn};
+    use embassy_futures::select::{Either, select};
+    use embassy_net::Stack;
+    use embassy_net::tcp::TcpSocket;
+    use embassy_time::{Duration, Timer};
+
+    use super::{Handler, MAX_REQUEST_LEN, Request, respond};
+
+    /// Connections served at once. Browsers often open a second one alongside the first.
+    const CONNECTION_COUNT: usize = 2;
+    const RX_BUFFER_LEN: usize = 1024;
+    const TX_BUFFER_LEN: usize = 2048;
+    /// How long a quiet client may hold a connection.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Serve HTTP on `port` of `stack`, passing each request to `handler`. Runs forever, so
+    /// run it in a task of its own or alongside other work with `select`. See the
+    /// [module docs](super) for an example.
+    pub async fn run_http_server(
+        stack: &'static Stack<'static>,
+        port: u16,
+        handler: &impl Handler,
+    ) -> ! {
+        info!("HTTP server listening on port {}", port);
+        const _: () = assert!(CONNECTION_COUNT == 2, "update the select below");
+        match select(
+            serve_connections(stack, port, handler),
+            serve_connections(stack, port, handler),
+        )
+        .await
+        {
+            Either::First(never) | Either::Second(never) => never,
+        }
+    }
+
+    /// Accept connections one at a time, answering one request on each.
+    async fn serve_connections(
+        stack: &'static Stack<'static>,
+        port: u16,
+        handler: &impl Handler,
+    ) -> ! {
+        let mut rx_buffer = [0; RX_BUFFER_LEN];
+        let mut tx_buffer = [0; TX_BUFFER_LEN];
+        let mut request = [0; MAX_REQUEST_LEN];
+        loop {
+            let mut socket = TcpSocket::new(*stack, &mut rx_buffer, &mut tx_buffer);
+            socket.set_timeout(Some(TIMEOUT));
+            if let Err(err) = socket.accept(port).await {
+                warn!("HTTP accept error: {:?}", err);
+                Timer::after_millis(500).await;
+                continue;
+            }
+
+            if let Some(len) = read_request(&mut socket, &mut request).await {
+                respond(handler, &request[..len], &mut socket).await;
+            }
+            socket.close();
+            let _ = socket.flush().await;
+        }
+    }
+
+    /// Read until `buffer` holds a whole request or is full. Returns the length read, or
+    /// `None` if the client went away first.
+    async fn read_request(socket: &mut TcpSocket<'_>, buffer: &mut [u8]) -> Option<usize> {
+        let mut len = 0;
+        loop {
+            // A malformed request is complete as far as reading goes.
+            if !matches!(Request::parse(&buffer[..len]), Ok(None)) || len == buffer.len() {
+                return Some(len);
+            }
+            match socket.read(&mut buffer[len..]).await {
+                Ok(0) => return None,
+                Ok(read_len) => len += read_len,
+                Err(err) => {
+                    warn!("HTTP read error: {:?}", err);
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+pub use wifi_impl::run_http_server;
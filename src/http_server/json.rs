@@ -0,0 +1,515 @@
+//! Reading and writing JSON without allocating.
+//!
+//! [`Object::parse`] checks that the whole text is well-formed JSON, then [`Object::get`] finds
+//! members by key. Values borrow from the text; strings are unescaped only when
+//! [`JsonStr::decode`] is called. [`ObjectWriter`] writes an object into any buffer, for
+//! response bodies and MQTT payloads alike.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::http_server::json::Object;
+//!
+//! fn read_light() -> Option<(u8, bool)> {
+//!     let body = r#"{"brightness": 128, "on": true, "name": "porch"}"#;
+//!     let object = Object::parse(body).ok()?;
+//!     let brightness = object.get("brightness")?.as_u8()?;
+//!     let on = object.get("on")?.as_bool()?;
+//!     Some((brightness, on))
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use core::fmt::{self, Write as _};
+
+use heapless::String;
+
+use super::HttpError;
+
+/// Deepest nesting of arrays and objects accepted, to bound stack use.
+const MAX_DEPTH: usize = 16;
+
+/// A JSON object.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Object<'a>(&'a str);
+
+impl<'a> Object<'a> {
+    /// Check that `text` is a single JSON object, with optional surrounding whitespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` isn't well-formed or nests deeper than 16 levels.
+    pub fn parse(text: &'a str) -> Result<Self, HttpError> {
+        let mut scanner = Scanner::new(text);
+        scanner.skip_whitespace();
+        let Value::Object(object) = scanner.value(0)? else {
+            return Err(HttpError::Malformed);
+        };
+        scanner.skip_whitespace();
+        if scanner.rest().is_empty() {
+            Ok(object)
+        } else {
+            Err(HttpError::Malformed)
+        }
+    }
+
+    /// The value of the first member named `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value<'a>> {
+        self.members()
+            .find(|(name, _value)| name.chars().eq(key.chars().map(Ok)))
+            .map(|(_name, value)| value)
+    }
+
+    /// Every member, in order.
+    pub fn members(&self) -> impl Iterator<Item = (JsonStr<'a>, Value<'a>)> {
+        // The text was checked when the object was made, so scanning can't fail here.
+        let mut scanner = Scanner::new(self.0);
+        scanner.expect(b'{').ok();
+        core::iter::from_fn(move || {
+            scanner.skip_whitespace();
+            if scanner.eat(b'}') {
+                return None;
+            }
+            scanner.eat(b',');
+            scanner.skip_whitespace();
+            let name = scanner.string().ok()?;
+            scanner.skip_whitespace();
+            scanner.expect(b':').ok()?;
+            scanner.skip_whitespace();
+            let value = scanner.value(1).ok()?;
+            Some((name, value))
+        })
+    }
+}
+
+/// A JSON value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Value<'a> {
+    /// `null`.
+    Null,
+    /// `true` or `false`.
+    Bool(bool),
+    /// A number, as written.
+    Number(&'a str),
+    /// A string.
+    String(JsonStr<'a>),
+    /// An array, as written.
+    Array(&'a str),
+    /// An object.
+    Object(Object<'a>),
+}
+
+impl<'a> Value<'a> {
+    /// The boolean, if this is one.
+    #[must_use]
+    pub const fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The number, if this is an integer that fits in an `i32`.
+    #[must_use]
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            Self::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The number, if this is an integer from 0 to 255.
+    #[must_use]
+    pub fn as_u8(&self) -> Option<u8> {
+        match self {
+            Self::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The number, if this is one.
+    #[must_use]
+    pub fn as_f32(&self) -> Option<f32> {
+        match self {
+            Self::Number(number) => number.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The string, if this is one.
+    #[must_use]
+    pub const fn as_str(&self) -> Option<JsonStr<'a>> {
+        match self {
+            Self::String(string) => Some(*string),
+            _ => None,
+        }
+    }
+
+    /// The object, if this is one.
+    #[must_use]
+    pub const fn as_object(&self) -> Option<Object<'a>> {
+        match self {
+            Self::Object(object) => Some(*object),
+            _ => None,
+        }
+    }
+}
+
+/// A JSON string, still escaped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct JsonStr<'a>(&'a str);
+
+impl<'a> JsonStr<'a> {
+    /// The string as written, without its quotes and still escaped.
+    #[must_use]
+    pub const fn raw(&self) -> &'a str {
+        self.0
+    }
+
+    /// The string with escapes decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the decoded string is longer than `N` bytes or has an invalid
+    /// escape.
+    pub fn decode<const N: usize>(&self) -> Result<String<N>, HttpError> {
+        let mut decoded = String::new();
+        for character in self.chars() {
+            decoded.push(character?).map_err(|_| HttpError::TooLarge)?;
+        }
+        Ok(decoded)
+    }
+
+    /// The decoded characters.
+    fn chars(&self) -> impl Iterator<Item = Result<char, HttpError>> + '_ {
+        let mut chars = self.0.chars();
+        core::iter::from_fn(move || {
+            let character = chars.next()?;
+            if character != '\\' {
+                return Some(Ok(character));
+            }
+            Some(match chars.next() {
+                Some('"') => Ok('"'),
+                Some('\\') => Ok('\\'),
+                Some('/') => Ok('/'),
+                Some('b') => Ok('\u{8}'),
+                Some('f') => Ok('\u{c}'),
+                Some('n') => Ok('\n'),
+                Some('r') => Ok('\r'),
+                Some('t') => Ok('\t'),
+                Some('u') => unicode_escape(&mut chars),
+                _ => Err(HttpError::Malformed),
+            })
+        })
+    }
+}
+
+/// Decode the rest of a `\u` escape, including the second half of a surrogate pair.
+fn unicode_escape(chars: &mut core::str::Chars<'_>) -> Result<char, HttpError> {
+    let first = hex4(chars)?;
+    let code = if (0xD800..0xDC00).contains(&first) {
+        let (Some('\\'), Some('u')) = (chars.next(), chars.next()) else {
+            return Err(HttpError::Malformed);
+        };
+        let second = hex4(chars)?;
+        if !(0xDC00..0xE000).contains(&second) {
+            return Err(HttpError::Malformed);
+        }
+        0x10000 + ((first - 0xD800) << 10) + (second - 0xDC00)
+    } else {
+        first
+    };
+    char::from_u32(code).ok_or(HttpError::Malformed)
+}
+
+fn hex4(chars: &mut core::str::Chars<'_>) -> Result<u32, HttpError> {
+    let mut value = 0;
+    for _ in 0..4 {
+        let digit = chars
+            .next()
+            .and_then(|character| character.to_digit(16))
+            .ok_or(HttpError::Malformed)?;
+        value = (value << 4) | digit;
+    }
+    Ok(value)
+}
+
+/// Writes a JSON object to any [`fmt::Write`] one member at a time, escaping keys and
+/// strings.
+pub struct ObjectWriter<'w, W> {
+    out: &'w mut W,
+    empty: bool,
+}
+
+impl<'w, W: fmt::Write> ObjectWriter<'w, W> {
+    /// Write the opening brace. Finish with [`end`](Self::end).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn start(out: &'w mut W) -> Result<Self, fmt::Error> {
+        out.write_char('{')?;
+        Ok(Self { out, empty: true })
+    }
+
+    /// Write the closing brace.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn end(self) -> fmt::Result {
+        self.out.write_char('}')
+    }
+
+    /// Write a member whose value is the string `value` formats to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn string(&mut self, key: &str, value: fmt::Arguments<'_>) -> fmt::Result {
+        self.key(key)?;
+        write_string(self.out, value)
+    }
+
+    /// Write a member whose value is the number `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn number(&mut self, key: &str, value: impl fmt::Display) -> fmt::Result {
+        self.key(key)?;
+        write!(self.out, "{value}")
+    }
+
+    /// Write a member whose value is `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn null(&mut self, key: &str) -> fmt::Result {
+        self.key(key)?;
+        self.out.write_str("null")
+    }
+
+    /// Write a member whose value is an array of `values`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full.
+    pub fn strings(&mut self, key: &str, values: &[&str]) -> fmt::Result {
+        self.key(key)?;
+        self.out.write_char('[')?;
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                self.out.write_char(',')?;
+            }
+            write_string(self.out, format_args!("{value}"))?;
+        }
+        self.out.write_char(']')
+    }
+
+    /// Write a member whose value is an object, filled in by `members`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `out` is full or `members` fails.
+    pub fn object(
+        &mut self,
+        key: &str,
+        members: impl FnOnce(&mut ObjectWriter<'_, W>) -> fmt::Result,
+    ) -> fmt::Result {
+        self.key(key)?;
+        let mut object = ObjectWriter::start(&mut *self.out)?;
+        members(&mut object)?;
+        object.end()
+    }
+
+    /// Write `"<key>":`, after a comma if needed.
+    fn key(&mut self, key: &str) -> fmt::Result {
+        if !self.empty {
+            self.out.write_char(',')?;
+        }
+        self.empty = false;
+        write_string(self.out, format_args!("{key}"))?;
+        self.out.write_char(':')
+    }
+}
+
+/// Write the string `value` formats to, quoted and escaped.
+fn write_string(out: &mut impl fmt::Write, value: fmt::Arguments<'_>) -> fmt::Result {
+    out.write_char('"')?;
+    Escaped(&mut *out).write_fmt(value)?;
+    out.write_char('"')
+}
+
+/// Escapes text written through it for use inside a JSON string.
+struct Escaped<'a, W>(&'a mut W);
+
+impl<W: fmt::Write> fmt::Write for Escaped<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        for character in text.chars() {
+            match character {
+                '"' => self.0.write_str("\\\"")?,
+                '\\' => self.0.write_str("\\\\")?,
+                control if u32::from(control) < 0x20 => {
+                    write!(self.0, "\\u{:04x}", u32::from(control))?;
+                }
+                _ => self.0.write_char(character)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Walks JSON text one value at a time.
+struct Scanner<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    const fn new(text: &'a str) -> Self {
+        Self { text, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.position..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.rest().bytes().next()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), HttpError> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(HttpError::Malformed)
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.position += 1;
+        }
+    }
+
+    /// Scan the value at the current position, `depth` levels deep.
+    fn value(&mut self, depth: usize) -> Result<Value<'a>, HttpError> {
+        let start = self.position;
+        match self.peek().ok_or(HttpError::Malformed)? {
+            b'{' => {
+                self.container(depth, b'}', true)?;
+                Ok(Value::Object(Object(&self.text[start..self.position])))
+            }
+            b'[' => {
+                self.container(depth, b']', false)?;
+                Ok(Value::Array(&self.text[start..self.position]))
+            }
+            b'"' => Ok(Value::String(self.string()?)),
+            b't' => self.literal("true", Value::Bool(true)),
+            b'f' => self.literal("false", Value::Bool(false)),
+            b'n' => self.literal("null", Value::Null),
+            _ => self.number(),
+        }
+    }
+
+    /// Scan an object (with `keyed` members) or an array, through its `close` bracket.
+    fn container(&mut self, depth: usize, close: u8, keyed: bool) -> Result<(), HttpError> {
+        if depth >= MAX_DEPTH {
+            return Err(HttpError::Malformed);
+        }
+        self.position += 1;
+        self.skip_whitespace();
+        if self.eat(close) {
+            return Ok(());
+        }
+        loop {
+            self.skip_whitespace();
+            if keyed {
+                self.string()?;
+                self.skip_whitespace();
+                self.expect(b':')?;
+                self.skip_whitespace();
+            }
+            self.value(depth + 1)?;
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(());
+            }
+            self.expect(b',')?;
+        }
+    }
+
+    /// Scan a string, checking its escapes.
+    fn string(&mut self) -> Result<JsonStr<'a>, HttpError> {
+        self.expect(b'"')?;
+        let start = self.position;
+        let mut escaped = false;
+        for (offset, byte) in self.rest().bytes().enumerate() {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => {
+                    let string = JsonStr(&self.text[start..start + offset]);
+                    self.position = start + offset + 1;
+                    return string
+                        .chars()
+                        .try_for_each(|character| character.map(|_| ()))
+                        .map(|()| string);
+                }
+                0..0x20 => return Err(HttpError::Malformed),
+                _ => {}
+            }
+        }
+        Err(HttpError::Malformed)
+    }
+
+    fn literal(&mut self, word: &str, value: Value<'a>) -> Result<Value<'a>, HttpError> {
+        if self.rest().starts_with(word) {
+            self.position += word.len();
+            Ok(value)
+        } else {
+            Err(HttpError::Malformed)
+        }
+    }
+
+    /// Scan a number: `-`, then digits without leading zeros, then an optional fraction and
+    /// exponent.
+    fn number(&mut self) -> Result<Value<'a>, HttpError> {
+        let start = self.position;
+        self.eat(b'-');
+        if !self.eat(b'0') && self.digits() == 0 {
+            return Err(HttpError::Malformed);
+        }
+        if self.eat(b'.') && self.digits() == 0 {
+            return Err(HttpError::Malformed);
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'+') {
+                self.eat(b'-');
+            }
+            if self.digits() == 0 {
+                return Err(HttpError::Malformed);
+            }
+        }
+        Ok(Value::Number(&self.text[start..self.position]))
+    }
+
+    fn digits(&mut self) -> usize {
+        let count = self.rest().bytes().take_while(u8::is_ascii_digit).count();
+        self.position += count;
+        count
+    }
+}
@@ -0,0 +1,254 @@
+use core::str::FromStr;
+
+use heapless::String;
+
+use super::json::Object;
+use super::{HttpError, MAX_REQUEST_LEN, Method};
+
+/// An HTTP/1.1 request, borrowed from the bytes it was parsed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Request<'a> {
+    /// Request method.
+    pub method: Method,
+    /// Path, such as `/servo/1`, without the query.
+    pub path: &'a str,
+    /// Parameters after `?` in the request target.
+    pub query: Params<'a>,
+    /// Body, exactly `Content-Length` bytes long.
+    pub body: &'a [u8],
+    headers: &'a str,
+}
+
+impl<'a> Request<'a> {
+    /// Parse the request at the start of `bytes`, or return `None` if it hasn't fully arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request is malformed, longer than [`MAX_REQUEST_LEN`], or
+    /// uses an unsupported method or transfer encoding.
+    pub fn parse(bytes: &'a [u8]) -> Result<Option<Self>, HttpError> {
+        let Some(head_len) = bytes.windows(4).position(|window| window == b"\r\n\r\n") else {
+            return Ok(None);
+        };
+        let head = core::str::from_utf8(&bytes[..head_len]).map_err(|_| HttpError::Malformed)?;
+        let (request_line, headers) = head.split_once("\r\n").unwrap_or((head, ""));
+
+        let mut parts = request_line.split(' ');
+        let (Some(method), Some(target), Some(version), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(HttpError::Malformed);
+        };
+        if !version.starts_with("HTTP/1.") || !target.starts_with('/') {
+            return Err(HttpError::Malformed);
+        }
+        let method = Method::from_str(method)?;
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        let mut request = Self {
+            method,
+            path,
+            query: Params(query),
+            body: &[],
+            headers,
+        };
+        if request.headers().any(|header| header.is_none()) {
+            return Err(HttpError::Malformed);
+        }
+        if request.header("transfer-encoding").is_some() {
+            return Err(HttpError::Unsupported);
+        }
+        let body_len = match request.header("content-length") {
+            Some(len) => len.parse().map_err(|_| HttpError::Malformed)?,
+            None => 0,
+        };
+        let body_start = head_len.checked_add(4).ok_or(HttpError::TooLarge)?;
+        let body_end = body_start
+            .checked_add(body_len)
+            .ok_or(HttpError::TooLarge)?;
+        // More than a connection can buffer will never fully arrive.
+        if body_end > MAX_REQUEST_LEN {
+            return Err(HttpError::TooLarge);
+        }
+        let Some(body) = bytes.get(body_start..body_end) else {
+            return Ok(None);
+        };
+        request.body = body;
+        Ok(Some(request))
+    }
+
+    /// The value of header `name`, matched case-insensitively.
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&'a str> {
+        self.headers()
+            .flatten()
+            .find(|(header, _value)| header.eq_ignore_ascii_case(name))
+            .map(|(_header, value)| value)
+    }
+
+    /// The body as URL-encoded form fields, as sent by an HTML `<form method="POST">`.
+    /// Empty if the body isn't text.
+    #[must_use]
+    pub fn form(&self) -> Params<'a> {
+        Params(core::str::from_utf8(self.body).unwrap_or(""))
+    }
+
+    /// The body as a JSON object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body isn't a JSON object.
+    pub fn json(&self) -> Result<Object<'a>, HttpError> {
+        let text = core::str::from_utf8(self.body).map_err(|_| HttpError::Malformed)?;
+        Object::parse(text)
+    }
+
+    /// Match the request against `method` and a path `pattern` such as `/servo/{id}`, where
+    /// each `{name}` segment matches any one path segment.
+    #[must_use]
+    pub fn route(&self, method: Method, pattern: &'static str) -> Option<PathParams<'a>> {
+        if self.method != method {
+            return None;
+        }
+        let mut path_segments = self.path.split('/');
+        for pattern_segment in pattern.split('/') {
+            let path_segment = path_segments.next()?;
+            let matches = if is_capture(pattern_segment) {
+                !path_segment.is_empty()
+            } else {
+                path_segment == pattern_segment
+            };
+            if !matches {
+                return None;
+            }
+        }
+        path_segments.next().is_none().then_some(PathParams {
+            pattern,
+            path: self.path,
+        })
+    }
+
+    /// Each header line as `Some((name, value))`, or `None` for a line without a colon.
+    fn headers(&self) -> impl Iterator<Item = Option<(&'a str, &'a str)>> {
+        self.headers
+            .split("\r\n")
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                line.split_once(':')
+                    .map(|(name, value)| (name.trim(), value.trim()))
+            })
+    }
+}
+
+/// URL-encoded `key=value` pairs, from a query string or form body.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Params<'a>(&'a str);
+
+impl<'a> Params<'a> {
+    /// The first value for `key`.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Decoded<'a>> {
+        self.iter()
+            .find(|(name, _value)| name.bytes().eq(key.bytes()))
+            .map(|(_name, value)| value)
+    }
+
+    /// Every pair, in order. A key without `=` has an empty value.
+    pub fn iter(&self) -> impl Iterator<Item = (Decoded<'a>, Decoded<'a>)> {
+        self.0
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (Decoded(name), Decoded(value))
+            })
+    }
+}
+
+/// `{name}` values captured by [`Request::route`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PathParams<'a> {
+    pattern: &'static str,
+    path: &'a str,
+}
+
+impl<'a> PathParams<'a> {
+    /// The segment matched by `{name}`.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Decoded<'a>> {
+        self.pattern
+            .split('/')
+            .zip(self.path.split('/'))
+            .find(|(pattern_segment, _)| {
+                is_capture(pattern_segment)
+                    && pattern_segment[1..pattern_segment.len() - 1] == *name
+            })
+            .map(|(_, path_segment)| Decoded(path_segment))
+    }
+}
+
+fn is_capture(pattern_segment: &str) -> bool {
+    pattern_segment.len() > 2 && pattern_segment.starts_with('{') && pattern_segment.ends_with('}')
+}
+
+/// A URL-encoded value, decoded on request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decoded<'a>(&'a str);
+
+impl<'a> Decoded<'a> {
+    /// The value as sent, still encoded.
+    #[must_use]
+    pub const fn raw(&self) -> &'a str {
+        self.0
+    }
+
+    /// The value with `+` and `%XX` escapes decoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the decoded value is longer than `N` bytes or isn't UTF-8.
+    pub fn decode<const N: usize>(&self) -> Result<String<N>, HttpError> {
+        let mut bytes = heapless::Vec::<u8, N>::new();
+        for byte in self.bytes() {
+            bytes.push(byte).map_err(|_| HttpError::TooLarge)?;
+        }
+        String::from_utf8(bytes).map_err(|_| HttpError::Malformed)
+    }
+
+    /// The decoded value parsed as `T`, such as a number or `bool`.
+    #[must_use]
+    pub fn parse<T: FromStr>(&self) -> Option<T> {
+        self.decode::<32>().ok()?.parse().ok()
+    }
+
+    /// The decoded bytes. A `%` not followed by two hex digits stands for itself.
+    fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        let mut bytes = self.0.bytes();
+        core::iter::from_fn(move || {
+            let byte = bytes.next()?;
+            Some(match byte {
+                b'+' => b' ',
+                b'%' => {
+                    let mut lookahead = bytes.clone();
+                    match (
+                        lookahead.next().and_then(hex),
+                        lookahead.next().and_then(hex),
+                    ) {
+                        (Some(high), Some(low)) => {
+                            bytes = lookahead;
+                            (high << 4) | low
+                        }
+                        _ => b'%',
+                    }
+                }
+                _ => byte,
+            })
+        })
+    }
+}
+
+fn hex(digit: u8) -> Option<u8> {
+    char::from(digit)
+        .to_digit(16)
+        .and_then(|value| u8::try_from(value).ok())
+}
@@ -0,0 +1,432 @@
+//! A device abstraction for key matrices (such as 4x4 membrane keypads) and sets of
+//! direct buttons, with debouncing, n-key rollover, and chord detection.
+//!
+//! See [`Keypad`] for usage examples.
+
+use defmt::{info, warn};
+use embassy_executor::Spawner;
+use embassy_rp::Peri;
+use embassy_rp::gpio::{AnyPin, Flex, Input, Pull};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel as EmbassyChannel;
+use embassy_time::{Duration, Instant, Timer};
+use heapless::Vec;
+use portable_atomic::{AtomicU32, Ordering};
+
+use crate::button::PressedTo;
+use crate::{Error, Result};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Maximum number of matrix rows.
+pub const MAX_KEYPAD_ROWS: usize = 8;
+
+/// Maximum number of matrix columns.
+pub const MAX_KEYPAD_COLS: usize = 8;
+
+/// Maximum number of direct buttons.
+pub const MAX_DIRECT_KEYS: usize = 16;
+
+/// Maximum number of chords a keypad watches for.
+pub const MAX_CHORDS: usize = 8;
+
+/// Number of events buffered for [`Keypad::wait_for_event`] before new ones are dropped.
+const EVENT_QUEUE_LEN: usize = 16;
+
+/// Time between scans of the whole keypad.
+const SCAN_INTERVAL: Duration = Duration::from_millis(2);
+
+/// Consecutive identical scans needed before a key changes state (~10 ms).
+const DEBOUNCE_SCANS: usize = 5;
+
+/// Settling time after driving a matrix row low, before reading the columns. Also lets the
+/// previous row's columns pull back up after it was released.
+const ROW_SETTLE: Duration = Duration::from_micros(10);
+
+// ============================================================================
+// Public API
+// ============================================================================
+
+/// Events reported by a [`Keypad`].
+///
+/// Keys are numbered row by row: `key = row * columns + column`. Direct buttons are numbered
+/// in the order their pins were given.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, defmt::Format)]
+pub enum KeyEvent {
+    /// A key was pressed (after debouncing).
+    Down { key: u8 },
+    /// A key was released (after debouncing).
+    Up { key: u8 },
+    /// Every key of the chord at `index` (in the slice given to the constructor) has been
+    /// held together for the chord's hold time. Fires once per hold.
+    Chord { index: u8 },
+}
+
+/// A set of keys that trigger [`KeyEvent::Chord`] when held down together.
+///
+/// See [`Keypad`] for usage examples.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, defmt::Format)]
+pub struct Chord {
+    keys: u64,
+    hold: Duration,
+}
+
+impl Chord {
+    /// A chord of `keys` that fires after they have all been held for `hold`.
+    #[must_use]
+    pub const fn new(keys: &[u8], hold: Duration) -> Self {
+        assert!(!keys.is_empty(), "a chord needs at least one key");
+        let mut mask = 0u64;
+        let mut index = 0;
+        while index < keys.len() {
+            assert!(keys[index] < 64, "key index out of range");
+            mask |= 1 << keys[index];
+            index += 1;
+        }
+        Self { keys: mask, hold }
+    }
+}
+
+/// Static resources for the [`Keypad`] device abstraction.
+///
+/// See [`Keypad`] for usage examples.
+pub struct KeypadStatic {
+    events: EmbassyChannel<CriticalSectionRawMutex, KeyEvent, EVENT_QUEUE_LEN>,
+    dropped_events: AtomicU32,
+}
+
+impl KeypadStatic {
+    /// Create static resources for a [`Keypad`]; usually via [`Keypad::new_static`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            events: EmbassyChannel::new(),
+            dropped_events: AtomicU32::new(0),
+        }
+    }
+
+    /// Queue an event without blocking the scan; if the queue is full, drop and count it.
+    fn send(&self, event: KeyEvent) {
+        if self.events.try_send(event).is_err() {
+            warn!("Keypad event queue full; dropping {:?}", event);
+            self.dropped_events.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    async fn receive(&self) -> KeyEvent {
+        self.events.receive().await
+    }
+}
+
+/// A device abstraction for a key matrix or a set of direct buttons.
+///
+/// A background task scans every key every 2 ms, debounces each key independently, and
+/// reports [`KeyEvent::Down`]/[`KeyEvent::Up`] for every key that changes—any number of
+/// keys may be down at once (n-key rollover). Matrices need a diode per key for rollover
+/// beyond two keys; without diodes, three keys on the corners of a rectangle make the fourth
+/// appear pressed.
+///
+/// # Matrix wiring
+///
+/// Rows are driven low one at a time while the others float, so two keys pressed in one
+/// column never connect a driven-high row to a driven-low one. Columns use internal pull-ups
+/// and read low when a key connects them to the active row.
+///
+/// # Examples
+///
+/// ## 4x4 membrane keypad
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::keypad::{Chord, KeyEvent, Keypad, KeypadStatic};
+/// use embassy_time::Duration;
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+///
+/// const LABELS: [char; 16] = [
+///     '1', '2', '3', 'A', //
+///     '4', '5', '6', 'B', //
+///     '7', '8', '9', 'C', //
+///     '*', '0', '#', 'D', //
+/// ];
+///
+/// async fn example(
+///     p: embassy_rp::Peripherals,
+///     spawner: embassy_executor::Spawner,
+/// ) -> device_kit::Result<()> {
+///     static KEYPAD_STATIC: KeypadStatic = Keypad::new_static();
+///     // '*' + '#' held for one second
+///     let chords = [Chord::new(&[12, 14], Duration::from_secs(1))];
+///     let keypad = Keypad::new_matrix(
+///         &KEYPAD_STATIC,
+///         [p.PIN_2.into(), p.PIN_3.into(), p.PIN_4.into(), p.PIN_5.into()], // rows
+///         [p.PIN_6.into(), p.PIN_7.into(), p.PIN_8.into(), p.PIN_9.into()], // columns
+///         &chords,
+///         spawner,
+///     )?;
+///
+///     loop {
+///         match keypad.wait_for_event().await {
+///             KeyEvent::Down { key } => defmt::info!("pressed {}", LABELS[usize::from(key)]),
+///             KeyEvent::Up { .. } => {}
+///             KeyEvent::Chord { .. } => defmt::info!("*# held"),
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## Two direct buttons: both held = factory reset
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::button::PressedTo;
+/// use device_kit::keypad::{Chord, KeyEvent, Keypad, KeypadStatic};
+/// use embassy_time::Duration;
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+///
+/// async fn example(
+///     p: embassy_rp::Peripherals,
+///     spawner: embassy_executor::Spawner,
+/// ) -> device_kit::Result<()> {
+///     static BUTTONS_STATIC: KeypadStatic = Keypad::new_static();
+///     let chords = [Chord::new(&[0, 1], Duration::from_secs(5))];
+///     let buttons = Keypad::new_direct(
+///         &BUTTONS_STATIC,
+///         [p.PIN_13.into(), p.PIN_14.into()],
+///         PressedTo::Ground,
+///         &chords,
+///         spawner,
+///     )?;
+///
+///     loop {
+///         if let KeyEvent::Chord { index: 0 } = buttons.wait_for_event().await {
+///             defmt::info!("factory reset");
+///         }
+///     }
+/// }
+/// ```
+pub struct Keypad<'a> {
+    keypad_static: &'a KeypadStatic,
+}
+
+impl Keypad<'_> {
+    /// Create static channel resources for keypad events.
+    ///
+    /// See [`Keypad`] for usage examples.
+    #[must_use]
+    pub const fn new_static() -> KeypadStatic {
+        KeypadStatic::new()
+    }
+
+    /// Scan an `R`×`C` key matrix.
+    ///
+    /// See [`Keypad`] for usage examples.
+    ///
+    /// # Errors
+    /// Returns an error if the background task cannot be spawned.
+    pub fn new_matrix<const R: usize, const C: usize>(
+        keypad_static: &'static KeypadStatic,
+        rows: [Peri<'static, AnyPin>; R],
+        cols: [Peri<'static, AnyPin>; C],
+        chords: &[Chord],
+        spawner: Spawner,
+    ) -> Result<Self> {
+        assert!(R <= MAX_KEYPAD_ROWS, "too many keypad rows");
+        assert!(C <= MAX_KEYPAD_COLS, "too many keypad columns");
+        let mut row_pins = Vec::new();
+        for row in rows {
+            // Idle floating; a row is selected by driving it low.
+            let mut row = Flex::new(row);
+            row.set_pull(Pull::None);
+            row.set_low();
+            row.set_as_input();
+            row_pins.push(row).ok().expect("rows fit");
+        }
+        let mut col_inputs = Vec::new();
+        for col in cols {
+            col_inputs
+                .push(Input::new(col, Pull::Up))
+                .ok()
+                .expect("columns fit");
+        }
+        let source = KeySource::Matrix {
+            rows: row_pins,
+            cols: col_inputs,
+        };
+        Self::spawn(keypad_static, source, chords, spawner)
+    }
+
+    /// Watch `N` direct buttons, all wired the same way.
+    ///
+    /// See [`Keypad`] for usage examples.
+    ///
+    /// # Errors
+    /// Returns an error if the background task cannot be spawned.
+    pub fn new_direct<const N: usize>(
+        keypad_static: &'static KeypadStatic,
+        pins: [Peri<'static, AnyPin>; N],
+        pressed_to: PressedTo,
+        chords: &[Chord],
+        spawner: Spawner,
+    ) -> Result<Self> {
+        assert!(N <= MAX_DIRECT_KEYS, "too many direct keys");
+        let pull = match pressed_to {
+            PressedTo::Voltage => Pull::Down,
+            PressedTo::Ground => Pull::Up,
+        };
+        let mut inputs = Vec::new();
+        for pin in pins {
+            inputs.push(Input::new(pin, pull)).ok().expect("keys fit");
+        }
+        let source = KeySource::Direct { inputs, pressed_to };
+        Self::spawn(keypad_static, source, chords, spawner)
+    }
+
+    fn spawn(
+        keypad_static: &'static KeypadStatic,
+        source: KeySource,
+        chords: &[Chord],
+        spawner: Spawner,
+    ) -> Result<Self> {
+        let chords = Vec::from_slice(chords).map_err(|()| Error::IndexOutOfBounds)?;
+        let token = keypad_task(source, chords, keypad_static).map_err(Error::TaskSpawn)?;
+        spawner.spawn(token);
+        Ok(Self { keypad_static })
+    }
+
+    /// Wait for the next key or chord event.
+    ///
+    /// See [`Keypad`] for usage examples.
+    pub async fn wait_for_event(&self) -> KeyEvent {
+        self.keypad_static.receive().await
+    }
+
+    /// How many events were dropped because [`wait_for_event`](Self::wait_for_event) wasn't
+    /// called in time.
+    ///
+    /// The scan never waits for the app, so that debouncing and chord timing stay accurate.
+    /// Up to 16 events are buffered.
+    #[must_use]
+    pub fn dropped_events(&self) -> u32 {
+        self.keypad_static.dropped_events.load(Ordering::Relaxed)
+    }
+}
+
+// ============================================================================
+// Scanning
+// ============================================================================
+
+enum KeySource {
+    Matrix {
+        rows: Vec<Flex<'static>, MAX_KEYPAD_ROWS>,
+        cols: Vec<Input<'static>, MAX_KEYPAD_COLS>,
+    },
+    Direct {
+        inputs: Vec<Input<'static>, MAX_DIRECT_KEYS>,
+        pressed_to: PressedTo,
+    },
+}
+
+impl KeySource {
+    /// Read every key once; bit `n` is set if key `n` reads as pressed.
+    async fn scan(&mut self) -> u64 {
+        let mut bits = 0u64;
+        match self {
+            Self::Matrix { rows, cols } => {
+                let col_count = cols.len();
+                for (row_index, row) in rows.iter_mut().enumerate() {
+                    // The output latch stays low, so enabling the driver selects the row.
+                    row.set_as_output();
+                    Timer::after(ROW_SETTLE).await;
+                    for (col_index, col) in cols.iter().enumerate() {
+                        if col.is_low() {
+                            bits |= 1 << (row_index * col_count + col_index);
+                        }
+                    }
+                    row.set_as_input();
+                }
+            }
+            Self::Direct { inputs, pressed_to } => {
+                for (index, input) in inputs.iter().enumerate() {
+                    let pressed = match pressed_to {
+                        PressedTo::Voltage => input.is_high(),
+                        PressedTo::Ground => input.is_low(),
+                    };
+                    if pressed {
+                        bits |= 1 << index;
+                    }
+                }
+            }
+        }
+        bits
+    }
+}
+
+/// Progress of one chord toward firing.
+#[derive(Clone, Copy)]
+enum ChordState {
+    Released,
+    Held { since: Instant },
+    Fired,
+}
+
+#[embassy_executor::task(pool_size = 2)]
+async fn keypad_task(
+    mut source: KeySource,
+    chords: Vec<Chord, MAX_CHORDS>,
+    keypad_static: &'static KeypadStatic,
+) -> ! {
+    let mut history = [0u64; DEBOUNCE_SCANS];
+    let mut pressed = 0u64;
+    let mut chord_states = [ChordState::Released; MAX_CHORDS];
+
+    info!("Keypad task started");
+    loop {
+        Timer::after(SCAN_INTERVAL).await;
+        history.rotate_left(1);
+        history[DEBOUNCE_SCANS - 1] = source.scan().await;
+
+        // A key changes state only once every recent scan agrees.
+        let all_down = history.iter().fold(u64::MAX, |acc, scan| acc & scan);
+        let any_down = history.iter().fold(0, |acc, scan| acc | scan);
+        let next_pressed = (pressed | all_down) & any_down;
+
+        let mut changed = pressed ^ next_pressed;
+        pressed = next_pressed;
+        while changed != 0 {
+            let key = changed.trailing_zeros() as u8;
+            changed &= changed - 1;
+            let event = if pressed & (1 << key) != 0 {
+                KeyEvent::Down { key }
+            } else {
+                KeyEvent::Up { key }
+            };
+            keypad_static.send(event);
+        }
+
+        let now = Instant::now();
+        for (index, (chord, state)) in chords.iter().zip(chord_states.iter_mut()).enumerate() {
+            if pressed & chord.keys != chord.keys {
+                *state = ChordState::Released;
+                continue;
+            }
+            let since = match *state {
+                ChordState::Fired => continue,
+                ChordState::Released => {
+                    *state = ChordState::Held { since: now };
+                    now
+                }
+                ChordState::Held { since } => since,
+            };
+            if now.duration_since(since) >= chord.hold {
+                *state = ChordState::Fired;
+                keypad_static.send(KeyEvent::Chord { index: index as u8 });
+            }
+        }
+    }
+}
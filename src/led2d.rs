@@ -7,7 +7,9 @@
 //!
 //! For custom graphics, create a [`Frame`] and use the
 //! [`embedded-graphics`](https://docs.rs/embedded-graphics) drawing API. See the
-//! [`Frame`] documentation for an example.
+//! [`Frame`] documentation for an example. To layer frames, blit sprites, or transition
+//! between frames, see [`compose`]. To show frames sent from a computer over the network,
+//! see [`frame_stream`].
 //!
 //! # Quick Start with `led2d!`
 //!
@@ -64,7 +66,8 @@
 //!   - `serpentine_column_major` - Common serpentine wiring pattern
 //!   - `LedLayout` expression - Custom LED layout value in LED-index order
 //! - `max_frames` - Maximum animation frames allowed (not buffered)
-//! - `font` - Built-in font variant (see [`Led2dFont`])
+//! - `font` - Built-in font variant (see [`Led2dFont`]); call `with_font` on the device for a
+//!   proportional [`BitmapFont`](bitmap_font::BitmapFont)
 //!
 //! ## Generated API
 //!
@@ -132,6 +135,13 @@
 //! }
 //! ```
 
+pub mod bitmap_font;
+pub mod compose;
+pub mod frame_stream;
+pub mod scroll;
+pub mod text_layout;
+pub mod video;
+
 // Re-export for macro use
 #[doc(hidden)]
 pub use paste;
@@ -145,7 +155,7 @@ use embassy_time::{Duration, Timer};
 use embedded_graphics::{
     draw_target::DrawTarget,
     mono_font::{
-        DecorationDimensions, MonoFont,
+        DecorationDimensions, MonoFont, MonoTextStyle,
         ascii::{
             FONT_4X6, FONT_5X7, FONT_5X8, FONT_6X9, FONT_6X10, FONT_6X12, FONT_6X13,
             FONT_6X13_BOLD, FONT_6X13_ITALIC, FONT_7X13, FONT_7X13_BOLD, FONT_7X13_ITALIC,
@@ -156,6 +166,7 @@ use embedded_graphics::{
     },
     pixelcolor::Rgb888, // cmk should this just be color?
     prelude::*,
+    text::{Baseline, Text},
 };
 use heapless::Vec;
 use smart_leds::RGB8;
@@ -165,6 +176,7 @@ use crate::led_strip::Frame as StripFrame;
 #[cfg(feature = "host")]
 type StripFrame<const N: usize> = [RGB8; N];
 use crate::Result;
+use crate::animation::{AnimationSource, AnimationStream, UnlockSignal};
 
 /// Convert RGB8 (smart-leds) to Rgb888 (embedded-graphics).
 #[must_use]
@@ -355,6 +367,16 @@ impl Led2dFont {
         }
     }
 
+    /// Blank columns between neighboring characters: the empty right-hand column of an
+    /// untrimmed font's cells. Trimmed variants drop it, and `Font3x4Trim` never had one.
+    #[must_use]
+    pub const fn character_spacing(self) -> i32 {
+        match self {
+            Self::Font3x4Trim => 0,
+            _ => 1 - self.spacing_reduction().0,
+        }
+    }
+
     /// Return spacing reduction for trimmed variants (width, height).
     #[must_use]
     pub const fn spacing_reduction(self) -> (i32, i32) {
@@ -408,6 +430,156 @@ impl Led2dFont {
     }
 }
 
+/// A font for [`Led2d`] text: one of the built-in monospace [`Led2dFont`]s or a proportional
+/// [`BitmapFont`](bitmap_font::BitmapFont).
+///
+/// Both convert with `.into()`, so text APIs accept either.
+#[derive(Clone, Copy, Debug)]
+pub enum TextFont {
+    Mono(Led2dFont),
+    Bitmap(&'static bitmap_font::BitmapFont<'static>),
+}
+
+impl From<Led2dFont> for TextFont {
+    fn from(font: Led2dFont) -> Self {
+        Self::Mono(font)
+    }
+}
+
+impl From<&'static bitmap_font::BitmapFont<'static>> for TextFont {
+    fn from(font: &'static bitmap_font::BitmapFont<'static>) -> Self {
+        Self::Bitmap(font)
+    }
+}
+
+impl TextFont {
+    /// Distance in pixels between the tops of consecutive lines.
+    #[must_use]
+    pub fn line_height(self) -> u32 {
+        match self {
+            Self::Mono(font) => {
+                (font.to_font().character_size.height as i32 - font.spacing_reduction().1) as u32
+            }
+            Self::Bitmap(font) => font.height() as u32,
+        }
+    }
+
+    /// Distance in pixels from the left edge of `ch` to the left edge of the character after
+    /// it. Proportional fonts apply kerning with `next`.
+    #[must_use]
+    pub fn advance(self, ch: char, next: Option<char>) -> i32 {
+        match self {
+            Self::Mono(font) => {
+                font.to_font().character_size.width as i32 - font.spacing_reduction().0
+            }
+            Self::Bitmap(font) => font.advance(ch, next),
+        }
+    }
+
+    /// Width in pixels of a single line of text (without newlines), from the left edge of
+    /// its first character to the right edge of its last, without trailing spacing.
+    #[must_use]
+    pub fn line_width(self, line: &str) -> u32 {
+        match self {
+            Self::Mono(font) => {
+                let advances = line.chars().count() as i32 * self.advance(' ', None);
+                (advances - font.character_spacing()).max(0) as u32
+            }
+            Self::Bitmap(font) => font.line_width(line) as u32,
+        }
+    }
+
+    /// Draw one character with its top-left corner at `position`, clipped to `target`.
+    pub fn draw_char<D>(self, target: &mut D, ch: char, position: Point, color: RGB8)
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        match self {
+            Self::Mono(font) => {
+                let font = font.to_font();
+                let mut buf = [0u8; 4];
+                let slice = ch.encode_utf8(&mut buf);
+                let style = MonoTextStyle::new(&font, rgb8_to_rgb888(color));
+                Text::with_baseline(slice, position, style, Baseline::Top)
+                    .draw(target)
+                    .ok();
+            }
+            Self::Bitmap(font) => {
+                let color = rgb8_to_rgb888(color);
+                font.for_each_pixel(ch, |x, y| {
+                    let point = position + Point::new(x as i32, y as i32);
+                    target.draw_iter(core::iter::once(Pixel(point, color))).ok();
+                });
+            }
+        }
+    }
+
+    /// Draw one line of text (without newlines) with its top-left corner at `origin`, clipped
+    /// to `target`. Colors cycle per character starting at `color_index`, which is advanced
+    /// so the next line continues the cycle.
+    pub(crate) fn draw_line<D>(
+        self,
+        target: &mut D,
+        line: &str,
+        origin: Point,
+        colors: &[RGB8],
+        color_index: &mut usize,
+    ) where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let line_height = self.line_height() as i32;
+        let display = target.bounding_box().size;
+        let (width_limit, height_limit) = (display.width as i32, display.height as i32);
+        let mut position = origin;
+        let mut chars = line.chars().peekable();
+
+        while let Some(ch) = chars.next() {
+            let color = if colors.is_empty() {
+                smart_leds::colors::WHITE
+            } else {
+                colors[*color_index % colors.len()]
+            };
+            *color_index = color_index.wrapping_add(1);
+
+            let advance = self.advance(ch, chars.peek().copied());
+            // Only draw glyphs that overlap the display.
+            if position.x + advance.max(1) > 0
+                && position.x < width_limit
+                && position.y + line_height > 0
+                && position.y < height_limit
+            {
+                self.draw_char(target, ch, position, color);
+            }
+            position.x += advance;
+        }
+    }
+}
+
+/// Render text into a frame with any [`TextFont`].
+///
+/// Text starts at the top-left corner and `\n` starts a new line. Characters that don't fit
+/// the width are clipped, and `colors` cycle per drawn character (white if empty).
+pub fn render_text<const W: usize, const H: usize>(
+    frame: &mut Frame<W, H>,
+    font: TextFont,
+    text: &str,
+    colors: &[RGB8],
+) -> Result<()> {
+    match font {
+        TextFont::Mono(font) => render_text_to_frame(
+            frame,
+            &font.to_font(),
+            text,
+            colors,
+            font.spacing_reduction(),
+        ),
+        TextFont::Bitmap(font) => {
+            bitmap_font::render_bitmap_text_to_frame(frame, font, text, colors);
+            Ok(())
+        }
+    }
+}
+
 // cmk0 should also define Default via the trait
 /// A 2D array of RGB pixels representing a single display frame.
 ///
@@ -581,6 +753,94 @@ pub type Led2dCompletionSignal = Signal<CriticalSectionRawMutex, ()>;
 pub enum Command<const N: usize, const MAX_FRAMES: usize> {
     DisplayStatic(StripFrame<N>),
     Animate(Vec<(StripFrame<N>, Duration), MAX_FRAMES>),
+    Video {
+        video: video::Video<'static>,
+        mapping_by_xy: [u16; N],
+    },
+    Stream {
+        stream: &'static (dyn Led2dFrameStream<N> + Sync),
+        mapping_by_xy: [u16; N],
+    },
+    Scroll {
+        scroll: scroll::TextScroll,
+        mapping_by_xy: [u16; N],
+        width: usize,
+        done_signal: &'static Led2dCompletionSignal,
+    },
+}
+
+#[doc(hidden)] // Required pub for the command type
+/// Type-erased [`AnimationStream`] so the (non-generic) device loop can render from it.
+pub trait Led2dFrameStream<const N: usize> {
+    fn try_next_frame(&self, mapping_by_xy: &[u16; N]) -> Option<(StripFrame<N>, Duration)>;
+    fn unlocked(&self) -> &UnlockSignal;
+}
+
+impl<S, const W: usize, const H: usize, const N: usize> Led2dFrameStream<N>
+    for AnimationStream<S, Frame<W, H>>
+where
+    S: AnimationSource<Frame<W, H>>,
+{
+    fn try_next_frame(&self, mapping_by_xy: &[u16; N]) -> Option<(StripFrame<N>, Duration)> {
+        let (frame, duration) = self.try_advance()?;
+        Some((to_strip_frame(frame, mapping_by_xy), duration))
+    }
+
+    fn unlocked(&self) -> &UnlockSignal {
+        AnimationStream::unlocked(self)
+    }
+}
+
+/// Convert a 2D frame to LED strip order using a (row-major) `mapping_by_xy`.
+fn to_strip_frame<const W: usize, const H: usize, const N: usize>(
+    frame_2d: Frame<W, H>,
+    mapping_by_xy: &[u16; N],
+) -> StripFrame<N> {
+    let mut frame_1d = [RGB8::new(0, 0, 0); N];
+    for row_index in 0..H {
+        for column_index in 0..W {
+            let led_index = mapping_by_xy[row_index * W + column_index] as usize;
+            frame_1d[led_index] = frame_2d[row_index][column_index];
+        }
+    }
+    StripFrame::from(frame_1d)
+}
+
+/// Draw target that writes straight into LED strip order, for rendering in the device loop
+/// where the display's width and height are only known at runtime.
+struct StripCanvas<'a, const N: usize> {
+    frame: [RGB8; N],
+    mapping_by_xy: &'a [u16; N],
+    width: usize,
+}
+
+impl<const N: usize> OriginDimensions for StripCanvas<'_, N> {
+    fn size(&self) -> Size {
+        Size::new(self.width as u32, (N / self.width) as u32)
+    }
+}
+
+impl<const N: usize> DrawTarget for StripCanvas<'_, N> {
+    type Color = Rgb888;
+    type Error = core::convert::Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> core::result::Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let height = N / self.width;
+        for Pixel(coord, color) in pixels {
+            if coord.x >= 0
+                && (coord.x as usize) < self.width
+                && coord.y >= 0
+                && (coord.y as usize) < height
+            {
+                let xy_index = coord.y as usize * self.width + coord.x as usize;
+                self.frame[self.mapping_by_xy[xy_index] as usize] = rgb888_to_rgb8(color);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Static type for the [`Led2d`] device abstraction.
@@ -590,6 +850,7 @@ pub enum Command<const N: usize, const MAX_FRAMES: usize> {
 pub struct Led2dStatic<const N: usize, const MAX_FRAMES: usize> {
     pub command_signal: Led2dCommandSignal<N, MAX_FRAMES>,
     pub completion_signal: Led2dCompletionSignal,
+    pub scroll_done_signal: Led2dCompletionSignal,
 }
 
 impl<const N: usize, const MAX_FRAMES: usize> Led2dStatic<N, MAX_FRAMES> {
@@ -598,6 +859,7 @@ impl<const N: usize, const MAX_FRAMES: usize> Led2dStatic<N, MAX_FRAMES> {
         Self {
             command_signal: Signal::new(),
             completion_signal: Signal::new(),
+            scroll_done_signal: Signal::new(),
         }
     }
 }
@@ -643,6 +905,7 @@ where
 pub struct Led2d<const N: usize, const MAX_FRAMES: usize> {
     command_signal: &'static Led2dCommandSignal<N, MAX_FRAMES>,
     completion_signal: &'static Led2dCompletionSignal,
+    scroll_done_signal: &'static Led2dCompletionSignal,
     mapping_by_xy: [u16; N],
     width: usize,
 }
@@ -668,30 +931,18 @@ impl<const N: usize, const MAX_FRAMES: usize> Led2d<N, MAX_FRAMES> {
         Self {
             command_signal: &led2d_static.command_signal,
             completion_signal: &led2d_static.completion_signal,
+            scroll_done_signal: &led2d_static.scroll_done_signal,
             mapping_by_xy: led_layout.mapping_by_xy(),
             width: W,
         }
     }
 
-    /// Convert (column, row) coordinates to LED strip index using the stored LED layout.
-    #[must_use]
-    fn xy_to_index(&self, column_index: usize, row_index: usize) -> usize {
-        self.mapping_by_xy[row_index * self.width + column_index] as usize
-    }
-
     /// Convert 2D frame to 1D array using the LED layout.
     fn convert_frame<const W: usize, const H: usize>(
         &self,
         frame_2d: Frame<W, H>,
     ) -> StripFrame<N> {
-        let mut frame_1d = [RGB8::new(0, 0, 0); N];
-        for row_index in 0..H {
-            for column_index in 0..W {
-                let led_index = self.xy_to_index(column_index, row_index);
-                frame_1d[led_index] = frame_2d[row_index][column_index];
-            }
-        }
-        StripFrame::from(frame_1d)
+        to_strip_frame(frame_2d, &self.mapping_by_xy)
     }
 
     /// Render a fully defined frame to the display.
@@ -743,6 +994,72 @@ impl<const N: usize, const MAX_FRAMES: usize> Led2d<N, MAX_FRAMES> {
         defmt::info!("Led2d::animate: completed (animation started)");
         Ok(())
     }
+
+    /// Loop a compressed [`Video`](video::Video) until interrupted by another command.
+    ///
+    /// Frames are decoded from `video` as they are shown, so any number of frames fits
+    /// regardless of `MAX_FRAMES`. See the [`video`] module for the format.
+    pub async fn animate_video(&self, video: video::Video<'static>) -> Result<()> {
+        assert!(
+            video.width() == self.width && video.width() * video.height() == N,
+            "video size must match the display"
+        );
+        self.command_signal.signal(Command::Video {
+            video,
+            mapping_by_xy: self.mapping_by_xy,
+        });
+        self.completion_signal.wait().await;
+        Ok(())
+    }
+
+    /// Play frames rendered on demand by `stream` until interrupted by another command.
+    ///
+    /// Unlike [`animate`](Self::animate), frames are not stored, so the animation can run
+    /// indefinitely regardless of `MAX_FRAMES`. See [`crate::animation`] for details.
+    pub async fn animate_stream<S, const W: usize, const H: usize>(
+        &self,
+        stream: &'static AnimationStream<S, Frame<W, H>>,
+    ) -> Result<()>
+    where
+        S: AnimationSource<Frame<W, H>> + Send,
+    {
+        assert!(
+            W == self.width && W * H == N,
+            "stream frame size must match the display"
+        );
+        self.command_signal.signal(Command::Stream {
+            stream,
+            mapping_by_xy: self.mapping_by_xy,
+        });
+        self.completion_signal.wait().await;
+        Ok(())
+    }
+
+    /// Start scrolling text across the display. Returns once scrolling has started.
+    ///
+    /// A looping scroll continues until interrupted by another command. A one-shot scroll
+    /// ends with the display blank; use [`wait_scroll_done`](Self::wait_scroll_done) to
+    /// await that. See the [`scroll`] module for details.
+    pub async fn scroll_text(&self, scroll: scroll::TextScroll) -> Result<()> {
+        // Forget any earlier scroll that finished without being awaited.
+        self.scroll_done_signal.reset();
+        self.command_signal.signal(Command::Scroll {
+            scroll,
+            mapping_by_xy: self.mapping_by_xy,
+            width: self.width,
+            done_signal: self.scroll_done_signal,
+        });
+        self.completion_signal.wait().await;
+        Ok(())
+    }
+
+    /// Wait until the most recent one-shot [`scroll_text`](Self::scroll_text) has scrolled
+    /// completely off the display.
+    ///
+    /// Never returns for a looping scroll, or for one that was interrupted by another command.
+    pub async fn wait_scroll_done(&self) {
+        self.scroll_done_signal.wait().await;
+    }
 }
 
 // Must be `pub` (not `pub(crate)`) because called by macro-generated code that expands at the call site in downstream crates.
@@ -763,10 +1080,16 @@ where
     S: WriteFrame<N>,
 {
     defmt::info!("led2d_device_loop: task started");
+    let mut pending_command: Option<Command<N, MAX_FRAMES>> = None;
     loop {
-        defmt::debug!("led2d_device_loop: waiting for command");
-        let command = command_signal.wait().await;
-        command_signal.reset();
+        let command = if let Some(command) = pending_command.take() {
+            command
+        } else {
+            defmt::debug!("led2d_device_loop: waiting for command");
+            let command = command_signal.wait().await;
+            command_signal.reset();
+            command
+        };
 
         match command {
             Command::DisplayStatic(frame) => {
@@ -778,41 +1101,66 @@ where
                     "led2d_device_loop: received Animate command with {} frames",
                     frames.len()
                 );
-                let next_command =
+                // Loop back to handle whatever interrupted the animation.
+                pending_command = Some(
                     run_animation_loop(frames, command_signal, completion_signal, &led_strip)
-                        .await?;
+                        .await?,
+                );
                 defmt::info!("led2d_device_loop: animation interrupted");
-                match next_command {
-                    Command::DisplayStatic(frame) => {
-                        defmt::info!(
-                            "led2d_device_loop: processing DisplayStatic from animation interrupt"
-                        );
-                        led_strip.write_frame(frame).await?;
-                        completion_signal.signal(());
-                    }
-                    Command::Animate(new_frames) => {
-                        defmt::info!("led2d_device_loop: restarting with new animation");
-                        // Process the new animation immediately without waiting for next command
-                        let next_command = run_animation_loop(
-                            new_frames,
-                            command_signal,
-                            completion_signal,
-                            &led_strip,
-                        )
-                        .await?;
-                        // Handle any command that interrupted this animation
-                        match next_command {
-                            Command::DisplayStatic(frame) => {
-                                led_strip.write_frame(frame).await?;
-                                completion_signal.signal(());
-                            }
-                            Command::Animate(_) => {
-                                // Another animation interrupted; loop back to handle it
-                                continue;
-                            }
-                        }
-                    }
-                }
+            }
+            Command::Video {
+                video,
+                mapping_by_xy,
+            } => {
+                defmt::info!(
+                    "led2d_device_loop: received Video command with {} frames",
+                    video.frame_count()
+                );
+                pending_command = Some(
+                    run_video_loop(
+                        video,
+                        &mapping_by_xy,
+                        command_signal,
+                        completion_signal,
+                        &led_strip,
+                    )
+                    .await?,
+                );
+                defmt::info!("led2d_device_loop: video interrupted");
+            }
+            Command::Stream {
+                stream,
+                mapping_by_xy,
+            } => {
+                pending_command = Some(
+                    run_stream_loop(
+                        stream,
+                        &mapping_by_xy,
+                        command_signal,
+                        completion_signal,
+                        &led_strip,
+                    )
+                    .await?,
+                );
+                defmt::info!("led2d_device_loop: stream interrupted");
+            }
+            Command::Scroll {
+                scroll,
+                mapping_by_xy,
+                width,
+                done_signal,
+            } => {
+                // `None` means a one-shot scroll finished; wait for the next command.
+                pending_command = run_scroll_loop(
+                    scroll,
+                    &mapping_by_xy,
+                    width,
+                    done_signal,
+                    command_signal,
+                    completion_signal,
+                    &led_strip,
+                )
+                .await?;
             }
         }
     }
@@ -849,6 +1197,118 @@ where
     }
 }
 
+/// Render and show frames from an animation stream until interrupted.
+async fn run_stream_loop<const N: usize, const MAX_FRAMES: usize, S>(
+    stream: &'static (dyn Led2dFrameStream<N> + Sync),
+    mapping_by_xy: &[u16; N],
+    command_signal: &'static Led2dCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static Led2dCompletionSignal,
+    led_strip: &S,
+) -> Result<Command<N, MAX_FRAMES>>
+where
+    S: WriteFrame<N>,
+{
+    completion_signal.signal(());
+
+    loop {
+        let (strip_frame, duration) = loop {
+            if let Some(next) = stream.try_next_frame(mapping_by_xy) {
+                break next;
+            }
+            stream.unlocked().wait().await;
+        };
+        led_strip.write_frame(strip_frame).await?;
+
+        // A zero duration ends the stream; hold its last frame until the next command.
+        let timer = async {
+            if duration > Duration::from_ticks(0) {
+                Timer::after(duration).await;
+            } else {
+                core::future::pending::<()>().await;
+            }
+        };
+        if let Either::First(new_command) = select(command_signal.wait(), timer).await {
+            defmt::info!("run_stream_loop: received new command, interrupting");
+            command_signal.reset();
+            return Ok(new_command);
+        }
+    }
+}
+
+/// Scroll text one pixel per frame until interrupted or, for a one-shot scroll, finished.
+///
+/// Returns the interrupting command, or `None` after signaling `done_signal`.
+async fn run_scroll_loop<const N: usize, const MAX_FRAMES: usize, S>(
+    mut scroll: scroll::TextScroll,
+    mapping_by_xy: &[u16; N],
+    width: usize,
+    done_signal: &'static Led2dCompletionSignal,
+    command_signal: &'static Led2dCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static Led2dCompletionSignal,
+    led_strip: &S,
+) -> Result<Option<Command<N, MAX_FRAMES>>>
+where
+    S: WriteFrame<N>,
+{
+    completion_signal.signal(());
+
+    let mut canvas = StripCanvas {
+        frame: [RGB8::new(0, 0, 0); N],
+        mapping_by_xy,
+        width,
+    };
+    while let Some(duration) = scroll.render_next(&mut canvas) {
+        led_strip
+            .write_frame(StripFrame::from(canvas.frame))
+            .await?;
+
+        if let Either::First(new_command) =
+            select(command_signal.wait(), Timer::after(duration)).await
+        {
+            defmt::info!("run_scroll_loop: received new command, interrupting");
+            command_signal.reset();
+            return Ok(Some(new_command));
+        }
+    }
+    defmt::info!("run_scroll_loop: one-shot scroll finished");
+    done_signal.signal(());
+    Ok(None)
+}
+
+/// Decode and show a video frame by frame until interrupted.
+///
+/// Only the current frame is held in RAM. Each decoded pixel goes straight to its LED index.
+async fn run_video_loop<const N: usize, const MAX_FRAMES: usize, S>(
+    video: video::Video<'static>,
+    mapping_by_xy: &[u16; N],
+    command_signal: &'static Led2dCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static Led2dCompletionSignal,
+    led_strip: &S,
+) -> Result<Command<N, MAX_FRAMES>>
+where
+    S: WriteFrame<N>,
+{
+    completion_signal.signal(());
+
+    let mut player = video.player();
+    let mut strip_frame = [RGB8::new(0, 0, 0); N];
+    loop {
+        let duration = player.next_frame(|pixel_index, color| {
+            strip_frame[mapping_by_xy[pixel_index] as usize] = color;
+        });
+        led_strip.write_frame(StripFrame::from(strip_frame)).await?;
+
+        match select(command_signal.wait(), Timer::after(duration)).await {
+            Either::First(new_command) => {
+                defmt::info!("run_video_loop: received new command, interrupting");
+                command_signal.reset();
+                return Ok(new_command);
+            }
+            Either::Second(()) => continue,
+        }
+    }
+}
+
 #[doc(hidden)]
 #[macro_export]
 #[cfg(not(feature = "host"))]
@@ -1350,8 +1810,8 @@ macro_rules! led2d_from_strip {
             /// LED matrix device handle generated by [`led2d_from_strip!`](crate::led2d::led2d_from_strip).
             $vis struct [<$name:camel>] {
                 led2d: $crate::led2d::Led2d<$n_const, $max_frames_const>,
-                font: embedded_graphics::mono_font::MonoFont<'static>,
-                font_variant: $crate::led2d::Led2dFont,
+                font: $crate::led2d::TextFont,
+                brightness_control: $crate::led_strip::brightness::BrightnessControl,
             }
 
             /// Frame type for this LED matrix display.
@@ -1420,11 +1880,28 @@ macro_rules! led2d_from_strip {
                     defmt::info!("Led2d::new: device created successfully");
                     Ok(Self {
                         led2d,
-                        font: $crate::led2d::Led2dFont::$font_variant.to_font(),
-                        font_variant: $crate::led2d::Led2dFont::$font_variant,
+                        font: $crate::led2d::TextFont::Mono($crate::led2d::Led2dFont::$font_variant),
+                        brightness_control: led_strip.brightness_control(),
                     })
                 }
 
+                /// Change brightness immediately, without interrupting what is on display.
+                /// `255` is as bright as `max_current` allows and `0` is off.
+                $vis fn set_brightness(&self, brightness: u8) {
+                    self.brightness_control.set_brightness(brightness);
+                }
+
+                /// Change brightness gradually over `duration`, without interrupting what is on display.
+                $vis fn fade_brightness(&self, brightness: u8, duration: Duration) {
+                    self.brightness_control.fade_brightness(brightness, duration);
+                }
+
+                /// A copyable handle for changing this display's brightness from elsewhere, such as
+                /// [`AutoDim`](crate::led_strip::auto_dim::AutoDim).
+                $vis const fn brightness_control(&self) -> $crate::led_strip::brightness::BrightnessControl {
+                    self.brightness_control
+                }
+
                 /// Render a fully defined frame to the display.
                 $vis async fn write_frame(&self, frame: $crate::led2d::Frame<$cols_const, $rows_const>) -> $crate::Result<()> {
                     self.led2d.write_frame(frame).await
@@ -1435,6 +1912,48 @@ macro_rules! led2d_from_strip {
                     self.led2d.animate(frames).await
                 }
 
+                /// Loop a compressed video (see [`video`](crate::led2d::video)) until interrupted.
+                /// Frames are decoded as they play, so `max_frames` does not limit its length.
+                $vis async fn animate_video(&self, video: $crate::led2d::video::Video<'static>) -> $crate::Result<()> {
+                    self.led2d.animate_video(video).await
+                }
+
+                /// Play frames rendered on demand by `stream` until interrupted. See [`animation`](crate::animation).
+                $vis async fn animate_stream<S>(
+                    &self,
+                    stream: &'static $crate::animation::AnimationStream<S, $crate::led2d::Frame<$cols_const, $rows_const>>,
+                ) -> $crate::Result<()>
+                where
+                    S: $crate::animation::AnimationSource<$crate::led2d::Frame<$cols_const, $rows_const>> + Send,
+                {
+                    self.led2d.animate_stream(stream).await
+                }
+
+                /// Scroll text across the display using the configured font, with colors cycling
+                /// per character. Returns once scrolling has started. See [`scroll`](crate::led2d::scroll).
+                $vis async fn scroll_text(
+                    &self,
+                    text: &str,
+                    colors: &[smart_leds::RGB8],
+                    options: $crate::led2d::scroll::ScrollOptions,
+                ) -> $crate::Result<()> {
+                    let scroll = $crate::led2d::scroll::TextScroll::new(text, colors, self.font, options);
+                    self.led2d.scroll_text(scroll).await
+                }
+
+                /// Wait until a one-shot [`scroll_text`](Self::scroll_text) has scrolled off the display.
+                $vis async fn wait_scroll_done(&self) {
+                    self.led2d.wait_scroll_done().await
+                }
+
+                /// Use `font` for text instead of the one configured in the macro, for example a
+                /// proportional [`BitmapFont`](crate::led2d::bitmap_font::BitmapFont).
+                #[must_use]
+                $vis fn with_font(mut self, font: impl Into<$crate::led2d::TextFont>) -> Self {
+                    self.font = font.into();
+                    self
+                }
+
                 /// Render text into a frame using the configured font and spacing.
                 pub fn write_text_to_frame(
                     &self,
@@ -1442,7 +1961,31 @@ macro_rules! led2d_from_strip {
                     colors: &[smart_leds::RGB8],
                     frame: &mut $crate::led2d::Frame<$cols_const, $rows_const>,
                 ) -> $crate::Result<()> {
-                    $crate::led2d::render_text_to_frame(frame, &self.font, text, colors, self.font_variant.spacing_reduction())
+                    $crate::led2d::render_text(frame, self.font, text, colors)
+                }
+
+                /// Render aligned, wrapped text into a frame using the configured font.
+                /// See [`text_layout`](crate::led2d::text_layout).
+                pub fn write_text_layout_to_frame(
+                    &self,
+                    text: &str,
+                    colors: &[smart_leds::RGB8],
+                    layout: &$crate::led2d::text_layout::TextLayout,
+                    frame: &mut $crate::led2d::Frame<$cols_const, $rows_const>,
+                ) {
+                    layout.draw(frame, self.font, text, colors);
+                }
+
+                /// Render aligned, wrapped text and display it on the LED matrix.
+                pub async fn write_text_layout(
+                    &self,
+                    text: &str,
+                    colors: &[smart_leds::RGB8],
+                    layout: &$crate::led2d::text_layout::TextLayout,
+                ) -> $crate::Result<()> {
+                    let mut frame = Self::new_frame();
+                    self.write_text_layout_to_frame(text, colors, layout, &mut frame);
+                    self.write_frame(frame).await
                 }
 
                 /// Render text and display it on the LED matrix.
@@ -0,0 +1,357 @@
+//! Compact proportional (variable-width) bitmap fonts for [`Led2d`](super::Led2d) text.
+//!
+//! The built-in [`Led2dFont`](super::Led2dFont)s are monospace, so an `i` takes as much room
+//! as an `M`. A [`BitmapFont`] stores each glyph at its own width, plus optional kerning
+//! pairs, which fits noticeably more text on 8- and 12-column panels.
+//!
+//! Convert a BDF or PCF font with `cargo xtask font-convert`, embed the result with
+//! `include_bytes!`, and select it with the generated device's `with_font` method. Glyphs are
+//! read straight from flash.
+//!
+//! # Format
+//!
+//! All multi-byte values are little-endian.
+//!
+//! | Bytes | Field |
+//! |---|---|
+//! | 4 | Magic `DKF1` |
+//! | 1 | Height `H` of every glyph, which is also the line height (1..=32) |
+//! | 1 | Spacing: blank columns added after every glyph |
+//! | 2 | Glyph count `G` (at least 1) |
+//! | 2 | Kerning pair count `K` |
+//! | `7 * G` | Glyph table, sorted by code point |
+//! | `5 * K` | Kerning table, sorted by `(left, right)` |
+//! | rest | Glyph bitmaps |
+//!
+//! A glyph table entry is the code point (4 bytes), the glyph width `w` in pixels (1 byte),
+//! and the offset of its bitmap from the start of the bitmaps (2 bytes). A bitmap is
+//! `w * H` bits in row-major order, most significant bit first, padded to a whole byte.
+//!
+//! A kerning entry is the left and right glyph indexes (2 bytes each) and a signed
+//! adjustment in pixels (1 byte) added to the advance between them.
+//!
+//! # Example
+//!
+//! Real fonts come from `cargo xtask font-convert tom-thumb.bdf thumb.dkf` and
+//! `static THUMB: BitmapFont = BitmapFont::new(include_bytes!("../thumb.dkf"));`. This one is
+//! small enough to write by hand:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::bitmap_font::BitmapFont;
+//!
+//! // 3 pixels high, 1 column of spacing, 2 glyphs, 1 kerning pair.
+//! static TINY: BitmapFont = BitmapFont::new(&[
+//!     b'D', b'K', b'F', b'1', 3, 1, 2, 0, 1, 0,
+//!     b'T', 0, 0, 0, 3, 0, 0, // 'T' is 3 wide, bitmap at 0
+//!     b'i', 0, 0, 0, 1, 2, 0, // 'i' is 1 wide, bitmap at 2
+//!     0, 0, 1, 0, 0xFF, // 'T' then 'i': one column closer
+//!     0b1110_1001, 0b0000_0000, // 'T': ###, .#., .#.
+//!     0b1010_0000, // 'i': #, ., #
+//! ]);
+//!
+//! fn width() -> usize {
+//!     // 3 + 1 spacing - 1 kerning + 1
+//!     TINY.line_width("Ti")
+//! }
+//! ```
+
+use super::Frame;
+
+/// The 4-byte magic number that starts every font.
+pub const MAGIC: [u8; 4] = *b"DKF1";
+
+const HEADER_LEN: usize = 10;
+const GLYPH_ENTRY_LEN: usize = 7;
+const KERNING_ENTRY_LEN: usize = 5;
+
+/// A proportional bitmap font stored in a byte slice (usually `include_bytes!` in flash).
+///
+/// See the [module documentation](self) for the format and an example.
+#[derive(Clone, Copy, Debug)]
+pub struct BitmapFont<'a> {
+    data: &'a [u8],
+    height: u8,
+    spacing: u8,
+    glyph_count: u16,
+    kerning_count: u16,
+}
+
+impl<'a> BitmapFont<'a> {
+    /// Wrap encoded font bytes, checking the header.
+    ///
+    /// Use in a `const` or `static` to check the header at compile time. Glyph bitmaps are
+    /// checked as they are drawn.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the magic number is wrong, the font has no glyphs, its height is not
+    /// 1..=32, or the data is too short to hold its tables.
+    #[must_use]
+    pub const fn new(data: &'a [u8]) -> Self {
+        assert!(data.len() >= HEADER_LEN, "font data too short");
+        assert!(
+            data[0] == MAGIC[0]
+                && data[1] == MAGIC[1]
+                && data[2] == MAGIC[2]
+                && data[3] == MAGIC[3],
+            "not a DKF1 font"
+        );
+        let height = data[4];
+        let spacing = data[5];
+        let glyph_count = u16::from_le_bytes([data[6], data[7]]);
+        let kerning_count = u16::from_le_bytes([data[8], data[9]]);
+        assert!(height > 0 && height <= 32, "font height must be 1..=32");
+        assert!(glyph_count > 0, "font must have at least one glyph");
+        let font = Self {
+            data,
+            height,
+            spacing,
+            glyph_count,
+            kerning_count,
+        };
+        assert!(
+            data.len() >= font.bitmaps_offset(),
+            "font data too short for its tables"
+        );
+        font
+    }
+
+    /// Height of every glyph in pixels, which is also the line height.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    /// Blank columns added after every glyph.
+    #[must_use]
+    pub const fn spacing(&self) -> usize {
+        self.spacing as usize
+    }
+
+    /// Number of glyphs in the font.
+    #[must_use]
+    pub const fn glyph_count(&self) -> usize {
+        self.glyph_count as usize
+    }
+
+    /// Number of kerning pairs in the font.
+    #[must_use]
+    pub const fn kerning_count(&self) -> usize {
+        self.kerning_count as usize
+    }
+
+    /// Size of the encoded font in bytes.
+    #[must_use]
+    pub const fn len_bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Look up the glyph for `ch`, falling back to `?` for characters the font lacks.
+    ///
+    /// Returns `None` if neither is in the font.
+    #[must_use]
+    pub fn glyph(&self, ch: char) -> Option<Glyph<'a>> {
+        let index = self.glyph_index(ch).or_else(|| self.glyph_index('?'))?;
+        Some(self.glyph_at(index))
+    }
+
+    /// Pixels added to the advance from `left` to `right`; usually zero or negative.
+    #[must_use]
+    pub fn kerning(&self, left: char, right: char) -> i32 {
+        if self.kerning_count == 0 {
+            return 0;
+        }
+        let (Some(left), Some(right)) = (self.glyph_index(left), self.glyph_index(right)) else {
+            return 0;
+        };
+        let (mut low, mut high) = (0, self.kerning_count());
+        while low < high {
+            let middle = (low + high) / 2;
+            let start = self.kerning_offset() + middle * KERNING_ENTRY_LEN;
+            let key = (self.u16_at(start), self.u16_at(start + 2));
+            match key.cmp(&(left, right)) {
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+                core::cmp::Ordering::Equal => return i32::from(self.byte(start + 4) as i8),
+            }
+        }
+        0
+    }
+
+    /// Distance in pixels from the left edge of `ch` to the left edge of the character after
+    /// it, including spacing and kerning with `next`.
+    #[must_use]
+    pub fn advance(&self, ch: char, next: Option<char>) -> i32 {
+        let Some(glyph) = self.glyph(ch) else {
+            return 0;
+        };
+        let kerning = next.map_or(0, |next| self.kerning(ch, next));
+        glyph.width() as i32 + self.spacing() as i32 + kerning
+    }
+
+    /// Width in pixels of a single line of text, excluding spacing after the last glyph.
+    #[must_use]
+    pub fn line_width(&self, line: &str) -> usize {
+        let mut width = 0i32;
+        let mut chars = line.chars().peekable();
+        while let Some(ch) = chars.next() {
+            width += match chars.peek() {
+                Some(next) => self.advance(ch, Some(*next)),
+                None => self.glyph(ch).map_or(0, |glyph| glyph.width() as i32),
+            };
+        }
+        width.max(0) as usize
+    }
+
+    /// Call `set_pixel(x, y)` for every lit pixel of `ch`, relative to its top-left corner.
+    pub fn for_each_pixel(&self, ch: char, mut set_pixel: impl FnMut(usize, usize)) {
+        let Some(glyph) = self.glyph(ch) else {
+            return;
+        };
+        for y in 0..glyph.height() {
+            for x in 0..glyph.width() {
+                if glyph.pixel(x, y) {
+                    set_pixel(x, y);
+                }
+            }
+        }
+    }
+
+    const fn kerning_offset(&self) -> usize {
+        HEADER_LEN + self.glyph_count as usize * GLYPH_ENTRY_LEN
+    }
+
+    const fn bitmaps_offset(&self) -> usize {
+        self.kerning_offset() + self.kerning_count as usize * KERNING_ENTRY_LEN
+    }
+
+    /// Binary search the glyph table for `ch`.
+    fn glyph_index(&self, ch: char) -> Option<u16> {
+        let code_point = u32::from(ch);
+        let (mut low, mut high) = (0, self.glyph_count());
+        while low < high {
+            let middle = (low + high) / 2;
+            let start = HEADER_LEN + middle * GLYPH_ENTRY_LEN;
+            let entry_code_point = u32::from_le_bytes([
+                self.byte(start),
+                self.byte(start + 1),
+                self.byte(start + 2),
+                self.byte(start + 3),
+            ]);
+            match entry_code_point.cmp(&code_point) {
+                core::cmp::Ordering::Less => low = middle + 1,
+                core::cmp::Ordering::Greater => high = middle,
+                core::cmp::Ordering::Equal => return Some(middle as u16),
+            }
+        }
+        None
+    }
+
+    fn glyph_at(&self, index: u16) -> Glyph<'a> {
+        let start = HEADER_LEN + usize::from(index) * GLYPH_ENTRY_LEN;
+        let width = self.byte(start + 4);
+        let bitmap_start = self.bitmaps_offset() + usize::from(self.u16_at(start + 5));
+        let bitmap_len = (usize::from(width) * self.height()).div_ceil(8);
+        let bits = self
+            .data
+            .get(bitmap_start..bitmap_start + bitmap_len)
+            .expect("glyph bitmap fits in font data");
+        Glyph {
+            width,
+            height: self.height,
+            bits,
+        }
+    }
+
+    fn u16_at(&self, offset: usize) -> u16 {
+        u16::from_le_bytes([self.byte(offset), self.byte(offset + 1)])
+    }
+
+    fn byte(&self, offset: usize) -> u8 {
+        *self.data.get(offset).expect("font data truncated")
+    }
+}
+
+/// One character's bitmap from a [`BitmapFont`].
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph<'a> {
+    width: u8,
+    height: u8,
+    bits: &'a [u8],
+}
+
+impl Glyph<'_> {
+    /// Width in pixels (not counting the font's spacing).
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    /// Height in pixels; the same for every glyph in a font.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    /// Whether the pixel at column `x`, row `y` is lit.
+    #[must_use]
+    pub fn pixel(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.width() && y < self.height(), "pixel out of glyph");
+        let bit = y * self.width() + x;
+        self.bits[bit / 8] & (0x80 >> (bit % 8)) != 0
+    }
+}
+
+/// Draw `text` into `frame` with `font`, following [`render_text_to_frame`](super::render_text_to_frame):
+/// text starts at the top-left, `\n` starts a new line, characters that don't fit the width
+/// are clipped, and colors cycle per drawn character (white if `colors` is empty).
+pub fn render_bitmap_text_to_frame<const W: usize, const H: usize>(
+    frame: &mut Frame<W, H>,
+    font: &BitmapFont<'_>,
+    text: &str,
+    colors: &[smart_leds::RGB8],
+) {
+    let line_height = font.height() as i32;
+    let mut x = 0i32;
+    let mut y = 0i32;
+    let mut color_index: usize = 0;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if ch == '\n' {
+            x = 0;
+            y += line_height;
+            if y >= H as i32 {
+                break;
+            }
+            continue;
+        }
+        let next = chars.peek().copied().filter(|next| *next != '\n');
+        let advance = font.advance(ch, next);
+
+        // Clip characters that exceed width limit (no wrapping until explicit \n)
+        let glyph_width = font.glyph(ch).map_or(0, |glyph| glyph.width() as i32);
+        if x + glyph_width > W as i32 {
+            x += advance;
+            continue;
+        }
+
+        let color = if colors.is_empty() {
+            smart_leds::colors::WHITE
+        } else {
+            colors[color_index % colors.len()]
+        };
+        color_index = color_index.wrapping_add(1);
+
+        font.for_each_pixel(ch, |glyph_x, glyph_y| {
+            let (column, row) = (x + glyph_x as i32, y + glyph_y as i32);
+            if column >= 0 && column < W as i32 && row < H as i32 {
+                frame[row as usize][column as usize] = color;
+            }
+        });
+        x += advance;
+    }
+}
@@ -0,0 +1,453 @@
+//! Layer compositing, sprite blitting, and transitions for [`Frame`]s.
+//!
+//! A [`Frame`] is a plain grid of colors. This module combines frames:
+//!
+//! - [`blend`] mixes two colors with a [`BlendMode`] (normal, add, multiply, screen) and an
+//!   opacity.
+//! - A [`Layer`] is a frame plus its [`Alpha`] (opaque, uniform, a per-pixel mask, or black as
+//!   transparent) and blend mode. [`composite`] stacks layers bottom to top;
+//!   [`Frame::draw_layer`] adds one layer to an existing frame.
+//! - [`Frame::blit`] copies a smaller sprite frame onto a frame at any position, optionally
+//!   skipping a transparent key color.
+//! - [`transition`] produces the frames that move from one frame to another with a
+//!   [`Transition`]: crossfade, wipe, slide, or dissolve.
+//!
+//! Transition frames suit `animate`, but `animate` loops until the next command. To play a
+//! transition once, write each frame with `write_frame` and wait its duration.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::compose::{
+//!     Alpha, BlendMode, Direction, Layer, Transition, TransitionFrames, composite, transition,
+//! };
+//! use device_kit::led2d::{Frame, Led2dFont, render_text};
+//! use embassy_time::Duration;
+//! use embedded_graphics::prelude::Point;
+//! use smart_leds::colors;
+//!
+//! fn clock_face(time: &str) -> Frame<12, 4> {
+//!     let background = Frame::filled(colors::NAVY);
+//!     let mut digits = Frame::new();
+//!     render_text(&mut digits, Led2dFont::Font3x4Trim.into(), time, &[colors::WHITE]).unwrap();
+//!     let glow = Frame::filled(colors::MAROON);
+//!     let mut face = composite(&[
+//!         Layer::new(&background),
+//!         Layer::new(&digits).with_alpha(Alpha::BlackIsTransparent),
+//!         Layer::new(&glow).with_mode(BlendMode::Add),
+//!     ]);
+//!     // A 1x1 sprite in the corner.
+//!     face.blit(&Frame::<1, 1>::filled(colors::YELLOW), Point::new(11, 0), None);
+//!     face
+//! }
+//!
+//! fn minute_change() -> TransitionFrames<12, 4> {
+//!     transition(
+//!         &clock_face("1234"),
+//!         &clock_face("1235"),
+//!         Transition::Slide(Direction::Up),
+//!         8,
+//!         Duration::from_millis(400),
+//!     )
+//! }
+//! ```
+
+use embassy_time::Duration;
+use embedded_graphics::prelude::Point;
+use smart_leds::RGB8;
+
+use super::Frame;
+
+/// How a top color combines with the color beneath it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, defmt::Format)]
+pub enum BlendMode {
+    /// The top color replaces the bottom color.
+    #[default]
+    Normal,
+    /// Channels add, saturating at full brightness. Good for glows and light sources.
+    Add,
+    /// Channels multiply, so the result is never brighter than either color. Good for tinting
+    /// and masks.
+    Multiply,
+    /// The inverse of multiplying the inverses, so the result is never darker than either color.
+    Screen,
+}
+
+impl BlendMode {
+    /// Combine `top` over `bottom` at full opacity.
+    #[must_use]
+    pub const fn apply(self, bottom: RGB8, top: RGB8) -> RGB8 {
+        const fn channel(mode: BlendMode, bottom: u8, top: u8) -> u8 {
+            let (bottom, top) = (bottom as u16, top as u16);
+            let value = match mode {
+                BlendMode::Normal => top,
+                BlendMode::Add => {
+                    if bottom + top > 255 {
+                        255
+                    } else {
+                        bottom + top
+                    }
+                }
+                BlendMode::Multiply => (bottom * top + 127) / 255,
+                BlendMode::Screen => 255 - ((255 - bottom) * (255 - top) + 127) / 255,
+            };
+            value as u8
+        }
+        RGB8::new(
+            channel(self, bottom.r, top.r),
+            channel(self, bottom.g, top.g),
+            channel(self, bottom.b, top.b),
+        )
+    }
+}
+
+/// Combine `top` over `bottom` with `mode`, then mix that result with `bottom` by `alpha`
+/// (0 keeps `bottom`, 255 is the full blend).
+#[must_use]
+pub const fn blend(bottom: RGB8, top: RGB8, mode: BlendMode, alpha: u8) -> RGB8 {
+    const fn mix(bottom: u8, blended: u8, alpha: u8) -> u8 {
+        let alpha = alpha as u16;
+        ((bottom as u16 * (255 - alpha) + blended as u16 * alpha + 127) / 255) as u8
+    }
+    let blended = mode.apply(bottom, top);
+    RGB8::new(
+        mix(bottom.r, blended.r, alpha),
+        mix(bottom.g, blended.g, alpha),
+        mix(bottom.b, blended.b, alpha),
+    )
+}
+
+/// Opacity of each pixel in a [`Layer`].
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Alpha<'a, const W: usize, const H: usize> {
+    /// Every pixel is fully opaque.
+    #[default]
+    Opaque,
+    /// Every pixel has the same opacity (0 is invisible, 255 is opaque).
+    Uniform(u8),
+    /// Per-pixel opacity, indexed `[row][column]` like the frame.
+    Mask(&'a [[u8; W]; H]),
+    /// Black pixels are invisible and all others are opaque, so text and shapes drawn on a
+    /// blank frame overlay whatever is beneath them.
+    BlackIsTransparent,
+}
+
+impl<const W: usize, const H: usize> Alpha<'_, W, H> {
+    /// Opacity of the pixel at `(row_index, column_index)` with color `color`.
+    #[must_use]
+    pub const fn at(&self, row_index: usize, column_index: usize, color: RGB8) -> u8 {
+        match self {
+            Self::Opaque => 255,
+            Self::Uniform(alpha) => *alpha,
+            Self::Mask(mask) => mask[row_index][column_index],
+            Self::BlackIsTransparent => {
+                if color.r == 0 && color.g == 0 && color.b == 0 {
+                    0
+                } else {
+                    255
+                }
+            }
+        }
+    }
+}
+
+/// A frame to composite, with its opacity and blend mode.
+///
+/// See the [module documentation](self) for an overview.
+#[derive(Clone, Copy, Debug)]
+pub struct Layer<'a, const W: usize, const H: usize> {
+    /// The layer's pixels.
+    pub frame: &'a Frame<W, H>,
+    /// The layer's opacity.
+    pub alpha: Alpha<'a, W, H>,
+    /// How the layer combines with the layers beneath it.
+    pub mode: BlendMode,
+}
+
+impl<'a, const W: usize, const H: usize> Layer<'a, W, H> {
+    /// An opaque layer drawn with [`BlendMode::Normal`].
+    #[must_use]
+    pub const fn new(frame: &'a Frame<W, H>) -> Self {
+        Self {
+            frame,
+            alpha: Alpha::Opaque,
+            mode: BlendMode::Normal,
+        }
+    }
+
+    /// Set the layer's opacity.
+    #[must_use]
+    pub const fn with_alpha(mut self, alpha: Alpha<'a, W, H>) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Set the layer's blend mode.
+    #[must_use]
+    pub const fn with_mode(mut self, mode: BlendMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// Stack `layers` from first (bottom) to last (top) on a black frame.
+#[must_use]
+pub fn composite<const W: usize, const H: usize>(layers: &[Layer<'_, W, H>]) -> Frame<W, H> {
+    let mut frame = Frame::new();
+    for layer in layers {
+        frame.draw_layer(layer);
+    }
+    frame
+}
+
+impl<const W: usize, const H: usize> Frame<W, H> {
+    /// Draw `layer` over this frame.
+    pub fn draw_layer(&mut self, layer: &Layer<'_, W, H>) {
+        for (row_index, (row, top_row)) in self.0.iter_mut().zip(layer.frame.0.iter()).enumerate() {
+            for (column_index, (pixel, top)) in row.iter_mut().zip(top_row.iter()).enumerate() {
+                let alpha = layer.alpha.at(row_index, column_index, *top);
+                *pixel = blend(*pixel, *top, layer.mode, alpha);
+            }
+        }
+    }
+
+    /// Copy `sprite` onto this frame with its top-left corner at `top_left`.
+    ///
+    /// Sprite pixels equal to `transparent` are skipped, and parts of the sprite outside this
+    /// frame are clipped, so sprites can move partly off the edges.
+    pub fn blit<const SW: usize, const SH: usize>(
+        &mut self,
+        sprite: &Frame<SW, SH>,
+        top_left: Point,
+        transparent: Option<RGB8>,
+    ) {
+        for (sprite_row_index, sprite_row) in sprite.0.iter().enumerate() {
+            let Some(row) = usize::try_from(top_left.y + sprite_row_index as i32)
+                .ok()
+                .and_then(|row_index| self.0.get_mut(row_index))
+            else {
+                continue;
+            };
+            for (sprite_column_index, color) in sprite_row.iter().enumerate() {
+                if transparent == Some(*color) {
+                    continue;
+                }
+                if let Some(pixel) = usize::try_from(top_left.x + sprite_column_index as i32)
+                    .ok()
+                    .and_then(|column_index| row.get_mut(column_index))
+                {
+                    *pixel = *color;
+                }
+            }
+        }
+    }
+}
+
+/// Which way a [`Transition`] moves.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum Direction {
+    /// From the right edge toward the left.
+    Left,
+    /// From the left edge toward the right.
+    Right,
+    /// From the bottom edge toward the top.
+    Up,
+    /// From the top edge toward the bottom.
+    Down,
+}
+
+/// How [`transition`] moves from one frame to another.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum Transition {
+    /// Fade the old frame out while the new one fades in.
+    Crossfade,
+    /// Reveal the new frame behind an edge moving in the given direction; nothing moves.
+    Wipe(Direction),
+    /// Push the old frame out while the new one follows it in, both moving in the given
+    /// direction.
+    Slide(Direction),
+    /// Switch pixels to the new frame in a scattered order chosen by `seed`.
+    Dissolve {
+        /// Different seeds give different orders.
+        seed: u32,
+    },
+}
+
+impl Transition {
+    /// The frame `step` of `steps` along the way from `from` to `to`.
+    ///
+    /// Step 0 is `from` and step `steps` is `to`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `steps` is zero or `step` is greater than `steps`.
+    #[must_use]
+    pub fn render<const W: usize, const H: usize>(
+        self,
+        from: &Frame<W, H>,
+        to: &Frame<W, H>,
+        step: usize,
+        steps: usize,
+    ) -> Frame<W, H> {
+        assert!(steps > 0, "transition steps must be positive");
+        assert!(step <= steps, "transition step must not exceed steps");
+        let mut frame = *from;
+        match self {
+            Self::Crossfade => {
+                let alpha = (step * 255 / steps) as u8;
+                frame.draw_layer(&Layer::new(to).with_alpha(Alpha::Uniform(alpha)));
+            }
+            Self::Wipe(direction) => {
+                let columns = step * W / steps;
+                let rows = step * H / steps;
+                for (row_index, row) in frame.0.iter_mut().enumerate() {
+                    for (column_index, pixel) in row.iter_mut().enumerate() {
+                        let revealed = match direction {
+                            Direction::Left => column_index >= W - columns,
+                            Direction::Right => column_index < columns,
+                            Direction::Up => row_index >= H - rows,
+                            Direction::Down => row_index < rows,
+                        };
+                        if revealed {
+                            *pixel = to.0[row_index][column_index];
+                        }
+                    }
+                }
+            }
+            Self::Slide(direction) => {
+                let columns = step * W / steps;
+                let rows = step * H / steps;
+                for (row_index, row) in frame.0.iter_mut().enumerate() {
+                    for (column_index, pixel) in row.iter_mut().enumerate() {
+                        // Position in the strip of `from` followed by `to` that lands here.
+                        *pixel = match direction {
+                            Direction::Left => {
+                                let source = column_index + columns;
+                                if source < W {
+                                    from.0[row_index][source]
+                                } else {
+                                    to.0[row_index][source - W]
+                                }
+                            }
+                            Direction::Right => {
+                                if column_index >= columns {
+                                    from.0[row_index][column_index - columns]
+                                } else {
+                                    to.0[row_index][column_index + W - columns]
+                                }
+                            }
+                            Direction::Up => {
+                                let source = row_index + rows;
+                                if source < H {
+                                    from.0[source][column_index]
+                                } else {
+                                    to.0[source - H][column_index]
+                                }
+                            }
+                            Direction::Down => {
+                                if row_index >= rows {
+                                    from.0[row_index - rows][column_index]
+                                } else {
+                                    to.0[row_index + H - rows][column_index]
+                                }
+                            }
+                        };
+                    }
+                }
+            }
+            Self::Dissolve { seed } => {
+                // A pixel switches once the step passes its pseudo-random rank in 0..256.
+                let threshold = (step * 256 / steps) as u32;
+                for (row_index, row) in frame.0.iter_mut().enumerate() {
+                    for (column_index, pixel) in row.iter_mut().enumerate() {
+                        let rank = pixel_hash(seed, row_index, column_index) >> 24;
+                        if rank < threshold {
+                            *pixel = to.0[row_index][column_index];
+                        }
+                    }
+                }
+            }
+        }
+        frame
+    }
+}
+
+/// Mix a seed and a pixel position into well-scattered bits.
+const fn pixel_hash(seed: u32, row_index: usize, column_index: usize) -> u32 {
+    let mut x = seed ^ ((row_index as u32) << 16) ^ column_index as u32;
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7FEB_352D);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846C_A68B);
+    x ^= x >> 16;
+    x
+}
+
+/// The `steps` frames of a transition from `from` to `to`, each shown for `total / steps`.
+///
+/// The frames start one step past `from` and end exactly on `to`, so writing `from` first and
+/// then every frame plays the whole transition. See the [module documentation](self) for an
+/// example.
+///
+/// # Panics
+///
+/// Panics if `steps` is zero or `total / steps` is zero.
+#[must_use]
+pub fn transition<const W: usize, const H: usize>(
+    from: &Frame<W, H>,
+    to: &Frame<W, H>,
+    kind: Transition,
+    steps: usize,
+    total: Duration,
+) -> TransitionFrames<W, H> {
+    assert!(steps > 0, "transition steps must be positive");
+    let frame_duration = total / steps as u32;
+    assert!(
+        frame_duration.as_micros() > 0,
+        "transition frame duration must be positive"
+    );
+    TransitionFrames {
+        from: *from,
+        to: *to,
+        kind,
+        steps,
+        next_step: 1,
+        frame_duration,
+    }
+}
+
+/// Iterator over `(frame, duration)` pairs. See [`transition`].
+#[derive(Clone, Debug)]
+pub struct TransitionFrames<const W: usize, const H: usize> {
+    from: Frame<W, H>,
+    to: Frame<W, H>,
+    kind: Transition,
+    steps: usize,
+    next_step: usize,
+    frame_duration: Duration,
+}
+
+impl<const W: usize, const H: usize> Iterator for TransitionFrames<W, H> {
+    type Item = (Frame<W, H>, Duration);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_step > self.steps {
+            return None;
+        }
+        let frame = self
+            .kind
+            .render(&self.from, &self.to, self.next_step, self.steps);
+        self.next_step += 1;
+        Some((frame, self.frame_duration))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.steps + 1 - self.next_step;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const W: usize, const H: usize> ExactSizeIterator for TransitionFrames<W, H> {}
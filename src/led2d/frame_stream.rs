@@ -0,0 +1,676 @@
+//! Live frame streaming: push [`Frame`]s to a display over the network in real time.
+//!
+//! A host program (such as `cargo xtask stream-frames`) sends one packet per frame over TCP
+//! or UDP. Each packet carries a sequence number, so UDP frames that arrive late are dropped
+//! instead of flickering back. Frames can be sent raw or run-length compressed. When frames
+//! stop arriving, the receiver reports it so the device can fall back to a local animation.
+//!
+//! With the `wifi` feature, [`run_frame_stream`] listens on [`PORT`] for both transports.
+//! [`FrameReceiver`] and [`FrameDecoder`] decode packets and work anywhere, including in host
+//! tests.
+//!
+//! # Format
+//!
+//! All multi-byte values are little-endian.
+//!
+//! | Bytes | Field |
+//! |---|---|
+//! | 4 | Magic `DKS1` |
+//! | 4 | Sequence number, incremented (wrapping) for each frame |
+//! | 1 | Width |
+//! | 1 | Height |
+//! | 1 | [`Encoding`] |
+//! | 1 | Reserved, 0 |
+//! | 2 | Payload length in bytes |
+//!
+//! The payload covers exactly `W * H` pixels in row-major order:
+//!
+//! - [`Encoding::Raw`]: `r, g, b` for each pixel.
+//! - [`Encoding::RunLength`]: `(run, r, g, b)` groups, with `run` 1..=255.
+//!
+//! Over UDP, each datagram holds one packet, so a packet must fit in
+//! [`MAX_DATAGRAM_LEN`] bytes. Over TCP, packets follow each other on the connection and a
+//! new connection restarts sequence numbering.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::frame_stream::FrameReceiver;
+//! use smart_leds::RGB8;
+//!
+//! // A 2x1 frame, run-length encoded: two blue pixels.
+//! const PACKET: [u8; 18] = [
+//!     b'D', b'K', b'S', b'1', 7, 0, 0, 0, // sequence 7
+//!     2, 1, 1, 0, 4, 0, // 2x1, run-length, 4 payload bytes
+//!     2, 0, 0, 255, // two pixels of blue
+//! ];
+//!
+//! fn decode() {
+//!     let mut receiver = FrameReceiver::<2, 1>::new();
+//!     let frame = receiver.receive_packet(&PACKET).unwrap().expect("frame is new");
+//!     assert_eq!(frame[0], [RGB8::new(0, 0, 255); 2]);
+//!
+//!     // The same sequence number again is stale.
+//!     assert!(receiver.receive_packet(&PACKET).unwrap().is_none());
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use derive_more::derive::Display;
+use smart_leds::RGB8;
+
+use super::Frame;
+
+/// The 4-byte magic number that starts every packet.
+pub const MAGIC: [u8; 4] = *b"DKS1";
+
+/// Length of the packet header in bytes.
+pub const HEADER_LEN: usize = 14;
+
+/// TCP and UDP port the receiver listens on.
+pub const PORT: u16 = 6868;
+
+/// Largest packet that fits in one UDP datagram without IP fragmentation.
+pub const MAX_DATAGRAM_LEN: usize = 1472;
+
+/// How a packet's pixels are stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Encoding {
+    /// `r, g, b` for every pixel.
+    Raw = 0,
+    /// `(run, r, g, b)` groups.
+    RunLength = 1,
+}
+
+impl Encoding {
+    const fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Self::Raw),
+            1 => Some(Self::RunLength),
+            _ => None,
+        }
+    }
+
+    /// Bytes per raw pixel or run-length group.
+    const fn unit_len(self) -> usize {
+        match self {
+            Self::Raw => 3,
+            Self::RunLength => 4,
+        }
+    }
+}
+
+/// Why a packet was rejected.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum StreamError {
+    #[display("packet does not start with DKS1")]
+    BadMagic,
+    #[display("unknown encoding {_0}")]
+    UnknownEncoding(u8),
+    #[display("frame is {width}x{height}, display is {expected_width}x{expected_height}")]
+    SizeMismatch {
+        width: u8,
+        height: u8,
+        expected_width: usize,
+        expected_height: usize,
+    },
+    #[display("packet is shorter or longer than its header says")]
+    LengthMismatch,
+    #[display("pixel data does not cover the frame exactly")]
+    BadPixelData,
+}
+
+/// A parsed packet header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamHeader {
+    /// Frame sequence number.
+    pub sequence: u32,
+    /// Frame width in pixels.
+    pub width: u8,
+    /// Frame height in pixels.
+    pub height: u8,
+    /// How the payload is stored.
+    pub encoding: Encoding,
+    /// Payload length in bytes.
+    pub payload_len: u16,
+}
+
+impl StreamHeader {
+    /// Parse the first [`HEADER_LEN`] bytes of a packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the magic number or encoding is wrong.
+    pub fn parse(bytes: &[u8; HEADER_LEN]) -> Result<Self, StreamError> {
+        if bytes[0..4] != MAGIC {
+            return Err(StreamError::BadMagic);
+        }
+        let encoding =
+            Encoding::from_byte(bytes[10]).ok_or(StreamError::UnknownEncoding(bytes[10]))?;
+        Ok(Self {
+            sequence: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            width: bytes[8],
+            height: bytes[9],
+            encoding,
+            payload_len: u16::from_le_bytes([bytes[12], bytes[13]]),
+        })
+    }
+}
+
+/// The packet currently being decoded.
+#[derive(Clone, Copy, Debug)]
+struct Pending {
+    sequence: u32,
+    encoding: Encoding,
+    remaining_len: usize,
+    pixel_index: usize,
+    unit: [u8; 4],
+    unit_len: usize,
+}
+
+/// Decodes one packet at a time into a frame, as its pieces arrive.
+///
+/// Call [`begin`](Self::begin), [`payload`](Self::payload), and [`finish`](Self::finish),
+/// then pass the result to [`FrameReceiver::accept`]. Each transport that delivers packets
+/// in pieces needs its own decoder, so a packet from one can't disturb another's
+/// half-decoded frame.
+pub struct FrameDecoder<const W: usize, const H: usize> {
+    decoding: Frame<W, H>,
+    pending: Option<Pending>,
+}
+
+impl<const W: usize, const H: usize> Default for FrameDecoder<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> FrameDecoder<W, H> {
+    /// Create a decoder with no packet begun.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            decoding: Frame::new(),
+            pending: None,
+        }
+    }
+
+    /// Start decoding a packet with this header, abandoning any packet in progress.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame size doesn't match the display.
+    pub fn begin(&mut self, header: &StreamHeader) -> Result<(), StreamError> {
+        self.pending = None;
+        if usize::from(header.width) != W || usize::from(header.height) != H {
+            return Err(StreamError::SizeMismatch {
+                width: header.width,
+                height: header.height,
+                expected_width: W,
+                expected_height: H,
+            });
+        }
+        self.pending = Some(Pending {
+            sequence: header.sequence,
+            encoding: header.encoding,
+            remaining_len: usize::from(header.payload_len),
+            pixel_index: 0,
+            unit: [0; 4],
+            unit_len: 0,
+        });
+        Ok(())
+    }
+
+    /// Decode the next piece of the current packet's payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload runs past the frame or the header's length. The
+    /// packet is then abandoned.
+    pub fn payload(&mut self, bytes: &[u8]) -> Result<(), StreamError> {
+        let result = self.decode(bytes);
+        if result.is_err() {
+            self.pending = None;
+        }
+        result
+    }
+
+    /// Complete the current packet, returning its sequence number and frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no packet was begun or the payload did not cover the frame
+    /// exactly.
+    pub fn finish(&mut self) -> Result<(u32, &Frame<W, H>), StreamError> {
+        let pending = self.pending.take().ok_or(StreamError::LengthMismatch)?;
+        if pending.remaining_len != 0 {
+            return Err(StreamError::LengthMismatch);
+        }
+        if pending.pixel_index != W * H || pending.unit_len != 0 {
+            return Err(StreamError::BadPixelData);
+        }
+        Ok((pending.sequence, &self.decoding))
+    }
+
+    /// Abandon any packet in progress.
+    pub const fn reset(&mut self) {
+        self.pending = None;
+    }
+
+    fn decode(&mut self, bytes: &[u8]) -> Result<(), StreamError> {
+        let pending = self.pending.as_mut().ok_or(StreamError::LengthMismatch)?;
+        pending.remaining_len = pending
+            .remaining_len
+            .checked_sub(bytes.len())
+            .ok_or(StreamError::LengthMismatch)?;
+        let unit_len = pending.encoding.unit_len();
+        for &byte in bytes {
+            pending.unit[pending.unit_len] = byte;
+            pending.unit_len += 1;
+            if pending.unit_len < unit_len {
+                continue;
+            }
+            pending.unit_len = 0;
+            let (run, [r, g, b]) = match pending.encoding {
+                Encoding::Raw => (1, [pending.unit[0], pending.unit[1], pending.unit[2]]),
+                Encoding::RunLength => (
+                    usize::from(pending.unit[0]),
+                    [pending.unit[1], pending.unit[2], pending.unit[3]],
+                ),
+            };
+            let end = pending.pixel_index + run;
+            if run == 0 || end > W * H {
+                return Err(StreamError::BadPixelData);
+            }
+            for pixel_index in pending.pixel_index..end {
+                self.decoding[pixel_index / W][pixel_index % W] = RGB8::new(r, g, b);
+            }
+            pending.pixel_index = end;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes stream packets into frames, dropping any older than the last one shown.
+///
+/// Feed a whole packet with [`receive_packet`](Self::receive_packet), or, when packets
+/// arrive in pieces as they do over TCP, call [`begin`](Self::begin),
+/// [`payload`](Self::payload), and [`finish`](Self::finish). To take packets from several
+/// transports at once, decode each with its own [`FrameDecoder`] and hand the results to
+/// [`accept`](Self::accept).
+pub struct FrameReceiver<const W: usize, const H: usize> {
+    frame: Frame<W, H>,
+    decoder: FrameDecoder<W, H>,
+    last_sequence: Option<u32>,
+}
+
+impl<const W: usize, const H: usize> Default for FrameReceiver<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> FrameReceiver<W, H> {
+    /// Create a receiver that accepts any sequence number first.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            frame: Frame::new(),
+            decoder: FrameDecoder::new(),
+            last_sequence: None,
+        }
+    }
+
+    /// The most recently completed frame (blank before the first).
+    #[must_use]
+    pub const fn frame(&self) -> &Frame<W, H> {
+        &self.frame
+    }
+
+    /// Forget the last sequence number, so the next frame is accepted whatever its number.
+    /// Use when a sender may have restarted, such as on a new connection.
+    pub const fn reset(&mut self) {
+        self.decoder.reset();
+        self.last_sequence = None;
+    }
+
+    /// Decode one complete packet. Returns the new frame, or `None` if the packet's
+    /// sequence number is not newer than the last frame's.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the packet is malformed or the wrong size for this display.
+    pub fn receive_packet(&mut self, packet: &[u8]) -> Result<Option<&Frame<W, H>>, StreamError> {
+        let Some((header, payload)) = packet.split_first_chunk::<HEADER_LEN>() else {
+            return Err(StreamError::LengthMismatch);
+        };
+        let header = StreamHeader::parse(header)?;
+        if payload.len() != usize::from(header.payload_len) {
+            return Err(StreamError::LengthMismatch);
+        }
+        self.begin(&header)?;
+        self.payload(payload)?;
+        self.finish()
+    }
+
+    /// Start decoding a packet with this header.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the frame size doesn't match the display.
+    pub fn begin(&mut self, header: &StreamHeader) -> Result<(), StreamError> {
+        self.decoder.begin(header)
+    }
+
+    /// Decode the next piece of the current packet's payload.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the payload runs past the frame or the header's length. The
+    /// packet is then abandoned.
+    pub fn payload(&mut self, bytes: &[u8]) -> Result<(), StreamError> {
+        self.decoder.payload(bytes)
+    }
+
+    /// Complete the current packet. Returns the new frame, or `None` if the packet's
+    /// sequence number is not newer than the last frame's.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no packet was begun or the payload did not cover the frame
+    /// exactly.
+    pub fn finish(&mut self) -> Result<Option<&Frame<W, H>>, StreamError> {
+        let (sequence, decoded) = self.decoder.finish()?;
+        Ok(accept_frame(
+            &mut self.frame,
+            &mut self.last_sequence,
+            sequence,
+            decoded,
+        ))
+    }
+
+    /// Take a frame decoded elsewhere, such as by a [`FrameDecoder`] of another transport.
+    /// Returns the new frame, or `None` if `sequence` is not newer than the last frame's.
+    pub fn accept(&mut self, sequence: u32, frame: &Frame<W, H>) -> Option<&Frame<W, H>> {
+        accept_frame(&mut self.frame, &mut self.last_sequence, sequence, frame)
+    }
+}
+
+/// Copy `frame` into `shown` if `sequence` is newer than `last_sequence`.
+fn accept_frame<'a, const W: usize, const H: usize>(
+    shown: &'a mut Frame<W, H>,
+    last_sequence: &mut Option<u32>,
+    sequence: u32,
+    frame: &Frame<W, H>,
+) -> Option<&'a Frame<W, H>> {
+    if let Some(last_sequence) = *last_sequence {
+        // Newer means ahead by less than half the sequence space, so numbering can wrap.
+        if sequence.wrapping_sub(last_sequence).cast_signed() <= 0 {
+            return None;
+        }
+    }
+    *last_sequence = Some(sequence);
+    *shown = *frame;
+    Some(shown)
+}
+
+/// What [`run_frame_stream`] reports to its handler.
+#[derive(Clone, Copy, Debug)]
+pub enum StreamEvent<const W: usize, const H: usize> {
+    /// A new frame arrived; show it.
+    Frame(Frame<W, H>),
+    /// Frames stopped arriving; show something else until the next [`Frame`](Self::Frame).
+    Stopped,
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+mod wifi_impl {
+    use core::cell::RefCell;
+    use core::convert::Infallible;
+
+    use defmt::{Display2Format, info, warn};
+    use embassy_futures::select::{Either, Either3, select, select3};
+    use embassy_net::Stack;
+    use embassy_net::tcp::TcpSocket;
+    use embassy_net::udp::{PacketMetadata, UdpSocket};
+    use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+    use embassy_sync::signal::Signal;
+    use embassy_time::{Duration, Timer};
+    use embedded_io_async::Read;
+
+    use super::{
+        FrameDecoder, FrameReceiver, HEADER_LEN, MAX_DATAGRAM_LEN, PORT, StreamError, StreamEvent,
+        StreamHeader,
+    };
+    use crate::{Error, Result};
+
+    /// Packets the UDP socket can queue while a frame is being shown.
+    const QUEUED_DATAGRAMS: usize = 2;
+    /// Payload bytes read from TCP at a time.
+    const TCP_CHUNK_LEN: usize = 256;
+    /// How often to probe an idle TCP sender, and how long to wait before dropping it.
+    const TCP_KEEP_ALIVE: Duration = Duration::from_secs(1);
+    const TCP_TIMEOUT: Duration = Duration::from_secs(5);
+
+    type Updates = Signal<NoopRawMutex, Update>;
+
+    /// What the transports tell the handler loop.
+    #[derive(Clone, Copy)]
+    enum Update {
+        Frame,
+        Disconnected,
+    }
+
+    /// Receive frames on [`PORT`] over both TCP and UDP and pass each to `handle`.
+    ///
+    /// `handle` gets [`StreamEvent::Stopped`] once when no frame has arrived for
+    /// `stop_after` or a TCP sender disconnects, and [`StreamEvent::Frame`] again when
+    /// frames resume. If frames arrive faster than `handle` shows them, only the newest is
+    /// passed on. One TCP sender is served at a time.
+    ///
+    /// ```no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use panic_probe as _;
+    /// use device_kit::led2d::frame_stream::{StreamEvent, run_frame_stream};
+    /// use device_kit::led2d::{Frame, led2d};
+    /// use device_kit::led_strip::{Current, colors};
+    /// use embassy_time::Duration;
+    ///
+    /// led2d! {
+    ///     pub led12x8,
+    ///     pio: PIO0,
+    ///     pin: PIN_3,
+    ///     dma: DMA_CH1,
+    ///     width: 12,
+    ///     height: 8,
+    ///     led_layout: serpentine_column_major,
+    ///     max_current: Current::Milliamps(500),
+    ///     max_frames: 2,
+    ///     font: Font3x4Trim,
+    /// }
+    ///
+    /// async fn network_display(
+    ///     stack: &'static embassy_net::Stack<'static>,
+    ///     display: &Led12x8,
+    /// ) -> device_kit::Result<core::convert::Infallible> {
+    ///     // Blink between red and blue while nothing is streaming.
+    ///     let idle = [
+    ///         (Frame::filled(colors::RED), Duration::from_millis(500)),
+    ///         (Frame::filled(colors::BLUE), Duration::from_millis(500)),
+    ///     ];
+    ///     display.animate(idle).await?;
+    ///     run_frame_stream(stack, Duration::from_secs(2), async |event| match event {
+    ///         StreamEvent::Frame(frame) => display.write_frame(frame).await,
+    ///         StreamEvent::Stopped => display.animate(idle).await,
+    ///     })
+    ///     .await
+    /// }
+    /// # #[embassy_executor::main]
+    /// # async fn main(_spawner: embassy_executor::Spawner) {}
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the UDP port can't be bound or `handle` returns one.
+    pub async fn run_frame_stream<const W: usize, const H: usize>(
+        stack: &'static Stack<'static>,
+        stop_after: Duration,
+        handle: impl AsyncFnMut(StreamEvent<W, H>) -> Result<()>,
+    ) -> Result<Infallible> {
+        let mut udp_meta = [PacketMetadata::EMPTY; QUEUED_DATAGRAMS];
+        let mut udp_rx = [0; MAX_DATAGRAM_LEN * QUEUED_DATAGRAMS];
+        let mut udp_tx_meta = [PacketMetadata::EMPTY; 1];
+        let mut udp_tx = [0; 0];
+        let mut udp = UdpSocket::new(
+            *stack,
+            &mut udp_meta,
+            &mut udp_rx,
+            &mut udp_tx_meta,
+            &mut udp_tx,
+        );
+        udp.bind(PORT).map_err(Error::UdpBind)?;
+
+        let mut tcp_rx = [0; MAX_DATAGRAM_LEN * 2];
+        let mut tcp_tx = [0; 0];
+        let mut tcp = TcpSocket::new(*stack, &mut tcp_rx, &mut tcp_tx);
+        tcp.set_keep_alive(Some(TCP_KEEP_ALIVE));
+        tcp.set_timeout(Some(TCP_TIMEOUT));
+
+        info!("Frame stream listening on TCP and UDP port {}", PORT);
+
+        // The transports share only the sequence number and finished frame. TCP decodes
+        // across awaits, so it keeps its packet in progress in a decoder of its own.
+        let receiver = RefCell::new(FrameReceiver::<W, H>::new());
+        let mut tcp_decoder = FrameDecoder::<W, H>::new();
+        let updates = Updates::new();
+        match select3(
+            receive_udp(&mut udp, &receiver, &updates),
+            receive_tcp(&mut tcp, &mut tcp_decoder, &receiver, &updates),
+            handle_updates(&receiver, &updates, stop_after, handle),
+        )
+        .await
+        {
+            Either3::First(never) | Either3::Second(never) => never,
+            Either3::Third(result) => result,
+        }
+    }
+
+    async fn receive_udp<const W: usize, const H: usize>(
+        udp: &mut UdpSocket<'_>,
+        receiver: &RefCell<FrameReceiver<W, H>>,
+        updates: &Updates,
+    ) -> ! {
+        let mut packet = [0; MAX_DATAGRAM_LEN];
+        loop {
+            let Ok((len, _from)) = udp.recv_from(&mut packet).await else {
+                warn!("Frame stream: dropped an oversized datagram");
+                continue;
+            };
+            match receiver.borrow_mut().receive_packet(&packet[..len]) {
+                Ok(Some(_)) => updates.signal(Update::Frame),
+                Ok(None) => {}
+                Err(err) => warn!("Frame stream: {}", Display2Format(&err)),
+            }
+        }
+    }
+
+    async fn receive_tcp<const W: usize, const H: usize>(
+        tcp: &mut TcpSocket<'_>,
+        decoder: &mut FrameDecoder<W, H>,
+        receiver: &RefCell<FrameReceiver<W, H>>,
+        updates: &Updates,
+    ) -> ! {
+        loop {
+            if let Err(err) = tcp.accept(PORT).await {
+                warn!("Frame stream: accept failed: {:?}", err);
+                Timer::after_millis(500).await;
+                continue;
+            }
+            info!("Frame stream: TCP sender connected");
+            receiver.borrow_mut().reset();
+            // Packets follow each other on the connection, so after a bad one the stream
+            // can't be resynchronized.
+            loop {
+                match receive_tcp_packet(tcp, decoder, receiver).await {
+                    Ok(true) => updates.signal(Update::Frame),
+                    Ok(false) => {}
+                    Err(Some(err)) => {
+                        warn!("Frame stream: {}", Display2Format(&err));
+                        break;
+                    }
+                    Err(None) => break,
+                }
+            }
+            info!("Frame stream: TCP sender disconnected");
+            tcp.abort();
+            let _ = tcp.flush().await;
+            updates.signal(Update::Disconnected);
+        }
+    }
+
+    /// Read one packet from `tcp`. Returns whether it held a new frame, or an error that is
+    /// `None` if the connection closed.
+    async fn receive_tcp_packet<const W: usize, const H: usize>(
+        tcp: &mut TcpSocket<'_>,
+        decoder: &mut FrameDecoder<W, H>,
+        receiver: &RefCell<FrameReceiver<W, H>>,
+    ) -> core::result::Result<bool, Option<StreamError>> {
+        let mut header = [0; HEADER_LEN];
+        tcp.read_exact(&mut header).await.map_err(|_| None)?;
+        let header = StreamHeader::parse(&header)?;
+        decoder.begin(&header)?;
+
+        let mut chunk = [0; TCP_CHUNK_LEN];
+        let mut remaining_len = usize::from(header.payload_len);
+        while remaining_len > 0 {
+            let chunk = &mut chunk[..remaining_len.min(TCP_CHUNK_LEN)];
+            tcp.read_exact(chunk).await.map_err(|_| None)?;
+            decoder.payload(chunk)?;
+            remaining_len -= chunk.len();
+        }
+        let (sequence, frame) = decoder.finish()?;
+        Ok(receiver.borrow_mut().accept(sequence, frame).is_some())
+    }
+
+    async fn handle_updates<const W: usize, const H: usize>(
+        receiver: &RefCell<FrameReceiver<W, H>>,
+        updates: &Updates,
+        stop_after: Duration,
+        mut handle: impl AsyncFnMut(StreamEvent<W, H>) -> Result<()>,
+    ) -> Result<Infallible> {
+        let mut streaming = false;
+        loop {
+            let update = if streaming {
+                match select(updates.wait(), Timer::after(stop_after)).await {
+                    Either::First(update) => update,
+                    Either::Second(()) => Update::Disconnected,
+                }
+            } else {
+                updates.wait().await
+            };
+            match update {
+                Update::Frame => {
+                    streaming = true;
+                    let frame = *receiver.borrow().frame();
+                    handle(StreamEvent::Frame(frame)).await?;
+                }
+                Update::Disconnected if streaming => {
+                    streaming = false;
+                    // Let a restarted sender begin again from any sequence number.
+                    receiver.borrow_mut().reset();
+                    handle(StreamEvent::Stopped).await?;
+                }
+                Update::Disconnected => {}
+            }
+        }
+    }
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+pub use wifi_impl::run_frame_stream;
@@ -0,0 +1,243 @@
+//! Scrolling marquee text for [`Led2d`](super::Led2d) displays.
+//!
+//! `write_text` clips whatever does not fit the display. A [`TextScroll`] instead moves the
+//! whole text across the display one pixel at a time, so strings of any length can be read
+//! on a small matrix. Text enters from one edge and leaves through the opposite one; newlines
+//! start new lines, which suits vertical (credits-style) scrolling.
+//!
+//! Most users call the generated device's `scroll_text` method, which scrolls in the device's
+//! background task using its configured font. In [`ScrollMode::Once`], await
+//! `wait_scroll_done` to learn when the text has scrolled off the display.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::Frame;
+//! use device_kit::led2d::Led2dFont;
+//! use device_kit::led2d::scroll::{ScrollDirection, ScrollMode, ScrollOptions, TextScroll};
+//! use smart_leds::colors;
+//!
+//! fn first_frames() {
+//!     let options = ScrollOptions {
+//!         direction: ScrollDirection::Left,
+//!         pixels_per_second: 20,
+//!         mode: ScrollMode::Once,
+//!     };
+//!     let mut scroll = TextScroll::new(
+//!         "Hello, world!",
+//!         &[colors::RED, colors::GREEN],
+//!         Led2dFont::Font3x4Trim,
+//!         options,
+//!     );
+//!     let mut frame = Frame::<12, 4>::new();
+//!     // Each call draws the next position and says how long to show it.
+//!     while let Some(_duration) = scroll.render_next(&mut frame) {}
+//! }
+//! ```
+
+use embassy_time::Duration;
+use embedded_graphics::{draw_target::DrawTarget, pixelcolor::Rgb888, prelude::*};
+use heapless::{String, Vec};
+use smart_leds::RGB8;
+
+use super::TextFont;
+
+/// Longest text, in bytes, that a [`TextScroll`] can hold.
+pub const MAX_SCROLL_TEXT_LEN: usize = 256;
+
+/// Most per-character colors that a [`TextScroll`] can hold.
+pub const MAX_SCROLL_COLORS: usize = 16;
+
+/// Which way the text moves.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum ScrollDirection {
+    /// Enter from the right edge and move left (a classic marquee).
+    Left,
+    /// Enter from the left edge and move right.
+    Right,
+    /// Enter from the bottom edge and move up.
+    Up,
+    /// Enter from the top edge and move down.
+    Down,
+}
+
+/// What happens once the text has scrolled completely off the display.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum ScrollMode {
+    /// Start again from the entry edge, until interrupted by another command.
+    Loop,
+    /// Stop, leaving the display blank, and signal that scrolling is done.
+    Once,
+}
+
+/// Direction, speed, and repetition for a [`TextScroll`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub struct ScrollOptions {
+    /// Which way the text moves.
+    pub direction: ScrollDirection,
+    /// Speed of the text. It moves one pixel per frame, so this is also the frame rate.
+    pub pixels_per_second: u32,
+    /// Whether to loop or scroll through just once.
+    pub mode: ScrollMode,
+}
+
+impl ScrollOptions {
+    /// Default options: scroll left at 10 pixels per second, looping.
+    pub const DEFAULT: Self = Self {
+        direction: ScrollDirection::Left,
+        pixels_per_second: 10,
+        mode: ScrollMode::Loop,
+    };
+}
+
+impl Default for ScrollOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Text, colors, and the current position of a scrolling marquee.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Debug)]
+pub struct TextScroll {
+    text: String<MAX_SCROLL_TEXT_LEN>,
+    colors: Vec<RGB8, MAX_SCROLL_COLORS>,
+    font: TextFont,
+    options: ScrollOptions,
+    text_size: Size,
+    // Pixels moved so far, in 1..=travel; 0 once a one-shot scroll has finished.
+    offset: i32,
+}
+
+impl TextScroll {
+    /// Prepare `text` to scroll from its entry edge.
+    ///
+    /// `font` is a built-in [`Led2dFont`](super::Led2dFont) or a proportional
+    /// [`BitmapFont`](super::bitmap_font::BitmapFont). Colors cycle per character, skipping
+    /// newlines; with no colors the text is white.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `text` is longer than [`MAX_SCROLL_TEXT_LEN`] bytes, `colors` has more than
+    /// [`MAX_SCROLL_COLORS`] entries, or `pixels_per_second` is zero.
+    #[must_use]
+    pub fn new(
+        text: &str,
+        colors: &[RGB8],
+        font: impl Into<TextFont>,
+        options: ScrollOptions,
+    ) -> Self {
+        assert!(
+            options.pixels_per_second > 0,
+            "scroll speed must be positive"
+        );
+        let text = String::try_from(text).expect("scroll text fits in MAX_SCROLL_TEXT_LEN");
+        let colors = Vec::from_slice(colors).expect("scroll colors fit in MAX_SCROLL_COLORS");
+        let font = font.into();
+        let line_count = text.split('\n').count() as u32;
+        let longest_line = text
+            .split('\n')
+            .map(|line| font.line_width(line))
+            .max()
+            .unwrap_or(0);
+        Self {
+            text,
+            colors,
+            font,
+            options,
+            text_size: Size::new(longest_line, line_count * font.line_height()),
+            offset: 1,
+        }
+    }
+
+    /// The options this scroll was created with.
+    #[must_use]
+    pub const fn options(&self) -> ScrollOptions {
+        self.options
+    }
+
+    /// Size of the laid-out text in pixels: the longest line by the number of lines.
+    #[must_use]
+    pub const fn text_size(&self) -> Size {
+        self.text_size
+    }
+
+    /// How long each one-pixel step is shown.
+    #[must_use]
+    pub fn frame_duration(&self) -> Duration {
+        Duration::from_micros(1_000_000 / u64::from(self.options.pixels_per_second))
+    }
+
+    /// Clear `target`, draw the text at its current position, and step one pixel on.
+    ///
+    /// Returns how long to show the frame, or `None` once a [`ScrollMode::Once`] scroll has
+    /// finished (the previous frame, with the text just gone, was the last). The first frame
+    /// shows one pixel of the text; in [`ScrollMode::Loop`] a blank frame separates passes.
+    pub fn render_next<D>(&mut self, target: &mut D) -> Option<Duration>
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        if self.offset == 0 {
+            return None;
+        }
+        let display = target.bounding_box().size;
+        target.clear(Rgb888::BLACK).ok();
+        self.draw_at(target, self.origin(display));
+
+        let travel = self.travel(display);
+        self.offset = if self.offset < travel {
+            self.offset + 1
+        } else {
+            match self.options.mode {
+                ScrollMode::Loop => 1,
+                ScrollMode::Once => 0,
+            }
+        };
+        Some(self.frame_duration())
+    }
+
+    /// Move back to the entry edge, so even a finished one-shot scroll plays again.
+    pub fn restart(&mut self) {
+        self.offset = 1;
+    }
+
+    /// Pixels moved from just outside the entry edge to just outside the exit edge.
+    fn travel(&self, display: Size) -> i32 {
+        match self.options.direction {
+            ScrollDirection::Left | ScrollDirection::Right => {
+                (display.width + self.text_size.width) as i32
+            }
+            ScrollDirection::Up | ScrollDirection::Down => {
+                (display.height + self.text_size.height) as i32
+            }
+        }
+    }
+
+    /// Top-left corner of the text at the current offset.
+    fn origin(&self, display: Size) -> Point {
+        let offset = self.offset;
+        match self.options.direction {
+            ScrollDirection::Left => Point::new(display.width as i32 - offset, 0),
+            ScrollDirection::Right => Point::new(offset - self.text_size.width as i32, 0),
+            ScrollDirection::Up => Point::new(0, display.height as i32 - offset),
+            ScrollDirection::Down => Point::new(0, offset - self.text_size.height as i32),
+        }
+    }
+
+    fn draw_at<D>(&self, target: &mut D, origin: Point)
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let line_height = self.font.line_height() as i32;
+        let mut color_index: usize = 0;
+        for (line_index, line) in self.text.split('\n').enumerate() {
+            let line_origin = origin + Point::new(0, line_index as i32 * line_height);
+            self.font
+                .draw_line(target, line, line_origin, &self.colors, &mut color_index);
+        }
+    }
+}
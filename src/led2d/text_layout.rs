@@ -0,0 +1,194 @@
+//! Aligned, wrapped, multi-line text for [`Led2d`](super::Led2d) displays.
+//!
+//! `write_text` draws from the top-left corner and clips what doesn't fit. A [`TextLayout`]
+//! instead word-wraps text to the display width, aligns each line left, center, or right,
+//! positions the block at the top, middle, or bottom, and adds extra space between lines.
+//! Explicit `\n` newlines always start a new line.
+//!
+//! Use the generated device's `write_text_layout` method, or [`TextLayout::draw`] to render
+//! into a [`Frame`](super::Frame) (or any `embedded-graphics` draw target).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::text_layout::{HorizontalAlign, TextLayout, VerticalAlign};
+//! use device_kit::led2d::{Frame, Led2dFont};
+//! use smart_leds::colors;
+//!
+//! fn weather() -> Frame<24, 16> {
+//!     let layout = TextLayout {
+//!         horizontal: HorizontalAlign::Center,
+//!         vertical: VerticalAlign::Middle,
+//!         line_spacing: 1,
+//!         ..TextLayout::DEFAULT
+//!     };
+//!     let mut frame = Frame::new();
+//!     layout.draw(
+//!         &mut frame,
+//!         Led2dFont::Font3x5Trim,
+//!         "12:45\nRain 60%",
+//!         &[colors::WHITE, colors::CYAN],
+//!     );
+//!     frame
+//! }
+//! ```
+
+use embedded_graphics::{draw_target::DrawTarget, pixelcolor::Rgb888, prelude::*};
+use smart_leds::RGB8;
+
+use super::TextFont;
+
+/// Where each line sits horizontally.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum HorizontalAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// Where the block of lines sits vertically.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum VerticalAlign {
+    Top,
+    Middle,
+    Bottom,
+}
+
+/// Alignment, wrapping, and line spacing for multi-line text.
+///
+/// See the [module documentation](self) for an example.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub struct TextLayout {
+    /// Where each line sits horizontally.
+    pub horizontal: HorizontalAlign,
+    /// Where the block of lines sits vertically.
+    pub vertical: VerticalAlign,
+    /// Break lines at spaces (or mid-word, for words that are too long) to fit the width.
+    pub wrap: bool,
+    /// Extra pixels between lines; negative values pull lines closer.
+    pub line_spacing: i32,
+}
+
+impl TextLayout {
+    /// Default layout: top-left, word-wrapped, no extra line spacing.
+    pub const DEFAULT: Self = Self {
+        horizontal: HorizontalAlign::Left,
+        vertical: VerticalAlign::Top,
+        wrap: true,
+        line_spacing: 0,
+    };
+
+    /// Draw `text` into `target` with this layout.
+    ///
+    /// `colors` cycle per character across all lines, skipping newlines and the spaces that
+    /// wrapping removes; with no colors the text is white. Text that still doesn't fit is
+    /// clipped at the target's edges.
+    pub fn draw<D>(&self, target: &mut D, font: impl Into<TextFont>, text: &str, colors: &[RGB8])
+    where
+        D: DrawTarget<Color = Rgb888>,
+    {
+        let font = font.into();
+        let display = target.bounding_box().size;
+        let max_width = self.wrap.then_some(display.width);
+        let line_count = wrap_lines(text, font, max_width).count() as i32;
+        let line_pitch = font.line_height() as i32 + self.line_spacing;
+        let block_height = line_count * line_pitch - self.line_spacing;
+        let mut y = match self.vertical {
+            VerticalAlign::Top => 0,
+            VerticalAlign::Middle => (display.height as i32 - block_height) / 2,
+            VerticalAlign::Bottom => display.height as i32 - block_height,
+        };
+
+        let mut color_index: usize = 0;
+        for line in wrap_lines(text, font, max_width) {
+            let slack = display.width as i32 - font.line_width(line) as i32;
+            let x = match self.horizontal {
+                HorizontalAlign::Left => 0,
+                HorizontalAlign::Center => slack / 2,
+                HorizontalAlign::Right => slack,
+            };
+            font.draw_line(target, line, Point::new(x, y), colors, &mut color_index);
+            y += line_pitch;
+        }
+    }
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Split `text` into display lines: at every `\n` and, if `max_width` is given, wherever
+/// the next word would not fit.
+///
+/// Lines break at spaces, which are dropped. A word wider than `max_width` is broken
+/// between characters. Lines are slices of `text`, so nothing is allocated.
+pub fn wrap_lines(text: &str, font: impl Into<TextFont>, max_width: Option<u32>) -> WrapLines<'_> {
+    WrapLines {
+        rest: Some(text),
+        font: font.into(),
+        max_width,
+    }
+}
+
+/// Iterator over display lines. See [`wrap_lines`].
+#[derive(Clone, Debug)]
+pub struct WrapLines<'a> {
+    rest: Option<&'a str>,
+    font: TextFont,
+    max_width: Option<u32>,
+}
+
+impl<'a> Iterator for WrapLines<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+        let paragraph_end = rest.find('\n').unwrap_or(rest.len());
+        let paragraph = &rest[..paragraph_end];
+        let after_paragraph = rest.get(paragraph_end + 1..);
+
+        let fits = |end: usize| {
+            self.max_width
+                .is_none_or(|max_width| self.font.line_width(&paragraph[..end]) <= max_width)
+        };
+        if fits(paragraph.len()) {
+            self.rest = after_paragraph;
+            return Some(paragraph);
+        }
+
+        // Find the first character that doesn't fit, remembering the last space before it.
+        let mut last_space = None;
+        let mut overflow = paragraph.len();
+        for (index, ch) in paragraph.char_indices() {
+            if !fits(index + ch.len_utf8()) {
+                overflow = index;
+                break;
+            }
+            if ch == ' ' {
+                last_space = Some(index);
+            }
+        }
+
+        let line_end = if paragraph[overflow..].starts_with(' ') {
+            overflow
+        } else if let Some(space) = last_space.filter(|space| *space > 0) {
+            space
+        } else {
+            // No space to break at: break mid-word, taking at least one character.
+            overflow.max(paragraph.chars().next().map_or(0, char::len_utf8))
+        };
+        // The next line starts after any spaces at the break.
+        let next_start = paragraph.len() - paragraph[line_end..].trim_start_matches(' ').len();
+        self.rest = if next_start == paragraph.len() {
+            after_paragraph
+        } else {
+            Some(&rest[next_start..])
+        };
+        Some(paragraph[..line_end].trim_end_matches(' '))
+    }
+}
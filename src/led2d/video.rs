@@ -0,0 +1,292 @@
+//! Compact palette + run-length video format for [`Led2d`](super::Led2d) displays.
+//!
+//! Full-frame literals cost `W * H * 3` bytes per frame. This format stores a shared palette
+//! once and then run-length encodes palette indices, with a reserved index meaning "keep the
+//! previous frame's pixel", so mostly-static video shrinks to a few bytes per frame.
+//!
+//! Generate a video with `cargo xtask video-compress`, embed it with `include_bytes!`, and play
+//! it with the generated device's `animate_video` method. Frames are decoded one at a time
+//! straight from flash; nothing proportional to the frame count is held in RAM.
+//!
+//! # Format
+//!
+//! All multi-byte values are little-endian.
+//!
+//! | Bytes | Field |
+//! |---|---|
+//! | 4 | Magic `DKV1` |
+//! | 1 | Width |
+//! | 1 | Height |
+//! | 2 | Frame count (at least 1) |
+//! | 1 | Palette length `P` (1..=255) |
+//! | `3 * P` | Palette, as `r, g, b` triples |
+//!
+//! Each frame then follows:
+//!
+//! | Bytes | Field |
+//! |---|---|
+//! | 2 | Duration in milliseconds (at least 1) |
+//! | 2 each | `(run, index)` pairs covering exactly `W * H` pixels in row-major order |
+//!
+//! `run` is 1..=255. `index` selects a palette entry, or is [`KEEP_INDEX`] to leave `run`
+//! pixels unchanged from the previous frame. The first frame must not use [`KEEP_INDEX`], so
+//! playback can loop back to it.
+//!
+//! # Example
+//!
+//! Real videos come from `cargo xtask video-compress cat.mp4 cat.dkv --width 12 --height 8`
+//! and `static CAT: Video = Video::new(include_bytes!("../cat.dkv"));`. This one is small
+//! enough to write by hand:
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led2d::Frame;
+//! use device_kit::led2d::video::{KEEP_INDEX, Video};
+//!
+//! // 2x1 pixels, 2 frames, 3 palette entries.
+//! static TINY: Video = Video::new(&[
+//!     b'D', b'K', b'V', b'1', 2, 1, 2, 0, 3,
+//!     255, 0, 0, /**/ 0, 0, 255, /**/ 0, 128, 0, // red, blue, green
+//!     100, 0, /**/ 1, 0, /**/ 1, 1, // 100 ms: red, blue
+//!     100, 0, /**/ 1, KEEP_INDEX, /**/ 1, 2, // 100 ms: (unchanged), green
+//! ]);
+//!
+//! fn last_frame() -> Frame<2, 1> {
+//!     // Red (kept from the first frame), green
+//!     TINY.frames::<2, 1>().last().expect("video has frames").0
+//! }
+//! ```
+
+use embassy_time::Duration;
+use smart_leds::RGB8;
+
+use super::Frame;
+use crate::animation::AnimationSource;
+
+/// The 4-byte magic number that starts every video.
+pub const MAGIC: [u8; 4] = *b"DKV1";
+
+/// Palette index meaning "keep the previous frame's pixel".
+pub const KEEP_INDEX: u8 = 255;
+
+const HEADER_LEN: usize = 9;
+
+/// A compressed video stored in a byte slice (usually `include_bytes!` in flash).
+///
+/// See the [module documentation](self) for the format and an example.
+#[derive(Clone, Copy, Debug)]
+pub struct Video<'a> {
+    data: &'a [u8],
+    width: u8,
+    height: u8,
+    frame_count: u16,
+    palette_len: u8,
+}
+
+impl<'a> Video<'a> {
+    /// Wrap encoded video bytes, checking the header.
+    ///
+    /// Use in a `const` or `static` to check the header at compile time. Frame data is
+    /// checked as it is decoded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the magic number is wrong, the video is empty, or the data is too short to
+    /// hold the palette.
+    #[must_use]
+    pub const fn new(data: &'a [u8]) -> Self {
+        assert!(data.len() >= HEADER_LEN, "video data too short");
+        assert!(
+            data[0] == MAGIC[0]
+                && data[1] == MAGIC[1]
+                && data[2] == MAGIC[2]
+                && data[3] == MAGIC[3],
+            "not a DKV1 video"
+        );
+        let width = data[4];
+        let height = data[5];
+        let frame_count = u16::from_le_bytes([data[6], data[7]]);
+        let palette_len = data[8];
+        assert!(width > 0 && height > 0, "video must have positive size");
+        assert!(frame_count > 0, "video must have at least one frame");
+        assert!(palette_len > 0, "palette must not be empty");
+        assert!(
+            data.len() >= HEADER_LEN + palette_len as usize * 3,
+            "video data too short for palette"
+        );
+        Self {
+            data,
+            width,
+            height,
+            frame_count,
+            palette_len,
+        }
+    }
+
+    /// Width in pixels.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    /// Height in pixels.
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    /// Number of frames in one pass through the video.
+    #[must_use]
+    pub const fn frame_count(&self) -> usize {
+        self.frame_count as usize
+    }
+
+    /// Size of the encoded video in bytes.
+    #[must_use]
+    pub const fn len_bytes(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Start a [`VideoPlayer`] at the first frame.
+    #[must_use]
+    pub const fn player(&self) -> VideoPlayer<'a> {
+        VideoPlayer {
+            video: *self,
+            offset: self.frames_offset(),
+            frame_index: 0,
+        }
+    }
+
+    /// Decode one pass of the video as full frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `W` and `H` don't match the video's size.
+    #[must_use]
+    pub fn frames<const W: usize, const H: usize>(&self) -> VideoFrames<'a, W, H> {
+        assert!(
+            W == self.width() && H == self.height(),
+            "frame size must match video size"
+        );
+        VideoFrames {
+            player: self.player(),
+            frame: Frame::new(),
+            remaining: self.frame_count(),
+        }
+    }
+
+    const fn frames_offset(&self) -> usize {
+        HEADER_LEN + self.palette_len as usize * 3
+    }
+
+    fn palette_color(&self, index: u8) -> RGB8 {
+        assert!(index < self.palette_len, "palette index out of range");
+        let start = HEADER_LEN + usize::from(index) * 3;
+        let rgb = self
+            .data
+            .get(start..start + 3)
+            .expect("palette fits in video data");
+        RGB8::new(rgb[0], rgb[1], rgb[2])
+    }
+
+    fn byte(&self, offset: usize) -> u8 {
+        *self.data.get(offset).expect("video data truncated")
+    }
+}
+
+/// Decodes a [`Video`] frame by frame, looping forever.
+///
+/// Pixels are reported by their row-major index (`row * width + column`), so callers can
+/// write them anywhere—for example, straight into an LED-strip-ordered buffer.
+#[derive(Clone, Copy, Debug)]
+pub struct VideoPlayer<'a> {
+    video: Video<'a>,
+    offset: usize,
+    frame_index: u16,
+}
+
+impl VideoPlayer<'_> {
+    /// Decode the next frame, calling `set_pixel(pixel_index, color)` for every pixel that
+    /// changes, and return how long to show the frame. After the last frame, playback
+    /// restarts at the first.
+    ///
+    /// The first frame sets every pixel, so a buffer updated only by `set_pixel` always holds
+    /// the complete current frame.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the frame data is malformed.
+    pub fn next_frame(&mut self, mut set_pixel: impl FnMut(usize, RGB8)) -> Duration {
+        let video = self.video;
+        let pixel_count = video.width() * video.height();
+        let duration_ms =
+            u16::from_le_bytes([video.byte(self.offset), video.byte(self.offset + 1)]);
+        assert!(duration_ms > 0, "frame duration must be positive");
+        let mut offset = self.offset + 2;
+        let mut pixel_index = 0;
+        while pixel_index < pixel_count {
+            let run = usize::from(video.byte(offset));
+            let index = video.byte(offset + 1);
+            offset += 2;
+            assert!(run > 0, "run length must be positive");
+            assert!(pixel_index + run <= pixel_count, "run overflows frame");
+            if index == KEEP_INDEX {
+                assert!(self.frame_index > 0, "first frame cannot keep pixels");
+            } else {
+                let color = video.palette_color(index);
+                for pixel in pixel_index..pixel_index + run {
+                    set_pixel(pixel, color);
+                }
+            }
+            pixel_index += run;
+        }
+
+        self.frame_index += 1;
+        if self.frame_index == video.frame_count {
+            self.frame_index = 0;
+            self.offset = video.frames_offset();
+        } else {
+            self.offset = offset;
+        }
+        Duration::from_millis(u64::from(duration_ms))
+    }
+}
+
+/// Lets a player drive [`animate_stream`](super::Led2d::animate_stream), for example to
+/// pause a video and later resume it where it left off.
+impl<const W: usize, const H: usize> AnimationSource<Frame<W, H>> for VideoPlayer<'_> {
+    fn next_frame(&mut self, frame: &mut Frame<W, H>) -> Duration {
+        assert!(
+            W == self.video.width() && H == self.video.height(),
+            "frame size must match video size"
+        );
+        VideoPlayer::next_frame(self, |pixel_index, color| {
+            frame[pixel_index / W][pixel_index % W] = color;
+        })
+    }
+}
+
+/// Iterator over one pass of a [`Video`] as full frames. See [`Video::frames`].
+pub struct VideoFrames<'a, const W: usize, const H: usize> {
+    player: VideoPlayer<'a>,
+    frame: Frame<W, H>,
+    remaining: usize,
+}
+
+impl<const W: usize, const H: usize> Iterator for VideoFrames<'_, W, H> {
+    type Item = (Frame<W, H>, Duration);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let frame = &mut self.frame;
+        let duration = self.player.next_frame(|pixel_index, color| {
+            frame[pixel_index / W][pixel_index % W] = color;
+        });
+        Some((self.frame, duration))
+    }
+}
@@ -1,8 +1,19 @@
 //! A device abstraction for WS2812-style LED strips.
 //!
 //! See [`LedStrip`], [`led_strip!`] for single strips, and [`led_strips!`] for managing multiple strips on one PIO.
+//! Brightness can change at runtime; see [`brightness`] and, for ambient light sensing,
+//! [`auto_dim`]. For chips other than GRB WS2812s, including SK6812 RGBW, see [`color_order`];
+//! for clocked APA102, SK9822, and WS2801 strips, see [`clocked`]. [`dither`] smooths slow,
+//! dim fades, and [`calibration`] corrects a strip's white point and color mix.
 
+pub mod auto_dim;
+pub mod brightness;
+pub mod calibration;
+pub mod clocked;
+pub mod color_order;
+pub mod dither;
 pub mod gamma;
+pub mod power;
 
 include!("led_strip/strip.rs");
 #[doc(inline)]
@@ -12,6 +23,8 @@ pub use led_strip;
 pub use led_strips;
 
 /// Used by [`led_strips!`] to budget current for LED strips.
+///
+/// Frames that would draw more than the budget are dimmed to fit; see [`power`].
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Current {
     Milliamps(u16),
@@ -0,0 +1,236 @@
+//! Automatic LED strip dimming from an ambient light sensor.
+//!
+//! [`AutoDim`] samples a light-dependent resistor (photoresistor) on an ADC pin and fades the
+//! brightness of one or more LED strips to suit the room: bright in daylight, dim at night.
+//! Wire the photoresistor from 3.3 V to the ADC pin and a fixed resistor (around 10 kΩ) from
+//! the pin to ground, so readings rise with light. For the opposite wiring, give
+//! [`AutoDimOptions`] a `dark_reading` above its `bright_reading`.
+//!
+//! See [`AutoDim`] for an example.
+
+use embassy_executor::Spawner;
+use embassy_futures::select::{Either, select};
+use embassy_rp::Peri;
+use embassy_rp::adc::{self, Adc, AdcPin, Blocking, Channel};
+use embassy_rp::gpio::Pull;
+use embassy_rp::peripherals::ADC;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Timer};
+use heapless::Vec;
+
+use crate::led_strip::brightness::BrightnessControl;
+use crate::{Error, Result};
+
+/// Most strips one [`AutoDim`] can control.
+pub const MAX_AUTO_DIM_STRIPS: usize = 4;
+
+/// Smaller brightness changes are ignored, so sensor noise doesn't cause constant fades.
+const HYSTERESIS: u8 = 4;
+
+/// Sensor calibration and dimming behavior for [`AutoDim`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub struct AutoDimOptions {
+    /// 12-bit ADC reading (0..=4095) in a dark room.
+    pub dark_reading: u16,
+    /// 12-bit ADC reading (0..=4095) in full daylight.
+    pub bright_reading: u16,
+    /// Strip brightness at or below `dark_reading`.
+    pub dark_brightness: u8,
+    /// Strip brightness at or above `bright_reading`.
+    pub bright_brightness: u8,
+    /// Time between sensor readings.
+    pub sample_interval: Duration,
+    /// How long each brightness change takes.
+    pub fade: Duration,
+}
+
+impl AutoDimOptions {
+    /// Default options for a photoresistor with a 10 kΩ pull-down: readings from 200 (dark)
+    /// to 3000 (bright), dimming to 16 at night, sampled every second, fading over two seconds.
+    pub const DEFAULT: Self = Self {
+        dark_reading: 200,
+        bright_reading: 3000,
+        dark_brightness: 16,
+        bright_brightness: 255,
+        sample_interval: Duration::from_secs(1),
+        fade: Duration::from_secs(2),
+    };
+
+    /// Brightness for a sensor `reading`, interpolated between the dark and bright settings.
+    #[must_use]
+    pub const fn brightness_for(&self, reading: u16) -> u8 {
+        let (dark, bright) = (self.dark_reading as i32, self.bright_reading as i32);
+        if dark == bright {
+            return self.bright_brightness;
+        }
+        let reading = reading as i32;
+        // Position from dark (0) to bright (4096), clamped; works for either wiring.
+        let mut position = (reading - dark) * 4096 / (bright - dark);
+        if position < 0 {
+            position = 0;
+        } else if position > 4096 {
+            position = 4096;
+        }
+        let (low, high) = (self.dark_brightness as i32, self.bright_brightness as i32);
+        (low + (high - low) * position / 4096) as u8
+    }
+}
+
+impl Default for AutoDimOptions {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Static resources for the [`AutoDim`] device.
+pub struct AutoDimStatic {
+    enabled_signal: Signal<CriticalSectionRawMutex, bool>,
+}
+
+impl AutoDimStatic {
+    /// Create static resources.
+    #[must_use]
+    pub const fn new_static() -> Self {
+        Self {
+            enabled_signal: Signal::new(),
+        }
+    }
+}
+
+/// Fades LED strips with the ambient light measured by a photoresistor.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # use panic_probe as _;
+/// use device_kit::led_strip::auto_dim::{AutoDim, AutoDimOptions, AutoDimStatic};
+/// use device_kit::led_strip::{Current, led_strip};
+/// use embassy_executor::Spawner;
+///
+/// led_strip! {
+///     ClockStrip {
+///         pin: PIN_0,
+///         len: 60,
+///         max_current: Current::Milliamps(500),
+///     }
+/// }
+///
+/// #[embassy_executor::main]
+/// async fn main(spawner: Spawner) {
+///     let p = embassy_rp::init(Default::default());
+///     let strip = ClockStrip::new(p.PIO0, p.DMA_CH0, p.PIN_0, spawner).unwrap();
+///
+///     static AUTO_DIM_STATIC: AutoDimStatic = AutoDim::new_static();
+///     let auto_dim = AutoDim::new(
+///         &AUTO_DIM_STATIC,
+///         p.ADC,
+///         p.PIN_26,
+///         &[strip.brightness_control()],
+///         AutoDimOptions::DEFAULT,
+///         spawner,
+///     )
+///     .unwrap();
+///     // Take over brightness by hand, then hand it back.
+///     auto_dim.set_enabled(false);
+///     strip.set_brightness(255);
+///     auto_dim.set_enabled(true);
+/// }
+/// ```
+pub struct AutoDim {
+    auto_dim_static: &'static AutoDimStatic,
+}
+
+impl AutoDim {
+    /// Create static resources.
+    #[must_use]
+    pub const fn new_static() -> AutoDimStatic {
+        AutoDimStatic::new_static()
+    }
+
+    /// Start sampling the photoresistor on `pin` and dimming `strips`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the background task cannot be spawned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `strips` has more than [`MAX_AUTO_DIM_STRIPS`] entries or the sample interval
+    /// is zero.
+    pub fn new(
+        auto_dim_static: &'static AutoDimStatic,
+        adc: Peri<'static, ADC>,
+        pin: Peri<'static, impl AdcPin + 'static>,
+        strips: &[BrightnessControl],
+        options: AutoDimOptions,
+        spawner: Spawner,
+    ) -> Result<Self> {
+        assert!(
+            options.sample_interval.as_micros() > 0,
+            "auto-dim sample interval must be positive"
+        );
+        let strips = Vec::from_slice(strips).expect("auto-dim strips fit in MAX_AUTO_DIM_STRIPS");
+        let adc = Adc::new_blocking(adc, adc::Config::default());
+        let channel = Channel::new_pin(pin, Pull::None);
+        let token = auto_dim_task(adc, channel, strips, options, auto_dim_static)
+            .map_err(Error::TaskSpawn)?;
+        spawner.spawn(token);
+        Ok(Self { auto_dim_static })
+    }
+
+    /// Pause (`false`) or resume (`true`) automatic dimming.
+    ///
+    /// While paused, brightness stays wherever it is and can be set by hand. On resuming, the
+    /// next reading sets it again.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.auto_dim_static.enabled_signal.signal(enabled);
+    }
+}
+
+#[embassy_executor::task]
+async fn auto_dim_task(
+    mut adc: Adc<'static, Blocking>,
+    mut channel: Channel<'static>,
+    strips: Vec<BrightnessControl, MAX_AUTO_DIM_STRIPS>,
+    options: AutoDimOptions,
+    auto_dim_static: &'static AutoDimStatic,
+) -> ! {
+    let mut enabled = true;
+    // Exponential moving average of readings, to ride out flicker and shadows.
+    let mut smoothed: Option<u32> = None;
+    let mut applied: Option<u8> = None;
+    loop {
+        if enabled {
+            match adc.blocking_read(&mut channel) {
+                Ok(reading) => {
+                    let average = smoothed.map_or(u32::from(reading), |average| {
+                        (average * 3 + u32::from(reading)) / 4
+                    });
+                    smoothed = Some(average);
+                    let brightness = options.brightness_for(average as u16);
+                    if applied.is_none_or(|applied| applied.abs_diff(brightness) >= HYSTERESIS) {
+                        for strip in &strips {
+                            strip.fade_brightness(brightness, options.fade);
+                        }
+                        applied = Some(brightness);
+                    }
+                }
+                Err(_) => defmt::warn!("auto-dim: ADC conversion failed"),
+            }
+        }
+
+        if let Either::First(new_enabled) = select(
+            auto_dim_static.enabled_signal.wait(),
+            Timer::after(options.sample_interval),
+        )
+        .await
+        {
+            enabled = new_enabled;
+            // Reapply after resuming, even if the light hasn't changed.
+            applied = None;
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Runtime brightness control for LED strips.
+//!
+//! Every strip starts at full brightness, `255`. Call
+//! [`LedStrip::set_brightness`](super::LedStrip::set_brightness) or
+//! [`LedStrip::fade_brightness`](super::LedStrip::fade_brightness) to change it while frames and
+//! animations keep playing; `0` is off. Frames are still dimmed further if they would exceed
+//! `max_current` (see [`power`](super::power)), so no setting can exceed the budget.
+//!
+//! A [`BrightnessControl`] is a small copyable handle for the same operations, useful for
+//! code (such as [`AutoDim`](super::auto_dim::AutoDim)) that shouldn't depend on a strip's
+//! length or frame capacity.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led_strip::{Current, Frame, colors, led_strip};
+//! use embassy_executor::Spawner;
+//! use embassy_time::Duration;
+//!
+//! led_strip! {
+//!     LedStrip8 {
+//!         pin: PIN_0,
+//!         len: 8,
+//!         max_current: Current::Milliamps(250),
+//!     }
+//! }
+//!
+//! #[embassy_executor::main]
+//! async fn main(spawner: Spawner) {
+//!     let p = embassy_rp::init(Default::default());
+//!     let strip = LedStrip8::new(p.PIO0, p.DMA_CH0, p.PIN_0, spawner).unwrap();
+//!     strip.write_frame(Frame::filled(colors::WHITE)).await.unwrap();
+//!     // Dim to a quarter over two seconds; the frame stays on display.
+//!     strip.fade_brightness(64, Duration::from_secs(2));
+//! }
+//! ```
+
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Instant};
+
+use super::calibration::CalibrationTables;
+use super::{Frame, Rgb};
+
+/// Interval between brightness updates while fading.
+const FADE_STEP: Duration = Duration::from_millis(20);
+
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+/// A requested brightness and how long to take getting there.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BrightnessChange {
+    pub brightness: u8,
+    pub fade: Duration,
+}
+
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+pub type BrightnessSignal = Signal<CriticalSectionRawMutex, BrightnessChange>;
+
+/// Copyable handle that changes the brightness of one LED strip.
+///
+/// Get one from [`LedStrip::brightness_control`](super::LedStrip::brightness_control).
+#[derive(Clone, Copy)]
+pub struct BrightnessControl {
+    signal: &'static BrightnessSignal,
+}
+
+impl BrightnessControl {
+    pub(crate) const fn new(signal: &'static BrightnessSignal) -> Self {
+        Self { signal }
+    }
+
+    /// Change brightness immediately. `255` is full brightness; `0` is off.
+    pub fn set_brightness(&self, brightness: u8) {
+        self.fade_brightness(brightness, Duration::from_ticks(0));
+    }
+
+    /// Change brightness gradually over `duration`, starting from the current level.
+    ///
+    /// A new request replaces a fade in progress, continuing from wherever it had reached.
+    pub fn fade_brightness(&self, brightness: u8, duration: Duration) {
+        self.signal.signal(BrightnessChange {
+            brightness,
+            fade: duration,
+        });
+    }
+}
+
+/// Brightness state owned by a strip's animation task: the current level, any fade in
+/// progress, and the strip's correction tables at that level.
+pub(crate) struct Dimmer {
+    calibration: &'static CalibrationTables,
+    brightness: u8,
+    fade_from: u8,
+    fade_to: u8,
+    fade_start: Instant,
+    fade: Duration,
+    // The strip's correction tables at `brightness`. See `CalibrationTables::combo_tables`.
+    combo_tables: [[u8; 256]; 3],
+}
+
+impl Dimmer {
+    /// Start at full brightness.
+    pub(crate) fn new(calibration: &'static CalibrationTables) -> Self {
+        Self {
+            calibration,
+            brightness: u8::MAX,
+            fade_from: u8::MAX,
+            fade_to: u8::MAX,
+            fade_start: Instant::from_ticks(0),
+            fade: Duration::from_ticks(0),
+            combo_tables: calibration.combo_tables(u8::MAX),
+        }
+    }
+
+    /// Current brightness, `0..=255`.
+    pub(crate) const fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Gamma-correct, calibrate, and dim `frame` to 8 bits per channel.
+    pub(crate) fn correct<const N: usize>(&self, frame: &mut Frame<N>) {
+        for color in frame.iter_mut() {
+            *color = self.calibration.correct(*color, &self.combo_tables);
+        }
+    }
+
+    /// `pixel` gamma-corrected, calibrated, and dimmed at 16-bit precision, each channel
+    /// `0..=65535`, for drivers that round it themselves.
+    pub(crate) fn target(&self, pixel: Rgb) -> [u32; 3] {
+        self.calibration.linear(pixel, self.brightness)
+    }
+
+    pub(crate) const fn is_fading(&self) -> bool {
+        self.brightness != self.fade_to
+    }
+
+    /// When the next fade step is due, if fading.
+    pub(crate) fn next_step(&self) -> Option<Instant> {
+        self.is_fading().then(|| Instant::now() + FADE_STEP)
+    }
+
+    /// Begin moving toward a newly requested brightness.
+    pub(crate) fn start(&mut self, change: BrightnessChange) {
+        self.fade_from = self.brightness;
+        self.fade_to = change.brightness;
+        self.fade_start = Instant::now();
+        self.fade = change.fade;
+    }
+
+    /// Move the brightness to where the fade should be by now. Returns whether it changed,
+    /// in which case the displayed frame should be written again.
+    pub(crate) fn update(&mut self) -> bool {
+        let elapsed = Instant::now().saturating_duration_since(self.fade_start);
+        let brightness = if elapsed >= self.fade {
+            self.fade_to
+        } else {
+            let (from, to) = (i64::from(self.fade_from), i64::from(self.fade_to));
+            let progress = elapsed.as_micros() as i64;
+            (from + (to - from) * progress / self.fade.as_micros() as i64) as u8
+        };
+        if brightness == self.brightness {
+            return false;
+        }
+        self.brightness = brightness;
+        self.combo_tables = self.calibration.combo_tables(brightness);
+        true
+    }
+}
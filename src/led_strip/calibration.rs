@@ -0,0 +1,249 @@
+//! Color calibration for LED strips: white point, color temperature, and channel mixing.
+//!
+//! LEDs rarely show a neutral white at full red, green, and blue, and different batches tint
+//! differently. Give a strip a `calibration` in [`led_strip!`](crate::led_strip::led_strip),
+//! [`led_strips!`](crate::led_strip::led_strips), or
+//! [`clocked_led_strip!`](crate::led_strip::clocked::clocked_led_strip) to correct it, along
+//! with any [`Gamma`](crate::led_strip::gamma::Gamma), including per-channel exponents.
+//!
+//! The strip's gamma and calibration are baked into lookup tables at compile time. Gamma and
+//! white point cost nothing per pixel beyond the lookup every strip already does. A color
+//! matrix is baked in too, as one table per entry, so a pixel costs nine lookups and six
+//! additions with no multiplies.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led_strip::calibration::{ColorCalibration, WhitePoint};
+//! use device_kit::led_strip::gamma::Gamma;
+//! use device_kit::led_strip::{Current, led_strip};
+//!
+//! led_strip! {
+//!     WarmStrip {
+//!         pin: PIN_0,
+//!         len: 30,
+//!         max_current: Current::Milliamps(500),
+//!         gamma: Gamma::PerChannel { red: 2.4, green: 2.2, blue: 2.0 },
+//!         calibration: ColorCalibration {
+//!             white_point: WhitePoint::Kelvin(3000),
+//!             // Pull some green out of the red, which this batch renders slightly orange.
+//!             matrix: Some([[1.0, -0.05, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]]),
+//!         },
+//!     }
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use super::Rgb;
+use super::gamma::{Gamma, channel_table_16, ln, pow};
+
+/// How to balance the channels so that full white looks right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WhitePoint {
+    /// The LEDs' own white: every channel at full scale.
+    #[default]
+    Native,
+    /// The white of a light source at this color temperature, in kelvin (1000 to 40000).
+    /// Lower is warmer: about 2700 for incandescent, 6500 for daylight.
+    Kelvin(u16),
+    /// Scale each channel's full level to these values.
+    Scale { red: u8, green: u8, blue: u8 },
+}
+
+impl WhitePoint {
+    /// The channel scale factors, each `0.0..=1.0`.
+    const fn factors(self) -> [f64; 3] {
+        match self {
+            Self::Native => [1.0; 3],
+            Self::Kelvin(kelvin) => kelvin_factors(kelvin),
+            Self::Scale { red, green, blue } => [
+                red as f64 / 255.0,
+                green as f64 / 255.0,
+                blue as f64 / 255.0,
+            ],
+        }
+    }
+}
+
+/// Color correction for one strip, applied after gamma correction.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ColorCalibration {
+    /// Target white.
+    pub white_point: WhitePoint,
+    /// Channel mixing in linear light, as `matrix[output][input]`, or `None` to skip it.
+    /// Negative entries are allowed; results are clamped.
+    pub matrix: Option<[[f32; 3]; 3]>,
+}
+
+impl ColorCalibration {
+    /// No correction.
+    pub const NONE: Self = Self {
+        white_point: WhitePoint::Native,
+        matrix: None,
+    };
+}
+
+/// A strip's gamma and calibration, precomputed.
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+pub struct CalibrationTables {
+    // Per channel: gamma and white point, 0..=65535.
+    channels: [[u16; 256]; 3],
+    // `mix[output][input][value]`: the matrix entry times `channels[input][value]`. Summing
+    // over `input` gives an output channel in the same units, before clamping.
+    mix: Option<[[[i32; 256]; 3]; 3]>,
+}
+
+impl CalibrationTables {
+    #[must_use]
+    pub const fn new(gamma: Gamma, calibration: ColorCalibration) -> Self {
+        let factors = calibration.white_point.factors();
+        let mut channels = [[0u16; 256]; 3];
+        let mut channel = 0;
+        while channel < 3 {
+            let gamma_table = channel_table_16(gamma, channel);
+            let mut index = 0;
+            while index < 256 {
+                channels[channel][index] =
+                    (gamma_table[index] as f64 * factors[channel] + 0.5) as u16;
+                index += 1;
+            }
+            channel += 1;
+        }
+
+        let mix = match calibration.matrix {
+            Some(matrix) => {
+                let mut mix = [[[0i32; 256]; 3]; 3];
+                let mut output = 0;
+                while output < 3 {
+                    let mut input = 0;
+                    while input < 3 {
+                        let weight = matrix[output][input] as f64;
+                        let mut index = 0;
+                        while index < 256 {
+                            let level = weight * channels[input][index] as f64;
+                            mix[output][input][index] = (if level < 0.0 {
+                                level - 0.5
+                            } else {
+                                level + 0.5
+                            }) as i32;
+                            index += 1;
+                        }
+                        input += 1;
+                    }
+                    output += 1;
+                }
+                Some(mix)
+            }
+            None => None,
+        };
+
+        Self { channels, mix }
+    }
+
+    /// 8-bit lookup tables per channel for [`correct`](Self::correct) at `brightness`.
+    ///
+    /// Without a color matrix they map input values through gamma, white point, and
+    /// brightness. With one they map each mixed output channel, already corrected, through
+    /// brightness alone.
+    pub(crate) fn combo_tables(&self, brightness: u8) -> [[u8; 256]; 3] {
+        match self.mix {
+            None => self
+                .channels
+                .map(|table| table.map(|level| to_8_bit(u32::from(level), brightness))),
+            Some(_) => {
+                let mut table = [0u8; 256];
+                for (value, entry) in (0u32..).zip(table.iter_mut()) {
+                    *entry = to_8_bit(value * 257, brightness);
+                }
+                [table; 3]
+            }
+        }
+    }
+
+    /// `pixel` corrected to linear light at full brightness, each channel `0..=65535`.
+    fn mixed(&self, pixel: Rgb) -> [u32; 3] {
+        let values = [pixel.r, pixel.g, pixel.b].map(usize::from);
+        match &self.mix {
+            None => {
+                let [red, green, blue] = &self.channels;
+                [red[values[0]], green[values[1]], blue[values[2]]].map(u32::from)
+            }
+            Some(mix) => mix.each_ref().map(|[red, green, blue]| {
+                (red[values[0]] + green[values[1]] + blue[values[2]]).clamp(0, 65535) as u32
+            }),
+        }
+    }
+
+    /// `pixel` corrected to linear light at `brightness`, each channel `0..=65535`.
+    pub(crate) fn linear(&self, pixel: Rgb, brightness: u8) -> [u32; 3] {
+        self.mixed(pixel)
+            .map(|level| level * u32::from(brightness) / 255)
+    }
+
+    /// `pixel` corrected to 8 bits per channel using `tables` from
+    /// [`combo_tables`](Self::combo_tables).
+    pub(crate) fn correct(&self, pixel: Rgb, tables: &[[u8; 256]; 3]) -> Rgb {
+        let [red, green, blue] = tables;
+        if self.mix.is_none() {
+            return Rgb::new(
+                red[usize::from(pixel.r)],
+                green[usize::from(pixel.g)],
+                blue[usize::from(pixel.b)],
+            );
+        }
+        let [r, g, b] = self
+            .mixed(pixel)
+            .map(|level| usize::from(to_8_bit(level, u8::MAX)));
+        Rgb::new(red[r], green[g], blue[b])
+    }
+}
+
+/// Scale a 16-bit `level` by `brightness` and round it to 8 bits.
+const fn to_8_bit(level: u32, brightness: u8) -> u8 {
+    ((level * brightness as u32 / 255 + 128) / 257) as u8
+}
+
+/// Channel scale factors for the white of a black body at `kelvin`, after Tanner Helland's
+/// fit to the CIE 1964 color matching functions.
+const fn kelvin_factors(kelvin: u16) -> [f64; 3] {
+    let mut temperature = kelvin as f64 / 100.0;
+    if temperature < 10.0 {
+        temperature = 10.0;
+    } else if temperature > 400.0 {
+        temperature = 400.0;
+    }
+    let red = if temperature <= 66.0 {
+        255.0
+    } else {
+        329.698_727_446 * pow(temperature - 60.0, -0.133_204_759_2)
+    };
+    let green = if temperature <= 66.0 {
+        99.470_802_586_1 * ln(temperature) - 161.119_568_166_1
+    } else {
+        288.122_169_528_3 * pow(temperature - 60.0, -0.075_514_849_2)
+    };
+    let blue = if temperature >= 66.0 {
+        255.0
+    } else if temperature <= 19.0 {
+        0.0
+    } else {
+        138.517_731_223_1 * ln(temperature - 10.0) - 305.044_792_730_7
+    };
+    [
+        clamp_unit(red / 255.0),
+        clamp_unit(green / 255.0),
+        clamp_unit(blue / 255.0),
+    ]
+}
+
+const fn clamp_unit(value: f64) -> f64 {
+    if value < 0.0 {
+        0.0
+    } else if value > 1.0 {
+        1.0
+    } else {
+        value
+    }
+}
@@ -0,0 +1,904 @@
+//! Clocked (two-wire) LED strips: APA102/DotStar, SK9822, and WS2801.
+//!
+//! These strips take a separate clock and data line, driven here by an SPI peripheral, so
+//! they need no precise timing and can be refreshed much faster than WS2812-style strips.
+//! [`clocked_led_strip!`](crate::led_strip::clocked::clocked_led_strip) generates a strip
+//! type that derefs to [`LedStrip`](crate::led_strip::LedStrip), so frames, animations,
+//! brightness, current limiting, and
+//! [`led2d_from_strip!`](crate::led2d::led2d_from_strip) all work as with
+//! [`led_strip!`](crate::led_strip::led_strip).
+//!
+//! APA102 and SK9822 pixels carry a 5-bit global brightness as well as 8-bit color. The driver
+//! picks the lowest global brightness that can show each pixel, leaving the full 8 bits for
+//! color, so dim colors and low runtime brightness keep far more tonal resolution than on a
+//! WS2812.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led_strip::clocked::{ClockedProtocol, clocked_led_strip};
+//! use device_kit::led_strip::{Current, Frame, colors};
+//! use embassy_executor::Spawner;
+//!
+//! clocked_led_strip! {
+//!     DotStar {
+//!         protocol: ClockedProtocol::Apa102,
+//!         clk: PIN_18,
+//!         mosi: PIN_19,
+//!         len: 60,
+//!         max_current: Current::Milliamps(1000),
+//!     }
+//! }
+//!
+//! #[embassy_executor::main]
+//! async fn main(spawner: Spawner) {
+//!     let p = embassy_rp::init(Default::default());
+//!     let strip = DotStar::new(p.SPI0, p.PIN_18, p.PIN_19, p.DMA_CH0, spawner).unwrap();
+//!     strip.write_frame(Frame::filled(colors::ORANGE)).await.unwrap();
+//!     strip.set_brightness(8); // Still smooth color at very low brightness.
+//! }
+//! ```
+
+use embassy_rp::spi::{Async, Instance as SpiInstance, Spi};
+use embassy_time::{Duration, Timer};
+
+use super::brightness::{BrightnessSignal, Dimmer};
+use super::calibration::CalibrationTables;
+use super::color_order::ColorOrder;
+use super::power::CurrentLimiter;
+use super::{
+    Current, Frame, LedStripCommandSignal, LedStripCompletionSignal, Rgb, StripDriver,
+    run_strip_loop,
+};
+
+/// Full scale of a target channel intensity: corrected and dimmed at 16-bit precision.
+const FULL_SCALE: u32 = 65535;
+
+/// How long WS2801 chips need the clock held low before they latch new data.
+const WS2801_LATCH_MICROS: u64 = 500;
+
+/// Protocol spoken by a clocked LED strip.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ClockedProtocol {
+    /// APA102 and DotStar: 32-bit pixels with a 5-bit global brightness.
+    Apa102,
+    /// SK9822: like APA102, but needs an extra reset frame to latch each update.
+    Sk9822,
+    /// WS2801: plain 24-bit pixels, latched by a pause in the clock.
+    Ws2801,
+}
+
+impl ClockedProtocol {
+    /// The byte order the chips usually use: blue, green, red for APA102 and SK9822; red,
+    /// green, blue for WS2801.
+    #[must_use]
+    pub const fn default_color_order(self) -> ColorOrder {
+        match self {
+            Self::Apa102 | Self::Sk9822 => ColorOrder::Bgr,
+            Self::Ws2801 => ColorOrder::Rgb,
+        }
+    }
+
+    /// A dependable SPI clock in Hz: 4 MHz for APA102 and SK9822, 1 MHz for WS2801.
+    #[must_use]
+    pub const fn default_frequency(self) -> u32 {
+        match self {
+            Self::Apa102 | Self::Sk9822 => 4_000_000,
+            Self::Ws2801 => 1_000_000,
+        }
+    }
+
+    const fn has_global_brightness(self) -> bool {
+        matches!(self, Self::Apa102 | Self::Sk9822)
+    }
+}
+
+/// Split target channel intensities (each `0..=65535`) into an APA102 5-bit global
+/// brightness and 8-bit channel values, using the lowest global brightness that reaches the
+/// brightest channel.
+fn split_global_brightness(targets: [u32; 3]) -> (u8, [u8; 3]) {
+    let max = targets.into_iter().max().unwrap_or(0).min(FULL_SCALE);
+    if max == 0 {
+        return (0, [0; 3]);
+    }
+    let global = (max * 31).div_ceil(FULL_SCALE);
+    // Round each channel to the nearest step at this global brightness.
+    let channels = targets.map(|target| {
+        let value =
+            (target.min(FULL_SCALE) * 31 * 255 + global * FULL_SCALE / 2) / (global * FULL_SCALE);
+        value.min(255) as u8
+    });
+    (global as u8, channels)
+}
+
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+pub async fn clocked_strip_animation_loop<T, const N: usize, const MAX_FRAMES: usize>(
+    spi: Spi<'static, T, Async>,
+    command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static LedStripCompletionSignal,
+    brightness_signal: &'static BrightnessSignal,
+    protocol: ClockedProtocol,
+    calibration: &'static CalibrationTables,
+    color_order: ColorOrder,
+    max_current: Current,
+) -> !
+where
+    T: SpiInstance,
+{
+    assert!(
+        color_order.channels() == 3,
+        "clocked LED strips support only RGB color orders"
+    );
+    run_strip_loop(
+        ClockedDriver {
+            spi,
+            protocol,
+            color_order,
+        },
+        command_signal,
+        completion_signal,
+        brightness_signal,
+        calibration,
+        max_current,
+        None,
+    )
+    .await
+}
+
+struct ClockedDriver<T: SpiInstance + 'static> {
+    spi: Spi<'static, T, Async>,
+    protocol: ClockedProtocol,
+    color_order: ColorOrder,
+}
+
+impl<T: SpiInstance> ClockedDriver<T> {
+    async fn send(&mut self, bytes: &[u8]) {
+        if self.spi.write(bytes).await.is_err() {
+            defmt::warn!("clocked LED strip: SPI write failed");
+        }
+    }
+
+    /// Send 4-byte pixels framed for APA102 or SK9822.
+    async fn write_apa102<const N: usize>(&mut self, pixels: &[[u8; 4]; N]) {
+        self.send(&[0; 4]).await;
+        self.send(pixels.as_flattened()).await;
+        if self.protocol == ClockedProtocol::Sk9822 {
+            self.send(&[0; 4]).await;
+        }
+        // Data lags the clock by half a bit per pixel; these extra clocks push it to the end.
+        for _ in 0..N.div_ceil(16).div_ceil(4) {
+            self.send(&[0; 4]).await;
+        }
+    }
+}
+
+impl<T: SpiInstance, const N: usize> StripDriver<N> for ClockedDriver<T> {
+    async fn write(&mut self, frame: &Frame<N>, dimmer: &Dimmer, limiter: &CurrentLimiter) {
+        if !self.protocol.has_global_brightness() {
+            let mut corrected_frame = *frame;
+            dimmer.correct(&mut corrected_frame);
+            limiter.limit(&mut *corrected_frame);
+            let color_order = self.color_order;
+            let pixels = corrected_frame.0.map(|pixel| {
+                let [first, second, third, _] = color_order.wire_bytes(pixel);
+                [first, second, third]
+            });
+            self.send(pixels.as_flattened()).await;
+            Timer::after_micros(WS2801_LATCH_MICROS).await;
+            return;
+        }
+
+        // Keep correction and brightness at full precision; the global brightness field
+        // carries what 8 bits can't.
+        let mut targets = frame.0.map(|pixel| dimmer.target(pixel));
+
+        // Targets are in `CHANNEL_MA / 65535` units; the limiter counts in `CHANNEL_MA / 255`.
+        let sum: u32 = targets.iter().flatten().sum::<u32>() / 257;
+        let allowed = limiter.allowed(sum);
+        if allowed < sum {
+            for value in targets.iter_mut().flatten() {
+                *value = (u64::from(*value) * u64::from(allowed) / u64::from(sum)) as u32;
+            }
+        }
+
+        let color_order = self.color_order;
+        let pixels = targets.map(|target| {
+            let (global, [r, g, b]) = split_global_brightness(target);
+            let [first, second, third, _] = color_order.wire_bytes(Rgb::new(r, g, b));
+            [0xE0 | global, first, second, third]
+        });
+        self.write_apa102(&pixels).await;
+    }
+
+    fn refresh(&self) -> Option<Duration> {
+        None
+    }
+}
+
+/// Defines a clocked (APA102, SK9822, or WS2801) LED strip driven by an SPI peripheral.
+///
+/// # Syntax
+///
+/// ```ignore
+/// clocked_led_strip! {
+///     TypeName {                                // Name for the generated strip type
+///         protocol: ClockedProtocol::Apa102,    // Chip protocol
+///         clk: PIN_18,                          // SPI clock pin (required)
+///         mosi: PIN_19,                         // SPI data pin (required)
+///         len: 60,                              // Number of LEDs (required)
+///         max_current: Current::Milliamps(500), // Current budget
+///     }
+/// }
+/// ```
+///
+/// # Optional Fields
+///
+/// - `protocol: ClockedProtocol::Ws2801` - Chip protocol (defaults to Apa102)
+/// - `spi: SPI1` - SPI peripheral (defaults to SPI0)
+/// - `dma: DMA_CH1` - DMA channel (defaults to DMA_CH0)
+/// - `max_current: Current::Milliamps(500)` - Current budget (defaults to 250 mA)
+/// - `gamma: Gamma::Linear` - Gamma correction (defaults to Gamma2_2)
+/// - `calibration: ColorCalibration { ... }` - White point and color matrix correction (defaults to `ColorCalibration::NONE`)
+/// - `color_order: ColorOrder::Rgb` - Color byte order (defaults to the protocol's usual order)
+/// - `frequency: 8_000_000` - SPI clock in Hz (defaults to the protocol's usual rate)
+/// - `max_frames: 16` - Animation frame buffer size (defaults to 16)
+///
+/// # Generated API
+///
+/// The macro generates a struct with:
+/// - `new(spi, clk, mosi, dma, spawner)` - Constructor that sets up SPI and spawns the driver
+/// - All methods from [`LedStrip`](crate::led_strip::LedStrip) via `Deref`
+///
+/// See the [module documentation](crate::led_strip::clocked) for an example.
+#[macro_export]
+macro_rules! clocked_led_strip {
+    // Entry point - name and fields
+    (
+        $name:ident {
+            $($fields:tt)*
+        }
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $crate::led_strip::clocked::ClockedProtocol::Apa102,
+            spi: SPI0,
+            clk: _UNSET_,
+            mosi: _UNSET_,
+            dma: DMA_CH0,
+            len: _UNSET_,
+            max_current: $crate::led_strip::Current::Milliamps(250),
+            gamma: $crate::led_strip::gamma::Gamma::Gamma2_2,
+            calibration: $crate::led_strip::calibration::ColorCalibration::NONE,
+            color_order: _UNSET_,
+            frequency: _UNSET_,
+            max_frames: 16,
+            fields: [ $($fields)* ]
+        }
+    };
+
+    // Fill defaults: protocol
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ protocol: $new_protocol:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $new_protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: spi
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ spi: $new_spi:ident $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $new_spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: clk
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ clk: $new_clk:ident $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $new_clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: mosi
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ mosi: $new_mosi:ident $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $new_mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: dma
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ dma: $new_dma:ident $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $new_dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: len
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ len: $new_len:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $new_len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: max_current
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ max_current: $new_max_current:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $new_max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: gamma
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ gamma: $new_gamma:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $new_gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: calibration
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ calibration: $new_calibration:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $new_calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: color_order
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ color_order: $new_color_order:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $new_color_order,
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: frequency
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ frequency: $new_frequency:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $new_frequency,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: max_frames
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:tt,
+        mosi: $mosi:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:tt,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: [ max_frames: $new_max_frames:expr $(, $($rest:tt)* )? ]
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: $frequency,
+            max_frames: $new_max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill the protocol's usual color order if still unset
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:ident,
+        mosi: $mosi:ident,
+        dma: $dma:ident,
+        len: $len:expr,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: _UNSET_,
+        frequency: $frequency:tt,
+        max_frames: $max_frames:expr,
+        fields: []
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: ($protocol.default_color_order()),
+            frequency: $frequency,
+            max_frames: $max_frames,
+            fields: []
+        }
+    };
+
+    // Fill the protocol's usual frequency if still unset
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:ident,
+        mosi: $mosi:ident,
+        dma: $dma:ident,
+        len: $len:expr,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:expr,
+        frequency: _UNSET_,
+        max_frames: $max_frames:expr,
+        fields: []
+    ) => {
+        $crate::clocked_led_strip! {
+            @__fill_defaults
+            name: $name,
+            protocol: $protocol,
+            spi: $spi,
+            clk: $clk,
+            mosi: $mosi,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            calibration: $calibration,
+            color_order: $color_order,
+            frequency: ($protocol.default_frequency()),
+            max_frames: $max_frames,
+            fields: []
+        }
+    };
+
+    // All fields processed - expand the type
+    (@__fill_defaults
+        name: $name:ident,
+        protocol: $protocol:expr,
+        spi: $spi:ident,
+        clk: $clk:ident,
+        mosi: $mosi:ident,
+        dma: $dma:ident,
+        len: $len:expr,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        calibration: $calibration:expr,
+        color_order: $color_order:expr,
+        frequency: $frequency:expr,
+        max_frames: $max_frames:expr,
+        fields: []
+    ) => {
+        ::paste::paste! {
+            #[doc = concat!(
+                "Clocked LED strip generated by [`clocked_led_strip!`].\n\n",
+                "Derefs to [`LedStrip`] for all operations."
+            )]
+            pub struct $name {
+                strip: $crate::led_strip::LedStrip<{ $len }, { $max_frames }>,
+            }
+
+            impl $name {
+                pub const LEN: usize = $len;
+                pub const MAX_FRAMES: usize = $max_frames;
+
+                /// Create a new LED strip on an SPI peripheral.
+                ///
+                /// # Parameters
+                ///
+                /// - `spi`: SPI peripheral
+                /// - `clk`: GPIO pin for the LED clock signal
+                /// - `mosi`: GPIO pin for the LED data signal
+                /// - `dma`: DMA channel for LED data transfer
+                /// - `spawner`: Task spawner for background operations
+                pub fn new(
+                    spi: ::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$spi>,
+                    clk: impl Into<::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$clk>>,
+                    mosi: impl Into<::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$mosi>>,
+                    dma: impl Into<::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$dma>>,
+                    spawner: ::embassy_executor::Spawner,
+                ) -> $crate::Result<&'static Self> {
+                    static STRIP_STATIC: $crate::led_strip::LedStripStatic<{ $len }, { $max_frames }> =
+                        $crate::led_strip::LedStrip::new_static();
+                    static STRIP_CELL: ::static_cell::StaticCell<$name> = ::static_cell::StaticCell::new();
+
+                    let mut config = ::embassy_rp::spi::Config::default();
+                    config.frequency = $frequency;
+                    let spi = ::embassy_rp::spi::Spi::new_txonly(
+                        spi,
+                        clk.into(),
+                        mosi.into(),
+                        dma.into(),
+                        config,
+                    );
+
+                    let token = [<$name:snake _clocked_task>](
+                        spi,
+                        STRIP_STATIC.command_signal(),
+                        STRIP_STATIC.completion_signal(),
+                        STRIP_STATIC.brightness_signal(),
+                    )
+                    .map_err($crate::Error::TaskSpawn)?;
+                    spawner.spawn(token);
+
+                    let strip = $crate::led_strip::LedStrip::new(&STRIP_STATIC)?;
+                    let instance = STRIP_CELL.init($name { strip });
+                    Ok(instance)
+                }
+            }
+
+            impl ::core::ops::Deref for $name {
+                type Target = $crate::led_strip::LedStrip<{ $len }, { $max_frames }>;
+
+                fn deref(&self) -> &Self::Target {
+                    &self.strip
+                }
+            }
+
+            #[cfg(not(feature = "host"))]
+            impl $crate::led2d::WriteFrame<{ $len }> for $name {
+                async fn write_frame(&self, frame: $crate::led_strip::Frame<{ $len }>) -> $crate::Result<()> {
+                    self.strip.write_frame(frame).await
+                }
+            }
+
+            #[::embassy_executor::task]
+            async fn [<$name:snake _clocked_task>](
+                spi: ::embassy_rp::spi::Spi<'static, ::embassy_rp::peripherals::$spi, ::embassy_rp::spi::Async>,
+                command_signal: &'static $crate::led_strip::LedStripCommandSignal<{ $len }, { $max_frames }>,
+                completion_signal: &'static $crate::led_strip::LedStripCompletionSignal,
+                brightness_signal: &'static $crate::led_strip::brightness::BrightnessSignal,
+            ) -> ! {
+                // Gamma and calibration tables, computed at compile time
+                static CALIBRATION: $crate::led_strip::calibration::CalibrationTables =
+                    $crate::led_strip::calibration::CalibrationTables::new($gamma, $calibration);
+                $crate::led_strip::clocked::clocked_strip_animation_loop::<
+                    ::embassy_rp::peripherals::$spi,
+                    { $len },
+                    { $max_frames },
+                >(
+                    spi,
+                    command_signal,
+                    completion_signal,
+                    brightness_signal,
+                    $protocol,
+                    &CALIBRATION,
+                    $color_order,
+                    $max_current,
+                ).await
+            }
+        }
+    };
+}
+
+pub use clocked_led_strip;
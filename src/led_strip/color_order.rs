@@ -0,0 +1,141 @@
+//! Color channel order and RGBW support for LED strips.
+//!
+//! WS2812-style chips differ in the order they expect color bytes: WS2812B wants green, red,
+//! blue, while others want red first or blue first. SK6812 RGBW chips add a fourth, white
+//! byte. Set `color_order` on a strip in [`led_strip!`](crate::led_strip::led_strip) or
+//! [`led_strips!`](crate::led_strip::led_strips) to match your chip; frames are still written
+//! as RGB.
+//!
+//! For RGBW orders, the white channel is extracted from each pixel after gamma correction:
+//! the gray part of the color (the smallest of red, green, and blue) moves to the white LED,
+//! which renders it more efficiently and with a truer white. Current limiting counts all four
+//! channels.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led_strip::color_order::ColorOrder;
+//! use device_kit::led_strip::{Current, led_strip};
+//!
+//! led_strip! {
+//!     Sk6812Strip {
+//!         pin: PIN_0,
+//!         len: 30,
+//!         max_current: Current::Milliamps(500),
+//!         color_order: ColorOrder::Grbw,
+//!     }
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use smart_leds::RGB8;
+
+/// Order of the color bytes an LED chip expects, and whether it has a white channel.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorOrder {
+    /// Red, green, blue.
+    Rgb,
+    /// Red, blue, green.
+    Rbg,
+    /// Green, red, blue: WS2812B and most WS2812-style strips.
+    #[default]
+    Grb,
+    /// Green, blue, red.
+    Gbr,
+    /// Blue, red, green.
+    Brg,
+    /// Blue, green, red.
+    Bgr,
+    /// Red, green, blue, white.
+    Rgbw,
+    /// Green, red, blue, white: SK6812 RGBW.
+    Grbw,
+}
+
+impl ColorOrder {
+    /// Bytes sent per LED: 3, or 4 with a white channel.
+    #[must_use]
+    pub const fn channels(self) -> usize {
+        match self {
+            Self::Rgbw | Self::Grbw => 4,
+            _ => 3,
+        }
+    }
+
+    /// How many 3-byte driver words carry `len` LEDs.
+    ///
+    /// The WS2812 protocol is a plain byte stream, so 4-byte RGBW pixels are sent packed into
+    /// 3-byte words; bytes past the last LED are ignored by the strip.
+    #[must_use]
+    pub const fn wire_len(self, len: usize) -> usize {
+        (len * self.channels()).div_ceil(3)
+    }
+
+    /// The bytes to send for `color`, in wire order. Only the first
+    /// [`channels`](Self::channels) bytes are used.
+    #[must_use]
+    pub const fn wire_bytes(self, color: RGB8) -> [u8; 4] {
+        let RGB8 { r, g, b } = color;
+        match self {
+            Self::Rgb => [r, g, b, 0],
+            Self::Rbg => [r, b, g, 0],
+            Self::Grb => [g, r, b, 0],
+            Self::Gbr => [g, b, r, 0],
+            Self::Brg => [b, r, g, 0],
+            Self::Bgr => [b, g, r, 0],
+            Self::Rgbw | Self::Grbw => {
+                let white = min3(r, g, b);
+                let (r, g, b) = (r - white, g - white, b - white);
+                if matches!(self, Self::Rgbw) {
+                    [r, g, b, white]
+                } else {
+                    [g, r, b, white]
+                }
+            }
+        }
+    }
+
+    /// Pack `pixels` into driver words in wire order.
+    ///
+    /// The WS2812 driver sends each word as green, red, blue, so wire bytes are placed in
+    /// those fields in that order.
+    pub(crate) fn encode<const N: usize, const WIRE: usize>(
+        self,
+        pixels: &[RGB8; N],
+        wire: &mut [RGB8; WIRE],
+    ) {
+        let channels = self.channels();
+        let mut words = wire.iter_mut();
+        let mut word = RGB8::default();
+        let mut byte_index = 0;
+        let bytes = pixels
+            .iter()
+            .flat_map(|pixel| self.wire_bytes(*pixel).into_iter().take(channels));
+        for byte in bytes {
+            match byte_index {
+                0 => word.g = byte,
+                1 => word.r = byte,
+                _ => word.b = byte,
+            }
+            byte_index += 1;
+            if byte_index == 3 {
+                *words.next().expect("wire has room for every pixel") = word;
+                word = RGB8::default();
+                byte_index = 0;
+            }
+        }
+        if byte_index > 0 {
+            *words.next().expect("wire has room for every pixel") = word;
+        }
+        for rest in words {
+            *rest = RGB8::default();
+        }
+    }
+}
+
+const fn min3(a: u8, b: u8, c: u8) -> u8 {
+    let ab = if a < b { a } else { b };
+    if ab < c { ab } else { c }
+}
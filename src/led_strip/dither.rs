@@ -0,0 +1,100 @@
+//! Temporal dithering for smooth low-brightness LED strips.
+//!
+//! An 8-bit channel has few distinct levels near black, and gamma correction and dimming
+//! squeeze many colors onto the same few, so slow night-time fades visibly step. With
+//! `dither: Dither::On` in [`led_strip!`](crate::led_strip::led_strip) or
+//! [`led_strips!`](crate::led_strip::led_strips), the strip computes each channel at 16-bit
+//! precision and rewrites the frame on display many times a second. Each refresh rounds to 8
+//! bits and carries the rounding error into the next, so on average the LED shows the level
+//! in between.
+//!
+//! Dithering keeps the strip's data line busy and uses a little CPU for every refresh. Very
+//! dim levels on long strips can flicker, since the in-between level is built from fewer,
+//! slower refreshes.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::led_strip::dither::Dither;
+//! use device_kit::led_strip::{Current, led_strip};
+//!
+//! led_strip! {
+//!     NightLight {
+//!         pin: PIN_0,
+//!         len: 16,
+//!         max_current: Current::Milliamps(250),
+//!         dither: Dither::On,
+//!     }
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use embassy_time::Duration;
+
+use super::brightness::Dimmer;
+use super::{Frame, Rgb};
+
+/// Shortest time between dithering refreshes.
+const MIN_REFRESH: Duration = Duration::from_millis(1);
+
+/// Time a WS2812 takes to receive one 24-bit word, in microseconds.
+const WORD_MICROS: u64 = 30;
+
+/// Time a WS2812 needs the data line low before it latches a frame, in microseconds.
+const LATCH_MICROS: u64 = 300;
+
+/// Whether a strip dithers between refreshes.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Dither {
+    /// Show each frame once, rounded to 8 bits per channel.
+    #[default]
+    Off,
+    /// Refresh continuously, spreading rounding error over time.
+    On,
+}
+
+impl Dither {
+    /// Time between refreshes for a strip that sends `words` 24-bit words, if dithering: as
+    /// fast as the strip can take data, but no faster than [`MIN_REFRESH`].
+    pub(crate) fn refresh(self, words: usize) -> Option<Duration> {
+        match self {
+            Self::Off => None,
+            Self::On => {
+                let frame_time = Duration::from_micros(words as u64 * WORD_MICROS + LATCH_MICROS);
+                Some(frame_time.max(MIN_REFRESH))
+            }
+        }
+    }
+}
+
+/// Per-channel rounding error carried between refreshes.
+pub(crate) struct Ditherer<const N: usize> {
+    error: [[u16; 3]; N],
+}
+
+impl<const N: usize> Ditherer<N> {
+    pub(crate) const fn new() -> Self {
+        Self { error: [[0; 3]; N] }
+    }
+
+    /// Gamma-correct, calibrate, and dim `frame` at 16-bit precision, then round it to 8
+    /// bits, adding the error left over from the previous refresh.
+    pub(crate) fn apply(&mut self, frame: &mut Frame<N>, dimmer: &Dimmer) {
+        for (pixel, error) in frame.iter_mut().zip(self.error.iter_mut()) {
+            // 0..=65535, in steps of 1/257 of an 8-bit level.
+            let targets = dimmer.target(*pixel);
+            let mut channels = [0u8; 3];
+            for ((channel, target), error) in channels.iter_mut().zip(targets).zip(error.iter_mut())
+            {
+                let total = target + u32::from(*error);
+                let level = (total / 257).min(255);
+                *error = (total - level * 257) as u16;
+                *channel = level as u8;
+            }
+            let [r, g, b] = channels;
+            *pixel = Rgb::new(r, g, b);
+        }
+    }
+}
@@ -2,14 +2,39 @@
 //!
 //! Provides gamma correction tables and functions to combine gamma correction
 //! with current limiting (brightness scaling) into a single lookup table for efficiency.
+//!
+//! Custom exponents are evaluated by `const fn`s, so a strip's tables are computed at compile
+//! time. For white point and color matrix correction, see
+//! [`calibration`](crate::led_strip::calibration).
 
 /// Gamma correction mode for LED strips.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Gamma {
     /// Linear gamma (no correction). Gamma = 1.0
     Linear,
     /// Standard gamma 2.2 correction for perceived brightness.
     Gamma2_2,
+    /// Gamma correction with any positive exponent: corrected = (value/255)^exponent * 255
+    Custom(f32),
+    /// A separate exponent for each color channel, for LEDs whose red, green, and blue dies
+    /// respond differently.
+    PerChannel { red: f32, green: f32, blue: f32 },
+}
+
+impl Gamma {
+    /// The exponent applied to `channel` (0 red, 1 green, 2 blue).
+    const fn exponent(self, channel: usize) -> f64 {
+        match self {
+            Self::Linear => 1.0,
+            Self::Gamma2_2 => 2.2,
+            Self::Custom(exponent) => exponent as f64,
+            Self::PerChannel { red, green, blue } => match channel {
+                0 => red as f64,
+                1 => green as f64,
+                _ => blue as f64,
+            },
+        }
+    }
 }
 
 impl Default for Gamma {
@@ -55,6 +80,108 @@ const LINEAR_TABLE: [u8; 256] = [
     250, 251, 252, 253, 254, 255,
 ];
 
+/// Gamma 2.2 lookup table with 16-bit output: corrected = (value/255)^2.2 * 65535
+///
+/// Keeps the distinct dark levels that [`GAMMA_2_2_TABLE`] rounds to 0 or 1, for dithering.
+pub const GAMMA_2_2_TABLE_16: [u16; 256] = [
+    0, 0, 2, 4, 7, 11, 17, 24, 32, 42, 53, 65, 79, 94, 111, 129, 148, 169, 192, 216, 242, 270, 299,
+    330, 362, 396, 432, 469, 508, 549, 591, 635, 681, 729, 779, 830, 883, 938, 995, 1053, 1113,
+    1175, 1239, 1305, 1373, 1443, 1514, 1587, 1663, 1740, 1819, 1900, 1983, 2068, 2155, 2243, 2334,
+    2427, 2521, 2618, 2717, 2817, 2920, 3024, 3131, 3240, 3350, 3463, 3578, 3694, 3813, 3934, 4057,
+    4182, 4309, 4438, 4570, 4703, 4838, 4976, 5115, 5257, 5401, 5547, 5695, 5845, 5998, 6152, 6309,
+    6468, 6629, 6792, 6957, 7124, 7294, 7466, 7640, 7816, 7994, 8175, 8358, 8543, 8730, 8919, 9111,
+    9305, 9501, 9699, 9900, 10102, 10307, 10515, 10724, 10936, 11150, 11366, 11585, 11806, 12029,
+    12254, 12482, 12712, 12944, 13179, 13416, 13655, 13896, 14140, 14386, 14635, 14885, 15138,
+    15394, 15652, 15912, 16174, 16439, 16706, 16975, 17247, 17521, 17798, 18077, 18358, 18642,
+    18928, 19216, 19507, 19800, 20095, 20393, 20694, 20996, 21301, 21609, 21919, 22231, 22546,
+    22863, 23182, 23504, 23829, 24156, 24485, 24817, 25151, 25487, 25826, 26168, 26512, 26858,
+    27207, 27558, 27912, 28268, 28627, 28988, 29351, 29717, 30086, 30457, 30830, 31206, 31585,
+    31966, 32349, 32735, 33124, 33514, 33908, 34304, 34702, 35103, 35507, 35913, 36321, 36732,
+    37146, 37562, 37981, 38402, 38825, 39252, 39680, 40112, 40546, 40982, 41421, 41862, 42306,
+    42753, 43202, 43654, 44108, 44565, 45025, 45487, 45951, 46418, 46888, 47360, 47835, 48313,
+    48793, 49275, 49761, 50249, 50739, 51232, 51728, 52226, 52727, 53230, 53736, 54245, 54756,
+    55270, 55787, 56306, 56828, 57352, 57879, 58409, 58941, 59476, 60014, 60554, 61097, 61642,
+    62190, 62741, 63295, 63851, 64410, 64971, 65535,
+];
+
+/// Linear lookup table with 16-bit output.
+const LINEAR_TABLE_16: [u16; 256] = {
+    let mut table = [0u16; 256];
+    let mut index = 0;
+    while index < 256 {
+        table[index] = index as u16 * 257;
+        index += 1;
+    }
+    table
+};
+
+/// The 16-bit lookup table for `channel` (0 red, 1 green, 2 blue) under `gamma`, without
+/// brightness scaling: corrected = (value/255)^exponent * 65535
+pub(crate) const fn channel_table_16(gamma: Gamma, channel: usize) -> [u16; 256] {
+    match gamma {
+        Gamma::Linear => LINEAR_TABLE_16,
+        Gamma::Gamma2_2 => GAMMA_2_2_TABLE_16,
+        Gamma::Custom(_) | Gamma::PerChannel { .. } => {
+            let exponent = gamma.exponent(channel);
+            assert!(exponent > 0.0, "gamma exponent must be positive");
+            let mut table = [0u16; 256];
+            let mut index = 1;
+            while index < 256 {
+                table[index] = (pow(index as f64 / 255.0, exponent) * 65535.0 + 0.5) as u16;
+                index += 1;
+            }
+            table
+        }
+    }
+}
+
+const LN_2: f64 = core::f64::consts::LN_2;
+
+/// Natural logarithm of a positive, normal `value`, usable in `const` contexts.
+pub(crate) const fn ln(value: f64) -> f64 {
+    // value = mantissa * 2^exponent, with mantissa in [1, 2).
+    let bits = value.to_bits();
+    let exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    // ln(m) = 2 * atanh((m - 1) / (m + 1)), whose series converges quickly for m in [1, 2).
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z_squared = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    let mut denominator = 1.0;
+    while denominator < 40.0 {
+        sum += term / denominator;
+        term *= z_squared;
+        denominator += 2.0;
+    }
+    2.0 * sum + exponent as f64 * LN_2
+}
+
+/// `e^value` for `value` in about -700..700, usable in `const` contexts.
+pub(crate) const fn exp(value: f64) -> f64 {
+    // e^value = 2^k * e^r, with |r| < ln 2.
+    let k = (value / LN_2) as i64;
+    let r = value - k as f64 * LN_2;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut n = 1.0;
+    while n < 25.0 {
+        term *= r / n;
+        sum += term;
+        n += 1.0;
+    }
+    sum * f64::from_bits(((k + 1023) as u64) << 52)
+}
+
+/// `base^exponent` for non-negative `base`, usable in `const` contexts.
+pub(crate) const fn pow(base: f64, exponent: f64) -> f64 {
+    if base <= 0.0 {
+        0.0
+    } else {
+        exp(exponent * ln(base))
+    }
+}
+
 /// Generate a combined gamma correction and brightness scaling lookup table.
 ///
 /// This combines two operations into a single table lookup for efficiency:
@@ -62,11 +189,46 @@ const LINEAR_TABLE: [u8; 256] = [
 /// 2. Scale by `max_brightness` for current limiting
 ///
 /// The result is a table where `combo_table[input_value]` gives the final output value.
+///
+/// # Panics
+///
+/// Panics for [`Gamma::PerChannel`], which needs a table per channel; use
+/// [`generate_channel_combo_tables`] instead.
 #[must_use]
 pub const fn generate_combo_table(gamma: Gamma, max_brightness: u8) -> [u8; 256] {
+    assert!(
+        !matches!(gamma, Gamma::PerChannel { .. }),
+        "per-channel gamma needs generate_channel_combo_tables"
+    );
+    channel_combo_table(gamma, 0, max_brightness)
+}
+
+/// Generate [`generate_combo_table`]'s combined lookup table for each of red, green, and
+/// blue, using each channel's own exponent under [`Gamma::PerChannel`].
+#[must_use]
+pub const fn generate_channel_combo_tables(gamma: Gamma, max_brightness: u8) -> [[u8; 256]; 3] {
+    [
+        channel_combo_table(gamma, 0, max_brightness),
+        channel_combo_table(gamma, 1, max_brightness),
+        channel_combo_table(gamma, 2, max_brightness),
+    ]
+}
+
+/// The combined gamma and brightness table for `channel` (0 red, 1 green, 2 blue).
+const fn channel_combo_table(gamma: Gamma, channel: usize, max_brightness: u8) -> [u8; 256] {
     let gamma_table = match gamma {
-        Gamma::Linear => &LINEAR_TABLE,
-        Gamma::Gamma2_2 => &GAMMA_2_2_TABLE,
+        Gamma::Linear => LINEAR_TABLE,
+        Gamma::Gamma2_2 => GAMMA_2_2_TABLE,
+        Gamma::Custom(_) | Gamma::PerChannel { .. } => {
+            let table_16 = channel_table_16(gamma, channel);
+            let mut table = [0u8; 256];
+            let mut index = 0;
+            while index < 256 {
+                table[index] = ((table_16[index] as u32 + 128) / 257) as u8;
+                index += 1;
+            }
+            table
+        }
     };
 
     let mut result = [0u8; 256];
@@ -0,0 +1,228 @@
+//! Content-aware current limiting for LED strips.
+//!
+//! Each WS2812-style LED draws about [`CHANNEL_MA`] for each fully lit color channel. Rather
+//! than dimming every frame as if all LEDs were full white, strips estimate the current of
+//! each frame as it is written and scale it down only when it would exceed the strip's
+//! `max_current`. A mostly dark clock face therefore shows at full brightness, while a full
+//! white frame is dimmed to fit the budget.
+//!
+//! Strips generated together by [`led_strips!`](crate::led_strip::led_strips) can also share a
+//! `total_current` budget, for when one power supply feeds them all. When their frames
+//! together would draw too much, each strip is scaled in proportion to what it asks for. When
+//! the shares change noticeably, strips holding a still frame rewrite it, so a strip that lit
+//! up early gives back what a newly lit strip is owed. A strip's share is worked out when it
+//! shows a new frame or changes brightness; the frequent rewrites of dithering reuse it.
+
+use core::cell::Cell;
+
+use embassy_sync::blocking_mutex::Mutex;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::signal::Signal;
+use smart_leds::RGB8;
+
+use super::Current;
+
+/// Current drawn by one color channel of one LED at full value, in milliamps.
+pub const CHANNEL_MA: u32 = 20;
+
+/// Most strips that can share one [`PowerBudget`] (one per PIO state machine).
+pub const MAX_SHARED_STRIPS: usize = 4;
+
+/// Strips rewrite their frame for a new share only if it differs from what they show by more
+/// than `1 / REBALANCE_SLACK` of the total budget.
+const REBALANCE_SLACK: u32 = 64;
+
+/// Estimated current to show `pixels`, in milliamps.
+#[must_use]
+pub fn estimate_milliamps(pixels: &[RGB8]) -> u32 {
+    channel_sum(pixels) * CHANNEL_MA / 255
+}
+
+/// Sum of all channel values: current in units of `CHANNEL_MA / 255`.
+fn channel_sum(pixels: &[RGB8]) -> u32 {
+    pixels
+        .iter()
+        .map(|pixel| u32::from(pixel.r) + u32::from(pixel.g) + u32::from(pixel.b))
+        .sum()
+}
+
+/// A current budget in units of `CHANNEL_MA / 255`, or `None` if unlimited.
+const fn budget_units(current: Current) -> Option<u32> {
+    match current {
+        Current::Milliamps(ma) => Some(ma as u32 * 255 / CHANNEL_MA),
+        Current::Unlimited => None,
+    }
+}
+
+/// Scale every channel of `pixels` by `allowed / sum`.
+fn scale_to(pixels: &mut [RGB8], sum: u32, allowed: u32) {
+    let scale = |value: u8| (u64::from(value) * u64::from(allowed) / u64::from(sum)) as u8;
+    for pixel in pixels {
+        *pixel = RGB8::new(scale(pixel.r), scale(pixel.g), scale(pixel.b));
+    }
+}
+
+/// A current budget shared by the strips of one [`led_strips!`](crate::led_strip::led_strips)
+/// group.
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+pub struct PowerBudget {
+    total: Option<u32>,
+    // Per strip, in `CHANNEL_MA / 255` units: (wanted by its latest frame, actually shown).
+    usage: Mutex<CriticalSectionRawMutex, Cell<[(u32, u32); MAX_SHARED_STRIPS]>>,
+    // Per strip: set when what it shows no longer matches its share, so it rewrites its frame.
+    rebalance: [Signal<CriticalSectionRawMutex, ()>; MAX_SHARED_STRIPS],
+}
+
+impl PowerBudget {
+    #[must_use]
+    pub const fn new(total_current: Current) -> Self {
+        Self {
+            total: budget_units(total_current),
+            usage: Mutex::new(Cell::new([(0, 0); MAX_SHARED_STRIPS])),
+            rebalance: [const { Signal::new() }; MAX_SHARED_STRIPS],
+        }
+    }
+
+    /// Strip `slot`'s place in this budget, or `None` if the budget is unlimited and there is
+    /// nothing to share.
+    #[must_use]
+    pub const fn share(&'static self, slot: usize) -> Option<PowerShare> {
+        match self.total {
+            Some(_) => Some(PowerShare { budget: self, slot }),
+            None => None,
+        }
+    }
+
+    /// Record that strip `slot` wants `wanted` units (already within its own budget) and
+    /// return how many it may use.
+    ///
+    /// Each strip is owed its proportional share. This strip gets its share as far as the
+    /// others leave room; any other strip showing noticeably more or less than its share is
+    /// asked to rewrite its frame, which brings it to its share and frees or takes up the
+    /// difference.
+    fn allow(&self, slot: usize, wanted: u32) -> u32 {
+        self.usage.lock(|usage_cell| {
+            let mut usage = usage_cell.get();
+            let Some(total) = self.total else {
+                usage[slot] = (wanted, wanted);
+                usage_cell.set(usage);
+                return wanted;
+            };
+            // This write already brings the strip to its share.
+            self.rebalance[slot].reset();
+            usage[slot].0 = wanted;
+            let all_wanted: u64 = usage.iter().map(|(wanted, _)| u64::from(*wanted)).sum();
+            let fair_share = |wanted: u32| {
+                if all_wanted > u64::from(total) {
+                    (u64::from(total) * u64::from(wanted) / all_wanted) as u32
+                } else {
+                    wanted
+                }
+            };
+            let others_shown: u64 = usage
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != slot)
+                .map(|(_, (_, shown))| u64::from(*shown))
+                .sum();
+            let free = u64::from(total).saturating_sub(others_shown);
+            let allowed = u64::from(fair_share(wanted)).min(free) as u32;
+            usage[slot].1 = allowed;
+            usage_cell.set(usage);
+            let slack = total / REBALANCE_SLACK;
+            for (index, (strip_wanted, strip_shown)) in usage.iter().enumerate() {
+                if index != slot && strip_shown.abs_diff(fair_share(*strip_wanted)) > slack {
+                    self.rebalance[index].signal(());
+                }
+            }
+            allowed
+        })
+    }
+
+    /// Wait until strip `slot` should rewrite its frame to match its share.
+    async fn wait_for_rebalance(&self, slot: usize) {
+        self.rebalance[slot].wait().await;
+    }
+}
+
+/// A strip's place in a shared [`PowerBudget`].
+#[doc(hidden)] // Required pub for macro expansion in downstream crates
+#[derive(Clone, Copy)]
+pub struct PowerShare {
+    pub budget: &'static PowerBudget,
+    pub slot: usize,
+}
+
+/// Scales frames that would exceed a strip's own budget or its share of a group budget.
+pub(crate) struct CurrentLimiter {
+    max_current: Option<u32>,
+    share: Option<PowerShare>,
+    // The last share taken from the group budget, as (allowed, wanted) units.
+    last_share: Cell<(u32, u32)>,
+    // Set when the next write should take a new share instead of scaling like the last one.
+    rebudget: Cell<bool>,
+}
+
+impl CurrentLimiter {
+    pub(crate) const fn new(max_current: Current, share: Option<PowerShare>) -> Self {
+        if let Some(share) = share {
+            assert!(
+                share.slot < MAX_SHARED_STRIPS,
+                "power share slot must be below MAX_SHARED_STRIPS"
+            );
+        }
+        Self {
+            max_current: budget_units(max_current),
+            share,
+            last_share: Cell::new((0, 0)),
+            rebudget: Cell::new(true),
+        }
+    }
+
+    /// Take a new share from the group budget on the next write. Call this for a new frame,
+    /// a brightness change, or a rebalance, but not for a driver refresh of the same frame.
+    pub(crate) fn rebudget(&self) {
+        self.rebudget.set(true);
+    }
+
+    /// Dim `pixels` just enough to fit the budgets; frames that fit are left alone.
+    pub(crate) fn limit(&self, pixels: &mut [RGB8]) {
+        let sum = channel_sum(pixels);
+        let allowed = self.allowed(sum);
+        if allowed < sum {
+            scale_to(pixels, sum, allowed);
+        }
+    }
+
+    /// Wait until the strip should rewrite its frame because its share of the group budget
+    /// changed. Never returns for a strip without a share.
+    pub(crate) async fn wait_for_rebalance(&self) {
+        match self.share {
+            Some(share) => share.budget.wait_for_rebalance(share.slot).await,
+            None => core::future::pending().await,
+        }
+    }
+
+    /// How much of `wanted` (in `CHANNEL_MA / 255` units) a frame may draw, for drivers that
+    /// scale their own output.
+    pub(crate) fn allowed(&self, wanted: u32) -> u32 {
+        let allowed = self
+            .max_current
+            .map_or(wanted, |max_current| wanted.min(max_current));
+        let Some(share) = self.share else {
+            return allowed;
+        };
+        if self.rebudget.replace(false) {
+            let shared = share.budget.allow(share.slot, allowed);
+            self.last_share.set((shared, allowed));
+            return shared;
+        }
+        // A refresh only jitters the frame, so scale it as the last share did.
+        match self.last_share.get() {
+            (shared, last_wanted) if shared < last_wanted => {
+                (u64::from(allowed) * u64::from(shared) / u64::from(last_wanted)) as u32
+            }
+            _ => allowed,
+        }
+    }
+}
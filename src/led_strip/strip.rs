@@ -4,7 +4,7 @@
 // cmk000 why is this file named this?
 
 use core::cell::RefCell;
-use embassy_futures::select::{Either, select};
+use embassy_futures::select::{Either4, select4};
 use embassy_rp::pio::{Common, Instance};
 use embassy_rp::pio_programs::ws2812::{PioWs2812, PioWs2812Program};
 use embassy_sync::blocking_mutex::Mutex;
@@ -12,11 +12,17 @@ use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
 use embassy_sync::channel::Channel as EmbassyChannel;
 use embassy_sync::once_lock::OnceLock;
 use embassy_sync::signal::Signal;
-use embassy_time::{Duration, Timer};
+use embassy_time::{Duration, Instant, Timer};
 use heapless::Vec;
 use smart_leds::RGB8;
 
 use crate::Result;
+use crate::animation::{AnimationSource, AnimationStream, UnlockSignal};
+use crate::led_strip::brightness::{BrightnessControl, BrightnessSignal, Dimmer};
+use crate::led_strip::calibration::CalibrationTables;
+use crate::led_strip::color_order::ColorOrder;
+use crate::led_strip::dither::{Dither, Ditherer};
+use crate::led_strip::power::{CurrentLimiter, PowerShare};
 
 /// RGB color representation re-exported from `smart_leds`.
 pub type Rgb = RGB8;
@@ -205,6 +211,27 @@ pub type LedStripCompletionSignal = Signal<CriticalSectionRawMutex, ()>;
 pub enum Command<const N: usize, const MAX_FRAMES: usize> {
     DisplayStatic(Frame<N>),
     Animate(Vec<(Frame<N>, Duration), MAX_FRAMES>),
+    Stream(&'static (dyn FrameStream<N> + Sync)),
+}
+
+#[doc(hidden)] // Required pub for the command type
+/// Type-erased [`AnimationStream`] so the (non-generic) animation task can render from it.
+pub trait FrameStream<const N: usize> {
+    fn try_next_frame(&self) -> Option<(Frame<N>, Duration)>;
+    fn unlocked(&self) -> &UnlockSignal;
+}
+
+impl<S, const N: usize> FrameStream<N> for AnimationStream<S, Frame<N>>
+where
+    S: AnimationSource<Frame<N>>,
+{
+    fn try_next_frame(&self) -> Option<(Frame<N>, Duration)> {
+        self.try_advance()
+    }
+
+    fn unlocked(&self) -> &UnlockSignal {
+        AnimationStream::unlocked(self)
+    }
 }
 
 /// Static used to construct LED strip instances with animation support.
@@ -212,6 +239,7 @@ pub enum Command<const N: usize, const MAX_FRAMES: usize> {
 pub struct LedStripStatic<const N: usize, const MAX_FRAMES: usize> {
     command_signal: LedStripCommandSignal<N, MAX_FRAMES>,
     completion_signal: LedStripCompletionSignal,
+    brightness_signal: BrightnessSignal,
     commands: LedStripCommands<N>,
 }
 
@@ -222,6 +250,7 @@ impl<const N: usize, const MAX_FRAMES: usize> LedStripStatic<N, MAX_FRAMES> {
         Self {
             command_signal: Signal::new(),
             completion_signal: Signal::new(),
+            brightness_signal: Signal::new(),
             commands: LedStripCommands::new(),
         }
     }
@@ -234,6 +263,10 @@ impl<const N: usize, const MAX_FRAMES: usize> LedStripStatic<N, MAX_FRAMES> {
         &self.completion_signal
     }
 
+    pub fn brightness_signal(&'static self) -> &'static BrightnessSignal {
+        &self.brightness_signal
+    }
+
     pub fn commands(&'static self) -> &'static LedStripCommands<N> {
         &self.commands
     }
@@ -248,6 +281,7 @@ impl<const N: usize, const MAX_FRAMES: usize> LedStripStatic<N, MAX_FRAMES> {
 pub struct LedStrip<const N: usize, const MAX_FRAMES: usize> {
     command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
     completion_signal: &'static LedStripCompletionSignal,
+    brightness_signal: &'static BrightnessSignal,
 }
 
 impl<const N: usize, const MAX_FRAMES: usize> LedStrip<N, MAX_FRAMES> {
@@ -262,6 +296,7 @@ impl<const N: usize, const MAX_FRAMES: usize> LedStrip<N, MAX_FRAMES> {
         Ok(Self {
             command_signal: led_strip_static.command_signal(),
             completion_signal: led_strip_static.completion_signal(),
+            brightness_signal: led_strip_static.brightness_signal(),
         })
     }
 
@@ -302,6 +337,44 @@ impl<const N: usize, const MAX_FRAMES: usize> LedStrip<N, MAX_FRAMES> {
         self.completion_signal.wait().await;
         Ok(())
     }
+
+    /// Play frames rendered on demand by `stream` until interrupted by another command.
+    ///
+    /// Unlike [`animate`](Self::animate), frames are not stored, so the animation can run
+    /// indefinitely regardless of `MAX_FRAMES`. See [`crate::animation`] for an example.
+    pub async fn animate_stream<S>(
+        &self,
+        stream: &'static AnimationStream<S, Frame<N>>,
+    ) -> Result<()>
+    where
+        S: AnimationSource<Frame<N>> + Send,
+    {
+        self.command_signal.signal(Command::Stream(stream));
+        self.completion_signal.wait().await;
+        Ok(())
+    }
+
+    /// Change brightness immediately, without interrupting the frame or animation on display.
+    ///
+    /// `255` (the starting level) is as bright as `max_current` allows and `0` is off. See
+    /// [`brightness`](crate::led_strip::brightness).
+    pub fn set_brightness(&self, brightness: u8) {
+        self.brightness_control().set_brightness(brightness);
+    }
+
+    /// Change brightness gradually over `duration`, without interrupting the frame or
+    /// animation on display.
+    pub fn fade_brightness(&self, brightness: u8, duration: Duration) {
+        self.brightness_control()
+            .fade_brightness(brightness, duration);
+    }
+
+    /// A copyable handle for changing this strip's brightness from elsewhere, such as
+    /// [`AutoDim`](crate::led_strip::auto_dim::AutoDim).
+    #[must_use]
+    pub const fn brightness_control(&self) -> BrightnessControl {
+        BrightnessControl::new(self.brightness_signal)
+    }
 }
 
 #[doc(hidden)] // Required pub for macro expansion in downstream crates
@@ -309,92 +382,303 @@ pub async fn led_strip_animation_loop<
     PIO,
     const SM: usize,
     const N: usize,
+    const WIRE: usize,
     const MAX_FRAMES: usize,
     ORDER,
 >(
-    mut driver: PioWs2812<'static, PIO, SM, N, ORDER>,
+    driver: PioWs2812<'static, PIO, SM, WIRE, ORDER>,
     command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
     completion_signal: &'static LedStripCompletionSignal,
-    combo_table: &'static [u8; 256],
+    brightness_signal: &'static BrightnessSignal,
+    calibration: &'static CalibrationTables,
+    color_order: ColorOrder,
+    dither: Dither,
+    max_current: Current,
+    power_share: Option<PowerShare>,
 ) -> !
 where
     PIO: Instance,
     ORDER: embassy_rp::pio_programs::ws2812::RgbColorOrder,
 {
+    assert!(
+        WIRE == color_order.wire_len(N),
+        "driver length must match the strip's color order"
+    );
+    run_strip_loop(
+        Ws2812Driver {
+            driver,
+            color_order,
+            refresh: dither.refresh(WIRE),
+            ditherer: (dither == Dither::On).then(Ditherer::new),
+        },
+        command_signal,
+        completion_signal,
+        brightness_signal,
+        calibration,
+        max_current,
+        power_share,
+    )
+    .await
+}
+
+/// Sends frames to the hardware, applying brightness and the current limit in whatever way
+/// suits its protocol.
+trait StripDriver<const N: usize> {
+    async fn write(&mut self, frame: &Frame<N>, dimmer: &Dimmer, limiter: &CurrentLimiter);
+
+    /// How often to rewrite the frame on display, if the driver needs it (for dithering).
+    fn refresh(&self) -> Option<Duration>;
+}
+
+/// A one-wire WS2812-style strip on a PIO state machine.
+///
+/// The PIO driver sends `WIRE` 3-byte words, which carry the `N` pixels in the strip's color
+/// order.
+struct Ws2812Driver<
+    PIO: Instance + 'static,
+    const SM: usize,
+    const N: usize,
+    const WIRE: usize,
+    ORDER,
+> {
+    driver: PioWs2812<'static, PIO, SM, WIRE, ORDER>,
+    color_order: ColorOrder,
+    refresh: Option<Duration>,
+    ditherer: Option<Ditherer<N>>,
+}
+
+impl<PIO, const SM: usize, const N: usize, const WIRE: usize, ORDER> StripDriver<N>
+    for Ws2812Driver<PIO, SM, N, WIRE, ORDER>
+where
+    PIO: Instance,
+    ORDER: embassy_rp::pio_programs::ws2812::RgbColorOrder,
+{
+    async fn write(&mut self, frame: &Frame<N>, dimmer: &Dimmer, limiter: &CurrentLimiter) {
+        let mut corrected_frame = *frame;
+        match &mut self.ditherer {
+            Some(ditherer) => ditherer.apply(&mut corrected_frame, dimmer),
+            None => dimmer.correct(&mut corrected_frame),
+        }
+        // Limit the encoded bytes, so an RGBW strip's white channel counts too.
+        let mut wire = [Rgb::default(); WIRE];
+        self.color_order.encode(&corrected_frame, &mut wire);
+        limiter.limit(&mut wire);
+        self.driver.write(&wire).await;
+    }
+
+    fn refresh(&self) -> Option<Duration> {
+        self.refresh
+    }
+}
+
+async fn run_strip_loop<D, const N: usize, const MAX_FRAMES: usize>(
+    driver: D,
+    command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static LedStripCompletionSignal,
+    brightness_signal: &'static BrightnessSignal,
+    calibration: &'static CalibrationTables,
+    max_current: Current,
+    power_share: Option<PowerShare>,
+) -> !
+where
+    D: StripDriver<N>,
+{
+    let mut output = StripOutput {
+        driver,
+        dimmer: Dimmer::new(calibration),
+        limiter: CurrentLimiter::new(max_current, power_share),
+        shown: Frame::new(),
+    };
+    let mut pending_command: Option<Command<N, MAX_FRAMES>> = None;
     loop {
-        let command = command_signal.wait().await;
-        command_signal.reset();
+        let command = if let Some(command) = pending_command.take() {
+            command
+        } else {
+            let command = output
+                .wait_for_command(command_signal, brightness_signal, None)
+                .await
+                .expect("waiting without a deadline ends only with a command");
+            command_signal.reset();
+            command
+        };
 
         match command {
             Command::DisplayStatic(frame) => {
-                let mut corrected_frame = frame;
-                apply_correction(&mut corrected_frame, combo_table);
-                driver.write(&corrected_frame).await;
+                output.show(frame).await;
                 completion_signal.signal(());
             }
             Command::Animate(frames) => {
-                let next_command = run_frame_animation(
-                    &mut driver,
-                    frames,
-                    command_signal,
-                    completion_signal,
-                    combo_table,
-                )
-                .await;
+                // Loop back to handle whatever interrupted the animation.
+                pending_command = Some(
+                    run_frame_animation(
+                        &mut output,
+                        frames,
+                        command_signal,
+                        completion_signal,
+                        brightness_signal,
+                    )
+                    .await,
+                );
                 command_signal.reset();
-                match next_command {
-                    Command::DisplayStatic(frame) => {
-                        let mut corrected_frame = frame;
-                        apply_correction(&mut corrected_frame, combo_table);
-                        driver.write(&corrected_frame).await;
-                        completion_signal.signal(());
-                    }
-                    Command::Animate(_) => {
-                        // Loop back to process new animation
-                        continue;
-                    }
+            }
+            Command::Stream(stream) => {
+                pending_command = Some(
+                    run_stream_animation(
+                        &mut output,
+                        stream,
+                        command_signal,
+                        completion_signal,
+                        brightness_signal,
+                    )
+                    .await,
+                );
+                command_signal.reset();
+            }
+        }
+    }
+}
+
+/// The driver plus the frame it is showing and how brightly, so brightness changes can
+/// rewrite the frame on display.
+struct StripOutput<D, const N: usize> {
+    driver: D,
+    dimmer: Dimmer,
+    limiter: CurrentLimiter,
+    shown: Frame<N>,
+}
+
+impl<D, const N: usize> StripOutput<D, N>
+where
+    D: StripDriver<N>,
+{
+    async fn show(&mut self, frame: Frame<N>) {
+        self.shown = frame;
+        self.limiter.rebudget();
+        self.write_shown().await;
+    }
+
+    async fn write_shown(&mut self) {
+        self.driver
+            .write(&self.shown, &self.dimmer, &self.limiter)
+            .await;
+    }
+
+    /// Wait for the next command, or until `deadline` (returning `None`), applying brightness
+    /// changes, fade steps, driver refreshes, and power rebalancing to the frame on display in
+    /// the meantime.
+    async fn wait_for_command<const MAX_FRAMES: usize>(
+        &mut self,
+        command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
+        brightness_signal: &'static BrightnessSignal,
+        deadline: Option<Instant>,
+    ) -> Option<Command<N, MAX_FRAMES>> {
+        loop {
+            let refresh = self
+                .driver
+                .refresh()
+                .map(|refresh| Instant::now() + refresh);
+            let wake = [deadline, self.dimmer.next_step(), refresh]
+                .into_iter()
+                .flatten()
+                .min();
+            let timer = async {
+                match wake {
+                    Some(wake) => Timer::at(wake).await,
+                    None => core::future::pending().await,
+                }
+            };
+            let mut rebalance = false;
+            let deadline_passed = match select4(
+                command_signal.wait(),
+                brightness_signal.wait(),
+                timer,
+                self.limiter.wait_for_rebalance(),
+            )
+            .await
+            {
+                Either4::First(command) => return Some(command),
+                Either4::Second(change) => {
+                    self.dimmer.start(change);
+                    false
                 }
+                Either4::Third(()) => deadline.is_some_and(|deadline| Instant::now() >= deadline),
+                Either4::Fourth(()) => {
+                    rebalance = true;
+                    false
+                }
+            };
+            let brightness_changed = self.dimmer.update();
+            if deadline_passed {
+                // The caller writes its next frame at the new brightness.
+                return None;
+            }
+            if brightness_changed || rebalance {
+                self.limiter.rebudget();
+            }
+            // A dithering driver rewrites on every wake to carry its rounding error forward.
+            if brightness_changed || rebalance || refresh.is_some() {
+                self.write_shown().await;
             }
         }
     }
 }
 
-async fn run_frame_animation<PIO, const SM: usize, const N: usize, const MAX_FRAMES: usize, ORDER>(
-    driver: &mut PioWs2812<'static, PIO, SM, N, ORDER>,
+async fn run_frame_animation<D, const N: usize, const MAX_FRAMES: usize>(
+    output: &mut StripOutput<D, N>,
     frames: Vec<(Frame<N>, Duration), MAX_FRAMES>,
     command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
     completion_signal: &'static LedStripCompletionSignal,
-    combo_table: &'static [u8; 256],
+    brightness_signal: &'static BrightnessSignal,
 ) -> Command<N, MAX_FRAMES>
 where
-    PIO: Instance,
-    ORDER: embassy_rp::pio_programs::ws2812::RgbColorOrder,
+    D: StripDriver<N>,
 {
     completion_signal.signal(());
 
     loop {
         for (frame, duration) in &frames {
-            let mut corrected_frame = *frame;
-            apply_correction(&mut corrected_frame, combo_table);
-            driver.write(&corrected_frame).await;
-
-            match select(command_signal.wait(), Timer::after(*duration)).await {
-                Either::First(new_command) => {
-                    return new_command;
-                }
-                Either::Second(()) => continue,
+            output.show(*frame).await;
+
+            let deadline = Instant::now() + *duration;
+            if let Some(new_command) = output
+                .wait_for_command(command_signal, brightness_signal, Some(deadline))
+                .await
+            {
+                return new_command;
             }
         }
     }
 }
 
-fn apply_correction<const N: usize>(frame: &mut Frame<N>, combo_table: &[u8; 256]) {
-    for color in frame.iter_mut() {
-        *color = Rgb::new(
-            combo_table[usize::from(color.r)],
-            combo_table[usize::from(color.g)],
-            combo_table[usize::from(color.b)],
-        );
+async fn run_stream_animation<D, const N: usize, const MAX_FRAMES: usize>(
+    output: &mut StripOutput<D, N>,
+    stream: &'static (dyn FrameStream<N> + Sync),
+    command_signal: &'static LedStripCommandSignal<N, MAX_FRAMES>,
+    completion_signal: &'static LedStripCompletionSignal,
+    brightness_signal: &'static BrightnessSignal,
+) -> Command<N, MAX_FRAMES>
+where
+    D: StripDriver<N>,
+{
+    completion_signal.signal(());
+
+    loop {
+        let (frame, duration) = loop {
+            if let Some(next) = stream.try_next_frame() {
+                break next;
+            }
+            stream.unlocked().wait().await;
+        };
+        output.show(frame).await;
+
+        // A zero duration ends the stream; hold its last frame until the next command.
+        let deadline = (duration > Duration::from_ticks(0)).then(|| Instant::now() + duration);
+        if let Some(new_command) = output
+            .wait_for_command(command_signal, brightness_signal, deadline)
+            .await
+        {
+            return new_command;
+        }
     }
 }
 
@@ -408,6 +692,18 @@ fn apply_correction<const N: usize>(frame: &mut Frame<N>, combo_table: &[u8; 256
 /// using a single PIO peripheral. It handles interrupt bindings, PIO bus sharing, and
 /// per-strip brightness limiting based on current budget.
 ///
+/// Each strip's `max_current` limits the estimated current of each frame it shows (see
+/// [`power`](crate::led_strip::power)). To also cap the strips' combined draw, for example
+/// when they share one power supply, add `total_current: Current::Milliamps(...)` before the
+/// group name.
+///
+/// Strips default to GRB WS2812 byte order. Give a strip `color_order: ColorOrder::Rgb` (or
+/// another [`ColorOrder`](crate::led_strip::color_order::ColorOrder)) for other chips,
+/// including RGBW chips such as the SK6812. Add `dither: Dither::On` for smoother dim levels
+/// (see [`dither`](crate::led_strip::dither)), and `calibration: ColorCalibration { ... }` to
+/// correct a strip's white point or color mix (see
+/// [`calibration`](crate::led_strip::calibration)).
+///
 /// The macro generates:
 /// - A `pio0_split()` (or `pio1_split()`, `pio2_split()`) function that splits the PIO
 /// - One type per strip with `new_static()` and `new()` constructors
@@ -433,6 +729,7 @@ macro_rules! led_strips {
     (@__expand
         pio: $pio:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         strips: [
             $(
                 $label:ident {
@@ -442,6 +739,9 @@ macro_rules! led_strips {
                     len: $len:expr,
                     max_current: $max_current:expr,
                     gamma: $gamma:expr,
+                    color_order: $color_order:expr,
+                    dither: $dither:expr,
+                    calibration: $calibration:expr,
                     max_frames: $max_frames:expr
                     $(,
                         led2d: {
@@ -493,6 +793,10 @@ macro_rules! led_strips {
         }
 
         paste::paste! {
+            // Current budget shared by every strip in the group
+            static [<$group:upper _POWER_BUDGET>]: $crate::led_strip::power::PowerBudget =
+                $crate::led_strip::power::PowerBudget::new($total_current);
+
             // Create strip types
             $(
                 #[doc = concat!(
@@ -508,16 +812,14 @@ macro_rules! led_strips {
                     pub const LEN: usize = $len;
                     pub const MAX_FRAMES: usize = $max_frames;
 
-                    // Calculate max brightness from current budget
                     // Each WS2812B LED draws ~60mA at full brightness
                     /// cmk00 OK to assume 60 mA per LED
                     const WORST_CASE_MA: u32 = ($len as u32) * 60;
+                    /// Brightness at which an all-white frame fits the current budget. Frames
+                    /// are limited by their actual content, so most can show brighter.
                     pub const MAX_BRIGHTNESS: u8 =
                         $max_current.max_brightness(Self::WORST_CASE_MA);
 
-                    // Combined gamma correction and brightness scaling table
-                    const COMBO_TABLE: [u8; 256] = $crate::led_strip::gamma::generate_combo_table($gamma, Self::MAX_BRIGHTNESS);
-
                     pub(crate) const fn new_static() -> $crate::led_strip::LedStripStatic<{ $len }, { $max_frames }> {
                         $crate::led_strip::LedStrip::new_static()
                     }
@@ -538,6 +840,7 @@ macro_rules! led_strips {
                             pin.into(),
                             STRIP_STATIC.command_signal(),
                             STRIP_STATIC.completion_signal(),
+                            STRIP_STATIC.brightness_signal(),
                         )
                         .map_err($crate::Error::TaskSpawn)?;
                         spawner.spawn(token);
@@ -570,13 +873,17 @@ macro_rules! led_strips {
                     pin: ::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$pin>,
                     command_signal: &'static $crate::led_strip::LedStripCommandSignal<{ $len }, { $max_frames }>,
                     completion_signal: &'static $crate::led_strip::LedStripCompletionSignal,
+                    brightness_signal: &'static $crate::led_strip::brightness::BrightnessSignal,
                 ) -> ! {
+                    // Gamma and calibration tables, computed at compile time
+                    static CALIBRATION: $crate::led_strip::calibration::CalibrationTables =
+                        $crate::led_strip::calibration::CalibrationTables::new($gamma, $calibration);
                     let program = bus.get_program();
                     let driver = bus.with_common(|common| {
                         ::embassy_rp::pio_programs::ws2812::PioWs2812::<
                             ::embassy_rp::peripherals::$pio,
                             $sm_index,
-                            { $len },
+                            { $crate::led_strip::color_order::ColorOrder::wire_len($color_order, $len) },
                             _
                         >::new(common, sm, dma, pin, program)
                     });
@@ -584,9 +891,20 @@ macro_rules! led_strips {
                         ::embassy_rp::peripherals::$pio,
                         $sm_index,
                         { $len },
+                        { $crate::led_strip::color_order::ColorOrder::wire_len($color_order, $len) },
                         { $max_frames },
                         _
-                    >(driver, command_signal, completion_signal, &[<$label:camel LedStrip>]::COMBO_TABLE).await
+                    >(
+                        driver,
+                        command_signal,
+                        completion_signal,
+                        brightness_signal,
+                        &CALIBRATION,
+                        $color_order,
+                        $dither,
+                        $max_current,
+                        [<$group:upper _POWER_BUDGET>].share($sm_index),
+                    ).await
                 }
 
                 $(
@@ -669,6 +987,25 @@ macro_rules! led_strips {
     (@__select_sm 2, $sm0:ident, $sm1:ident, $sm2:ident, $sm3:ident) => { $sm2 };
     (@__select_sm 3, $sm0:ident, $sm1:ident, $sm2:ident, $sm3:ident) => { $sm3 };
 
+    // Entry point with explicit pio, a total current budget shared by all strips, and group syntax
+    (
+        pio: $pio:ident,
+        total_current: $total_current:expr,
+        $group:ident {
+            $( $label:ident: { $($fields:tt)* } ),+ $(,)?
+        }
+    ) => {
+        led_strips! {
+            @__with_defaults
+            pio: $pio,
+            group: $group,
+            total_current: $total_current,
+            sm_counter: 0,
+            strips_out: [],
+            strips_in: [ $( $label: { $($fields)* } ),+ ]
+        }
+    };
+
     // Entry point with explicit pio and group syntax
     (
         pio: $pio:ident,
@@ -680,6 +1017,25 @@ macro_rules! led_strips {
             @__with_defaults
             pio: $pio,
             group: $group,
+            total_current: $crate::led_strip::Current::Unlimited,
+            sm_counter: 0,
+            strips_out: [],
+            strips_in: [ $( $label: { $($fields)* } ),+ ]
+        }
+    };
+
+    // Entry point without pio (defaults to PIO0), with a total current budget and group syntax
+    (
+        total_current: $total_current:expr,
+        $group:ident {
+            $( $label:ident: { $($fields:tt)* } ),+ $(,)?
+        }
+    ) => {
+        led_strips! {
+            @__with_defaults
+            pio: PIO0,
+            group: $group,
+            total_current: $total_current,
             sm_counter: 0,
             strips_out: [],
             strips_in: [ $( $label: { $($fields)* } ),+ ]
@@ -696,6 +1052,7 @@ macro_rules! led_strips {
             @__with_defaults
             pio: PIO0,
             group: $group,
+            total_current: $crate::led_strip::Current::Unlimited,
             sm_counter: 0,
             strips_out: [],
             strips_in: [ $( $label: { $($fields)* } ),+ ]
@@ -706,6 +1063,7 @@ macro_rules! led_strips {
     (@__with_defaults
         pio: $pio:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         sm_counter: $sm:tt,
         strips_out: [ $($out:tt)* ],
         strips_in: [ $label:ident: { $($fields:tt)* } $(, $($rest:tt)* )? ]
@@ -718,11 +1076,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($($rest)*)? ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: __MISSING_PIN__,
             dma: __DEFAULT_DMA__,
             len: __MISSING_LEN__,
             max_current: $crate::led_strip::Current::Unlimited,
             gamma: $crate::led_strip::gamma::Gamma::Linear,
+            color_order: $crate::led_strip::color_order::ColorOrder::Grb,
+            dither: $crate::led_strip::dither::Dither::Off,
+            calibration: $crate::led_strip::calibration::ColorCalibration::NONE,
             max_frames: 32,
             led2d: __NONE__,
             fields: [ $($fields)* ]
@@ -733,6 +1095,7 @@ macro_rules! led_strips {
     (@__with_defaults
         pio: $pio:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         sm_counter: $sm:tt,
         strips_out: [ $($out:tt)* ],
         strips_in: []
@@ -741,6 +1104,7 @@ macro_rules! led_strips {
             @__expand
             pio: $pio,
             group: $group,
+            total_current: $total_current,
             strips: [ $($out)* ]
         }
     };
@@ -753,11 +1117,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ pin: $new_pin:ident $(, $($rest:tt)* )? ]
@@ -770,11 +1138,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $new_pin,
             dma: $dma,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -788,11 +1160,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ dma: $new_dma:ident $(, $($rest:tt)* )? ]
@@ -805,11 +1181,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $new_dma,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -823,11 +1203,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ len: $new_len:expr $(, $($rest:tt)* )? ]
@@ -840,11 +1224,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $dma,
             len: $new_len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -858,11 +1246,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ max_current: $new_max_current:expr $(, $($rest:tt)* )? ]
@@ -875,11 +1267,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $dma,
             len: $len,
             max_current: $new_max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -893,11 +1289,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ gamma: $new_gamma:expr $(, $($rest:tt)* )? ]
@@ -910,11 +1310,144 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $dma,
             len: $len,
             max_current: $max_current,
             gamma: $new_gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            led2d: $led2d,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    (@__fill_strip_defaults
+        pio: $pio:ident,
+        sm_counter: $sm:tt,
+        strips_out: [ $($out:tt)* ],
+        strips_remaining: [ $($remaining:tt)* ],
+        label: $label:ident,
+        group: $group:ident,
+        total_current: $total_current:expr,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        led2d: $led2d:tt,
+        fields: [ color_order: $new_color_order:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strips! {
+            @__fill_strip_defaults
+            pio: $pio,
+            sm_counter: $sm,
+            strips_out: [ $($out)* ],
+            strips_remaining: [ $($remaining)* ],
+            label: $label,
+            group: $group,
+            total_current: $total_current,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $new_color_order,
+            dither: $dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            led2d: $led2d,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    (@__fill_strip_defaults
+        pio: $pio:ident,
+        sm_counter: $sm:tt,
+        strips_out: [ $($out:tt)* ],
+        strips_remaining: [ $($remaining:tt)* ],
+        label: $label:ident,
+        group: $group:ident,
+        total_current: $total_current:expr,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        led2d: $led2d:tt,
+        fields: [ dither: $new_dither:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strips! {
+            @__fill_strip_defaults
+            pio: $pio,
+            sm_counter: $sm,
+            strips_out: [ $($out)* ],
+            strips_remaining: [ $($remaining)* ],
+            label: $label,
+            group: $group,
+            total_current: $total_current,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $color_order,
+            dither: $new_dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            led2d: $led2d,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    (@__fill_strip_defaults
+        pio: $pio:ident,
+        sm_counter: $sm:tt,
+        strips_out: [ $($out:tt)* ],
+        strips_remaining: [ $($remaining:tt)* ],
+        label: $label:ident,
+        group: $group:ident,
+        total_current: $total_current:expr,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:expr,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        led2d: $led2d:tt,
+        fields: [ calibration: $new_calibration:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strips! {
+            @__fill_strip_defaults
+            pio: $pio,
+            sm_counter: $sm,
+            strips_out: [ $($out)* ],
+            strips_remaining: [ $($remaining)* ],
+            label: $label,
+            group: $group,
+            total_current: $total_current,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $new_calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -928,11 +1461,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: [ max_frames: $new_max_frames:expr $(, $($rest:tt)* )? ]
@@ -945,11 +1482,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $dma,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $new_max_frames,
             led2d: $led2d,
             fields: [ $($($rest)*)? ]
@@ -963,11 +1504,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:tt,
         dma: $dma:ident,
         len: $len:tt,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: __NONE__,
         fields: [ led2d: { $($led2d_fields:tt)* } $(, $($rest:tt)* )? ]
@@ -980,11 +1525,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: $dma,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: __HAS_LED2D__ { $($led2d_fields)* },
             fields: [ $($($rest)*)? ]
@@ -1000,11 +1549,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: __DEFAULT_DMA__,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: []
@@ -1017,11 +1570,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: DMA_CH0,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: []
@@ -1034,11 +1591,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: __DEFAULT_DMA__,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: []
@@ -1051,11 +1612,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: DMA_CH1,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: []
@@ -1068,11 +1633,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: __DEFAULT_DMA__,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: []
@@ -1085,11 +1654,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: DMA_CH2,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: []
@@ -1102,11 +1675,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: __DEFAULT_DMA__,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: $led2d:tt,
         fields: []
@@ -1119,11 +1696,15 @@ macro_rules! led_strips {
             strips_remaining: [ $($remaining)* ],
             label: $label,
             group: $group,
+            total_current: $total_current,
             pin: $pin,
             dma: DMA_CH3,
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             led2d: $led2d,
             fields: []
@@ -1138,11 +1719,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: $dma:ident,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: __NONE__,
         fields: []
@@ -1151,6 +1736,7 @@ macro_rules! led_strips {
             @__inc_counter
             pio: $pio,
             group: $group,
+            total_current: $total_current,
             sm: $sm,
             strips_out: [
                 $($out)*
@@ -1161,6 +1747,9 @@ macro_rules! led_strips {
                     len: $len,
                     max_current: $max_current,
                     gamma: $gamma,
+                    color_order: $color_order,
+                    dither: $dither,
+                    calibration: $calibration,
                     max_frames: $max_frames
                 },
             ],
@@ -1175,11 +1764,15 @@ macro_rules! led_strips {
         strips_remaining: [ $($remaining:tt)* ],
         label: $label:ident,
         group: $group:ident,
+        total_current: $total_current:expr,
         pin: $pin:ident,
         dma: $dma:ident,
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         led2d: __HAS_LED2D__ { $($led2d_fields:tt)* },
         fields: []
@@ -1188,6 +1781,7 @@ macro_rules! led_strips {
             @__inc_counter
             pio: $pio,
             group: $group,
+            total_current: $total_current,
             sm: $sm,
             strips_out: [
                 $($out)*
@@ -1198,6 +1792,9 @@ macro_rules! led_strips {
                     len: $len,
                     max_current: $max_current,
                     gamma: $gamma,
+                    color_order: $color_order,
+                    dither: $dither,
+                    calibration: $calibration,
                     max_frames: $max_frames,
                     led2d: { $($led2d_fields)* }
                 },
@@ -1206,17 +1803,17 @@ macro_rules! led_strips {
         }
     };
     // Increment counter by expanding to literal numbers
-    (@__inc_counter pio: $pio:ident, group: $group:ident, sm: 0, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
-        led_strips! { @__with_defaults pio: $pio, group: $group, sm_counter: 1, strips_out: [$($out)*], strips_in: [$($in)*] }
+    (@__inc_counter pio: $pio:ident, group: $group:ident, total_current: $total_current:expr, sm: 0, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
+        led_strips! { @__with_defaults pio: $pio, group: $group, total_current: $total_current, sm_counter: 1, strips_out: [$($out)*], strips_in: [$($in)*] }
     };
-    (@__inc_counter pio: $pio:ident, group: $group:ident, sm: 1, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
-        led_strips! { @__with_defaults pio: $pio, group: $group, sm_counter: 2, strips_out: [$($out)*], strips_in: [$($in)*] }
+    (@__inc_counter pio: $pio:ident, group: $group:ident, total_current: $total_current:expr, sm: 1, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
+        led_strips! { @__with_defaults pio: $pio, group: $group, total_current: $total_current, sm_counter: 2, strips_out: [$($out)*], strips_in: [$($in)*] }
     };
-    (@__inc_counter pio: $pio:ident, group: $group:ident, sm: 2, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
-        led_strips! { @__with_defaults pio: $pio, group: $group, sm_counter: 3, strips_out: [$($out)*], strips_in: [$($in)*] }
+    (@__inc_counter pio: $pio:ident, group: $group:ident, total_current: $total_current:expr, sm: 2, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
+        led_strips! { @__with_defaults pio: $pio, group: $group, total_current: $total_current, sm_counter: 3, strips_out: [$($out)*], strips_in: [$($in)*] }
     };
-    (@__inc_counter pio: $pio:ident, group: $group:ident, sm: 3, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
-        led_strips! { @__with_defaults pio: $pio, group: $group, sm_counter: 4, strips_out: [$($out)*], strips_in: [$($in)*] }
+    (@__inc_counter pio: $pio:ident, group: $group:ident, total_current: $total_current:expr, sm: 3, strips_out: [$($out:tt)*], strips_in: [$($in:tt)*]) => {
+        led_strips! { @__with_defaults pio: $pio, group: $group, total_current: $total_current, sm_counter: 4, strips_out: [$($out)*], strips_in: [$($in)*] }
     };
 }
 
@@ -1279,7 +1876,10 @@ macro_rules! led_strips {
 ///
 /// - `pio: PIO1` - PIO peripheral (defaults to PIO0)
 /// - `dma: DMA_CH0` - DMA channel (defaults to DMA_CH0)
-/// - `gamma: Gamma::Gamma2_2` - Gamma correction (defaults to Gamma2_2)
+/// - `gamma: Gamma::Gamma2_2` - Gamma correction, including `Gamma::Custom(2.8)` or per-channel exponents (defaults to Gamma2_2)
+/// - `color_order: ColorOrder::Rgb` - Color byte order, or an RGBW order such as `ColorOrder::Grbw` (defaults to Grb)
+/// - `dither: Dither::On` - Temporal dithering for smooth dim levels (defaults to Off)
+/// - `calibration: ColorCalibration { ... }` - White point and color matrix correction (defaults to `ColorCalibration::NONE`)
 /// - `max_frames: 16` - Animation frame buffer size (defaults to 16)
 ///
 /// # Generated API
@@ -1327,6 +1927,9 @@ macro_rules! led_strip {
             len: _UNSET_,
             max_current: _UNSET_,
             gamma: $crate::led_strip::gamma::Gamma::Gamma2_2,
+            color_order: $crate::led_strip::color_order::ColorOrder::Grb,
+            dither: $crate::led_strip::dither::Dither::Off,
+            calibration: $crate::led_strip::calibration::ColorCalibration::NONE,
             max_frames: 16,
             fields: [ $($fields)* ]
         }
@@ -1341,6 +1944,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ pio: $new_pio:ident $(, $($rest:tt)* )? ]
     ) => {
@@ -1353,6 +1959,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1367,6 +1976,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ pin: $new_pin:ident $(, $($rest:tt)* )? ]
     ) => {
@@ -1379,6 +1991,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1393,6 +2008,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ dma: $new_dma:ident $(, $($rest:tt)* )? ]
     ) => {
@@ -1405,6 +2023,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1419,6 +2040,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ len: { $new_len:expr } $(, $($rest:tt)* )? ]
     ) => {
@@ -1431,6 +2055,9 @@ macro_rules! led_strip {
             len: { $new_len },
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1445,6 +2072,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ len: $new_len:expr $(, $($rest:tt)* )? ]
     ) => {
@@ -1457,6 +2087,9 @@ macro_rules! led_strip {
             len: $new_len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1471,6 +2104,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ max_current: $new_max_current:expr $(, $($rest:tt)* )? ]
     ) => {
@@ -1483,6 +2119,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $new_max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1497,6 +2136,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ gamma: $new_gamma:expr $(, $($rest:tt)* )? ]
     ) => {
@@ -1509,6 +2151,105 @@ macro_rules! led_strip {
             len: $len,
             max_current: $max_current,
             gamma: $new_gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: color_order
+    (@__fill_defaults
+        pio: $pio:ident,
+        name: $name:ident,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:tt,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        fields: [ color_order: $new_color_order:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strip! {
+            @__fill_defaults
+            pio: $pio,
+            name: $name,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $new_color_order,
+            dither: $dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: dither
+    (@__fill_defaults
+        pio: $pio:ident,
+        name: $name:ident,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:tt,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        fields: [ dither: $new_dither:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strip! {
+            @__fill_defaults
+            pio: $pio,
+            name: $name,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $color_order,
+            dither: $new_dither,
+            calibration: $calibration,
+            max_frames: $max_frames,
+            fields: [ $($($rest)*)? ]
+        }
+    };
+
+    // Fill defaults: calibration
+    (@__fill_defaults
+        pio: $pio:ident,
+        name: $name:ident,
+        pin: $pin:tt,
+        dma: $dma:ident,
+        len: $len:tt,
+        max_current: $max_current:tt,
+        gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
+        max_frames: $max_frames:expr,
+        fields: [ calibration: $new_calibration:expr $(, $($rest:tt)* )? ]
+    ) => {
+        led_strip! {
+            @__fill_defaults
+            pio: $pio,
+            name: $name,
+            pin: $pin,
+            dma: $dma,
+            len: $len,
+            max_current: $max_current,
+            gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $new_calibration,
             max_frames: $max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1523,6 +2264,9 @@ macro_rules! led_strip {
         len: $len:tt,
         max_current: $max_current:tt,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: [ max_frames: $new_max_frames:expr $(, $($rest:tt)* )? ]
     ) => {
@@ -1535,6 +2279,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $max_current,
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $new_max_frames,
             fields: [ $($($rest)*)? ]
         }
@@ -1549,6 +2296,9 @@ macro_rules! led_strip {
         len: $len:expr,
         max_current: _UNSET_,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: []
     ) => {
@@ -1561,6 +2311,9 @@ macro_rules! led_strip {
             len: $len,
             max_current: $crate::led_strip::Current::Milliamps(250),
             gamma: $gamma,
+            color_order: $color_order,
+            dither: $dither,
+            calibration: $calibration,
             max_frames: $max_frames,
             fields: []
         }
@@ -1575,6 +2328,9 @@ macro_rules! led_strip {
         len: $len:expr,
         max_current: $max_current:expr,
         gamma: $gamma:expr,
+        color_order: $color_order:expr,
+        dither: $dither:expr,
+        calibration: $calibration:expr,
         max_frames: $max_frames:expr,
         fields: []
     ) => {
@@ -1613,14 +2369,12 @@ macro_rules! led_strip {
                 pub const LEN: usize = $len;
                 pub const MAX_FRAMES: usize = $max_frames;
 
-                // Calculate max brightness from current budget
                 const WORST_CASE_MA: u32 = ($len as u32) * 60;
+                /// Brightness at which an all-white frame fits the current budget. Frames
+                /// are limited by their actual content, so most can show brighter.
                 pub const MAX_BRIGHTNESS: u8 =
                     $max_current.max_brightness(Self::WORST_CASE_MA);
 
-                // Combined gamma correction and brightness scaling table
-                const COMBO_TABLE: [u8; 256] = $crate::led_strip::gamma::generate_combo_table($gamma, Self::MAX_BRIGHTNESS);
-
                 /// Create a new LED strip with automatic PIO setup.
                 ///
                 /// This constructor handles PIO splitting and uses SM0 automatically.
@@ -1651,6 +2405,7 @@ macro_rules! led_strip {
                         pin.into(),
                         STRIP_STATIC.command_signal(),
                         STRIP_STATIC.completion_signal(),
+                        STRIP_STATIC.brightness_signal(),
                     )
                     .map_err($crate::Error::TaskSpawn)?;
                     spawner.spawn(token);
@@ -1684,13 +2439,17 @@ macro_rules! led_strip {
                 pin: ::embassy_rp::Peri<'static, ::embassy_rp::peripherals::$pin>,
                 command_signal: &'static $crate::led_strip::LedStripCommandSignal<{ $len }, { $max_frames }>,
                 completion_signal: &'static $crate::led_strip::LedStripCompletionSignal,
+                brightness_signal: &'static $crate::led_strip::brightness::BrightnessSignal,
             ) -> ! {
+                // Gamma and calibration tables, computed at compile time
+                static CALIBRATION: $crate::led_strip::calibration::CalibrationTables =
+                    $crate::led_strip::calibration::CalibrationTables::new($gamma, $calibration);
                 let program = bus.get_program();
                 let driver = bus.with_common(|common| {
                     ::embassy_rp::pio_programs::ws2812::PioWs2812::<
                         ::embassy_rp::peripherals::$pio,
                         0,
-                        { $len },
+                        { $crate::led_strip::color_order::ColorOrder::wire_len($color_order, $len) },
                         _
                     >::new(common, sm, dma, pin, program)
                 });
@@ -1698,9 +2457,20 @@ macro_rules! led_strip {
                     ::embassy_rp::peripherals::$pio,
                     0,
                     { $len },
+                    { $crate::led_strip::color_order::ColorOrder::wire_len($color_order, $len) },
                     { $max_frames },
                     _
-                >(driver, command_signal, completion_signal, &$name::COMBO_TABLE).await
+                >(
+                    driver,
+                    command_signal,
+                    completion_signal,
+                    brightness_signal,
+                    &CALIBRATION,
+                    $color_order,
+                    $dither,
+                    $max_current,
+                    None,
+                ).await
             }
         }
     };
@@ -29,6 +29,7 @@ compile_error!("Pico 1 (RP2040) only supports ARM architecture, not RISC-V");
 pub mod pio_irqs;
 
 // Only include modules that work without embassy when host feature is enabled
+pub mod animation;
 #[cfg(feature = "host")]
 pub(crate) mod bit_matrix_led4;
 // These modules require embassy_rp and are excluded when testing on host
@@ -40,22 +41,28 @@ pub mod button;
 pub mod char_lcd;
 #[cfg(not(feature = "host"))]
 pub mod clock;
+pub mod dmx;
+pub mod effects;
 #[cfg(not(feature = "host"))]
 mod error;
 #[cfg(not(feature = "host"))]
 pub mod flash_array;
+pub mod http_server;
 #[cfg(not(feature = "host"))]
 pub mod ir;
 #[cfg(not(feature = "host"))]
 pub mod ir_kepler;
 #[cfg(not(feature = "host"))]
 pub mod ir_mapping;
+#[cfg(not(feature = "host"))]
+pub mod keypad;
 pub mod led2d;
 #[cfg(not(feature = "host"))]
 pub mod led4;
 pub mod led_layout;
 #[cfg(not(feature = "host"))]
 pub mod led_strip;
+pub mod mqtt;
 #[cfg(not(feature = "host"))]
 pub mod rfid;
 #[cfg(not(feature = "host"))]
@@ -63,6 +70,10 @@ pub mod servo;
 #[cfg(not(feature = "host"))]
 pub mod servo_animate;
 #[cfg(not(feature = "host"))]
+pub mod servo_continuous;
+#[cfg(not(feature = "host"))]
+pub mod servo_group;
+#[cfg(not(feature = "host"))]
 pub mod time_sync;
 #[cfg(all(feature = "wifi", not(feature = "host")))]
 pub mod wifi;
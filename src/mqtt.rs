@@ -0,0 +1,703 @@
+//! A device abstraction for publishing and receiving MQTT messages over WiFi.
+//!
+//! [`Mqtt`] connects to an MQTT 3.1.1 broker over an existing network stack (typically from
+//! [`WifiAuto`](crate::wifi_auto::WifiAuto)), subscribes to the configured topics, and keeps
+//! the connection alive. When the connection drops, it reconnects with exponential backoff
+//! and subscribes again. A last will lets other clients see when the device goes offline.
+//!
+//! Messages are published at most once (QoS 0). Subscriptions may ask for at-least-once
+//! delivery (QoS 1); the client acknowledges each such message as it arrives.
+//!
+//! See [`Mqtt`] for usage and [`packet`] for the wire format. [`home_assistant`] announces
+//! devices to Home Assistant over the same connection.
+
+#![allow(clippy::future_not_send, reason = "single-threaded")]
+
+pub mod home_assistant;
+pub mod packet;
+
+use embassy_time::Duration;
+use heapless::{String, Vec};
+
+/// Longest topic [`Mqtt`] publishes or receives, in bytes.
+pub const MAX_TOPIC_LEN: usize = 128;
+
+/// Largest payload [`Mqtt`] publishes or receives, in bytes.
+pub const MAX_PAYLOAD_LEN: usize = 1024;
+
+/// The usual MQTT port for unencrypted connections.
+pub const DEFAULT_PORT: u16 = 1883;
+
+/// Delivery guarantee for a message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, defmt::Format)]
+#[repr(u8)]
+pub enum QoS {
+    /// Delivered at most once, with no acknowledgment.
+    #[default]
+    AtMostOnce = 0,
+    /// Delivered at least once; the receiver acknowledges it.
+    AtLeastOnce = 1,
+}
+
+/// A message the broker publishes on the client's behalf if the client disconnects
+/// unexpectedly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LastWill<'a> {
+    /// Topic to publish on, such as `devices/porch/status`.
+    pub topic: &'a str,
+    /// Message to publish, such as `offline`.
+    pub payload: &'a [u8],
+    /// Delivery guarantee for the will.
+    pub qos: QoS,
+    /// Whether the broker keeps the will as the topic's last known value.
+    pub retain: bool,
+}
+
+/// A topic filter to receive messages from, such as `devices/porch/display/#`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Subscription<'a> {
+    /// Topic filter; `+` matches one level and `#` matches the rest.
+    pub topic_filter: &'a str,
+    /// Highest delivery guarantee wanted for matching messages.
+    pub qos: QoS,
+}
+
+/// How long to wait between reconnection attempts: `min` after the first failure, doubling
+/// after each further failure up to `max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Backoff {
+    /// Delay after the first failure.
+    pub min: Duration,
+    /// Longest delay.
+    pub max: Duration,
+}
+
+impl Backoff {
+    /// 1 second, doubling up to 1 minute.
+    pub const DEFAULT: Self = Self {
+        min: Duration::from_secs(1),
+        max: Duration::from_secs(60),
+    };
+
+    /// Delay before reconnection attempt `attempt`, counting from 0.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 1_u64.checked_shl(attempt).unwrap_or(u64::MAX);
+        let ticks = self.min.as_ticks().saturating_mul(factor);
+        Duration::from_ticks(ticks.min(self.max.as_ticks()))
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Broker and session settings for [`Mqtt`].
+#[derive(Clone, Copy, Debug)]
+pub struct MqttConfig {
+    /// Broker host name or IP address, such as `"192.168.1.10"` or `"broker.local"`.
+    pub broker: &'static str,
+    /// Broker port, usually [`DEFAULT_PORT`].
+    pub port: u16,
+    /// Identifies this device to the broker; must be unique among its clients.
+    pub client_id: &'static str,
+    /// User name, if the broker requires one.
+    pub username: Option<&'static str>,
+    /// Password, if the broker requires one.
+    pub password: Option<&'static str>,
+    /// How often to ping an idle connection so the broker (and the device) can tell it is
+    /// still alive.
+    pub keep_alive: Duration,
+    /// Published by the broker if this device drops off the network.
+    pub will: Option<LastWill<'static>>,
+    /// Topics to receive, subscribed again on every reconnection.
+    pub subscriptions: &'static [Subscription<'static>],
+    /// Delay between reconnection attempts.
+    pub backoff: Backoff,
+}
+
+impl MqttConfig {
+    /// Settings for `broker` on the default port with no authentication, will, or
+    /// subscriptions, a 60-second keep-alive, and the default backoff.
+    #[must_use]
+    pub const fn new(broker: &'static str, client_id: &'static str) -> Self {
+        Self {
+            broker,
+            port: DEFAULT_PORT,
+            client_id,
+            username: None,
+            password: None,
+            keep_alive: Duration::from_secs(60),
+            will: None,
+            subscriptions: &[],
+            backoff: Backoff::DEFAULT,
+        }
+    }
+}
+
+/// A message received on a subscribed topic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MqttMessage {
+    /// Topic the message was published on.
+    pub topic: String<MAX_TOPIC_LEN>,
+    /// Message body.
+    pub payload: Vec<u8, MAX_PAYLOAD_LEN>,
+    /// Whether this is the broker's stored last value rather than a new message.
+    pub retain: bool,
+}
+
+impl MqttMessage {
+    /// The payload as text, if it is valid UTF-8.
+    #[must_use]
+    pub fn payload_str(&self) -> Option<&str> {
+        core::str::from_utf8(&self.payload).ok()
+    }
+}
+
+/// Events emitted by [`Mqtt`]. See the [`Mqtt`] documentation for usage details.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MqttEvent {
+    /// Connected and subscribed; queued messages are being published.
+    Connected,
+    /// The connection failed or dropped; reconnection is under way.
+    Disconnected(&'static str),
+    /// A message arrived on a subscribed topic.
+    Message(MqttMessage),
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+mod wifi_impl {
+    use core::convert::Infallible;
+
+    use defmt::{Display2Format, info, warn};
+    use embassy_executor::Spawner;
+    use embassy_futures::select::{Either3, select3};
+    use embassy_net::dns::DnsQueryType;
+    use embassy_net::tcp::TcpSocket;
+    use embassy_net::{IpAddress, Stack};
+    use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+    use embassy_sync::channel::{Channel, TrySendError};
+    use embassy_time::{Duration, Instant, Timer, with_timeout};
+    use embedded_io_async::Write;
+    use heapless::{String, Vec};
+    use portable_atomic::{AtomicU32, Ordering};
+    use static_cell::StaticCell;
+
+    use super::packet::{self, Connect, Packet, PacketError, Publish};
+    use super::{MAX_PAYLOAD_LEN, MAX_TOPIC_LEN, MqttConfig, MqttEvent, MqttMessage, QoS};
+    use crate::{Error, Result};
+
+    /// Messages waiting to be published, and events waiting to be read.
+    const QUEUE_LEN: usize = 4;
+    /// Room for the largest publish plus its header.
+    const PACKET_BUFFER_LEN: usize = MAX_TOPIC_LEN + MAX_PAYLOAD_LEN + 8;
+    /// How long the broker has to answer `CONNECT`.
+    const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// A message waiting to be published.
+    struct Outgoing {
+        topic: String<MAX_TOPIC_LEN>,
+        payload: Vec<u8, MAX_PAYLOAD_LEN>,
+        retain: bool,
+    }
+
+    type OutgoingQueue = Channel<CriticalSectionRawMutex, Outgoing, QUEUE_LEN>;
+    type MqttEvents = Channel<CriticalSectionRawMutex, MqttEvent, QUEUE_LEN>;
+
+    /// Resources needed to construct an [`Mqtt`] (see [`Mqtt`] docs).
+    pub struct MqttStatic {
+        outgoing: OutgoingQueue,
+        events: MqttEvents,
+        dropped_messages: AtomicU32,
+        mqtt_cell: StaticCell<Mqtt>,
+    }
+
+    /// Device abstraction for an MQTT 3.1.1 client.
+    ///
+    /// Uses an existing network stack (typically from [`WifiAuto`](crate::wifi_auto::WifiAuto)).
+    /// A background task owns the connection: it connects, subscribes to
+    /// [`MqttConfig::subscriptions`], pings the broker when idle, and reconnects after
+    /// [`MqttConfig::backoff`] when anything goes wrong.
+    ///
+    /// [`publish`](Self::publish) queues a message, which is sent once connected.
+    /// [`wait_for_event`](Self::wait_for_event) reports connection changes and incoming
+    /// messages. The task never waits for the app, so it keeps the connection alive even
+    /// while the app is busy: when 4 events are already unread, further messages are dropped
+    /// and counted by [`dropped_messages`](Self::dropped_messages), and a connection change
+    /// replaces the oldest unread event.
+    ///
+    /// # Examples
+    ///
+    /// Publish IR remote presses and take display commands from the network:
+    ///
+    /// ```no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// # use panic_probe as _;
+    /// use core::fmt::Write;
+    ///
+    /// use device_kit::ir::IrEvent;
+    /// use device_kit::mqtt::{
+    ///     LastWill, Mqtt, MqttConfig, MqttEvent, MqttStatic, QoS, Subscription,
+    /// };
+    /// use embassy_futures::select::{Either, select};
+    ///
+    /// const CONFIG: MqttConfig = MqttConfig {
+    ///     will: Some(LastWill {
+    ///         topic: "porch/status",
+    ///         payload: b"offline",
+    ///         qos: QoS::AtMostOnce,
+    ///         retain: true,
+    ///     }),
+    ///     subscriptions: &[Subscription {
+    ///         topic_filter: "porch/display",
+    ///         qos: QoS::AtLeastOnce,
+    ///     }],
+    ///     ..MqttConfig::new("192.168.1.10", "porch")
+    /// };
+    ///
+    /// # #[allow(dead_code)]
+    /// async fn run_mqtt(
+    ///     stack: &'static embassy_net::Stack<'static>,
+    ///     ir: &device_kit::ir::Ir<'static>,
+    ///     spawner: embassy_executor::Spawner,
+    /// ) -> device_kit::Result<()> {
+    ///     static MQTT_STATIC: MqttStatic = Mqtt::new_static();
+    ///     let mqtt = Mqtt::new(&MQTT_STATIC, stack, CONFIG, spawner)?;
+    ///
+    ///     loop {
+    ///         match select(ir.wait_for_press(), mqtt.wait_for_event()).await {
+    ///             Either::First(IrEvent::Press { addr, cmd }) => {
+    ///                 let mut payload = heapless::String::<32>::new();
+    ///                 write!(payload, "{addr:04X}:{cmd:02X}").ok();
+    ///                 mqtt.publish("porch/ir", payload.as_bytes(), false).await?;
+    ///             }
+    ///             Either::Second(MqttEvent::Connected) => {
+    ///                 mqtt.publish("porch/status", b"online", true).await?;
+    ///             }
+    ///             Either::Second(MqttEvent::Message(message)) => {
+    ///                 defmt::info!("Display: {}", message.payload_str().unwrap_or("?"));
+    ///             }
+    ///             Either::Second(MqttEvent::Disconnected(reason)) => {
+    ///                 defmt::info!("MQTT disconnected: {}", reason);
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub struct Mqtt {
+        outgoing: &'static OutgoingQueue,
+        events: &'static MqttEvents,
+        dropped_messages: &'static AtomicU32,
+    }
+
+    impl Mqtt {
+        /// Create [`Mqtt`] resources. See [`Mqtt`] docs for usage.
+        #[must_use]
+        pub const fn new_static() -> MqttStatic {
+            MqttStatic {
+                outgoing: Channel::new(),
+                events: Channel::new(),
+                dropped_messages: AtomicU32::new(0),
+                mqtt_cell: StaticCell::new(),
+            }
+        }
+
+        /// Create an [`Mqtt`] client and start connecting to `config.broker`.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the background task cannot be spawned.
+        pub fn new(
+            mqtt_static: &'static MqttStatic,
+            stack: &'static Stack<'static>,
+            config: MqttConfig,
+            spawner: Spawner,
+        ) -> Result<&'static Self> {
+            let mqtt = mqtt_static.mqtt_cell.init(Self {
+                outgoing: &mqtt_static.outgoing,
+                events: &mqtt_static.events,
+                dropped_messages: &mqtt_static.dropped_messages,
+            });
+            let token = mqtt_task(stack, config, mqtt).map_err(Error::TaskSpawn)?;
+            spawner.spawn(token);
+            Ok(mqtt)
+        }
+
+        /// Queue a message for publishing. Waits only if the queue is full.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if `topic` or `payload` is longer than [`MAX_TOPIC_LEN`] or
+        /// [`MAX_PAYLOAD_LEN`].
+        pub async fn publish(&self, topic: &str, payload: &[u8], retain: bool) -> Result<()> {
+            let topic = String::try_from(topic).map_err(|_| Error::FormatError)?;
+            let payload = Vec::from_slice(payload).map_err(|_| Error::FormatError)?;
+            self.outgoing
+                .send(Outgoing {
+                    topic,
+                    payload,
+                    retain,
+                })
+                .await;
+            Ok(())
+        }
+
+        /// Wait for and return the next [`MqttEvent`]. See [`Mqtt`] docs for an example.
+        pub async fn wait_for_event(&self) -> MqttEvent {
+            self.events.receive().await
+        }
+
+        /// How many incoming messages were dropped because events weren't read in time.
+        #[must_use]
+        pub fn dropped_messages(&self) -> u32 {
+            self.dropped_messages.load(Ordering::Relaxed)
+        }
+
+        /// Queue an incoming message for the app, or drop and count it if the queue is full.
+        fn deliver(&self, message: MqttMessage) {
+            if self.events.try_send(MqttEvent::Message(message)).is_err() {
+                warn!("MQTT event queue full; dropping a message");
+                self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        /// Queue a connection change for the app, dropping the oldest unread event to make
+        /// room, so the app always hears the latest state.
+        fn report(&self, event: MqttEvent) {
+            if let Err(TrySendError::Full(event)) = self.events.try_send(event) {
+                if let Ok(MqttEvent::Message(_)) = self.events.try_receive() {
+                    self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+                }
+                self.events.try_send(event).ok();
+            }
+        }
+    }
+
+    #[embassy_executor::task]
+    async fn mqtt_task(
+        stack: &'static Stack<'static>,
+        config: MqttConfig,
+        mqtt: &'static Mqtt,
+    ) -> ! {
+        run_mqtt_loop(stack, &config, mqtt).await
+    }
+
+    async fn run_mqtt_loop(
+        stack: &'static Stack<'static>,
+        config: &MqttConfig,
+        mqtt: &'static Mqtt,
+    ) -> ! {
+        static RX_BUFFER: StaticCell<[u8; PACKET_BUFFER_LEN]> = StaticCell::new();
+        static TX_BUFFER: StaticCell<[u8; PACKET_BUFFER_LEN]> = StaticCell::new();
+        static PACKET_BUFFER: StaticCell<[u8; PACKET_BUFFER_LEN]> = StaticCell::new();
+        static READ_BUFFER: StaticCell<[u8; PACKET_BUFFER_LEN]> = StaticCell::new();
+        let rx_buffer = RX_BUFFER.init([0; PACKET_BUFFER_LEN]);
+        let tx_buffer = TX_BUFFER.init([0; PACKET_BUFFER_LEN]);
+        let mut buffers = Buffers {
+            packet: PACKET_BUFFER.init([0; PACKET_BUFFER_LEN]),
+            read: READ_BUFFER.init([0; PACKET_BUFFER_LEN]),
+        };
+
+        info!("MQTT client started for broker {}", config.broker);
+        let mut attempt = 0;
+        loop {
+            let mut socket = TcpSocket::new(*stack, rx_buffer, tx_buffer);
+            let mut session = Session {
+                socket: &mut socket,
+                buffers: &mut buffers,
+                config,
+                read_len: 0,
+                skip_len: 0,
+                next_packet_id: 1,
+            };
+            let reason = match session.run(stack, mqtt, &mut attempt).await {
+                Ok(never) => match never {},
+                Err(reason) => reason,
+            };
+            socket.abort();
+            let _ = socket.flush().await;
+
+            warn!("MQTT disconnected: {}", reason);
+            mqtt.report(MqttEvent::Disconnected(reason));
+            let delay = config.backoff.delay(attempt);
+            attempt = attempt.saturating_add(1);
+            info!("MQTT reconnecting in {} ms", delay.as_millis());
+            Timer::after(delay).await;
+        }
+    }
+
+    struct Buffers {
+        /// Packets being encoded.
+        packet: &'static mut [u8; PACKET_BUFFER_LEN],
+        /// Bytes read from the broker but not yet decoded.
+        read: &'static mut [u8; PACKET_BUFFER_LEN],
+    }
+
+    /// One connection to the broker.
+    struct Session<'s, 'b> {
+        socket: &'s mut TcpSocket<'b>,
+        buffers: &'s mut Buffers,
+        config: &'s MqttConfig,
+        read_len: usize,
+        /// Bytes still to discard from an incoming packet too large to buffer.
+        skip_len: usize,
+        next_packet_id: u16,
+    }
+
+    type SessionResult<T> = core::result::Result<T, &'static str>;
+
+    impl Session<'_, '_> {
+        /// Connect, subscribe, then relay messages until the connection fails. Resets
+        /// `attempt` once the broker accepts the connection.
+        async fn run(
+            &mut self,
+            stack: &Stack<'static>,
+            mqtt: &Mqtt,
+            attempt: &mut u32,
+        ) -> SessionResult<Infallible> {
+            let config = self.config;
+            let address = resolve(stack, config.broker).await?;
+            info!("MQTT connecting to {}:{}", address, config.port);
+            self.socket
+                .connect((address, config.port))
+                .await
+                .map_err(|_| "TCP connect failed")?;
+
+            let keep_alive_secs = u16::try_from(config.keep_alive.as_secs()).unwrap_or(u16::MAX);
+            let connect = Connect {
+                client_id: config.client_id,
+                username: config.username,
+                password: config.password.map(str::as_bytes),
+                keep_alive_secs,
+                will: config.will,
+            };
+            let len = connect.encode(self.buffers.packet).map_err(packet_error)?;
+            self.send_packet(len).await?;
+            match with_timeout(REPLY_TIMEOUT, self.read_connack()).await {
+                Ok(Ok(0)) => {}
+                Ok(Ok(return_code)) => return Err(connack_error(return_code)),
+                Ok(Err(reason)) => return Err(reason),
+                Err(_) => return Err("no reply to CONNECT"),
+            }
+            *attempt = 0;
+
+            if !config.subscriptions.is_empty() {
+                let packet_id = self.take_packet_id();
+                let len =
+                    packet::encode_subscribe(packet_id, config.subscriptions, self.buffers.packet)
+                        .map_err(packet_error)?;
+                self.send_packet(len).await?;
+            }
+            info!("MQTT connected as {}", config.client_id);
+            mqtt.report(MqttEvent::Connected);
+
+            let mut last_sent = Instant::now();
+            let mut ping_sent: Option<Instant> = None;
+            loop {
+                let deadline = match ping_sent {
+                    Some(ping_sent) => ping_sent + config.keep_alive,
+                    None => last_sent + config.keep_alive,
+                };
+                let ping_timer = async {
+                    if config.keep_alive.as_ticks() == 0 {
+                        core::future::pending::<()>().await;
+                    }
+                    Timer::at(deadline).await;
+                };
+                match select3(self.fill(), mqtt.outgoing.receive(), ping_timer).await {
+                    Either3::First(result) => {
+                        result?;
+                        while let Some(message) = self.take_packet(&mut ping_sent).await? {
+                            mqtt.deliver(message);
+                        }
+                    }
+                    Either3::Second(message) => {
+                        let publish = Publish {
+                            topic: &message.topic,
+                            payload: &message.payload,
+                            qos: QoS::AtMostOnce,
+                            retain: message.retain,
+                            packet_id: None,
+                        };
+                        let len = publish.encode(self.buffers.packet).map_err(packet_error)?;
+                        self.send_packet(len).await?;
+                        last_sent = Instant::now();
+                    }
+                    Either3::Third(()) => {
+                        if ping_sent.is_some() {
+                            return Err("broker stopped answering pings");
+                        }
+                        self.socket
+                            .write_all(&packet::PINGREQ)
+                            .await
+                            .map_err(|_| "TCP write failed")?;
+                        let now = Instant::now();
+                        ping_sent = Some(now);
+                        last_sent = now;
+                    }
+                }
+            }
+        }
+
+        fn take_packet_id(&mut self) -> u16 {
+            let packet_id = self.next_packet_id;
+            self.next_packet_id = self.next_packet_id.checked_add(1).unwrap_or(1);
+            packet_id
+        }
+
+        async fn send_packet(&mut self, len: usize) -> SessionResult<()> {
+            self.socket
+                .write_all(&self.buffers.packet[..len])
+                .await
+                .map_err(|_| "TCP write failed")
+        }
+
+        /// Read more bytes from the broker into the read buffer.
+        async fn fill(&mut self) -> SessionResult<()> {
+            let read_len = self.read_len;
+            let len = self
+                .socket
+                .read(&mut self.buffers.read[read_len..])
+                .await
+                .map_err(|_| "TCP read failed")?;
+            if len == 0 {
+                return Err("broker closed the connection");
+            }
+            // Drop the part of an oversized packet that is still arriving.
+            let skipped = len.min(self.skip_len);
+            self.skip_len -= skipped;
+            self.buffers
+                .read
+                .copy_within(read_len + skipped..read_len + len, read_len);
+            self.read_len += len - skipped;
+            Ok(())
+        }
+
+        /// Read until the broker's reply to `CONNECT` arrives, returning its return code.
+        async fn read_connack(&mut self) -> SessionResult<u8> {
+            loop {
+                if let Some((packet, len)) =
+                    Packet::parse(&self.buffers.read[..self.read_len]).map_err(packet_error)?
+                {
+                    let Packet::ConnAck { return_code, .. } = packet else {
+                        return Err("expected CONNACK");
+                    };
+                    self.consume(len);
+                    return Ok(return_code);
+                }
+                self.fill().await?;
+            }
+        }
+
+        /// Decode the next complete packet in the read buffer, answering it if needed.
+        /// Returns any message it carried; `None` once no complete message remains.
+        async fn take_packet(
+            &mut self,
+            ping_sent: &mut Option<Instant>,
+        ) -> SessionResult<Option<MqttMessage>> {
+            loop {
+                let read = &self.buffers.read[..self.read_len];
+                let Some((packet, len)) = Packet::parse(read).map_err(packet_error)? else {
+                    self.skip_oversized()?;
+                    return Ok(None);
+                };
+                let message = match packet {
+                    Packet::Publish(publish) => {
+                        if let Some(packet_id) = publish.packet_id {
+                            let mut puback = [0; 4];
+                            let puback_len = packet::encode_puback(packet_id, &mut puback)
+                                .map_err(packet_error)?;
+                            self.socket
+                                .write_all(&puback[..puback_len])
+                                .await
+                                .map_err(|_| "TCP write failed")?;
+                        }
+                        to_message(&publish)
+                    }
+                    Packet::PingResp => {
+                        *ping_sent = None;
+                        None
+                    }
+                    Packet::SubAck { return_codes, .. } => {
+                        if return_codes.contains(&0x80) {
+                            warn!("MQTT broker refused a subscription");
+                        }
+                        None
+                    }
+                    _ => None,
+                };
+                self.consume(len);
+                if message.is_some() {
+                    return Ok(message);
+                }
+            }
+        }
+
+        /// If the read buffer is full of one incomplete packet, drop it and skip the rest
+        /// as it arrives.
+        fn skip_oversized(&mut self) -> SessionResult<()> {
+            if self.read_len < PACKET_BUFFER_LEN {
+                return Ok(());
+            }
+            let total_len = Packet::total_len(&self.buffers.read[..self.read_len])
+                .map_err(packet_error)?
+                .ok_or("malformed packet")?;
+            warn!("MQTT dropping a {}-byte packet", total_len);
+            self.skip_len = total_len - self.read_len;
+            self.read_len = 0;
+            Ok(())
+        }
+
+        fn consume(&mut self, len: usize) {
+            self.buffers.read.copy_within(len..self.read_len, 0);
+            self.read_len -= len;
+        }
+    }
+
+    /// Copy a received publish into an owned message, or `None` if it is too large.
+    fn to_message(publish: &Publish<'_>) -> Option<MqttMessage> {
+        let (Ok(topic), Ok(payload)) = (
+            String::try_from(publish.topic),
+            Vec::from_slice(publish.payload),
+        ) else {
+            warn!("MQTT dropping an oversized message on {}", publish.topic);
+            return None;
+        };
+        Some(MqttMessage {
+            topic,
+            payload,
+            retain: publish.retain,
+        })
+    }
+
+    /// Look up `broker`, which may also be an IP address.
+    async fn resolve(stack: &Stack<'static>, broker: &str) -> SessionResult<IpAddress> {
+        let addresses = stack
+            .dns_query(broker, DnsQueryType::A)
+            .await
+            .map_err(|_| "DNS lookup failed")?;
+        addresses.first().copied().ok_or("no DNS results")
+    }
+
+    fn packet_error(err: PacketError) -> &'static str {
+        warn!("MQTT packet error: {}", Display2Format(&err));
+        "MQTT packet error"
+    }
+
+    const fn connack_error(return_code: u8) -> &'static str {
+        match return_code {
+            1 => "broker refused protocol version",
+            2 => "broker refused client ID",
+            3 => "broker unavailable",
+            4 => "bad user name or password",
+            5 => "not authorized",
+            _ => "broker refused connection",
+        }
+    }
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+pub use wifi_impl::{Mqtt, MqttStatic};
@@ -0,0 +1,568 @@
+//! Home Assistant MQTT discovery, so devices show up as entities without any YAML.
+//!
+//! Describe the board as a [`Device`] and its parts as [`Entity`]s, and [`HomeAssistant`]
+//! writes the retained discovery messages, the state updates, and decodes the commands Home
+//! Assistant sends back. Each part maps to the entity type that fits it:
+//!
+//! | Part                       | [`EntityKind`]                                  |
+//! |----------------------------|-------------------------------------------------|
+//! | LED strip or 2D display    | [`Light`](EntityKind::Light): on/off, brightness, color, effect |
+//! | Servo                      | [`Number`](EntityKind::Number), e.g. [`EntityKind::SERVO`] |
+//! | Button                     | [`Event`](EntityKind::Event), e.g. [`EntityKind::BUTTON`] |
+//! | IR remote or RFID reader   | [`Event`](EntityKind::Event), or [`Sensor`](EntityKind::Sensor) holding the last code or card |
+//! | `Led4` or `CharLcd`        | [`Text`](EntityKind::Text)                      |
+//!
+//! Topics are built from [`Device::node_id`] and [`Entity::object_id`]: an entity's state is
+//! published to `<node_id>/<object_id>/state` and commands arrive on
+//! `<node_id>/<object_id>/set`. A light's brightness, color, and effect use their own
+//! `brightness`, `rgb`, and `effect` subtopics, such as `porch/strip/rgb/set`. The device's
+//! availability is `<node_id>/status`, which should also be the MQTT last will so entities go
+//! unavailable when the board drops off the network.
+//!
+//! With WiFi, [`HomeAssistant::announce`] and [`HomeAssistant::publish_state`] send through
+//! an [`Mqtt`](super::Mqtt) client.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::mqtt::home_assistant::{
+//!     Device, Entity, EntityKind, HomeAssistant, Incoming, OFFLINE, Value,
+//! };
+//! use device_kit::mqtt::{LastWill, Mqtt, MqttConfig, MqttEvent, MqttStatic, QoS, Subscription};
+//! use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+//! use embassy_sync::signal::Signal;
+//!
+//! static HOME_ASSISTANT: HomeAssistant = HomeAssistant::new(
+//!     Device {
+//!         model: Some("Pico 2 W porch light"),
+//!         ..Device::new("porch", "Porch")
+//!     },
+//!     &[
+//!         Entity {
+//!             object_id: "strip",
+//!             name: "Strip",
+//!             kind: EntityKind::Light {
+//!                 brightness: true,
+//!                 color: true,
+//!                 effects: &["rainbow", "comet"],
+//!             },
+//!         },
+//!         Entity {
+//!             object_id: "button",
+//!             name: "Button",
+//!             kind: EntityKind::BUTTON,
+//!         },
+//!     ],
+//! );
+//!
+//! const CONFIG: MqttConfig = MqttConfig {
+//!     will: Some(LastWill {
+//!         topic: "porch/status",
+//!         payload: OFFLINE.as_bytes(),
+//!         qos: QoS::AtMostOnce,
+//!         retain: true,
+//!     }),
+//!     subscriptions: &[
+//!         Subscription { topic_filter: "homeassistant/status", qos: QoS::AtMostOnce },
+//!         Subscription { topic_filter: "porch/+/set", qos: QoS::AtMostOnce },
+//!         Subscription { topic_filter: "porch/+/+/set", qos: QoS::AtMostOnce },
+//!     ],
+//!     ..MqttConfig::new("192.168.1.10", "porch")
+//! };
+//!
+//! // Announcing publishes several messages, so it runs in its own task and the event loop
+//! // below never stops reading while the publish queue drains.
+//! static ANNOUNCE: Signal<CriticalSectionRawMutex, ()> = Signal::new();
+//!
+//! #[embassy_executor::task]
+//! async fn announce_task(mqtt: &'static Mqtt) -> ! {
+//!     loop {
+//!         ANNOUNCE.wait().await;
+//!         // The discovery messages are built from constants, so this fails only if they
+//!         // don't fit in an MQTT message, which shows up on the first run.
+//!         defmt::unwrap!(HOME_ASSISTANT.announce(mqtt).await);
+//!     }
+//! }
+//!
+//! # #[allow(dead_code)]
+//! async fn run_home_assistant(
+//!     stack: &'static embassy_net::Stack<'static>,
+//!     spawner: embassy_executor::Spawner,
+//! ) -> device_kit::Result<()> {
+//!     static MQTT_STATIC: MqttStatic = Mqtt::new_static();
+//!     let mqtt = Mqtt::new(&MQTT_STATIC, stack, CONFIG, spawner)?;
+//!     spawner.spawn(announce_task(mqtt)?);
+//!
+//!     loop {
+//!         match mqtt.wait_for_event().await {
+//!             MqttEvent::Connected => ANNOUNCE.signal(()),
+//!             MqttEvent::Message(message) => match HOME_ASSISTANT.parse(&message) {
+//!                 Some(Incoming::HomeAssistantOnline) => ANNOUNCE.signal(()),
+//!                 Some(Incoming::Command { entity, value }) => {
+//!                     // Apply `value` to the part, then confirm the new state.
+//!                     HOME_ASSISTANT
+//!                         .publish_state(mqtt, entity.object_id, value)
+//!                         .await?;
+//!                 }
+//!                 None => {}
+//!             },
+//!             MqttEvent::Disconnected(_) => {}
+//!         }
+//!     }
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use core::fmt::{self, Write};
+
+use heapless::String;
+use smart_leds::RGB8;
+
+use super::{MAX_PAYLOAD_LEN, MAX_TOPIC_LEN, MqttMessage};
+use crate::http_server::json::ObjectWriter;
+
+/// Topic prefix Home Assistant watches for discovery messages.
+pub const DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// Topic where Home Assistant announces that it (re)started.
+pub const STATUS_TOPIC: &str = "homeassistant/status";
+
+/// Availability payload while the device is connected.
+pub const ONLINE: &str = "online";
+
+/// Availability payload for the last will, once the device has gone.
+pub const OFFLINE: &str = "offline";
+
+/// An MQTT topic built by [`HomeAssistant`].
+pub type Topic = String<MAX_TOPIC_LEN>;
+
+/// An MQTT payload built by [`HomeAssistant`].
+pub type Payload = String<MAX_PAYLOAD_LEN>;
+
+/// The board, as Home Assistant's device registry shows it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Device {
+    /// Identifies the device and prefixes its topics. Use letters, digits, `_`, and `-` only.
+    pub node_id: &'static str,
+    /// Name shown in Home Assistant.
+    pub name: &'static str,
+    /// Maker shown in the device info, if any.
+    pub manufacturer: Option<&'static str>,
+    /// Model shown in the device info, if any.
+    pub model: Option<&'static str>,
+}
+
+impl Device {
+    /// A device with no manufacturer or model.
+    #[must_use]
+    pub const fn new(node_id: &'static str, name: &'static str) -> Self {
+        Self {
+            node_id,
+            name,
+            manufacturer: None,
+            model: None,
+        }
+    }
+}
+
+/// One controllable or observable part of the device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entity {
+    /// Identifies the entity within its device. Use letters, digits, `_`, and `-` only.
+    pub object_id: &'static str,
+    /// Name shown in Home Assistant.
+    pub name: &'static str,
+    /// What kind of entity it is and what it supports.
+    pub kind: EntityKind,
+}
+
+/// The Home Assistant entity type, with its options.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EntityKind {
+    /// Can be switched on and off, and optionally dimmed, colored, and given an effect.
+    Light {
+        /// Whether brightness (0 to 255) can be set.
+        brightness: bool,
+        /// Whether an RGB color can be set.
+        color: bool,
+        /// Effect names to offer; empty for none.
+        effects: &'static [&'static str],
+    },
+    /// A number set from a slider.
+    Number {
+        /// Smallest value.
+        min: i32,
+        /// Largest value.
+        max: i32,
+        /// Slider step.
+        step: u32,
+        /// Unit shown after the value, if any.
+        unit: Option<&'static str>,
+    },
+    /// Something that happens, such as a button press, reported with one of `event_types`.
+    Event {
+        /// The event types the entity reports.
+        event_types: &'static [&'static str],
+    },
+    /// A read-only value, such as the last card read.
+    Sensor {
+        /// Unit shown after the value, if any.
+        unit: Option<&'static str>,
+    },
+    /// Text that can be set, such as what a display shows.
+    Text {
+        /// Longest text accepted, in characters.
+        max_len: u16,
+    },
+}
+
+impl EntityKind {
+    /// A servo positioned in degrees, 0 to 180, matching
+    /// [`Servo::set_degrees`](crate::servo::Servo::set_degrees).
+    pub const SERVO: Self = Self::Number {
+        min: 0,
+        max: 180,
+        step: 1,
+        unit: Some("°"),
+    };
+
+    /// A button reporting `short` and `long` presses, matching
+    /// [`PressDuration`](crate::button::PressDuration).
+    pub const BUTTON: Self = Self::Event {
+        event_types: &["short", "long"],
+    };
+
+    /// Component name in discovery topics.
+    const fn component(&self) -> &'static str {
+        match self {
+            Self::Light { .. } => "light",
+            Self::Number { .. } => "number",
+            Self::Event { .. } => "event",
+            Self::Sensor { .. } => "sensor",
+            Self::Text { .. } => "text",
+        }
+    }
+}
+
+/// A state sent to Home Assistant or a command received from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Value<'a> {
+    /// Light on or off.
+    Power(bool),
+    /// Light brightness.
+    Brightness(u8),
+    /// Light color.
+    Color(RGB8),
+    /// Light effect, one of the entity's `effects`.
+    Effect(&'a str),
+    /// Number value.
+    Number(i32),
+    /// Text or sensor value.
+    Text(&'a str),
+    /// Event that just happened, one of the entity's `event_types`. Never a command.
+    Event(&'a str),
+}
+
+impl Value<'_> {
+    /// Subtopic between the entity's base topic and `/state` or `/set`.
+    const fn subtopic(&self) -> &'static str {
+        match self {
+            Self::Brightness(_) => "/brightness",
+            Self::Color(_) => "/rgb",
+            Self::Effect(_) => "/effect",
+            Self::Power(_) | Self::Number(_) | Self::Text(_) | Self::Event(_) => "",
+        }
+    }
+}
+
+/// A message from Home Assistant, decoded by [`HomeAssistant::parse`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Incoming<'a> {
+    /// Home Assistant (re)started; call [`HomeAssistant::announce`] again.
+    HomeAssistantOnline,
+    /// Set `entity` to `value`.
+    Command {
+        /// The entity to change.
+        entity: &'static Entity,
+        /// Its new value.
+        value: Value<'a>,
+    },
+}
+
+/// A device and its entities, for Home Assistant MQTT discovery. See the
+/// [module docs](self) for usage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HomeAssistant {
+    device: Device,
+    entities: &'static [Entity],
+}
+
+impl HomeAssistant {
+    /// Describe `device` with `entities`.
+    #[must_use]
+    pub const fn new(device: Device, entities: &'static [Entity]) -> Self {
+        Self { device, entities }
+    }
+
+    /// The device.
+    #[must_use]
+    pub const fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// The entities, in announcement order.
+    #[must_use]
+    pub const fn entities(&self) -> &'static [Entity] {
+        self.entities
+    }
+
+    /// Topic for the device's availability, `<node_id>/status`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic is longer than [`MAX_TOPIC_LEN`].
+    pub fn availability_topic(&self) -> Result<Topic, fmt::Error> {
+        let mut topic = Topic::new();
+        write!(topic, "{}/status", self.device.node_id)?;
+        Ok(topic)
+    }
+
+    /// The retained discovery message that makes `entity` appear in Home Assistant.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic or payload doesn't fit.
+    pub fn discovery(&self, entity: &Entity) -> Result<(Topic, Payload), fmt::Error> {
+        let node_id = self.device.node_id;
+        let mut topic = Topic::new();
+        write!(
+            topic,
+            "{DISCOVERY_PREFIX}/{}/{node_id}/{}/config",
+            entity.kind.component(),
+            entity.object_id
+        )?;
+
+        let mut payload = Payload::new();
+        let mut json = ObjectWriter::start(&mut payload)?;
+        // Topics below starting with `~` are relative to this base.
+        json.string("~", format_args!("{node_id}/{}", entity.object_id))?;
+        json.string("name", format_args!("{}", entity.name))?;
+        json.string("unique_id", format_args!("{node_id}_{}", entity.object_id))?;
+        json.string("availability_topic", format_args!("{node_id}/status"))?;
+        json.object("device", |device| {
+            device.strings("identifiers", &[node_id])?;
+            device.string("name", format_args!("{}", self.device.name))?;
+            if let Some(manufacturer) = self.device.manufacturer {
+                device.string("manufacturer", format_args!("{manufacturer}"))?;
+            }
+            if let Some(model) = self.device.model {
+                device.string("model", format_args!("{model}"))?;
+            }
+            Ok(())
+        })?;
+
+        match entity.kind {
+            EntityKind::Light {
+                brightness,
+                color,
+                effects,
+            } => {
+                write_topics(&mut json, "")?;
+                if brightness {
+                    write_topics(&mut json, "brightness_")?;
+                }
+                if color {
+                    write_topics(&mut json, "rgb_")?;
+                }
+                if !effects.is_empty() {
+                    write_topics(&mut json, "effect_")?;
+                    json.strings("effect_list", effects)?;
+                }
+            }
+            EntityKind::Number {
+                min,
+                max,
+                step,
+                unit,
+            } => {
+                write_topics(&mut json, "")?;
+                json.number("min", min)?;
+                json.number("max", max)?;
+                json.number("step", step)?;
+                if let Some(unit) = unit {
+                    json.string("unit_of_measurement", format_args!("{unit}"))?;
+                }
+                json.string("mode", format_args!("slider"))?;
+            }
+            EntityKind::Event { event_types } => {
+                json.string("state_topic", format_args!("~/state"))?;
+                json.strings("event_types", event_types)?;
+            }
+            EntityKind::Sensor { unit } => {
+                json.string("state_topic", format_args!("~/state"))?;
+                if let Some(unit) = unit {
+                    json.string("unit_of_measurement", format_args!("{unit}"))?;
+                }
+            }
+            EntityKind::Text { max_len } => {
+                write_topics(&mut json, "")?;
+                json.number("max", max_len)?;
+            }
+        }
+        json.end()?;
+        Ok((topic, payload))
+    }
+
+    /// The message reporting that the entity `object_id` now has `value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the topic or payload doesn't fit.
+    pub fn state(&self, object_id: &str, value: Value<'_>) -> Result<(Topic, Payload), fmt::Error> {
+        let mut topic = Topic::new();
+        write!(
+            topic,
+            "{}/{object_id}{}/state",
+            self.device.node_id,
+            value.subtopic()
+        )?;
+
+        let mut payload = Payload::new();
+        match value {
+            Value::Power(on) => payload.write_str(if on { "ON" } else { "OFF" })?,
+            Value::Brightness(brightness) => write!(payload, "{brightness}")?,
+            Value::Color(RGB8 { r, g, b }) => write!(payload, "{r},{g},{b}")?,
+            Value::Number(number) => write!(payload, "{number}")?,
+            Value::Effect(text) | Value::Text(text) => payload.write_str(text)?,
+            Value::Event(event_type) => {
+                let mut json = ObjectWriter::start(&mut payload)?;
+                json.string("event_type", format_args!("{event_type}"))?;
+                json.end()?;
+            }
+        }
+        Ok((topic, payload))
+    }
+
+    /// Decode `message` if it is meant for this device, returning `None` for anything else,
+    /// including commands with values the entity doesn't accept.
+    #[must_use]
+    pub fn parse<'a>(&self, message: &'a MqttMessage) -> Option<Incoming<'a>> {
+        let payload = message.payload_str()?;
+        if message.topic.as_str() == STATUS_TOPIC {
+            return (payload == ONLINE).then_some(Incoming::HomeAssistantOnline);
+        }
+
+        let path = message
+            .topic
+            .strip_prefix(self.device.node_id)?
+            .strip_prefix('/')?
+            .strip_suffix("/set")?;
+        let (object_id, subtopic) = path.split_once('/').unwrap_or((path, ""));
+        let entity = self
+            .entities
+            .iter()
+            .find(|entity| entity.object_id == object_id)?;
+
+        let value = match (entity.kind, subtopic) {
+            (EntityKind::Light { .. }, "") => match payload {
+                "ON" => Value::Power(true),
+                "OFF" => Value::Power(false),
+                _ => return None,
+            },
+            (
+                EntityKind::Light {
+                    brightness: true, ..
+                },
+                "brightness",
+            ) => Value::Brightness(payload.parse().ok()?),
+            (EntityKind::Light { color: true, .. }, "rgb") => Value::Color(parse_rgb(payload)?),
+            (EntityKind::Light { effects, .. }, "effect") if effects.contains(&payload) => {
+                Value::Effect(payload)
+            }
+            (EntityKind::Number { min, max, .. }, "") => {
+                let number = payload.parse().ok()?;
+                if !(min..=max).contains(&number) {
+                    return None;
+                }
+                Value::Number(number)
+            }
+            (EntityKind::Text { max_len }, "")
+                if payload.chars().count() <= usize::from(max_len) =>
+            {
+                Value::Text(payload)
+            }
+            _ => return None,
+        };
+        Some(Incoming::Command { entity, value })
+    }
+}
+
+/// Parse Home Assistant's `r,g,b` color payload.
+fn parse_rgb(payload: &str) -> Option<RGB8> {
+    let mut channels = payload
+        .split(',')
+        .map(|channel| channel.trim().parse().ok());
+    let color = RGB8::new(channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(color)
+}
+
+/// Write the `<prefix>command_topic` and `<prefix>state_topic` pair for a subtopic.
+fn write_topics(json: &mut ObjectWriter<'_, Payload>, prefix: &str) -> fmt::Result {
+    // `rgb_` becomes `~/rgb/set`; the empty prefix becomes `~/set`.
+    let subtopic = prefix.strip_suffix('_').unwrap_or(prefix);
+    let separator = if subtopic.is_empty() { "" } else { "/" };
+    for (suffix, direction) in [("command_topic", "set"), ("state_topic", "state")] {
+        let mut key = String::<32>::new();
+        write!(key, "{prefix}{suffix}")?;
+        json.string(&key, format_args!("~{separator}{subtopic}/{direction}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(all(feature = "wifi", not(feature = "host")))]
+mod wifi_impl {
+    use super::{HomeAssistant, ONLINE, Value};
+    use crate::mqtt::Mqtt;
+    use crate::{Error, Result};
+
+    impl HomeAssistant {
+        /// Publish the discovery message for every entity, then mark the device online.
+        /// Call after each [`MqttEvent::Connected`](crate::mqtt::MqttEvent::Connected) and
+        /// [`Incoming::HomeAssistantOnline`](super::Incoming::HomeAssistantOnline).
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if a discovery message doesn't fit.
+        pub async fn announce(&self, mqtt: &Mqtt) -> Result<()> {
+            for entity in self.entities {
+                let (topic, payload) = self.discovery(entity).map_err(|_| Error::FormatError)?;
+                mqtt.publish(&topic, payload.as_bytes(), true).await?;
+            }
+            let topic = self.availability_topic().map_err(|_| Error::FormatError)?;
+            mqtt.publish(&topic, ONLINE.as_bytes(), true).await
+        }
+
+        /// Report that the entity `object_id` now has `value`. States other than events are
+        /// retained, so Home Assistant sees them after it restarts.
+        ///
+        /// # Errors
+        ///
+        /// Returns an error if the topic or payload doesn't fit.
+        pub async fn publish_state(
+            &self,
+            mqtt: &Mqtt,
+            object_id: &str,
+            value: Value<'_>,
+        ) -> Result<()> {
+            let (topic, payload) = self
+                .state(object_id, value)
+                .map_err(|_| Error::FormatError)?;
+            let retain = !matches!(value, Value::Event(_));
+            mqtt.publish(&topic, payload.as_bytes(), retain).await
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! MQTT 3.1.1 packet encoding and decoding.
+//!
+//! Covers what a client needs: it encodes `CONNECT`, `PUBLISH`, `PUBACK`, `SUBSCRIBE`,
+//! `PINGREQ`, and `DISCONNECT`, and decodes what a broker sends back. Packets are written
+//! into and read from caller-provided buffers; nothing is allocated.
+//!
+//! ```no_run
+//! # #![no_std]
+//! # #![no_main]
+//! # use panic_probe as _;
+//! use device_kit::mqtt::QoS;
+//! use device_kit::mqtt::packet::{Packet, Publish};
+//!
+//! fn round_trip() {
+//!     let publish = Publish {
+//!         topic: "lights/kitchen",
+//!         payload: b"on",
+//!         qos: QoS::AtMostOnce,
+//!         retain: false,
+//!         packet_id: None,
+//!     };
+//!     let mut buffer = [0; 64];
+//!     let len = publish.encode(&mut buffer).unwrap();
+//!
+//!     let (packet, used) = Packet::parse(&buffer[..len]).unwrap().expect("packet is complete");
+//!     assert_eq!(used, len);
+//!     assert_eq!(packet, Packet::Publish(publish));
+//! }
+//! # #[embassy_executor::main]
+//! # async fn main(_spawner: embassy_executor::Spawner) {}
+//! ```
+
+use derive_more::derive::Display;
+
+use super::{LastWill, QoS, Subscription};
+
+/// `PINGREQ`, which keeps an idle connection alive.
+pub const PINGREQ: [u8; 2] = [0xC0, 0x00];
+
+/// `DISCONNECT`, which closes a connection without publishing the last will.
+pub const DISCONNECT: [u8; 2] = [0xE0, 0x00];
+
+const CONNECT: u8 = 1;
+const CONNACK: u8 = 2;
+const PUBLISH: u8 = 3;
+const PUBACK: u8 = 4;
+const SUBSCRIBE: u8 = 8;
+const SUBACK: u8 = 9;
+const PINGRESP: u8 = 13;
+
+/// Largest value the remaining-length field can hold.
+const MAX_REMAINING_LEN: usize = 268_435_455;
+
+/// Why a packet couldn't be encoded or decoded.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq)]
+pub enum PacketError {
+    #[display("buffer too small for packet")]
+    BufferTooSmall,
+    #[display("string or packet too long for MQTT")]
+    TooLong,
+    #[display("malformed packet")]
+    Malformed,
+}
+
+/// A `CONNECT` packet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Connect<'a> {
+    /// Identifies this client to the broker; must be unique among its clients.
+    pub client_id: &'a str,
+    /// User name, if the broker requires one.
+    pub username: Option<&'a str>,
+    /// Password, if the broker requires one. Only sent with a user name.
+    pub password: Option<&'a [u8]>,
+    /// Seconds the broker should wait without hearing from the client before giving up on
+    /// it, or 0 for never.
+    pub keep_alive_secs: u16,
+    /// Message the broker publishes if the connection drops without a `DISCONNECT`.
+    pub will: Option<LastWill<'a>>,
+}
+
+impl Connect<'_> {
+    /// Write the packet to `buffer`, returning its length.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buffer` is too small or a field is too long.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, PacketError> {
+        let username = self.username;
+        let password = self.password.filter(|_| username.is_some());
+
+        let mut flags = 0x02; // clean session
+        let mut payload_len = string_len(self.client_id)?;
+        if let Some(will) = &self.will {
+            flags |= 0x04 | ((will.qos as u8) << 3);
+            if will.retain {
+                flags |= 0x20;
+            }
+            payload_len += string_len(will.topic)? + bytes_len(will.payload)?;
+        }
+        if let Some(username) = username {
+            flags |= 0x80;
+            payload_len += string_len(username)?;
+        }
+        if let Some(password) = password {
+            flags |= 0x40;
+            payload_len += bytes_len(password)?;
+        }
+
+        // Protocol name, level, flags, and keep alive.
+        let mut writer = Writer::start(buffer, CONNECT << 4, 10 + payload_len)?;
+        writer.put_str("MQTT")?;
+        writer.put(&[4, flags])?;
+        writer.put(&self.keep_alive_secs.to_be_bytes())?;
+        writer.put_str(self.client_id)?;
+        if let Some(will) = &self.will {
+            writer.put_str(will.topic)?;
+            writer.put_bytes(will.payload)?;
+        }
+        if let Some(username) = username {
+            writer.put_str(username)?;
+        }
+        if let Some(password) = password {
+            writer.put_bytes(password)?;
+        }
+        Ok(writer.len)
+    }
+}
+
+/// A `PUBLISH` packet, sent by either side.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Publish<'a> {
+    /// Topic name, such as `home/kitchen/light`.
+    pub topic: &'a str,
+    /// Message body.
+    pub payload: &'a [u8],
+    /// Delivery guarantee.
+    pub qos: QoS,
+    /// Whether the broker should keep this as the topic's last known value.
+    pub retain: bool,
+    /// Packet identifier; required for [`QoS::AtLeastOnce`], absent for
+    /// [`QoS::AtMostOnce`].
+    pub packet_id: Option<u16>,
+}
+
+impl Publish<'_> {
+    /// Write the packet to `buffer`, returning its length.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `buffer` is too small, the topic is too long, or `packet_id`
+    /// doesn't match `qos`.
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize, PacketError> {
+        if self.packet_id.is_some() != (self.qos == QoS::AtLeastOnce) {
+            return Err(PacketError::Malformed);
+        }
+        let id_len = if self.packet_id.is_some() { 2 } else { 0 };
+        let first = (PUBLISH << 4) | ((self.qos as u8) << 1) | u8::from(self.retain);
+        let remaining_len = string_len(self.topic)? + id_len + self.payload.len();
+        let mut writer = Writer::start(buffer, first, remaining_len)?;
+        writer.put_str(self.topic)?;
+        if let Some(packet_id) = self.packet_id {
+            writer.put(&packet_id.to_be_bytes())?;
+        }
+        writer.put(self.payload)?;
+        Ok(writer.len)
+    }
+}
+
+/// Write a `PUBACK` acknowledging `packet_id`, returning its length.
+///
+/// # Errors
+///
+/// Returns an error if `buffer` is too small.
+pub fn encode_puback(packet_id: u16, buffer: &mut [u8]) -> Result<usize, PacketError> {
+    let mut writer = Writer::start(buffer, PUBACK << 4, 2)?;
+    writer.put(&packet_id.to_be_bytes())?;
+    Ok(writer.len)
+}
+
+/// Write a `SUBSCRIBE` for `subscriptions`, returning its length.
+///
+/// # Errors
+///
+/// Returns an error if `buffer` is too small, a topic filter is too long, or
+/// `subscriptions` is empty.
+pub fn encode_subscribe(
+    packet_id: u16,
+    subscriptions: &[Subscription],
+    buffer: &mut [u8],
+) -> Result<usize, PacketError> {
+    if subscriptions.is_empty() {
+        return Err(PacketError::Malformed);
+    }
+    let mut remaining_len = 2;
+    for subscription in subscriptions {
+        remaining_len += string_len(subscription.topic_filter)? + 1;
+    }
+    // SUBSCRIBE's reserved flags are 0b0010.
+    let mut writer = Writer::start(buffer, (SUBSCRIBE << 4) | 0x02, remaining_len)?;
+    writer.put(&packet_id.to_be_bytes())?;
+    for subscription in subscriptions {
+        writer.put_str(subscription.topic_filter)?;
+        writer.put(&[subscription.qos as u8])?;
+    }
+    Ok(writer.len)
+}
+
+/// A packet received from the broker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Packet<'a> {
+    /// Reply to `CONNECT`. A `return_code` of 0 means accepted.
+    ConnAck {
+        session_present: bool,
+        return_code: u8,
+    },
+    /// A message on a subscribed topic.
+    Publish(Publish<'a>),
+    /// Acknowledgment of a [`QoS::AtLeastOnce`] publish.
+    PubAck { packet_id: u16 },
+    /// Reply to `SUBSCRIBE`, with the granted QoS (or `0x80` for failure) per topic filter.
+    SubAck {
+        packet_id: u16,
+        return_codes: &'a [u8],
+    },
+    /// Reply to [`PINGREQ`].
+    PingResp,
+    /// Any other packet, undecoded.
+    Other {
+        packet_type: u8,
+        flags: u8,
+        body: &'a [u8],
+    },
+}
+
+impl<'a> Packet<'a> {
+    /// Total length of the packet at the start of `bytes`, or `None` if not enough has
+    /// arrived to tell.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the remaining-length field is malformed.
+    pub fn total_len(bytes: &[u8]) -> Result<Option<usize>, PacketError> {
+        Ok(fixed_header_len(bytes)?.map(|(header_len, remaining_len)| header_len + remaining_len))
+    }
+
+    /// Decode the packet at the start of `bytes`. Returns it with its length, or `None` if
+    /// it hasn't fully arrived.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the packet is malformed.
+    pub fn parse(bytes: &'a [u8]) -> Result<Option<(Self, usize)>, PacketError> {
+        let Some((header_len, remaining_len)) = fixed_header_len(bytes)? else {
+            return Ok(None);
+        };
+        let len = header_len + remaining_len;
+        let Some(body) = bytes.get(header_len..len) else {
+            return Ok(None);
+        };
+        let packet_type = bytes[0] >> 4;
+        let flags = bytes[0] & 0x0F;
+        let packet = match (packet_type, body) {
+            (CONNACK, [ack_flags, return_code]) => Self::ConnAck {
+                session_present: ack_flags & 0x01 != 0,
+                return_code: *return_code,
+            },
+            (PUBLISH, _) => Self::Publish(parse_publish(flags, body)?),
+            (PUBACK, [high, low]) => Self::PubAck {
+                packet_id: u16::from_be_bytes([*high, *low]),
+            },
+            (SUBACK, [high, low, return_codes @ ..]) => Self::SubAck {
+                packet_id: u16::from_be_bytes([*high, *low]),
+                return_codes,
+            },
+            (PINGRESP, []) => Self::PingResp,
+            (CONNACK | PUBACK | SUBACK | PINGRESP, _) => return Err(PacketError::Malformed),
+            _ => Self::Other {
+                packet_type,
+                flags,
+                body,
+            },
+        };
+        Ok(Some((packet, len)))
+    }
+}
+
+/// Lengths of the fixed header and the rest of the packet at the start of `bytes`, or `None`
+/// if the fixed header hasn't fully arrived.
+fn fixed_header_len(bytes: &[u8]) -> Result<Option<(usize, usize)>, PacketError> {
+    let mut remaining_len = 0;
+    // The remaining length takes 1 to 4 bytes, 7 bits each, least significant first.
+    for (index, &byte) in bytes.iter().skip(1).take(4).enumerate() {
+        remaining_len |= usize::from(byte & 0x7F) << (7 * index);
+        if byte & 0x80 == 0 {
+            return Ok(Some((2 + index, remaining_len)));
+        }
+    }
+    if bytes.len() > 4 {
+        return Err(PacketError::Malformed);
+    }
+    Ok(None)
+}
+
+fn parse_publish(flags: u8, body: &[u8]) -> Result<Publish<'_>, PacketError> {
+    let qos = match (flags >> 1) & 0x03 {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        // Exactly-once delivery is never requested, so a broker won't send it.
+        _ => return Err(PacketError::Malformed),
+    };
+    let topic_len = usize::from(packet_id(body)?);
+    let topic = body
+        .get(2..2 + topic_len)
+        .and_then(|topic| core::str::from_utf8(topic).ok())
+        .ok_or(PacketError::Malformed)?;
+    let mut rest = &body[2 + topic_len..];
+    let packet_id = if qos == QoS::AtLeastOnce {
+        let packet_id = packet_id(rest)?;
+        rest = &rest[2..];
+        Some(packet_id)
+    } else {
+        None
+    };
+    Ok(Publish {
+        topic,
+        payload: rest,
+        qos,
+        retain: flags & 0x01 != 0,
+        packet_id,
+    })
+}
+
+/// The big-endian `u16` at the start of `body`.
+fn packet_id(body: &[u8]) -> Result<u16, PacketError> {
+    match body {
+        [high, low, ..] => Ok(u16::from_be_bytes([*high, *low])),
+        _ => Err(PacketError::Malformed),
+    }
+}
+
+/// Encoded length of an MQTT string.
+fn string_len(text: &str) -> Result<usize, PacketError> {
+    bytes_len(text.as_bytes())
+}
+
+/// Encoded length of MQTT binary data.
+fn bytes_len(bytes: &[u8]) -> Result<usize, PacketError> {
+    if bytes.len() > usize::from(u16::MAX) {
+        return Err(PacketError::TooLong);
+    }
+    Ok(2 + bytes.len())
+}
+
+/// Writes one packet into a buffer, checking space as it goes.
+struct Writer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Writer<'a> {
+    /// Write the fixed header for a packet with `remaining_len` bytes after it.
+    fn start(buffer: &'a mut [u8], first: u8, remaining_len: usize) -> Result<Self, PacketError> {
+        if remaining_len > MAX_REMAINING_LEN {
+            return Err(PacketError::TooLong);
+        }
+        let mut writer = Self { buffer, len: 0 };
+        writer.put(&[first])?;
+        let mut value = remaining_len;
+        loop {
+            let mut byte = (value % 128) as u8;
+            value /= 128;
+            if value > 0 {
+                byte |= 0x80;
+            }
+            writer.put(&[byte])?;
+            if value == 0 {
+                return Ok(writer);
+            }
+        }
+    }
+
+    fn put(&mut self, bytes: &[u8]) -> Result<(), PacketError> {
+        let end = self.len + bytes.len();
+        self.buffer
+            .get_mut(self.len..end)
+            .ok_or(PacketError::BufferTooSmall)?
+            .copy_from_slice(bytes);
+        self.len = end;
+        Ok(())
+    }
+
+    fn put_bytes(&mut self, bytes: &[u8]) -> Result<(), PacketError> {
+        let len = u16::try_from(bytes.len()).map_err(|_| PacketError::TooLong)?;
+        self.put(&len.to_be_bytes())?;
+        self.put(bytes)
+    }
+
+    fn put_str(&mut self, text: &str) -> Result<(), PacketError> {
+        self.put_bytes(text.as_bytes())
+    }
+}
@@ -5,26 +5,58 @@
 //!
 //! Use the [`servo_even!`] or [`servo_odd!`] macros for even or odd GPIO pins respectively.
 //!
+//! Every physical servo differs a little. [`Servo::calibrate`] jogs a servo to 0°, 90°, and
+//! 180° and saves the measured pulse widths to a [`FlashBlock`]. [`Servo::new`] and the
+//! macros take that block and load the calibration at construction.
+//!
 //! See [`Servo`] for usage examples.
 
 use defmt::info;
+use embassy_futures::select::{Either, select};
 use embassy_rp::clocks::clk_sys_freq;
 use embassy_rp::pwm::{Config, Pwm};
 
+use crate::Result;
+use crate::button::{Button, PressDuration};
+use crate::flash_array::FlashBlock;
+use crate::ir_mapping::IrLedLayout;
+
 const SERVO_PERIOD_US: u16 = 20_000; // 20 ms
 
+/// Shortest pulse the calibration workflow will jog to.
+const CALIBRATION_MIN_US: u16 = 300;
+
+/// Longest pulse the calibration workflow will jog to.
+const CALIBRATION_MAX_US: u16 = 2_700;
+
+/// Pulse change for one short button press during calibration.
+const BUTTON_JOG_US: i16 = 10;
+
 /// Create a servo on an even-numbered GPIO pin (0, 2, 4, 6...).
 ///
+/// The fifth argument is the `&mut FlashBlock` holding the servo's calibration (see
+/// [`Servo::new`]), and the macro returns a `Result`. Leave it out for a servo that needs
+/// no calibration, such as a continuous servo or ESC (see [`Servo::new_uncalibrated`]).
+///
 /// See [`Servo`] for details and examples.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! servo_even {
-    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr) => {
+    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr, $flash_block:expr) => {
         $crate::servo::Servo::new(
             embassy_rp::pwm::Pwm::new_output_a($slice, $pin, embassy_rp::pwm::Config::default()),
             $crate::servo::ServoChannel::A,
             $min_us,
             $max_us,
+            $flash_block,
+        )
+    };
+    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr) => {
+        $crate::servo::Servo::new_uncalibrated(
+            embassy_rp::pwm::Pwm::new_output_a($slice, $pin, embassy_rp::pwm::Config::default()),
+            $crate::servo::ServoChannel::A,
+            $min_us,
+            $max_us,
         )
     };
 }
@@ -33,16 +65,29 @@ pub use servo_even;
 
 /// Create a servo on an odd-numbered GPIO pin (1, 3, 5, 7...).
 ///
+/// The fifth argument is the `&mut FlashBlock` holding the servo's calibration (see
+/// [`Servo::new`]), and the macro returns a `Result`. Leave it out for a servo that needs
+/// no calibration, such as a continuous servo or ESC (see [`Servo::new_uncalibrated`]).
+///
 /// See [`Servo`] for details and examples.
 #[doc(hidden)]
 #[macro_export]
 macro_rules! servo_odd {
-    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr) => {
+    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr, $flash_block:expr) => {
         $crate::servo::Servo::new(
             embassy_rp::pwm::Pwm::new_output_b($slice, $pin, embassy_rp::pwm::Config::default()),
             $crate::servo::ServoChannel::B,
             $min_us,
             $max_us,
+            $flash_block,
+        )
+    };
+    ($pin:expr, $slice:expr, $min_us:expr, $max_us:expr) => {
+        $crate::servo::Servo::new_uncalibrated(
+            embassy_rp::pwm::Pwm::new_output_b($slice, $pin, embassy_rp::pwm::Config::default()),
+            $crate::servo::ServoChannel::B,
+            $min_us,
+            $max_us,
         )
     };
 }
@@ -58,22 +103,26 @@ pub use servo_odd;
 /// # use device_kit::servo::servo_odd;
 /// # #[panic_handler]
 /// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
-/// async fn example(p: embassy_rp::Peripherals) {
+/// # use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+/// async fn example(p: embassy_rp::Peripherals) -> device_kit::Result<()> {
+///     static FLASH_STATIC: FlashArrayStatic = FlashArray::<1>::new_static();
+///     let [mut calibration_block] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+///
 ///     // Create a servo on GPIO 15 with pulse range 500-2500 microseconds
-///     // (500µs = 0°, 2500µs = 180° for typical SG90)
+///     // (500µs = 0°, 2500µs = 180° for typical SG90) until it is calibrated.
 ///     // GPIO 15 is odd. Calculate slice: (15 / 2) % 8 = 7 % 8 = 7 → PWM_SLICE7
-///     let mut servo = servo_odd!(p.PIN_15, p.PWM_SLICE7, 500, 2500);
+///     let mut servo = servo_odd!(p.PIN_15, p.PWM_SLICE7, 500, 2500, &mut calibration_block)?;
 ///
 ///     servo.set_degrees(45);  // Move to 45 degrees
 ///     servo.center();          // Move to center position
+///     Ok(())
 /// }
 /// ```
 pub struct Servo<'d> {
     pwm: Pwm<'d>,
     cfg: Config, // Store config to avoid recreating default (which resets divider)
     top: u16,
-    min_us: u16,
-    max_us: u16,
+    calibration: ServoCalibration,
     channel: ServoChannel, // Track which channel (A or B) this servo uses
 }
 
@@ -89,7 +138,10 @@ pub enum ServoChannel {
 }
 
 impl<'d> Servo<'d> {
-    /// Create a servo on a PWM output channel.
+    /// Create a servo on a PWM output channel, loading its calibration from `flash_block`.
+    ///
+    /// Falls back to the linear `min_us..=max_us` range if the block holds no calibration
+    /// (for example, before [`Servo::calibrate`] has ever run).
     ///
     /// Consider using the [`servo_even!`] or [`servo_odd!`] macros instead for simpler usage.
     ///
@@ -97,21 +149,75 @@ impl<'d> Servo<'d> {
     /// ```no_run
     /// # #![no_std]
     /// # #![no_main]
+    /// use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+    /// use device_kit::servo::{Servo, ServoChannel};
+    /// # #[panic_handler]
+    /// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+    /// async fn example(p: embassy_rp::Peripherals) -> device_kit::Result<()> {
+    ///     static FLASH_STATIC: FlashArrayStatic = FlashArray::<1>::new_static();
+    ///     let [mut calibration_block] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+    ///     // GPIO 15 is odd, uses channel B. Calculate slice: (15 / 2) % 8 = 7
+    ///     let pwm = embassy_rp::pwm::Pwm::new_output_b(p.PWM_SLICE7, p.PIN_15, embassy_rp::pwm::Config::default());
+    ///     let mut servo = Servo::new(pwm, ServoChannel::B, 500, 2500, &mut calibration_block)?;
+    ///     servo.set_degrees(90); // Uses the saved 90° pulse width, if any.
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the flash block cannot be read.
+    pub fn new(
+        pwm: Pwm<'d>,
+        channel: ServoChannel,
+        min_us: u16,
+        max_us: u16,
+        flash_block: &mut FlashBlock,
+    ) -> Result<Self> {
+        let calibration = flash_block
+            .load::<ServoCalibration>()?
+            .unwrap_or_else(|| ServoCalibration::from_range(min_us, max_us));
+        info!("servo calibration {:?}", calibration);
+        Ok(Self::init(pwm, channel, calibration))
+    }
+
+    /// Create a servo that maps `min_us..=max_us` linearly, without a saved calibration.
+    ///
+    /// Use this where the pulse range is the whole story, such as for a continuous servo or
+    /// ESC. The [`servo_even!`] and [`servo_odd!`] macros call this when given no flash block.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #![no_std]
+    /// # #![no_main]
     /// use device_kit::servo::{Servo, ServoChannel};
     /// # #[panic_handler]
     /// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
     /// async fn example(p: embassy_rp::Peripherals) {
     ///     // GPIO 15 is odd, uses channel B. Calculate slice: (15 / 2) % 8 = 7
     ///     let pwm = embassy_rp::pwm::Pwm::new_output_b(p.PWM_SLICE7, p.PIN_15, embassy_rp::pwm::Config::default());
-    ///     let mut servo = Servo::new(pwm, ServoChannel::B, 500, 2500);
+    ///     let mut servo = Servo::new_uncalibrated(pwm, ServoChannel::B, 500, 2500);
     /// }
     /// ```
-    pub fn new(pwm: Pwm<'d>, channel: ServoChannel, min_us: u16, max_us: u16) -> Self {
-        Self::init(pwm, channel, min_us, max_us)
+    pub fn new_uncalibrated(pwm: Pwm<'d>, channel: ServoChannel, min_us: u16, max_us: u16) -> Self {
+        Self::init(pwm, channel, ServoCalibration::from_range(min_us, max_us))
     }
 
     /// Configure PWM and initialize servo. Internal shared logic.
-    fn init(mut pwm: Pwm<'d>, channel: ServoChannel, min_us: u16, max_us: u16) -> Self {
+    fn init(pwm: Pwm<'d>, channel: ServoChannel, calibration: ServoCalibration) -> Self {
+        let center_us = calibration.pulse_us(90);
+        Self::init_at(pwm, channel, calibration, center_us)
+    }
+
+    /// Configure PWM so that the very first pulse is `start_us`, without centering.
+    ///
+    /// ESCs read any pulse above zero throttle at power-up as throttle, so they start here.
+    pub(crate) fn init_at(
+        mut pwm: Pwm<'d>,
+        channel: ServoChannel,
+        calibration: ServoCalibration,
+        start_us: u16,
+    ) -> Self {
         let clk = clk_sys_freq() as u64; // Hz
         // Aim for tick ≈ 1 µs: divider = clk_sys / 1_000_000 (with /16 fractional)
         let mut div_int = (clk / 1_000_000).clamp(1, 255) as u16;
@@ -131,9 +237,10 @@ impl<'d> Servo<'d> {
         cfg.divider = (div_int as u8).into();
 
         // Set the appropriate compare register based on channel
+        let start_us = start_us.min(top);
         match channel {
-            ServoChannel::A => cfg.compare_a = 1500, // start ~center
-            ServoChannel::B => cfg.compare_b = 1500, // start ~center
+            ServoChannel::A => cfg.compare_a = start_us,
+            ServoChannel::B => cfg.compare_b = start_us,
         }
 
         cfg.enable = true; // Enable PWM output
@@ -144,30 +251,113 @@ impl<'d> Servo<'d> {
             clk, div_int, div_frac, top
         );
 
-        let mut s = Self {
+        Self {
             pwm,
             cfg, // Store config to avoid losing divider on reconfiguration
             top,
-            min_us,
-            max_us,
+            calibration,
             channel,
-        };
-        s.center();
-        s
+        }
     }
 
-    /// Center (~midpoint of min/max).
+    /// Center (the calibrated 90° position).
     pub fn center(&mut self) {
-        self.set_pulse_us(self.min_us + (self.max_us - self.min_us) / 2);
+        self.set_degrees(90);
     }
 
-    /// Set position in degrees 0..=180 mapped into [min_us, max_us].
+    /// Set position in degrees 0..=180 mapped through the servo's [`ServoCalibration`].
     pub fn set_degrees(&mut self, degrees: u16) {
-        assert!((0..=180).contains(&degrees));
-        let us = self.min_us as u32
-            + (u32::from(degrees)) * (u32::from(self.max_us) - u32::from(self.min_us)) / 180;
+        let us = self.calibration.pulse_us(degrees);
         info!("Servo set_degrees({}) -> {}µs", degrees, us);
-        self.set_pulse_us(us as u16);
+        self.set_pulse_us(us);
+    }
+
+    /// The angle (0..=180) the servo was last commanded to, as read back from its pulse width.
+    #[must_use]
+    pub fn degrees(&self) -> u16 {
+        let pulse_us = match self.channel {
+            ServoChannel::A => self.cfg.compare_a,
+            ServoChannel::B => self.cfg.compare_b,
+        };
+        self.calibration.degrees(pulse_us)
+    }
+
+    /// The calibration currently used to map degrees to pulse widths.
+    #[must_use]
+    pub const fn calibration(&self) -> ServoCalibration {
+        self.calibration
+    }
+
+    /// Replace the calibration (without saving it). See [`Servo::calibrate`].
+    pub const fn set_calibration(&mut self, calibration: ServoCalibration) {
+        self.calibration = calibration;
+    }
+
+    /// Interactively calibrate the servo and save the result to `flash_block`.
+    ///
+    /// The servo visits 0°, 90°, then 180°. At each angle, jog it with
+    /// [`CalibrationAction::Jog`] until the horn points where that angle should be, then
+    /// send [`CalibrationAction::Record`]. Afterwards the servo uses the new piecewise-linear
+    /// map immediately, and [`Servo::new`] loads it on the next boot.
+    ///
+    /// Any [`CalibrationInput`] can drive the workflow: a `(Button, Button)` pair
+    /// (short press jogs down/up, long press records) or an [`IrLedLayout`] that maps
+    /// remote buttons to [`CalibrationAction`]s.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # #![no_std]
+    /// # #![no_main]
+    /// use device_kit::button::{Button, PressedTo};
+    /// use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+    /// use device_kit::servo::servo_odd;
+    /// # #[panic_handler]
+    /// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+    /// async fn example(p: embassy_rp::Peripherals) -> device_kit::Result<()> {
+    ///     static FLASH_STATIC: FlashArrayStatic = FlashArray::<1>::new_static();
+    ///     let [mut calibration_block] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+    ///     let mut servo = servo_odd!(p.PIN_15, p.PWM_SLICE7, 500, 2500, &mut calibration_block)?;
+    ///
+    ///     let mut buttons = (
+    ///         Button::new(p.PIN_13, PressedTo::Ground), // down
+    ///         Button::new(p.PIN_14, PressedTo::Ground), // up
+    ///     );
+    ///     servo.calibrate(&mut buttons, &mut calibration_block).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calibration cannot be saved to flash.
+    pub async fn calibrate(
+        &mut self,
+        input: &mut impl CalibrationInput,
+        flash_block: &mut FlashBlock,
+    ) -> Result<ServoCalibration> {
+        let mut pulse_us = self.calibration.pulse_us;
+        for (pulse_us, degrees) in pulse_us.iter_mut().zip(CALIBRATION_DEGREES) {
+            info!("Calibrating {}°: jog, then record", degrees);
+            self.set_pulse_us(*pulse_us);
+            loop {
+                match input.wait_for_action().await {
+                    CalibrationAction::Jog(delta_us) => {
+                        *pulse_us = pulse_us
+                            .saturating_add_signed(delta_us)
+                            .clamp(CALIBRATION_MIN_US, CALIBRATION_MAX_US);
+                        info!("  {}° -> {}µs", degrees, *pulse_us);
+                        self.set_pulse_us(*pulse_us);
+                    }
+                    CalibrationAction::Record => break,
+                }
+            }
+        }
+        let calibration = ServoCalibration { pulse_us };
+        flash_block.save(&calibration)?;
+        info!("Saved servo calibration {:?}", calibration);
+        self.calibration = calibration;
+        self.center();
+        Ok(calibration)
     }
 
     /// Set raw pulse width in microseconds (clamped to frame).
@@ -205,4 +395,93 @@ impl<'d> Servo<'d> {
     }
 }
 
+// ============================================================================
+// Calibration
+// ============================================================================
+
+/// The angles, in order, whose pulse widths a [`ServoCalibration`] records.
+const CALIBRATION_DEGREES: [u16; 3] = [0, 90, 180];
+
+/// Pulse widths measured at 0°, 90°, and 180°, forming a piecewise-linear degrees → µs map.
+///
+/// Produced by [`Servo::calibrate`] and persisted in a [`FlashBlock`]; see
+/// [`Servo::new`].
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, defmt::Format,
+)]
+pub struct ServoCalibration {
+    /// Pulse widths in microseconds at 0°, 90°, and 180°.
+    pub pulse_us: [u16; 3],
+}
+
+impl ServoCalibration {
+    /// A linear calibration from `min_us` at 0° to `max_us` at 180°.
+    #[must_use]
+    pub const fn from_range(min_us: u16, max_us: u16) -> Self {
+        let mid_us = ((min_us as u32 + max_us as u32) / 2) as u16;
+        Self {
+            pulse_us: [min_us, mid_us, max_us],
+        }
+    }
+
+    /// The pulse width for `degrees` (0..=180), interpolated between the recorded points.
+    #[must_use]
+    pub fn pulse_us(&self, degrees: u16) -> u16 {
+        assert!((0..=180).contains(&degrees));
+        let [us_0, us_90, us_180] = self.pulse_us.map(i32::from);
+        let (from_us, to_us, offset) = if degrees <= 90 {
+            (us_0, us_90, i32::from(degrees))
+        } else {
+            (us_90, us_180, i32::from(degrees) - 90)
+        };
+        let us = from_us + (to_us - from_us) * offset / 90;
+        u16::try_from(us).expect("pulse width fits in u16")
+    }
+
+    /// The angle (0..=180) whose pulse width is nearest `pulse_us`; the inverse of
+    /// [`pulse_us`](Self::pulse_us).
+    #[must_use]
+    pub fn degrees(&self, pulse_us: u16) -> u16 {
+        (0..=180)
+            .min_by_key(|degrees| self.pulse_us(*degrees).abs_diff(pulse_us))
+            .unwrap_or(90)
+    }
+}
+
+/// One user action during [`Servo::calibrate`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, defmt::Format)]
+pub enum CalibrationAction {
+    /// Nudge the pulse width by this many microseconds.
+    Jog(i16),
+    /// Accept the current pulse width for this angle and move on.
+    Record,
+}
+
+/// A source of [`CalibrationAction`]s for [`Servo::calibrate`].
+pub trait CalibrationInput {
+    /// Wait for the next calibration action.
+    async fn wait_for_action(&mut self) -> CalibrationAction;
+}
+
+/// Two buttons: `(down, up)`. A short press jogs by 10 µs; a long press on either records.
+impl CalibrationInput for (Button<'_>, Button<'_>) {
+    async fn wait_for_action(&mut self) -> CalibrationAction {
+        let (down, up) = self;
+        match select(down.wait_for_press_duration(), up.wait_for_press_duration()).await {
+            Either::First(PressDuration::Short) => CalibrationAction::Jog(-BUTTON_JOG_US),
+            Either::Second(PressDuration::Short) => CalibrationAction::Jog(BUTTON_JOG_US),
+            Either::First(PressDuration::Long) | Either::Second(PressDuration::Long) => {
+                CalibrationAction::Record
+            }
+        }
+    }
+}
+
+/// An IR remote whose buttons are mapped directly to calibration actions.
+impl<const N: usize> CalibrationInput for IrLedLayout<'_, CalibrationAction, N> {
+    async fn wait_for_action(&mut self) -> CalibrationAction {
+        self.wait_for_press().await
+    }
+}
+
 pub use crate::servo;
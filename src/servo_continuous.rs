@@ -0,0 +1,314 @@
+//! Device abstractions for continuous-rotation servos and hobby ESCs (electronic speed
+//! controllers).
+//!
+//! Both are driven by the same 50 Hz pulse as a positional [`Servo`]. Build a
+//! [`ContinuousServo`] from one created with [`servo_even!`] or [`servo_odd!`], giving the
+//! full-reverse and full-forward pulse widths as `min_us` and `max_us`. An [`Esc`] is built
+//! from the PWM output directly, so that its first pulse is already zero throttle.
+//!
+//! See [`ContinuousServo`] and [`Esc`] for usage examples.
+
+use defmt::info;
+use embassy_rp::pwm::Pwm;
+use embassy_time::{Duration, Instant, Timer};
+
+use crate::servo::{Servo, ServoCalibration, ServoChannel};
+pub use crate::servo::{servo_even, servo_odd};
+
+/// How often the output is updated while ramping (one servo frame).
+const RAMP_TICK: Duration = Duration::from_millis(20);
+
+/// How long an ESC must see zero throttle before it arms.
+const ESC_ARM_DURATION: Duration = Duration::from_secs(3);
+
+// ============================================================================
+// ContinuousServo
+// ============================================================================
+
+/// A device abstraction for continuous-rotation servos (such as the FS90R) with signed
+/// speed control.
+///
+/// Speed is a percentage from -100 (full reverse) through 0 (stop) to 100 (full forward).
+/// Most continuous servos creep at the nominal neutral pulse; use
+/// [`trim_neutral`](Self::trim_neutral) to find the true stop point and
+/// [`set_deadband`](Self::set_deadband) to treat small speeds as stopped.
+///
+/// # Examples
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::servo_continuous::{ContinuousServo, servo_even};
+/// use embassy_time::Duration;
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+/// async fn example(p: embassy_rp::Peripherals) {
+///     // 1000µs = full reverse, 1500µs = stop, 2000µs = full forward.
+///     let mut wheel = ContinuousServo::new(servo_even!(p.PIN_0, p.PWM_SLICE0, 1000, 2000));
+///     wheel.trim_neutral(-12); // This servo creeps forward at 1500µs.
+///     wheel.set_deadband(3);
+///
+///     wheel.ramp_to(60, Duration::from_secs(1)).await; // Speed up gently.
+///     wheel.set_speed(-30); // Reverse immediately.
+///     wheel.stop();
+/// }
+/// ```
+pub struct ContinuousServo<'d> {
+    servo: Servo<'d>,
+    reverse_us: u16,
+    neutral_us: u16,
+    forward_us: u16,
+    deadband_percent: u8,
+    speed_percent: i8,
+}
+
+impl<'d> ContinuousServo<'d> {
+    /// Create a continuous servo from a [`Servo`] whose `min_us`/`max_us` are the
+    /// full-reverse/full-forward pulse widths. The neutral point is their midpoint.
+    ///
+    /// See [`ContinuousServo`] for usage examples.
+    #[must_use]
+    pub fn new(servo: Servo<'d>) -> Self {
+        let [reverse_us, neutral_us, forward_us] = servo.calibration().pulse_us;
+        Self::with_points(servo, reverse_us, neutral_us, forward_us)
+    }
+
+    fn with_points(servo: Servo<'d>, reverse_us: u16, neutral_us: u16, forward_us: u16) -> Self {
+        let mut continuous_servo = Self {
+            servo,
+            reverse_us,
+            neutral_us,
+            forward_us,
+            deadband_percent: 0,
+            speed_percent: 0,
+        };
+        continuous_servo.stop();
+        continuous_servo
+    }
+
+    /// Set the speed immediately, from -100 (full reverse) to 100 (full forward).
+    pub fn set_speed(&mut self, speed_percent: i8) {
+        assert!((-100..=100).contains(&speed_percent));
+        self.speed_percent = speed_percent;
+        let us = self.pulse_us(speed_percent);
+        info!("ContinuousServo set_speed({}) -> {}µs", speed_percent, us);
+        self.servo.set_pulse_us(us);
+    }
+
+    /// The most recently commanded speed.
+    #[must_use]
+    pub const fn speed(&self) -> i8 {
+        self.speed_percent
+    }
+
+    /// Stop (output the neutral pulse).
+    pub fn stop(&mut self) {
+        self.set_speed(0);
+    }
+
+    /// Change speed linearly from the current speed to `speed_percent` over `duration`.
+    ///
+    /// Ramping avoids current spikes and wheel slip from sudden speed changes.
+    pub async fn ramp_to(&mut self, speed_percent: i8, duration: Duration) {
+        assert!((-100..=100).contains(&speed_percent));
+        let from = i32::from(self.speed_percent);
+        let delta = i32::from(speed_percent) - from;
+        let total_ticks = duration.as_ticks();
+        let start = Instant::now();
+        loop {
+            let elapsed_ticks = start.elapsed().as_ticks();
+            if elapsed_ticks >= total_ticks {
+                break;
+            }
+            let step = i64::from(delta) * elapsed_ticks as i64 / total_ticks as i64;
+            let speed = i8::try_from(i64::from(from) + step).expect("speed fits in i8");
+            if speed != self.speed_percent {
+                self.set_speed(speed);
+            }
+            Timer::after(RAMP_TICK).await;
+        }
+        self.set_speed(speed_percent);
+    }
+
+    /// Treat speeds within ±`deadband_percent` as stopped.
+    pub fn set_deadband(&mut self, deadband_percent: u8) {
+        assert!(deadband_percent <= 100);
+        self.deadband_percent = deadband_percent;
+        self.set_speed(self.speed_percent);
+    }
+
+    /// Shift the neutral (stop) pulse by `delta_us` microseconds.
+    ///
+    /// Jog this until the servo stands still at speed 0; the forward and reverse ranges
+    /// keep their end points.
+    pub fn trim_neutral(&mut self, delta_us: i16) {
+        self.neutral_us = self.neutral_us.saturating_add_signed(delta_us).clamp(
+            self.reverse_us.min(self.forward_us),
+            self.reverse_us.max(self.forward_us),
+        );
+        self.set_speed(self.speed_percent);
+    }
+
+    /// The current neutral (stop) pulse width in microseconds.
+    #[must_use]
+    pub const fn neutral_us(&self) -> u16 {
+        self.neutral_us
+    }
+
+    /// Stop sending control signals. Most continuous servos stop; ESCs disarm.
+    pub fn disable(&mut self) {
+        self.servo.disable();
+    }
+
+    /// Resume sending control signals at the last commanded speed.
+    pub fn enable(&mut self) {
+        self.servo.enable();
+    }
+
+    fn pulse_us(&self, speed_percent: i8) -> u16 {
+        if speed_percent.unsigned_abs() <= self.deadband_percent {
+            return self.neutral_us;
+        }
+        let neutral = i32::from(self.neutral_us);
+        let end = if speed_percent > 0 {
+            i32::from(self.forward_us)
+        } else {
+            i32::from(self.reverse_us)
+        };
+        let us = neutral + (end - neutral) * i32::from(speed_percent.unsigned_abs()) / 100;
+        u16::try_from(us).expect("pulse width fits in u16")
+    }
+}
+
+// ============================================================================
+// Esc
+// ============================================================================
+
+/// Whether an [`Esc`] can drive the motor in reverse.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, defmt::Format)]
+pub enum EscMode {
+    /// Forward only: `min_us` is zero throttle and `max_us` is full throttle.
+    Forward,
+    /// Forward and reverse (car/boat ESCs): the midpoint is zero throttle.
+    Reversible,
+}
+
+/// A device abstraction for a hobby ESC (electronic speed controller) driving a brushless
+/// or brushed motor.
+///
+/// An ESC ignores throttle until it has been armed by holding zero throttle for a few
+/// seconds. Call [`arm`](Self::arm) once after power-up, before
+/// [`set_throttle`](Self::set_throttle).
+///
+/// # Examples
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::servo::ServoChannel;
+/// use device_kit::servo_continuous::{Esc, EscMode};
+/// use embassy_time::Duration;
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+/// async fn example(p: embassy_rp::Peripherals) {
+///     // GPIO 1 is odd, uses channel B. Calculate slice: (1 / 2) % 8 = 0
+///     let pwm = embassy_rp::pwm::Pwm::new_output_b(p.PWM_SLICE0, p.PIN_1, embassy_rp::pwm::Config::default());
+///     let mut esc = Esc::new(pwm, ServoChannel::B, 1000, 2000, EscMode::Forward);
+///     esc.arm().await;
+///     esc.ramp_to(40, Duration::from_secs(2)).await;
+///     esc.ramp_to(0, Duration::from_millis(500)).await;
+///     esc.disarm();
+/// }
+/// ```
+pub struct Esc<'d> {
+    motor: ContinuousServo<'d>,
+    mode: EscMode,
+    armed: bool,
+}
+
+impl<'d> Esc<'d> {
+    /// Create an ESC on a PWM output channel whose `min_us..=max_us` span the ESC's
+    /// throttle range.
+    ///
+    /// The very first pulse is zero throttle (`min_us`, or the midpoint for
+    /// [`EscMode::Reversible`]), but the ESC is not yet armed.
+    #[must_use]
+    pub fn new(
+        pwm: Pwm<'d>,
+        channel: ServoChannel,
+        min_us: u16,
+        max_us: u16,
+        mode: EscMode,
+    ) -> Self {
+        let calibration = ServoCalibration::from_range(min_us, max_us);
+        let [_, mid_us, _] = calibration.pulse_us;
+        let zero_us = match mode {
+            EscMode::Forward => min_us,
+            EscMode::Reversible => mid_us,
+        };
+        let servo = Servo::init_at(pwm, channel, calibration, zero_us);
+        let motor = ContinuousServo::with_points(servo, min_us, zero_us, max_us);
+        Self {
+            motor,
+            mode,
+            armed: false,
+        }
+    }
+
+    /// Run the arming sequence: hold zero throttle until the ESC accepts commands.
+    pub async fn arm(&mut self) {
+        info!("Arming ESC");
+        self.motor.enable();
+        self.motor.stop();
+        Timer::after(ESC_ARM_DURATION).await;
+        self.armed = true;
+        info!("ESC armed");
+    }
+
+    /// Cut throttle and stop sending pulses. Call [`arm`](Self::arm) again to resume.
+    pub fn disarm(&mut self) {
+        self.motor.stop();
+        self.motor.disable();
+        self.armed = false;
+    }
+
+    /// Whether [`arm`](Self::arm) has completed since the last [`disarm`](Self::disarm).
+    #[must_use]
+    pub const fn is_armed(&self) -> bool {
+        self.armed
+    }
+
+    /// Set throttle immediately: 0..=100, or -100..=100 for [`EscMode::Reversible`].
+    pub fn set_throttle(&mut self, throttle_percent: i8) {
+        self.assert_throttle(throttle_percent);
+        self.motor.set_speed(throttle_percent);
+    }
+
+    /// Change throttle linearly over `duration`. See [`ContinuousServo::ramp_to`].
+    pub async fn ramp_to(&mut self, throttle_percent: i8, duration: Duration) {
+        self.assert_throttle(throttle_percent);
+        self.motor.ramp_to(throttle_percent, duration).await;
+    }
+
+    /// The most recently commanded throttle.
+    #[must_use]
+    pub const fn throttle(&self) -> i8 {
+        self.motor.speed()
+    }
+
+    /// Treat throttles within ±`deadband_percent` as zero. See [`ContinuousServo::set_deadband`].
+    pub fn set_deadband(&mut self, deadband_percent: u8) {
+        self.motor.set_deadband(deadband_percent);
+    }
+
+    /// Shift the zero-throttle pulse. See [`ContinuousServo::trim_neutral`].
+    pub fn trim_neutral(&mut self, delta_us: i16) {
+        self.motor.trim_neutral(delta_us);
+    }
+
+    fn assert_throttle(&self, throttle_percent: i8) {
+        assert!(self.armed, "ESC must be armed before setting throttle");
+        match self.mode {
+            EscMode::Forward => assert!((0..=100).contains(&throttle_percent)),
+            EscMode::Reversible => assert!((-100..=100).contains(&throttle_percent)),
+        }
+    }
+}
@@ -0,0 +1,339 @@
+//! A device abstraction for moving several servos in lock-step from one timeline.
+//!
+//! See [`ServoGroup`] for usage and examples, and [`ServoAnimate`](crate::servo_animate::ServoAnimate)
+//! for the single-servo version.
+
+use embassy_executor::{SpawnError, Spawner};
+use embassy_futures::select::{Either, select};
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use embassy_time::{Duration, Instant, Timer};
+use heapless::Vec;
+
+use crate::servo::Servo;
+pub use crate::servo::{servo_even, servo_odd};
+pub use crate::servo_animate::{Step, concat_steps, linear};
+
+/// Maximum number of servos a single [`ServoGroup`] can drive.
+pub const MAX_SERVO_GROUP_CHANNELS: usize = 4;
+
+/// Maximum number of [`ServoGroup`]s in one program; each runs its own task.
+pub const MAX_SERVO_GROUPS: usize = 2;
+
+/// Maximum number of keyframes per channel (matches `ServoAnimate`).
+const MAX_TRACK_STEPS: usize = 16;
+
+/// How often servo positions are refreshed while blending into a new choreography.
+const BLEND_TICK: Duration = Duration::from_millis(20);
+
+type Track = Vec<Step, MAX_TRACK_STEPS>;
+type Tracks = Vec<Track, MAX_SERVO_GROUP_CHANNELS>;
+type Positions = Vec<u16, MAX_SERVO_GROUP_CHANNELS>;
+
+/// Commands sent to the servo group device.
+enum GroupCommand {
+    Set { degrees: Positions },
+    Animate { tracks: Tracks, blend: Duration },
+}
+
+/// Static resources for [`ServoGroup`].
+pub struct ServoGroupStatic {
+    commands: Channel<CriticalSectionRawMutex, GroupCommand, 2>,
+}
+
+impl ServoGroupStatic {
+    /// Create static resources for the servo group device.
+    #[must_use]
+    pub const fn new_static() -> Self {
+        Self {
+            commands: Channel::new(),
+        }
+    }
+}
+
+/// A device abstraction that drives up to [`MAX_SERVO_GROUP_CHANNELS`] servos from one
+/// shared timeline.
+///
+/// Each servo (channel) gets its own track of [`Step`] keyframes, built with the same
+/// [`linear`] and [`concat_steps`] helpers used by
+/// [`ServoAnimate`](crate::servo_animate::ServoAnimate). All tracks start at the same
+/// instant and each one repeats until a new command arrives. Step deadlines are measured
+/// from that shared start, so channels never drift apart.
+///
+/// An empty track leaves its servo where it is, but at least one track must have steps. A
+/// program can create up to [`MAX_SERVO_GROUPS`] groups. A new command interrupts the running
+/// choreography immediately; use [`ServoGroup::blend`] to glide from the current positions
+/// into the first keyframe of the new choreography instead of jumping.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::servo_group::{linear, servo_even, ServoGroup, ServoGroupStatic, Step};
+/// use embassy_time::Duration;
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+///
+/// async fn demo(p: embassy_rp::Peripherals, spawner: embassy_executor::Spawner) {
+///     static SERVO_GROUP_STATIC: ServoGroupStatic = ServoGroup::<2>::new_static();
+///     let servos = ServoGroup::new(
+///         &SERVO_GROUP_STATIC,
+///         [
+///             servo_even!(p.PIN_0, p.PWM_SLICE0, 500, 2500),
+///             servo_even!(p.PIN_2, p.PWM_SLICE1, 500, 2500),
+///         ],
+///         spawner,
+///     )
+///     .unwrap();
+///
+///     // Both servos move together.
+///     servos.set([0, 180]).await;
+///
+///     // Sweep in opposite directions, in lock-step, forever.
+///     const TWO_SECONDS: Duration = Duration::from_secs(2);
+///     let down = linear::<10>(180, 0, TWO_SECONDS);
+///     let up = linear::<10>(0, 180, TWO_SECONDS);
+///     servos.animate([&down, &up]).await;
+///
+///     // Later: glide over half a second into a wiggle on the first servo only.
+///     const WIGGLE: [Step; 2] = [
+///         Step { degrees: 80, duration: Duration::from_millis(250) },
+///         Step { degrees: 100, duration: Duration::from_millis(250) },
+///     ];
+///     servos.blend([&WIGGLE, &[]], Duration::from_millis(500)).await;
+/// }
+/// ```
+pub struct ServoGroup<const N: usize> {
+    commands: &'static Channel<CriticalSectionRawMutex, GroupCommand, 2>,
+}
+
+impl<const N: usize> ServoGroup<N> {
+    /// Create static resources for a servo group.
+    #[must_use]
+    pub const fn new_static() -> ServoGroupStatic {
+        ServoGroupStatic::new_static()
+    }
+
+    /// Create the servo group and spawn its task. See [`ServoGroup`] for a full example.
+    ///
+    /// Each servo starts from wherever it was last commanded to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task cannot be spawned, for example because
+    /// [`MAX_SERVO_GROUPS`] groups already exist.
+    #[must_use = "Device must be kept alive to drive the servo task"]
+    pub fn new(
+        servo_group_static: &'static ServoGroupStatic,
+        servos: [Servo<'static>; N],
+        spawner: Spawner,
+    ) -> Result<Self, SpawnError> {
+        assert!(
+            (1..=MAX_SERVO_GROUP_CHANNELS).contains(&N),
+            "servo group supports 1..=MAX_SERVO_GROUP_CHANNELS servos"
+        );
+        let mut servo_vec: Vec<Servo<'static>, MAX_SERVO_GROUP_CHANNELS> = Vec::new();
+        for servo in servos {
+            servo_vec.push(servo).ok().expect("servo count fits");
+        }
+        let token = device_loop(servo_group_static, servo_vec)?;
+        spawner.spawn(token);
+        Ok(Self {
+            commands: &servo_group_static.commands,
+        })
+    }
+
+    /// Move every servo to its target angle (0..=180) at the same time.
+    pub async fn set(&self, degrees: [u16; N]) {
+        let mut positions = Positions::new();
+        for degrees in degrees {
+            assert!((0..=180).contains(&degrees));
+            positions.push(degrees).expect("servo count fits");
+        }
+        self.commands
+            .send(GroupCommand::Set { degrees: positions })
+            .await;
+    }
+
+    /// Run one track of keyframes per servo from a shared start time.
+    /// Each track repeats until interrupted by a new command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if every track is empty, or if a track's steps add up to zero duration.
+    pub async fn animate(&self, tracks: [&[Step]; N]) {
+        self.blend(tracks, Duration::from_ticks(0)).await;
+    }
+
+    /// Like [`ServoGroup::animate`], but first glide every servo from its current angle to
+    /// its track's first keyframe over `blend_duration`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if every track is empty, or if a track's steps add up to zero duration.
+    pub async fn blend(&self, tracks: [&[Step]; N], blend_duration: Duration) {
+        assert!(
+            tracks.iter().any(|steps| !steps.is_empty()),
+            "a choreography needs at least one non-empty track"
+        );
+        let mut track_vec = Tracks::new();
+        for steps in tracks {
+            let mut track = Track::new();
+            let mut total = Duration::from_ticks(0);
+            for step in steps {
+                assert!((0..=180).contains(&step.degrees));
+                total += step.duration;
+                track.push(*step).expect("track fits");
+            }
+            assert!(
+                steps.is_empty() || total.as_ticks() > 0,
+                "a track must have a positive total duration"
+            );
+            track_vec.push(track).expect("servo count fits");
+        }
+        self.commands
+            .send(GroupCommand::Animate {
+                tracks: track_vec,
+                blend: blend_duration,
+            })
+            .await;
+    }
+}
+
+#[embassy_executor::task(pool_size = MAX_SERVO_GROUPS)]
+async fn device_loop(
+    servo_group_static: &'static ServoGroupStatic,
+    mut servos: Vec<Servo<'static>, MAX_SERVO_GROUP_CHANNELS>,
+) -> ! {
+    let mut current: Positions = servos.iter().map(Servo::degrees).collect();
+
+    let mut pending_command: Option<GroupCommand> = None;
+
+    loop {
+        let command = if let Some(command) = pending_command.take() {
+            command
+        } else {
+            servo_group_static.commands.receive().await
+        };
+
+        match command {
+            GroupCommand::Set { degrees } => {
+                for ((servo, current), degrees) in
+                    servos.iter_mut().zip(current.iter_mut()).zip(degrees)
+                {
+                    move_to(servo, current, degrees);
+                }
+            }
+            GroupCommand::Animate { tracks, blend } => {
+                pending_command = run_choreography(
+                    &tracks,
+                    blend,
+                    &mut servos,
+                    &mut current,
+                    &servo_group_static.commands,
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Blend into, then play, a choreography. Returns the command that interrupted it, or
+/// `None` if every track was empty.
+async fn run_choreography(
+    tracks: &Tracks,
+    blend: Duration,
+    servos: &mut [Servo<'static>],
+    current: &mut [u16],
+    commands: &Channel<CriticalSectionRawMutex, GroupCommand, 2>,
+) -> Option<GroupCommand> {
+    if blend.as_ticks() > 0 {
+        let from: Positions = current.iter().copied().collect();
+        let blend_start = Instant::now();
+        loop {
+            let elapsed = blend_start.elapsed();
+            if elapsed >= blend {
+                break;
+            }
+            for (((servo, current), from), track) in servos
+                .iter_mut()
+                .zip(current.iter_mut())
+                .zip(from.iter())
+                .zip(tracks.iter())
+            {
+                if let Some(first) = track.first() {
+                    move_to(servo, current, lerp(*from, first.degrees, elapsed, blend));
+                }
+            }
+            if let Either::Second(command) =
+                select(Timer::after(BLEND_TICK), commands.receive()).await
+            {
+                return Some(command);
+            }
+        }
+    }
+
+    // Every channel's deadlines are measured from this one shared start.
+    let start = Instant::now();
+    let mut cursors: Vec<Option<(usize, Instant)>, MAX_SERVO_GROUP_CHANNELS> = Vec::new();
+    for ((servo, current), track) in servos.iter_mut().zip(current.iter_mut()).zip(tracks) {
+        let cursor = track.first().map(|step| {
+            move_to(servo, current, step.degrees);
+            (0, start + step.duration)
+        });
+        cursors.push(cursor).expect("servo count fits");
+    }
+
+    loop {
+        let Some(next_deadline) = cursors
+            .iter()
+            .flatten()
+            .map(|(_, deadline)| *deadline)
+            .min()
+        else {
+            return None;
+        };
+        match select(Timer::at(next_deadline), commands.receive()).await {
+            Either::First(()) => {}
+            Either::Second(command) => return Some(command),
+        }
+        for (((servo, current), track), cursor) in servos
+            .iter_mut()
+            .zip(current.iter_mut())
+            .zip(tracks)
+            .zip(cursors.iter_mut())
+        {
+            let Some((index, deadline)) = cursor else {
+                continue;
+            };
+            if *deadline > next_deadline {
+                continue;
+            }
+            // Only non-empty tracks get a cursor, so these never fail.
+            let Some(next_index) = (*index + 1).checked_rem(track.len()) else {
+                continue;
+            };
+            let Some(step) = track.get(next_index) else {
+                continue;
+            };
+            *index = next_index;
+            move_to(servo, current, step.degrees);
+            *deadline += step.duration;
+        }
+    }
+}
+
+fn move_to(servo: &mut Servo<'static>, current: &mut u16, degrees: u16) {
+    if *current != degrees {
+        servo.set_degrees(degrees);
+        *current = degrees;
+    }
+}
+
+/// Linearly interpolate between two angles by `elapsed / total`.
+fn lerp(from: u16, to: u16, elapsed: Duration, total: Duration) -> u16 {
+    let delta = i64::from(to) - i64::from(from);
+    let step = delta * elapsed.as_ticks() as i64 / total.as_ticks() as i64;
+    u16::try_from(i64::from(from) + step).expect("angle fits")
+}
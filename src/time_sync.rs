@@ -43,10 +43,12 @@ impl UnixSeconds {
 
 #[cfg(feature = "wifi")]
 mod wifi_impl {
+    use core::cell::Cell;
     use core::convert::Infallible;
     use defmt::*;
     use embassy_executor::Spawner;
     use embassy_net::{Stack, dns, udp};
+    use embassy_sync::blocking_mutex::Mutex;
     use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
     use embassy_sync::signal::Signal;
     use embassy_time::{Duration, Timer};
@@ -70,9 +72,13 @@ mod wifi_impl {
     /// Signal type used by [`TimeSync`] to publish events (see [`TimeSync`] docs).
     type TimeSyncEvents = Signal<CriticalSectionRawMutex, TimeSyncEvent>;
 
+    /// Time of the most recent successful sync.
+    type LastSync = Mutex<CriticalSectionRawMutex, Cell<Option<UnixSeconds>>>;
+
     /// Resources needed to construct a [`TimeSync`] (see [`TimeSync`] docs).
     pub struct TimeSyncStatic {
         events: TimeSyncEvents,
+        last_sync: LastSync,
         time_sync_cell: StaticCell<TimeSync>,
     }
 
@@ -121,6 +127,7 @@ mod wifi_impl {
     /// ```
     pub struct TimeSync {
         events: &'static TimeSyncEvents,
+        last_sync: &'static LastSync,
     }
 
     impl TimeSync {
@@ -129,6 +136,7 @@ mod wifi_impl {
         pub const fn new_static() -> TimeSyncStatic {
             TimeSyncStatic {
                 events: Signal::new(),
+                last_sync: Mutex::new(Cell::new(None)),
                 time_sync_cell: StaticCell::new(),
             }
         }
@@ -142,11 +150,16 @@ mod wifi_impl {
             stack: &'static Stack<'static>,
             spawner: Spawner,
         ) -> &'static Self {
-            let token = unwrap!(time_sync_stack_loop(stack, &time_sync_static.events));
+            let token = unwrap!(time_sync_stack_loop(
+                stack,
+                &time_sync_static.events,
+                &time_sync_static.last_sync
+            ));
             spawner.spawn(token);
 
             time_sync_static.time_sync_cell.init(Self {
                 events: &time_sync_static.events,
+                last_sync: &time_sync_static.last_sync,
             })
         }
 
@@ -154,20 +167,30 @@ mod wifi_impl {
         pub async fn wait_for_sync(&self) -> TimeSyncEvent {
             self.events.wait().await
         }
+
+        /// The time reported by the most recent successful sync, or `None` before the first.
+        #[must_use]
+        pub fn last_sync(&self) -> Option<UnixSeconds> {
+            self.last_sync.lock(Cell::get)
+        }
     }
 
     #[embassy_executor::task]
     async fn time_sync_stack_loop(
         stack: &'static Stack<'static>,
         sync_events: &'static TimeSyncEvents,
+        last_sync: &'static LastSync,
     ) -> ! {
-        let err = run_time_sync_loop(stack, sync_events).await.unwrap_err();
+        let err = run_time_sync_loop(stack, sync_events, last_sync)
+            .await
+            .unwrap_err();
         core::panic!("{err}");
     }
 
     async fn run_time_sync_loop(
         stack: &'static Stack<'static>,
         sync_events: &'static TimeSyncEvents,
+        last_sync: &'static LastSync,
         // cmk Infallible to ! everywhere
     ) -> Result<Infallible> {
         info!("TimeSync received network stack");
@@ -185,6 +208,7 @@ mod wifi_impl {
                         unix_seconds.as_i64()
                     );
 
+                    last_sync.lock(|cell| cell.set(Some(unix_seconds)));
                     sync_events.signal(TimeSyncEvent::Success { unix_seconds });
                     break;
                 }
@@ -226,6 +250,7 @@ mod wifi_impl {
                         unix_seconds.as_i64()
                     );
 
+                    last_sync.lock(|cell| cell.set(Some(unix_seconds)));
                     sync_events.signal(TimeSyncEvent::Success { unix_seconds });
                     last_success_elapsed = 0; // reset backoff
                 }
@@ -397,6 +422,12 @@ mod stub {
         pub async fn wait_for_sync(&self) -> TimeSyncEvent {
             self.events.wait().await
         }
+
+        /// Always `None`, since this stub never syncs.
+        #[must_use]
+        pub const fn last_sync(&self) -> Option<UnixSeconds> {
+            None
+        }
     }
 }
 
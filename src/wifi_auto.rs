@@ -30,6 +30,7 @@ use crate::flash_array::FlashBlock;
 use crate::{Error, Result};
 
 mod credentials;
+pub mod dashboard;
 mod dhcp;
 mod dns;
 pub mod fields;
@@ -81,6 +82,7 @@ pub struct WifiAutoStatic {
     defaults: Mutex<CriticalSectionRawMutex, RefCell<Option<InnerWifiCredentials>>>,
     button: Mutex<CriticalSectionRawMutex, RefCell<Option<Button<'static>>>>,
     fields_storage: StaticCell<Vec<&'static dyn WifiAutoField, MAX_WIFI_AUTO_FIELDS>>,
+    settings_changed: Signal<CriticalSectionRawMutex, ()>,
 }
 
 /// WiFi auto-provisioning with captive portal and custom configuration fields.
@@ -93,6 +95,8 @@ pub struct WifiAutoStatic {
 /// - Automatic captive portal on first boot or failed connections
 /// - Customizable configuration fields beyond WiFi credentials
 /// - Button-triggered reconfiguration
+/// - LAN reconfiguration through a [`Dashboard`](dashboard::Dashboard), reported by
+///   [`wait_settings_changed`](Self::wait_settings_changed)
 /// - Event-driven UI updates via [`connect`](Self::connect)
 ///
 /// Supports any PIO instance that implements [`WifiPio`], including `PIO0` and `PIO1`
@@ -177,6 +181,7 @@ pub struct WifiAuto {
     defaults: &'static Mutex<CriticalSectionRawMutex, RefCell<Option<InnerWifiCredentials>>>,
     button: &'static Mutex<CriticalSectionRawMutex, RefCell<Option<Button<'static>>>>,
     fields: &'static [&'static dyn WifiAutoField],
+    settings_changed: &'static Signal<CriticalSectionRawMutex, ()>,
 }
 
 impl WifiAutoStatic {
@@ -190,6 +195,7 @@ impl WifiAutoStatic {
             defaults: Mutex::new(RefCell::new(None)),
             button: Mutex::new(RefCell::new(None)),
             fields_storage: StaticCell::new(),
+            settings_changed: Signal::new(),
         }
     }
 
@@ -307,6 +313,7 @@ impl WifiAuto {
             defaults: wifi_auto_static.defaults(),
             button: wifi_auto_static.button(),
             fields: fields_ref,
+            settings_changed: &wifi_auto_static.settings_changed,
         });
 
         if force_captive_portal {
@@ -327,6 +334,24 @@ impl WifiAuto {
         self.wifi
     }
 
+    /// Return the custom fields passed to [`WifiAuto::new`], such as for editing them on a
+    /// [`Dashboard`](dashboard::Dashboard) after provisioning.
+    #[must_use]
+    pub fn fields(&self) -> &'static [&'static dyn WifiAutoField] {
+        self.fields
+    }
+
+    /// Wait until the custom fields are changed after provisioning, such as from a
+    /// [`Dashboard`](dashboard::Dashboard), then reload the values you use from them.
+    pub async fn wait_settings_changed(&self) {
+        self.settings_changed.wait().await;
+    }
+
+    /// Report that the custom fields were saved with new values.
+    pub(crate) fn notify_settings_changed(&self) {
+        self.settings_changed.signal(());
+    }
+
     fn take_button(&self) -> Option<Button<'static>> {
         self.button.lock(|cell| cell.borrow_mut().take())
     }
@@ -0,0 +1,1010 @@
+//! A device abstraction for a web dashboard served by the device once WiFi is connected.
+//!
+//! [`Dashboard`] shows uptime, WiFi signal strength, IP address, the time and timezone, the
+//! last NTP sync, and how full each flash block is. Below the status it renders the
+//! [`WifiAutoField`]s passed to [`WifiAuto::new`](super::WifiAuto::new), so settings collected
+//! by the captive portal can be changed later, followed by live controls for your devices.
+//! Settings are hidden behind the PIN from a [`PinField`] set in the captive portal: the
+//! page shows their current values only after the PIN is entered, and saving asks for it
+//! again. Saving wakes [`WifiAuto::wait_settings_changed`] so the app can apply them without
+//! a reset. Live controls ask for the PIN too, unless [`DashboardConfig::open_controls`]
+//! lets anyone on the network use them.
+//! [`SliderControl`], [`TextControl`], and [`LedPreview`] are ready-made controls; a custom
+//! control is just another [`WifiAutoField`].
+//!
+//! `GET /status` returns the status as JSON for scripts.
+//!
+//! See [`Dashboard`] for an example.
+
+#![allow(clippy::future_not_send, reason = "single-threaded")]
+
+use core::cell::{Cell, RefCell};
+use core::fmt::Write as _;
+
+use defmt::{Debug2Format, info, warn};
+use embassy_executor::Spawner;
+use embassy_net::Stack;
+use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
+use embassy_sync::signal::Signal;
+use embassy_time::{Duration, Instant};
+use embedded_io_async::Write;
+use heapless::{String, Vec};
+use portable_atomic::{AtomicI32, Ordering};
+use smart_leds::RGB8;
+use static_cell::StaticCell;
+use time::{OffsetDateTime, UtcOffset};
+
+use super::WifiAuto;
+use super::fields::PinField;
+use super::portal::{
+    FormData, HtmlBuffer, WifiAutoField, escape_html, form_map, save_fields, write_escaped_html,
+};
+use crate::clock::Clock;
+use crate::flash_array::{FlashArrayStatic, FlashBlock};
+use crate::http_server::json::ObjectWriter;
+use crate::http_server::{Handler, Method, Request, Response, Status, run_http_server};
+use crate::time_sync::TimeSync;
+use crate::{Error, Result};
+
+/// Most live controls one [`Dashboard`] shows.
+const MAX_CONTROLS: usize = 8;
+/// Form field holding the PIN typed to use settings or controls.
+const CURRENT_PIN: &str = "current_pin";
+/// Longest lockout after wrong PINs. Lockouts start at 2 seconds and double with each
+/// wrong PIN in a row, on any connection, up to this.
+const MAX_PIN_LOCKOUT_SECS: u64 = 15 * 60;
+
+/// What a [`Dashboard`] reports on besides WiFi. See [`Dashboard`] for an example.
+#[derive(Clone, Copy)]
+pub struct DashboardConfig {
+    /// Page heading and title.
+    pub title: &'static str,
+    /// TCP port to serve on.
+    pub port: u16,
+    /// Clock for the current time and timezone.
+    pub clock: Option<&'static Clock>,
+    /// Time sync for the last NTP sync.
+    pub time_sync: Option<&'static TimeSync>,
+    /// Flash whose reserved blocks to report on.
+    pub flash: Option<&'static FlashArrayStatic>,
+    /// Names for flash blocks, by block id. Unnamed blocks show their id.
+    pub flash_names: &'static [&'static str],
+    /// PIN that must be entered to see or save settings, and to use the live controls.
+    /// Until it's set, neither can be changed.
+    pub pin: &'static PinField,
+    /// Let anyone who can reach the device use the live controls without the PIN. Off by
+    /// default; settings always need the PIN.
+    pub open_controls: bool,
+}
+
+impl DashboardConfig {
+    /// A dashboard titled `title` on port 80 that reports only on WiFi and accepts settings
+    /// and controls only with the PIN from `pin`.
+    #[must_use]
+    pub const fn new(title: &'static str, pin: &'static PinField) -> Self {
+        Self {
+            title,
+            port: 80,
+            clock: None,
+            time_sync: None,
+            flash: None,
+            flash_names: &[],
+            pin,
+            open_controls: false,
+        }
+    }
+}
+
+/// Static for [`Dashboard`]. See [`Dashboard`] for usage example.
+pub struct DashboardStatic {
+    dashboard_cell: StaticCell<Dashboard>,
+    controls_storage: StaticCell<Vec<&'static dyn WifiAutoField, MAX_CONTROLS>>,
+}
+
+/// A web dashboard for the running device.
+///
+/// Serves a status page with editable settings and live controls. See the
+/// [module docs](self) for what it shows.
+///
+/// # Example
+///
+/// A slider moves a servo and a text box writes to a 4-digit display.
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// # use panic_probe as _;
+/// use device_kit::clock::Clock;
+/// use device_kit::flash_array::FlashArrayStatic;
+/// use device_kit::led4::{BlinkState, Led4};
+/// use device_kit::servo_animate::ServoAnimate;
+/// use device_kit::time_sync::TimeSync;
+/// use device_kit::wifi_auto::dashboard::{
+///     Dashboard, DashboardConfig, DashboardStatic, SliderControl, SliderControlStatic,
+///     TextControl, TextControlStatic,
+/// };
+/// use device_kit::wifi_auto::fields::PinField;
+/// use device_kit::wifi_auto::{WifiAuto, WifiAutoField};
+/// use embassy_futures::select::{Either, select};
+///
+/// # #[allow(dead_code)]
+/// async fn run_dashboard(
+///     wifi_auto: &'static WifiAuto,
+///     stack: &'static embassy_net::Stack<'static>,
+///     clock: &'static Clock,
+///     time_sync: &'static TimeSync,
+///     flash: &'static FlashArrayStatic,
+///     pin_field: &'static PinField,
+///     servo: &'static ServoAnimate,
+///     led4: &'static Led4<'static>,
+///     spawner: embassy_executor::Spawner,
+/// ) -> device_kit::Result<()> {
+///     static ARM_STATIC: SliderControlStatic = SliderControl::new_static();
+///     let arm = SliderControl::new(&ARM_STATIC, "arm", "Arm angle", 0..=180, 90);
+///     static TEXT_STATIC: TextControlStatic<4> = TextControl::new_static();
+///     let text = TextControl::new(&TEXT_STATIC, "text", "Display text");
+///
+///     static DASHBOARD_STATIC: DashboardStatic = Dashboard::new_static();
+///     let controls: [&'static dyn WifiAutoField; 2] = [arm, text];
+///     Dashboard::new(
+///         &DASHBOARD_STATIC,
+///         wifi_auto,
+///         stack,
+///         DashboardConfig {
+///             clock: Some(clock),
+///             time_sync: Some(time_sync),
+///             flash: Some(flash),
+///             flash_names: &["WiFi", "Timezone"],
+///             ..DashboardConfig::new("Porch clock", pin_field)
+///         },
+///         controls,
+///         spawner,
+///     )?;
+///
+///     // Apply changes as they arrive from the browser.
+///     loop {
+///         match select(arm.wait(), text.wait()).await {
+///             Either::First(degrees) => servo.set(u16::try_from(degrees).unwrap_or(90)).await,
+///             Either::Second(text) => {
+///                 let mut chars = [' '; 4];
+///                 for (cell, character) in chars.iter_mut().zip(text.chars()) {
+///                     *cell = character;
+///                 }
+///                 led4.write_text(chars, BlinkState::Solid);
+///             }
+///         }
+///     }
+/// }
+/// # #[embassy_executor::main]
+/// # async fn main(_spawner: embassy_executor::Spawner) {}
+/// ```
+pub struct Dashboard {
+    wifi_auto: &'static WifiAuto,
+    stack: &'static Stack<'static>,
+    config: DashboardConfig,
+    pin_lockout: Mutex<CriticalSectionRawMutex, Cell<PinLockout>>,
+    controls: &'static [&'static dyn WifiAutoField],
+}
+
+impl DashboardStatic {
+    const fn new() -> Self {
+        Self {
+            dashboard_cell: StaticCell::new(),
+            controls_storage: StaticCell::new(),
+        }
+    }
+}
+
+impl Dashboard {
+    /// Create static resources for [`Dashboard`].
+    ///
+    /// See [`Dashboard`] for a complete example.
+    #[must_use]
+    pub const fn new_static() -> DashboardStatic {
+        DashboardStatic::new()
+    }
+
+    /// Start serving the dashboard on `stack`, showing `controls` in order.
+    ///
+    /// See [`Dashboard`] for a complete example.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server task cannot be spawned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more than 8 controls.
+    pub fn new<const N: usize>(
+        dashboard_static: &'static DashboardStatic,
+        wifi_auto: &'static WifiAuto,
+        stack: &'static Stack<'static>,
+        config: DashboardConfig,
+        controls: [&'static dyn WifiAutoField; N],
+        spawner: Spawner,
+    ) -> Result<&'static Self> {
+        assert!(
+            N <= MAX_CONTROLS,
+            "Dashboard supports at most {} controls",
+            MAX_CONTROLS
+        );
+        let mut storage: Vec<&'static dyn WifiAutoField, MAX_CONTROLS> = Vec::new();
+        for control in controls {
+            storage.push(control).unwrap_or_else(|_| unreachable!());
+        }
+        let controls = dashboard_static.controls_storage.init(storage).as_slice();
+
+        let dashboard = dashboard_static.dashboard_cell.init(Self {
+            wifi_auto,
+            stack,
+            config,
+            controls,
+            pin_lockout: Mutex::new(Cell::new(PinLockout {
+                failures: 0,
+                until: Instant::MIN,
+            })),
+        });
+        let token = dashboard_task(dashboard)?;
+        spawner.spawn(token);
+        Ok(dashboard)
+    }
+
+    fn status(&self) -> Snapshot {
+        let offset = self.config.clock.map_or(UtcOffset::UTC, |clock| {
+            UtcOffset::from_whole_seconds(clock.offset_minutes() * 60).unwrap_or(UtcOffset::UTC)
+        });
+        Snapshot {
+            uptime_secs: Instant::now().as_secs(),
+            rssi: self.wifi_auto.wifi().rssi(),
+            address: self.stack.config_v4().map(|config| config.address),
+            now: self.config.clock.map(Clock::now_local),
+            last_sync: self.config.time_sync.map(|time_sync| {
+                time_sync
+                    .last_sync()
+                    .and_then(|unix_seconds| unix_seconds.to_offset_datetime(offset))
+            }),
+        }
+    }
+
+    fn render_page(&self, page: &mut HtmlBuffer) -> core::fmt::Result {
+        self.render_head(page)?;
+        page.write_str("<h2>Status</h2><table>")?;
+        let status = self.status();
+        status.render(page)?;
+        page.write_str("</table>")?;
+
+        if let Some(flash) = self.config.flash {
+            page.write_str("<h2>Flash</h2><table>")?;
+            for block_id in 0..flash.block_count() {
+                let name = self.flash_name(block_id);
+                write!(page, "<tr><th>{}</th><td>", escape_html::<192>(&name))?;
+                match flash.used_bytes(block_id) {
+                    Ok(Some(used)) => write!(
+                        page,
+                        "{used} of {capacity} bytes\
+                         <progress value=\"{used}\" max=\"{capacity}\"></progress>",
+                        capacity = FlashBlock::CAPACITY
+                    )?,
+                    Ok(None) => page.write_str("Empty")?,
+                    Err(_) => page.write_str("Unreadable")?,
+                }
+                page.write_str("</td></tr>")?;
+            }
+            page.write_str("</table>")?;
+        }
+
+        render_form(
+            page,
+            "Controls",
+            "/controls",
+            "Apply",
+            self.controls,
+            !self.config.open_controls,
+        )?;
+        // Settings may hold secrets, so their values wait until the PIN is entered.
+        if !self.wifi_auto.fields().is_empty() {
+            page.write_str("<h2>Settings</h2><form method=\"POST\" action=\"/settings/edit\">")?;
+            write_pin_input(page)?;
+            page.write_str("<button type=\"submit\">Edit settings</button></form>")?;
+        }
+        page.write_str("<p><a href=\"/\">Refresh</a></p></body></html>")
+    }
+
+    /// The settings form with current values, shown once the PIN is accepted.
+    fn render_settings_page(&self, page: &mut HtmlBuffer) -> core::fmt::Result {
+        self.render_head(page)?;
+        render_form(
+            page,
+            "Settings",
+            "/settings",
+            "Save",
+            self.wifi_auto.fields(),
+            true,
+        )?;
+        page.write_str("<p><a href=\"/\">Back to the dashboard</a></p></body></html>")
+    }
+
+    fn render_head(&self, page: &mut HtmlBuffer) -> core::fmt::Result {
+        let title = escape_html::<256>(self.config.title);
+        write!(
+            page,
+            "<!DOCTYPE html>\
+             <html>\
+             <head>\
+                 <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+                 <title>{title}</title>\
+                 <link rel=\"icon\" href=\"data:,\">\
+                 <style>\
+                     body {{ font-family: Arial, sans-serif; max-width: 500px; margin: 50px auto; padding: 20px; }}\
+                     h1 {{ color: #333; }}\
+                     h2 {{ color: #555; font-size: 1.1rem; margin-top: 30px; }}\
+                     table {{ width: 100%; border-collapse: collapse; }}\
+                     th, td {{ text-align: left; padding: 6px 0; border-bottom: 1px solid #eee; }}\
+                     progress {{ width: 100%; }}\
+                     input, select {{ width: 100%; padding: 10px; margin: 10px 0; box-sizing: border-box; }}\
+                     label {{ display: block; margin-top: 10px; }}\
+                     .leds {{ display: grid; gap: 2px; margin: 10px 0; }}\
+                     .leds span {{ aspect-ratio: 1; border-radius: 50%; }}\
+                     button {{ width: 100%; padding: 12px; background-color: #4CAF50; color: white; border: none; cursor: pointer; }}\
+                     button:hover {{ background-color: #45a049; }}\
+                 </style>\
+             </head>\
+             <body>\
+                 <h1>{title}</h1>"
+        )
+    }
+
+    /// Check the PIN in `form`, answering with an error page and returning `false` unless
+    /// it's set, not locked out, and right. Settings and controls share the lockout.
+    async fn check_pin<W: Write>(
+        &self,
+        form: &FormData<'_>,
+        response: &mut Response<'_, W>,
+    ) -> Result<bool> {
+        if !self.config.pin.is_satisfied().unwrap_or(false) {
+            warn!("Dashboard form rejected: no PIN set");
+            send_error_page(
+                response,
+                Status::Forbidden,
+                "No PIN",
+                "Set a PIN in the captive portal before using settings or controls here.",
+            )
+            .await;
+            return Ok(false);
+        }
+        if let Some(remaining) = self.pin_locked_for() {
+            warn!("Dashboard form rejected: locked out after wrong PINs");
+            let mut message = String::<64>::new();
+            write!(
+                message,
+                "Too many wrong PINs. Try again in {} seconds.",
+                remaining.as_secs().max(1)
+            )
+            .map_err(|_| Error::FormatError)?;
+            send_error_page(response, Status::TooManyRequests, "Locked", &message).await;
+            return Ok(false);
+        }
+        if !self.pin_accepted(form) {
+            warn!("Dashboard form rejected: wrong PIN");
+            send_error_page(
+                response,
+                Status::Forbidden,
+                "Wrong PIN",
+                "Nothing was changed.",
+            )
+            .await;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Whether `form` carries the PIN. Always `false` before a PIN is set. A wrong PIN
+    /// locks out every connection for a while; a right one ends the run of failures.
+    fn pin_accepted(&self, form: &FormData<'_>) -> bool {
+        let attempt = form.get(CURRENT_PIN).unwrap_or_default().trim();
+        let accepted = self.config.pin.matches(attempt).unwrap_or_else(|err| {
+            warn!("Dashboard PIN unreadable: {}", Debug2Format(&err));
+            false
+        });
+        self.pin_lockout.lock(|lockout| {
+            let failures = if accepted {
+                0
+            } else {
+                lockout.get().failures.saturating_add(1)
+            };
+            let lockout_secs = if accepted {
+                0
+            } else {
+                2_u64.saturating_pow(failures).min(MAX_PIN_LOCKOUT_SECS)
+            };
+            let until = Instant::now()
+                .checked_add(Duration::from_secs(lockout_secs))
+                .unwrap_or(Instant::MAX);
+            lockout.set(PinLockout { failures, until });
+        });
+        accepted
+    }
+
+    /// How much longer wrong PINs keep the dashboard locked, if they do.
+    fn pin_locked_for(&self) -> Option<Duration> {
+        let until = self.pin_lockout.lock(|lockout| lockout.get().until);
+        until
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| *remaining > Duration::from_ticks(0))
+    }
+
+    fn flash_name(&self, block_id: u32) -> String<32> {
+        let mut name = String::new();
+        match usize::try_from(block_id)
+            .ok()
+            .and_then(|index| self.config.flash_names.get(index))
+        {
+            Some(flash_name) => {
+                for character in flash_name.chars() {
+                    if name.push(character).is_err() {
+                        break;
+                    }
+                }
+            }
+            None => {
+                write!(name, "Block {block_id}").ok();
+            }
+        }
+        name
+    }
+}
+
+/// Render `fields` in a form posted to `action`, or nothing if there are none. With
+/// `ask_pin`, the form also asks for the current PIN.
+fn render_form(
+    page: &mut HtmlBuffer,
+    heading: &str,
+    action: &str,
+    button: &str,
+    fields: &[&'static dyn WifiAutoField],
+    ask_pin: bool,
+) -> core::fmt::Result {
+    if fields.is_empty() {
+        return Ok(());
+    }
+    write!(
+        page,
+        "<h2>{heading}</h2><form method=\"POST\" action=\"{action}\">"
+    )?;
+    for field in fields {
+        if let Err(err) = field.render(page) {
+            warn!("Dashboard field render failed: {}", Debug2Format(&err));
+        }
+    }
+    if ask_pin {
+        write_pin_input(page)?;
+    }
+    write!(page, "<button type=\"submit\">{button}</button></form>")
+}
+
+/// A required input for the current PIN.
+fn write_pin_input(page: &mut HtmlBuffer) -> core::fmt::Result {
+    write!(
+        page,
+        "<label for=\"{CURRENT_PIN}\">Current PIN:</label>\
+         <input type=\"password\" id=\"{CURRENT_PIN}\" name=\"{CURRENT_PIN}\" \
+         inputmode=\"numeric\" autocomplete=\"current-password\" required>"
+    )
+}
+
+impl Handler for Dashboard {
+    async fn handle<W: Write>(
+        &self,
+        request: &Request<'_>,
+        response: &mut Response<'_, W>,
+    ) -> Result<()> {
+        if request.route(Method::Get, "/").is_some() {
+            let mut page = HtmlBuffer::new();
+            self.render_page(&mut page)
+                .map_err(|_| Error::FormatError)?;
+            response
+                .send(Status::Ok, "text/html", page.as_bytes())
+                .await;
+        } else if request.route(Method::Get, "/status").is_some() {
+            let mut json = String::<256>::new();
+            self.status()
+                .render_json(&mut json)
+                .map_err(|_| Error::FormatError)?;
+            response
+                .send(Status::Ok, "application/json", json.as_bytes())
+                .await;
+        } else if request.route(Method::Post, "/controls").is_some() {
+            let params = form_map(request.form());
+            let form = FormData::new(&params);
+            if !self.config.open_controls && !self.check_pin(&form, response).await? {
+                return Ok(());
+            }
+            let accepted = accept_form(&form, self.controls);
+            finish_form(accepted, response).await;
+        } else if request.route(Method::Post, "/settings/edit").is_some() {
+            let params = form_map(request.form());
+            if self.check_pin(&FormData::new(&params), response).await? {
+                let mut page = HtmlBuffer::new();
+                self.render_settings_page(&mut page)
+                    .map_err(|_| Error::FormatError)?;
+                response
+                    .send(Status::Ok, "text/html", page.as_bytes())
+                    .await;
+            }
+        } else if request.route(Method::Post, "/settings").is_some() {
+            let params = form_map(request.form());
+            let form = FormData::new(&params);
+            if !self.check_pin(&form, response).await? {
+                return Ok(());
+            }
+            let accepted = accept_form(&form, self.wifi_auto.fields());
+            if accepted {
+                info!("Dashboard settings saved");
+                self.wifi_auto.notify_settings_changed();
+            }
+            finish_form(accepted, response).await;
+        }
+        Ok(())
+    }
+}
+
+/// Save a submitted form to every field, returning whether they all accepted it. A
+/// rejected form changes no field.
+fn accept_form(form: &FormData<'_>, fields: &[&'static dyn WifiAutoField]) -> bool {
+    save_fields(form, fields)
+        .inspect_err(|err| warn!("Dashboard field parse failed: {}", Debug2Format(err)))
+        .is_ok()
+}
+
+/// Return to the dashboard, or show an error if the form wasn't `accepted`.
+async fn finish_form<W: Write>(accepted: bool, response: &mut Response<'_, W>) {
+    if accepted {
+        response.redirect("/").await;
+    } else {
+        send_error_page(
+            response,
+            Status::BadRequest,
+            "Error",
+            "Some values weren't accepted.",
+        )
+        .await;
+    }
+}
+
+/// Answer with a page showing `heading` and `message`, linking back to the dashboard.
+async fn send_error_page<W: Write>(
+    response: &mut Response<'_, W>,
+    status: Status,
+    heading: &str,
+    message: &str,
+) {
+    let mut page = String::<1024>::new();
+    let written = write!(
+        page,
+        "<!DOCTYPE html>\
+         <html>\
+         <head>\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+             <title>{heading}</title>\
+             <style>\
+                 body {{ font-family: Arial, sans-serif; max-width: 500px; margin: 50px auto; padding: 20px; text-align: center; }}\
+                 h1 {{ color: #f44336; }}\
+             </style>\
+         </head>\
+         <body>\
+             <h1>{heading}</h1>\
+             <p>{message}</p>\
+             <p><a href=\"/\">Back to the dashboard</a></p>\
+         </body>\
+         </html>"
+    );
+    if written.is_err() {
+        page.clear();
+        page.push_str(heading).ok();
+    }
+    response.send(status, "text/html", page.as_bytes()).await;
+}
+
+#[embassy_executor::task]
+async fn dashboard_task(dashboard: &'static Dashboard) -> ! {
+    info!("Dashboard starting on port {}", dashboard.config.port);
+    run_http_server(dashboard.stack, dashboard.config.port, dashboard).await
+}
+
+/// Wrong PINs in a row, and when the lockout they caused ends.
+#[derive(Clone, Copy)]
+struct PinLockout {
+    failures: u32,
+    until: Instant,
+}
+
+/// Status values at one moment. `None` marks a value that isn't known yet; the outer
+/// `Option` of `last_sync` is `None` when there's no [`TimeSync`] to ask.
+struct Snapshot {
+    uptime_secs: u64,
+    rssi: Option<i32>,
+    address: Option<embassy_net::Ipv4Cidr>,
+    now: Option<OffsetDateTime>,
+    last_sync: Option<Option<OffsetDateTime>>,
+}
+
+impl Snapshot {
+    fn render(&self, page: &mut HtmlBuffer) -> core::fmt::Result {
+        let seconds = self.uptime_secs;
+        write!(
+            page,
+            "<tr><th>Uptime</th><td>{}d {:02}:{:02}:{:02}</td></tr>",
+            seconds / 86_400,
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60
+        )?;
+        page.write_str("<tr><th>WiFi signal</th><td>")?;
+        match self.rssi {
+            Some(rssi) => write!(page, "{rssi} dBm")?,
+            None => page.write_str("Unknown")?,
+        }
+        page.write_str("</td></tr><tr><th>IP address</th><td>")?;
+        match self.address {
+            Some(address) => write!(page, "{}", address.address())?,
+            None => page.write_str("None")?,
+        }
+        page.write_str("</td></tr>")?;
+        if let Some(now) = self.now {
+            page.write_str("<tr><th>Time</th><td>")?;
+            write!(page, "{}", DateTimeText(now))?;
+            let offset = now.offset();
+            let sign = if offset.is_negative() { '-' } else { '+' };
+            write!(
+                page,
+                "</td></tr><tr><th>Timezone</th><td>UTC{sign}{:02}:{:02}</td></tr>",
+                offset.whole_hours().unsigned_abs(),
+                offset.minutes_past_hour().unsigned_abs()
+            )?;
+        }
+        if let Some(last_sync) = self.last_sync {
+            page.write_str("<tr><th>Last NTP sync</th><td>")?;
+            match last_sync {
+                Some(last_sync) => write!(page, "{}", DateTimeText(last_sync))?,
+                None => page.write_str("Never")?,
+            }
+            page.write_str("</td></tr>")?;
+        }
+        Ok(())
+    }
+
+    fn render_json(&self, out: &mut impl core::fmt::Write) -> core::fmt::Result {
+        let mut json = ObjectWriter::start(out)?;
+        json.number("uptime_secs", self.uptime_secs)?;
+        match self.rssi {
+            Some(rssi) => json.number("rssi_dbm", rssi)?,
+            None => json.null("rssi_dbm")?,
+        }
+        match self.address {
+            Some(address) => json.string("ip", format_args!("{}", address.address()))?,
+            None => json.null("ip")?,
+        }
+        if let Some(now) = self.now {
+            json.string("time", format_args!("{}", DateTimeText(now)))?;
+            json.number("utc_offset_minutes", now.offset().whole_minutes())?;
+        }
+        if let Some(last_sync) = self.last_sync {
+            match last_sync {
+                Some(last_sync) => {
+                    json.string("last_sync", format_args!("{}", DateTimeText(last_sync)))?;
+                }
+                None => json.null("last_sync")?,
+            }
+        }
+        json.end()
+    }
+}
+
+/// Displays a date and time as `2026-03-14 15:09:26`.
+struct DateTimeText(OffsetDateTime);
+
+impl core::fmt::Display for DateTimeText {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let datetime = self.0;
+        write!(
+            f,
+            "{}-{:02}-{:02} {:02}:{:02}:{:02}",
+            datetime.year(),
+            u8::from(datetime.month()),
+            datetime.day(),
+            datetime.hour(),
+            datetime.minute(),
+            datetime.second()
+        )
+    }
+}
+
+/// A slider for a number in a range, such as a servo angle.
+///
+/// Moving the slider submits the dashboard's controls; await [`wait`](Self::wait) for each
+/// new value. See [`Dashboard`] for an example.
+pub struct SliderControl {
+    name: &'static str,
+    label: &'static str,
+    min: i32,
+    max: i32,
+    value: AtomicI32,
+    changes: Signal<CriticalSectionRawMutex, i32>,
+}
+
+/// Static for [`SliderControl`]. See [`Dashboard`] for usage example.
+pub struct SliderControlStatic {
+    cell: StaticCell<SliderControl>,
+}
+
+impl SliderControl {
+    /// Create static resources for [`SliderControl`].
+    #[must_use]
+    pub const fn new_static() -> SliderControlStatic {
+        SliderControlStatic {
+            cell: StaticCell::new(),
+        }
+    }
+
+    /// Create a slider with form field `name`, shown as `label`, starting at `initial`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `initial` is outside `range`.
+    pub fn new(
+        slider_control_static: &'static SliderControlStatic,
+        name: &'static str,
+        label: &'static str,
+        range: core::ops::RangeInclusive<i32>,
+        initial: i32,
+    ) -> &'static Self {
+        assert!(range.contains(&initial), "initial value must be in range");
+        slider_control_static.cell.init(Self {
+            name,
+            label,
+            min: *range.start(),
+            max: *range.end(),
+            value: AtomicI32::new(initial),
+            changes: Signal::new(),
+        })
+    }
+
+    /// Wait for the next value set from the dashboard.
+    pub async fn wait(&self) -> i32 {
+        self.changes.wait().await
+    }
+
+    /// The current value.
+    #[must_use]
+    pub fn value(&self) -> i32 {
+        self.value.load(Ordering::Relaxed)
+    }
+
+    /// Show `value` on the slider, such as after a change made on the device.
+    pub fn set_value(&self, value: i32) {
+        self.value
+            .store(value.clamp(self.min, self.max), Ordering::Relaxed);
+    }
+
+    /// The value in `form`, or `None` if the form doesn't include this slider.
+    fn submitted_value(&self, form: &FormData<'_>) -> Result<Option<i32>> {
+        let Some(value) = form.get(self.name) else {
+            return Ok(None);
+        };
+        let value = value.parse::<i32>().map_err(|_| Error::FormatError)?;
+        if !(self.min..=self.max).contains(&value) {
+            return Err(Error::FormatError);
+        }
+        Ok(Some(value))
+    }
+}
+
+impl WifiAutoField for SliderControl {
+    fn render(&self, page: &mut HtmlBuffer) -> Result<()> {
+        let value = self.value();
+        write!(
+            page,
+            "<label for=\"{name}\">{label}: <output id=\"{name}_value\">{value}</output></label>\
+             <input type=\"range\" id=\"{name}\" name=\"{name}\" min=\"{min}\" max=\"{max}\" \
+             value=\"{value}\" oninput=\"{name}_value.value = this.value\" \
+             onchange=\"this.form.submit()\">",
+            name = self.name,
+            label = self.label,
+            min = self.min,
+            max = self.max,
+        )
+        .map_err(|_| Error::FormatError)
+    }
+
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        self.submitted_value(form).map(|_| ())
+    }
+
+    fn parse(&self, form: &FormData<'_>) -> Result<()> {
+        let Some(value) = self.submitted_value(form)? else {
+            return Ok(());
+        };
+        if self.value.swap(value, Ordering::Relaxed) != value {
+            self.changes.signal(value);
+        }
+        Ok(())
+    }
+}
+
+/// A text box of up to `N` bytes, such as for a display's message.
+///
+/// Await [`wait`](Self::wait) for each new text. See [`Dashboard`] for an example.
+pub struct TextControl<const N: usize> {
+    name: &'static str,
+    label: &'static str,
+    text: Mutex<CriticalSectionRawMutex, RefCell<String<N>>>,
+    changes: Signal<CriticalSectionRawMutex, String<N>>,
+}
+
+/// Static for [`TextControl`]. See [`Dashboard`] for usage example.
+pub struct TextControlStatic<const N: usize> {
+    cell: StaticCell<TextControl<N>>,
+}
+
+impl<const N: usize> TextControl<N> {
+    /// Create static resources for [`TextControl`].
+    #[must_use]
+    pub const fn new_static() -> TextControlStatic<N> {
+        TextControlStatic {
+            cell: StaticCell::new(),
+        }
+    }
+
+    /// Create an empty text box with form field `name`, shown as `label`.
+    pub fn new(
+        text_control_static: &'static TextControlStatic<N>,
+        name: &'static str,
+        label: &'static str,
+    ) -> &'static Self {
+        text_control_static.cell.init(Self {
+            name,
+            label,
+            text: Mutex::new(RefCell::new(String::new())),
+            changes: Signal::new(),
+        })
+    }
+
+    /// Wait for the next text set from the dashboard.
+    pub async fn wait(&self) -> String<N> {
+        self.changes.wait().await
+    }
+
+    /// The current text.
+    #[must_use]
+    pub fn text(&self) -> String<N> {
+        self.text.lock(|text| text.borrow().clone())
+    }
+
+    /// Show `text` in the box, such as after a change made on the device.
+    pub fn set_text(&self, text: &String<N>) {
+        self.text
+            .lock(|current| current.borrow_mut().clone_from(text));
+    }
+
+    /// The text in `form`, or `None` if the form doesn't include this text box.
+    fn submitted_text(&self, form: &FormData<'_>) -> Result<Option<String<N>>> {
+        form.get(self.name)
+            .map(|value| String::<N>::try_from(value).map_err(|()| Error::FormatError))
+            .transpose()
+    }
+}
+
+impl<const N: usize> WifiAutoField for TextControl<N> {
+    fn render(&self, page: &mut HtmlBuffer) -> Result<()> {
+        write!(
+            page,
+            "<label for=\"{name}\">{label}:</label>\
+             <input type=\"text\" id=\"{name}\" name=\"{name}\" maxlength=\"{N}\" value=\"",
+            name = self.name,
+            label = self.label,
+        )
+        .map_err(|_| Error::FormatError)?;
+        write_escaped_html(page, &self.text()).map_err(|_| Error::FormatError)?;
+        page.push_str("\">").map_err(|()| Error::FormatError)
+    }
+
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        self.submitted_text(form).map(|_| ())
+    }
+
+    fn parse(&self, form: &FormData<'_>) -> Result<()> {
+        let Some(text) = self.submitted_text(form)? else {
+            return Ok(());
+        };
+        let changed = self.text.lock(|current| {
+            let mut current = current.borrow_mut();
+            let changed = *current != text;
+            current.clone_from(&text);
+            changed
+        });
+        if changed {
+            self.changes.signal(text);
+        }
+        Ok(())
+    }
+}
+
+/// A read-only view of up to `N` LED colors, such as the last frame written to a strip.
+///
+/// Call [`show`](Self::show) whenever the LEDs change; the dashboard shows the latest colors
+/// when the page loads.
+pub struct LedPreview<const N: usize> {
+    label: &'static str,
+    columns: usize,
+    /// The colors and how many of them are in use.
+    colors: Mutex<CriticalSectionRawMutex, Cell<([RGB8; N], usize)>>,
+}
+
+/// Static for [`LedPreview`].
+pub struct LedPreviewStatic<const N: usize> {
+    cell: StaticCell<LedPreview<N>>,
+}
+
+impl<const N: usize> LedPreview<N> {
+    /// Create static resources for [`LedPreview`].
+    #[must_use]
+    pub const fn new_static() -> LedPreviewStatic<N> {
+        LedPreviewStatic {
+            cell: StaticCell::new(),
+        }
+    }
+
+    /// Create a preview shown as `label`, wrapping after `columns` LEDs (such as a panel's
+    /// width).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `columns` is zero.
+    pub fn new(
+        led_preview_static: &'static LedPreviewStatic<N>,
+        label: &'static str,
+        columns: usize,
+    ) -> &'static Self {
+        assert!(columns > 0, "columns must be positive");
+        led_preview_static.cell.init(Self {
+            label,
+            columns,
+            colors: Mutex::new(Cell::new(([RGB8::default(); N], 0))),
+        })
+    }
+
+    /// Record the current colors. Colors beyond `N` are ignored.
+    pub fn show(&self, colors: &[RGB8]) {
+        let len = colors.len().min(N);
+        self.colors.lock(|cell| {
+            let (mut stored, _) = cell.get();
+            stored[..len].copy_from_slice(&colors[..len]);
+            cell.set((stored, len));
+        });
+    }
+}
+
+impl<const N: usize> WifiAutoField for LedPreview<N> {
+    fn render(&self, page: &mut HtmlBuffer) -> Result<()> {
+        let (colors, len) = self.colors.lock(Cell::get);
+        write!(
+            page,
+            "<label>{}:</label><div class=\"leds\" style=\"grid-template-columns: repeat({}, 1fr)\">",
+            self.label, self.columns
+        )
+        .map_err(|_| Error::FormatError)?;
+        for color in &colors[..len] {
+            write!(
+                page,
+                "<span style=\"background:#{:02x}{:02x}{:02x}\"></span>",
+                color.r, color.g, color.b
+            )
+            .map_err(|_| Error::FormatError)?;
+        }
+        page.push_str("</div>").map_err(|()| Error::FormatError)
+    }
+
+    fn parse(&self, _form: &FormData<'_>) -> Result<()> {
+        Ok(())
+    }
+}
@@ -4,7 +4,10 @@
 //! [`WifiAuto::new()`](super::WifiAuto::new) for collecting additional
 //! configuration beyond WiFi credentials.
 //!
-//! See [`TimezoneField`] and [`TextField`] for complete examples of implementing custom fields.
+//! See [`TimezoneField`] and [`TextField`] for complete examples of implementing custom fields,
+//! [`DmxField`] for the universe and start address of a [`dmx`](crate::dmx) receiver, and
+//! [`PinField`] for the PIN that protects settings changed on the
+//! [`Dashboard`](super::dashboard::Dashboard).
 
 #![allow(
     unsafe_code,
@@ -17,6 +20,7 @@ use heapless::String;
 use static_cell::StaticCell;
 
 use super::portal::{FormData, HtmlBuffer, WifiAutoField};
+use crate::dmx::DmxConfig;
 use crate::flash_array::FlashBlock;
 use crate::{Error, Result};
 
@@ -148,6 +152,11 @@ impl TimezoneField {
     pub fn clear(&self) -> Result<()> {
         self.flash.borrow_mut().clear()
     }
+
+    fn submitted_offset(form: &FormData<'_>) -> Result<i32> {
+        let value = form.get("timezone").ok_or(Error::FormatError)?;
+        value.parse::<i32>().map_err(|_| Error::FormatError)
+    }
 }
 
 impl WifiAutoField for TimezoneField {
@@ -177,10 +186,12 @@ impl WifiAutoField for TimezoneField {
         Ok(())
     }
 
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        Self::submitted_offset(form).map(|_| ())
+    }
+
     fn parse(&self, form: &FormData<'_>) -> Result<()> {
-        let value = form.get("timezone").ok_or(Error::FormatError)?;
-        let offset = value.parse::<i32>().map_err(|_| Error::FormatError)?;
-        self.set_offset_minutes(offset)
+        self.set_offset_minutes(Self::submitted_offset(form)?)
     }
 
     fn is_satisfied(&self) -> Result<bool> {
@@ -521,6 +532,20 @@ impl<const N: usize> TextField<N> {
     pub fn set_text(&self, text: &String<N>) -> Result<()> {
         self.flash.borrow_mut().save(text)
     }
+
+    /// The trimmed text in `form`, or `None` if the form doesn't include this field.
+    fn submitted_text(&self, form: &FormData<'_>) -> Result<Option<String<N>>> {
+        let Some(value) = form.get(self.field_name) else {
+            return Ok(None);
+        };
+        let trimmed = value.trim();
+        if trimmed.is_empty() || trimmed.len() > N {
+            return Err(Error::FormatError);
+        }
+        let mut text = String::<N>::new();
+        text.push_str(trimmed).map_err(|_| Error::FormatError)?;
+        Ok(Some(text))
+    }
 }
 
 impl<const N: usize> WifiAutoField for TextField<N> {
@@ -549,22 +574,405 @@ impl<const N: usize> WifiAutoField for TextField<N> {
         Ok(())
     }
 
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        self.submitted_text(form).map(|_| ())
+    }
+
     fn parse(&self, form: &FormData<'_>) -> Result<()> {
-        let Some(value) = form.get(self.field_name) else {
-            info!("WifiAuto field: text input missing from submission");
-            return Ok(());
+        match self.submitted_text(form)? {
+            Some(text) => self.set_text(&text),
+            None => {
+                info!("WifiAuto field: text input missing from submission");
+                Ok(())
+            }
+        }
+    }
+
+    fn is_satisfied(&self) -> Result<bool> {
+        Ok(self.text()?.map_or(false, |text| !text.is_empty()))
+    }
+}
+
+/// A PIN field that protects settings changed over the LAN after provisioning.
+///
+/// The captive portal asks for a PIN of 4 to 8 digits and stores it to flash. Pass the
+/// field to [`WifiAuto::new`](super::WifiAuto::new) with your other fields, and to
+/// [`DashboardConfig::new`](super::dashboard::DashboardConfig::new) so the dashboard
+/// asks for the PIN before saving any of them. When shown on the dashboard, leaving the
+/// field blank keeps the current PIN.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::button::PressedTo;
+/// use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+/// use device_kit::wifi_auto::WifiAuto;
+/// use device_kit::wifi_auto::dashboard::{Dashboard, DashboardConfig, DashboardStatic};
+/// use device_kit::wifi_auto::fields::{PinField, PinFieldStatic, TimezoneField, TimezoneFieldStatic};
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+/// async fn example(
+///     spawner: embassy_executor::Spawner,
+///     p: embassy_rp::Peripherals,
+/// ) -> Result<(), device_kit::Error> {
+///     static FLASH_STATIC: FlashArrayStatic = FlashArray::<3>::new_static();
+///     let [wifi_flash, timezone_flash, pin_flash] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+///
+///     static TIMEZONE_STATIC: TimezoneFieldStatic = TimezoneField::new_static();
+///     let timezone_field = TimezoneField::new(&TIMEZONE_STATIC, timezone_flash);
+///     static PIN_STATIC: PinFieldStatic = PinField::new_static();
+///     let pin_field = PinField::new(&PIN_STATIC, pin_flash);
+///
+///     let wifi_auto = WifiAuto::new(
+///         p.PIN_23,
+///         p.PIN_25,
+///         p.PIO0,
+///         p.PIN_24,
+///         p.PIN_29,
+///         p.DMA_CH0,
+///         wifi_flash,
+///         p.PIN_13,
+///         PressedTo::Ground,
+///         "ClockStation",
+///         [timezone_field, pin_field],
+///         spawner,
+///     )?;
+///     let (stack, _button) = wifi_auto.connect(spawner, |_event| async {}).await?;
+///
+///     // Serve the timezone over the LAN, behind the PIN.
+///     static DASHBOARD_STATIC: DashboardStatic = Dashboard::new_static();
+///     Dashboard::new(
+///         &DASHBOARD_STATIC,
+///         wifi_auto,
+///         stack,
+///         DashboardConfig::new("Clock", pin_field),
+///         [],
+///         spawner,
+///     )?;
+///
+///     loop {
+///         wifi_auto.wait_settings_changed().await;
+///         let offset_minutes = timezone_field.offset_minutes()?.unwrap_or(0);
+///         defmt::info!("Timezone is now {} minutes from UTC", offset_minutes);
+///     }
+/// }
+/// ```
+pub struct PinField {
+    flash: RefCell<FlashBlock>,
+}
+
+// SAFETY: PinField is used in a single-threaded Embassy executor on RP2040/RP2350.
+// There are no interrupts that access this data, and all async operations are cooperative
+// (non-preemptive). The Sync bound is required only because WifiAutoField trait objects
+// are stored in static storage, not because of actual concurrent access.
+unsafe impl Sync for PinField {}
+
+/// Static for [`PinField`]. See [`PinField`] for usage example.
+pub struct PinFieldStatic {
+    cell: StaticCell<PinField>,
+}
+
+impl PinFieldStatic {
+    const fn new() -> Self {
+        Self {
+            cell: StaticCell::new(),
+        }
+    }
+}
+
+/// Longest PIN accepted by [`PinField`].
+const MAX_PIN_LEN: usize = 8;
+/// Shortest PIN accepted by [`PinField`].
+const MIN_PIN_LEN: usize = 4;
+
+impl PinField {
+    /// Create static resources for [`PinField`].
+    ///
+    /// See [`PinField`] for a complete example.
+    pub const fn new_static() -> PinFieldStatic {
+        PinFieldStatic::new()
+    }
+
+    /// Initialize a new PIN field.
+    ///
+    /// See [`PinField`] for a complete example.
+    pub fn new(pin_field_static: &'static PinFieldStatic, flash: FlashBlock) -> &'static Self {
+        pin_field_static.cell.init(Self {
+            flash: RefCell::new(flash),
+        })
+    }
+
+    /// Load the stored PIN.
+    ///
+    /// Returns `None` if no PIN has been set yet.
+    pub fn pin(&self) -> Result<Option<String<MAX_PIN_LEN>>> {
+        self.flash.borrow_mut().load::<String<MAX_PIN_LEN>>()
+    }
+
+    /// Save a new PIN of 4 to 8 digits to flash.
+    ///
+    /// Only writes to flash if the PIN has changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::FormatError`] if `pin` isn't 4 to 8 digits.
+    pub fn set_pin(&self, pin: &str) -> Result<()> {
+        check_pin(pin)?;
+        if self.pin()?.as_deref() != Some(pin) {
+            let pin = String::<MAX_PIN_LEN>::try_from(pin).map_err(|()| Error::FormatError)?;
+            self.flash.borrow_mut().save(&pin)?;
+        }
+        Ok(())
+    }
+
+    /// Check `attempt` against the stored PIN. Always `false` if no PIN has been set.
+    pub fn matches(&self, attempt: &str) -> Result<bool> {
+        Ok(self.pin()?.is_some_and(|pin| {
+            // Compare every byte so the time taken doesn't reveal how much matched.
+            pin.len() == attempt.len()
+                && pin
+                    .bytes()
+                    .zip(attempt.bytes())
+                    .fold(0, |difference, (left, right)| difference | (left ^ right))
+                    == 0
+        }))
+    }
+
+    /// Clear the stored PIN, so the captive portal asks for a new one.
+    pub fn clear(&self) -> Result<()> {
+        self.flash.borrow_mut().clear()
+    }
+
+    /// The new PIN in `form`, or `None` if there's none and the current PIN stays.
+    fn submitted_pin<'a>(&self, form: &'a FormData<'_>) -> Result<Option<&'a str>> {
+        let Some(value) = form.get("pin") else {
+            info!("WifiAuto field: PIN input missing from submission");
+            return Ok(None);
         };
-        let trimmed = value.trim();
-        if trimmed.is_empty() || trimmed.len() > N {
+        let value = value.trim();
+        if value.is_empty() {
+            return if self.pin()?.is_some() {
+                Ok(None)
+            } else {
+                Err(Error::FormatError)
+            };
+        }
+        check_pin(value)?;
+        Ok(Some(value))
+    }
+}
+
+/// Check that `pin` is 4 to 8 digits.
+fn check_pin(pin: &str) -> Result<()> {
+    if (MIN_PIN_LEN..=MAX_PIN_LEN).contains(&pin.len())
+        && pin.bytes().all(|byte| byte.is_ascii_digit())
+    {
+        Ok(())
+    } else {
+        Err(Error::FormatError)
+    }
+}
+
+impl WifiAutoField for PinField {
+    fn render(&self, page: &mut HtmlBuffer) -> Result<()> {
+        info!("WifiAuto field: rendering PIN input");
+        let (label, required) = if self.pin()?.is_some() {
+            ("New settings PIN (blank keeps current)", "")
+        } else {
+            ("Settings PIN (4-8 digits)", " required")
+        };
+        FmtWrite::write_fmt(
+            page,
+            format_args!(
+                "<label for=\"pin\">{label}:</label>\
+                 <input type=\"password\" id=\"pin\" name=\"pin\" inputmode=\"numeric\" \
+                 pattern=\"[0-9]{{{MIN_PIN_LEN},{MAX_PIN_LEN}}}\" maxlength=\"{MAX_PIN_LEN}\" \
+                 autocomplete=\"new-password\"{required}>"
+            ),
+        )
+        .map_err(|_| Error::FormatError)?;
+        Ok(())
+    }
+
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        self.submitted_pin(form).map(|_| ())
+    }
+
+    fn parse(&self, form: &FormData<'_>) -> Result<()> {
+        match self.submitted_pin(form)? {
+            Some(pin) => self.set_pin(pin),
+            None => Ok(()),
+        }
+    }
+
+    fn is_satisfied(&self) -> Result<bool> {
+        Ok(self.pin()?.is_some())
+    }
+}
+
+/// A DMX universe and start address field for WiFi provisioning.
+///
+/// Collects the [`DmxConfig`] for a [`dmx`](crate::dmx) receiver in the captive portal, so
+/// the device can be patched into a lighting setup without reflashing.
+///
+/// # Example
+///
+/// ```no_run
+/// # #![no_std]
+/// # #![no_main]
+/// use device_kit::button::PressedTo;
+/// use device_kit::dmx::DmxMapper;
+/// use device_kit::flash_array::{FlashArray, FlashArrayStatic};
+/// use device_kit::wifi_auto::WifiAuto;
+/// use device_kit::wifi_auto::fields::{DmxField, DmxFieldStatic};
+/// # #[panic_handler]
+/// # fn panic(_info: &core::panic::PanicInfo) -> ! { loop {} }
+/// async fn example(
+///     spawner: embassy_executor::Spawner,
+///     p: embassy_rp::Peripherals,
+/// ) -> Result<(), device_kit::Error> {
+///     static FLASH_STATIC: FlashArrayStatic = FlashArray::<2>::new_static();
+///     let [wifi_flash, dmx_flash] = FlashArray::new(&FLASH_STATIC, p.FLASH)?;
+///
+///     static DMX_STATIC: DmxFieldStatic = DmxField::new_static();
+///     let dmx_field = DmxField::new(&DMX_STATIC, dmx_flash);
+///
+///     let wifi_auto = WifiAuto::new(
+///         p.PIN_23,
+///         p.PIN_25,
+///         p.PIO0,
+///         p.PIN_24,
+///         p.PIN_29,
+///         p.DMA_CH0,
+///         wifi_flash,
+///         p.PIN_13,
+///         PressedTo::Ground,
+///         "PixelNode",
+///         [dmx_field],
+///         spawner,
+///     )?;
+///
+///     // Later, build the receiver's mapper from the stored configuration
+///     let mapper = DmxMapper::<60>::linear(dmx_field.config()?.unwrap_or_default());
+///     Ok(())
+/// }
+/// ```
+pub struct DmxField {
+    flash: RefCell<FlashBlock>,
+}
+
+// SAFETY: DmxField is used in a single-threaded Embassy executor on RP2040/RP2350.
+// There are no interrupts that access this data, and all async operations are cooperative
+// (non-preemptive). The Sync bound is required only because WifiAutoField trait objects
+// are stored in static storage, not because of actual concurrent access.
+unsafe impl Sync for DmxField {}
+
+/// Static for [`DmxField`]. See [`DmxField`] for usage example.
+pub struct DmxFieldStatic {
+    cell: StaticCell<DmxField>,
+}
+
+impl DmxFieldStatic {
+    const fn new() -> Self {
+        Self {
+            cell: StaticCell::new(),
+        }
+    }
+}
+
+impl DmxField {
+    /// Create static resources for [`DmxField`].
+    ///
+    /// See [`DmxField`] for a complete example.
+    pub const fn new_static() -> DmxFieldStatic {
+        DmxFieldStatic::new()
+    }
+
+    /// Initialize a new DMX configuration field.
+    ///
+    /// See [`DmxField`] for a complete example.
+    pub fn new(dmx_field_static: &'static DmxFieldStatic, flash: FlashBlock) -> &'static Self {
+        dmx_field_static.cell.init(Self {
+            flash: RefCell::new(flash),
+        })
+    }
+
+    /// Load the stored DMX configuration.
+    ///
+    /// Returns `None` if no configuration has been saved yet.
+    pub fn config(&self) -> Result<Option<DmxConfig>> {
+        self.flash.borrow_mut().load::<DmxConfig>()
+    }
+
+    /// Save a new DMX configuration to flash.
+    ///
+    /// Only writes to flash if the value has changed, avoiding unnecessary flash wear.
+    pub fn set_config(&self, config: DmxConfig) -> Result<()> {
+        if !config.is_valid() {
             return Err(Error::FormatError);
         }
-        let mut text = String::<N>::new();
-        text.push_str(trimmed).map_err(|_| Error::FormatError)?;
-        self.set_text(&text)
+        if self.config()? != Some(config) {
+            self.flash.borrow_mut().save(&config)?;
+        }
+        Ok(())
+    }
+
+    /// Clear the stored configuration, returning the field to an unconfigured state.
+    pub fn clear(&self) -> Result<()> {
+        self.flash.borrow_mut().clear()
+    }
+
+    fn submitted_config(form: &FormData<'_>) -> Result<DmxConfig> {
+        let parse_number = |name: &str| {
+            form.get(name)
+                .ok_or(Error::FormatError)?
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| Error::FormatError)
+        };
+        let config = DmxConfig {
+            universe: parse_number("dmx_universe")?,
+            start_address: parse_number("dmx_start_address")?,
+        };
+        if !config.is_valid() {
+            return Err(Error::FormatError);
+        }
+        Ok(config)
+    }
+}
+
+impl WifiAutoField for DmxField {
+    fn render(&self, page: &mut HtmlBuffer) -> Result<()> {
+        info!("WifiAuto field: rendering DMX inputs");
+        let current = self.config()?.unwrap_or_default();
+        FmtWrite::write_fmt(
+            page,
+            format_args!(
+                "<label for=\"dmx_universe\">DMX universe:</label>\
+                 <input type=\"number\" id=\"dmx_universe\" name=\"dmx_universe\" \
+                 value=\"{}\" min=\"0\" max=\"63999\" required>\
+                 <label for=\"dmx_start_address\">DMX start address:</label>\
+                 <input type=\"number\" id=\"dmx_start_address\" name=\"dmx_start_address\" \
+                 value=\"{}\" min=\"1\" max=\"512\" required>",
+                current.universe, current.start_address
+            ),
+        )
+        .map_err(|_| Error::FormatError)?;
+        Ok(())
+    }
+
+    fn validate(&self, form: &FormData<'_>) -> Result<()> {
+        Self::submitted_config(form).map(|_| ())
+    }
+
+    fn parse(&self, form: &FormData<'_>) -> Result<()> {
+        self.set_config(Self::submitted_config(form)?)
     }
 
     fn is_satisfied(&self) -> Result<bool> {
-        Ok(self.text()?.map_or(false, |text| !text.is_empty()))
+        Ok(self.config()?.is_some())
     }
 }
 
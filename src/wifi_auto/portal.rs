@@ -2,16 +2,14 @@ use core::{cell::RefCell, fmt::Write};
 
 use defmt::{Debug2Format, info, unwrap, warn};
 use embassy_executor::Spawner;
-use embassy_net::{Stack, tcp::TcpSocket};
+use embassy_net::Stack;
 use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
 use embassy_sync::channel::Channel;
-use embassy_time::{Duration, Timer};
-use embedded_io_async::Write as _;
 use heapless::{FnvIndexMap, String};
-use static_cell::StaticCell;
 
 use super::credentials::WifiCredentials;
 use crate::Result;
+use crate::http_server::{Handler, Method, Params, Request, Response, Status, run_http_server};
 
 pub type HtmlBuffer = String<16384>;
 
@@ -28,6 +26,7 @@ pub type HtmlBuffer = String<16384>;
 /// # Methods
 ///
 /// - [`render`](Self::render): Generate HTML form elements for the captive portal
+/// - [`validate`](Self::validate): Check submitted form data without saving it
 /// - [`parse`](Self::parse): Parse and save submitted form data
 /// - [`is_satisfied`](Self::is_satisfied): Check if field has valid configuration
 pub trait WifiAutoField: Sync {
@@ -40,6 +39,19 @@ pub trait WifiAutoField: Sync {
     /// [`TextField`](super::fields::TextField) for examples.
     fn render(&self, page: &mut HtmlBuffer) -> Result<()>;
 
+    /// Check form data submitted by the user without saving it.
+    ///
+    /// Return the error [`parse`](Self::parse) would return for `form`, if any. Every
+    /// field validates a form before any field parses it, so a form that one field
+    /// rejects changes nothing. The default implementation accepts every form, leaving
+    /// validation to `parse`.
+    ///
+    /// See [`TimezoneField`](super::fields::TimezoneField) and
+    /// [`TextField`](super::fields::TextField) for examples.
+    fn validate(&self, _form: &FormData<'_>) -> Result<()> {
+        Ok(())
+    }
+
     /// Parse and save form data submitted by the user.
     ///
     /// Extract values from the `form` data and persist them (typically to flash).
@@ -67,7 +79,7 @@ pub struct FormData<'a> {
 }
 
 impl<'a> FormData<'a> {
-    fn new(params: &'a FormMap) -> Self {
+    pub(super) fn new(params: &'a FormMap) -> Self {
         Self { params }
     }
 
@@ -81,7 +93,18 @@ impl<'a> FormData<'a> {
 
 type FormKey = String<32>;
 type FormValue = String<256>;
-type FormMap = FnvIndexMap<FormKey, FormValue, 32>;
+pub(super) type FormMap = FnvIndexMap<FormKey, FormValue, 32>;
+
+/// Decode a submitted form for [`FormData`], skipping pairs too long to keep.
+pub(super) fn form_map(body: Params<'_>) -> FormMap {
+    let mut params = FormMap::new();
+    for (key, value) in body.iter() {
+        if let (Ok(key), Ok(value)) = (key.decode::<32>(), value.decode::<256>()) {
+            params.insert(key, value).ok();
+        }
+    }
+    params
+}
 
 static CREDENTIAL_CHANNEL: Channel<CriticalSectionRawMutex, WifiCredentials, 1> = Channel::new();
 
@@ -125,122 +148,84 @@ pub async fn collect_credentials(
 #[embassy_executor::task]
 async fn http_server_task(stack: &'static Stack<'static>) -> ! {
     info!("WifiAuto HTTP portal starting");
+    run_http_server(stack, 80, &PortalHandler).await
+}
 
-    static RX_BUFFER: StaticCell<[u8; 2048]> = StaticCell::new();
-    static TX_BUFFER: StaticCell<[u8; 4096]> = StaticCell::new();
-    static REQUEST_BUFFER: StaticCell<[u8; 1024]> = StaticCell::new();
-
-    let rx_buffer = RX_BUFFER.init([0; 2048]);
-    let tx_buffer = TX_BUFFER.init([0; 4096]);
-    let request = REQUEST_BUFFER.init([0; 1024]);
-
-    loop {
-        let mut socket = TcpSocket::new(*stack, rx_buffer, tx_buffer);
-        socket.set_timeout(Some(Duration::from_secs(30)));
-
-        info!("Waiting for HTTP connection...");
-        if let Err(err) = socket.accept(80).await {
-            warn!("Accept error: {:?}", err);
-            Timer::after_millis(500).await;
-            continue;
-        }
-
-        let request_len = match socket.read(request).await {
-            Ok(0) => {
-                info!("Client closed connection");
-                socket.flush().await.ok();
-                socket.close();
-                continue;
-            }
-            Ok(n) => n,
-            Err(err) => {
-                warn!("HTTP read error: {:?}", err);
-                socket.flush().await.ok();
-                socket.close();
-                continue;
-            }
-        };
-
-        let request_text = core::str::from_utf8(&request[..request_len]).unwrap_or("");
-        let mut lines = request_text.lines();
-        let request_line = lines.next().unwrap_or("");
-        let mut parts = request_line.split_whitespace();
-        let method = parts.next().unwrap_or("");
-
-        let response = match method {
-            "GET" => {
+/// Serves the configuration form and accepts its submission.
+struct PortalHandler;
+
+impl Handler for PortalHandler {
+    async fn handle<W: embedded_io_async::Write>(
+        &self,
+        request: &Request<'_>,
+        response: &mut Response<'_, W>,
+    ) -> Result<()> {
+        match request.method {
+            Method::Get => {
                 let state_snapshot = FORM_STATE.lock(|state| state.borrow().clone());
                 let fields_snapshot = FORM_FIELDS.lock(|fields| *fields.borrow());
-                generate_config_page(&state_snapshot, fields_snapshot)
+                let page = generate_config_page(&state_snapshot, fields_snapshot);
+                response
+                    .send(Status::Ok, "text/html", page.as_bytes())
+                    .await;
             }
-            "POST" => {
+            Method::Post => {
                 let fields_snapshot = FORM_FIELDS.lock(|fields| *fields.borrow());
-                if let Some(credentials) = parse_post(request_text, fields_snapshot) {
+                if let Some(credentials) = parse_form(request.form(), fields_snapshot) {
                     CREDENTIAL_CHANNEL.send(credentials).await;
-                    static_page(generate_success_page())
+                    response
+                        .send(Status::Ok, "text/html", SUCCESS_PAGE.as_bytes())
+                        .await;
                 } else {
                     warn!("WifiAuto portal failed to parse POST");
-                    static_page(generate_error_page())
+                    response
+                        .send(Status::BadRequest, "text/html", ERROR_PAGE.as_bytes())
+                        .await;
                 }
             }
-            _ => static_page(generate_error_page()),
-        };
-
-        if let Err(err) = socket.write_all(response.as_bytes()).await {
-            warn!("HTTP write error: {:?}", err);
-        }
-
-        socket.flush().await.ok();
-        socket.close();
-        Timer::after_millis(100).await;
-    }
-}
-
-fn parse_post(request: &str, fields: &[&'static dyn WifiAutoField]) -> Option<WifiCredentials> {
-    let body_start = request.find("\r\n\r\n")? + 4;
-    let body = &request[body_start..];
-
-    let mut params: FormMap = FormMap::new();
-    let mut ssid = heapless::String::<32>::new();
-    let mut password = heapless::String::<64>::new();
-
-    for param in body.split('&') {
-        if let Some((key, value)) = param.split_once('=') {
-            let decoded_key = url_decode::<32>(key);
-            let decoded_value = url_decode::<256>(value);
-            params
-                .insert(decoded_key.clone(), decoded_value.clone())
-                .ok();
-            match decoded_key.as_str() {
-                "ssid" => {
-                    ssid.push_str(&decoded_value)
-                        .expect("ssid exceeds capacity");
-                }
-                "password" => {
-                    password
-                        .push_str(&decoded_value)
-                        .expect("password exceeds capacity");
-                }
-                _ => {}
+            _ => {
+                response
+                    .send(Status::BadRequest, "text/html", ERROR_PAGE.as_bytes())
+                    .await;
             }
         }
+        Ok(())
     }
+}
 
+fn parse_form(body: Params<'_>, fields: &[&'static dyn WifiAutoField]) -> Option<WifiCredentials> {
+    let params = form_map(body);
+    let ssid = body.get("ssid")?.decode::<32>().ok()?;
+    let password = match body.get("password") {
+        Some(password) => password.decode::<64>().ok()?,
+        None => heapless::String::new(),
+    };
     if ssid.is_empty() {
         return None;
     }
 
-    let form = FormData::new(&params);
-    for field in fields {
-        if let Err(err) = field.parse(&form) {
-            warn!("WifiAuto field parse failed: {}", Debug2Format(&err));
-            return None;
-        }
+    if let Err(err) = save_fields(&FormData::new(&params), fields) {
+        warn!("WifiAuto field parse failed: {}", Debug2Format(&err));
+        return None;
     }
 
     Some(WifiCredentials { ssid, password })
 }
 
+/// Save `form` to every field, or to none of them if any field rejects it.
+pub(super) fn save_fields(
+    form: &FormData<'_>,
+    fields: &[&'static dyn WifiAutoField],
+) -> Result<()> {
+    for field in fields {
+        field.validate(form)?;
+    }
+    for field in fields {
+        field.parse(form)?;
+    }
+    Ok(())
+}
+
 fn generate_config_page(state: &FormState, fields: &[&'static dyn WifiAutoField]) -> HtmlBuffer {
     info!("WifiAuto portal rendering {} fields", fields.len());
     let mut page = HtmlBuffer::new();
@@ -257,11 +242,7 @@ fn generate_config_page(state: &FormState, fields: &[&'static dyn WifiAutoField]
 
     write!(
         page,
-        "HTTP/1.1 200 OK\r\n\
-         Content-Type: text/html\r\n\
-         Connection: close\r\n\
-         \r\n\
-         <!DOCTYPE html>\
+        "<!DOCTYPE html>\
          <html>\
          <head>\
              <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
@@ -311,12 +292,7 @@ fn generate_config_page(state: &FormState, fields: &[&'static dyn WifiAutoField]
     page
 }
 
-fn generate_success_page() -> &'static str {
-    "HTTP/1.1 200 OK\r\n\
-     Content-Type: text/html\r\n\
-     Connection: close\r\n\
-     \r\n\
-     <!DOCTYPE html>\
+const SUCCESS_PAGE: &str = "<!DOCTYPE html>\
      <html>\
      <head>\
          <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
@@ -332,15 +308,9 @@ fn generate_success_page() -> &'static str {
          <p>The device will restart and connect to your network.</p>\
          <p>You can close this page.</p>\
      </body>\
-     </html>"
-}
+     </html>";
 
-fn generate_error_page() -> &'static str {
-    "HTTP/1.1 400 Bad Request\r\n\
-     Content-Type: text/html\r\n\
-     Connection: close\r\n\
-     \r\n\
-     <!DOCTYPE html>\
+const ERROR_PAGE: &str = "<!DOCTYPE html>\
      <html>\
      <head>\
          <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
@@ -355,74 +325,28 @@ fn generate_error_page() -> &'static str {
          <p>Failed to process your request.</p>\
          <p><a href=\"/\">Try again</a></p>\
      </body>\
-     </html>"
-}
-
-fn static_page(content: &'static str) -> HtmlBuffer {
-    let mut page = HtmlBuffer::new();
-    page.push_str(content)
-        .expect("static content exceeds page capacity");
-    page
-}
-
-fn url_decode<const N: usize>(s: &str) -> heapless::String<N> {
-    let mut result = heapless::String::<N>::new();
-    let mut chars = s.chars();
-
-    while let Some(c) = chars.next() {
-        if c == '+' {
-            result.push(' ').expect("decoded URL exceeds capacity");
-        } else if c == '%' {
-            if let (Some(h1), Some(h2)) = (chars.next(), chars.next()) {
-                if let (Some(d1), Some(d2)) = (h1.to_digit(16), h2.to_digit(16)) {
-                    #[allow(clippy::cast_possible_truncation)]
-                    let byte = ((d1 << 4) | d2) as u8;
-                    if let Ok(ch) = core::str::from_utf8(&[byte]) {
-                        result.push_str(ch).expect("decoded URL exceeds capacity");
-                    }
-                }
-            }
-        } else {
-            result.push(c).expect("decoded URL exceeds capacity");
-        }
-    }
+     </html>";
 
-    result
+pub(super) fn escape_html<const N: usize>(value: &str) -> heapless::String<N> {
+    let mut escaped = heapless::String::<N>::new();
+    write_escaped_html(&mut escaped, value).expect("escaped HTML exceeds capacity");
+    escaped
 }
 
-fn escape_html<const N: usize>(value: &str) -> heapless::String<N> {
-    let mut escaped = heapless::String::<N>::new();
+/// Write `value` to `out` with HTML special characters escaped, without buffering it first.
+pub(super) fn write_escaped_html(
+    out: &mut impl core::fmt::Write,
+    value: &str,
+) -> core::fmt::Result {
     for ch in value.chars() {
         match ch {
-            '&' => {
-                escaped
-                    .push_str("&amp;")
-                    .expect("escaped HTML exceeds capacity");
-            }
-            '<' => {
-                escaped
-                    .push_str("&lt;")
-                    .expect("escaped HTML exceeds capacity");
-            }
-            '>' => {
-                escaped
-                    .push_str("&gt;")
-                    .expect("escaped HTML exceeds capacity");
-            }
-            '"' => {
-                escaped
-                    .push_str("&quot;")
-                    .expect("escaped HTML exceeds capacity");
-            }
-            '\'' => {
-                escaped
-                    .push_str("&#39;")
-                    .expect("escaped HTML exceeds capacity");
-            }
-            _ => {
-                escaped.push(ch).expect("escaped HTML exceeds capacity");
-            }
+            '&' => out.write_str("&amp;")?,
+            '<' => out.write_str("&lt;")?,
+            '>' => out.write_str("&gt;")?,
+            '"' => out.write_str("&quot;")?,
+            '\'' => out.write_str("&#39;")?,
+            _ => out.write_char(ch)?,
         }
     }
-    escaped
+    Ok(())
 }
@@ -109,7 +109,7 @@ use embassy_rp::{
 use embassy_sync::blocking_mutex::{Mutex, raw::CriticalSectionRawMutex};
 use embassy_sync::signal::Signal;
 use embassy_time::Timer;
-use portable_atomic::{AtomicBool, Ordering};
+use portable_atomic::{AtomicBool, AtomicI32, Ordering};
 use static_cell::StaticCell;
 
 use super::credentials::WifiCredentials;
@@ -207,6 +207,13 @@ impl StackStorage {
 // WiFi Virtual Device
 // ============================================================================
 
+/// Latest signal strength in dBm, read by the client-mode loop (0 until the first read).
+/// The Pico W has one WiFi chip, so one value serves every [`Wifi`].
+static CLIENT_RSSI: AtomicI32 = AtomicI32::new(0);
+
+/// How often the client-mode loop reads the signal strength.
+const RSSI_INTERVAL_SECS: u64 = 10;
+
 /// Signal type for WiFi events.
 pub type WifiEvents = Signal<CriticalSectionRawMutex, WifiEvent>;
 
@@ -291,6 +298,17 @@ impl Wifi {
         self.events.wait().await
     }
 
+    /// Signal strength of the joined network in dBm, refreshed every 10 seconds.
+    ///
+    /// Returns `None` in captive portal mode or before the first reading.
+    #[must_use]
+    pub fn rssi(&self) -> Option<i32> {
+        match CLIENT_RSSI.load(Ordering::Relaxed) {
+            0 => None,
+            rssi => Some(rssi),
+        }
+    }
+
     /// Create a new WiFi device and spawn its background task.
     ///
     /// This initializes the WiFi hardware and spawns tasks to manage the WiFi connection
@@ -799,9 +817,10 @@ async fn wifi_device_loop_client_impl<PIO: WifiPio>(
     stack_storage.init(stack);
     wifi_events.signal(WifiEvent::ClientReady);
 
-    // Keep task alive (could monitor link status in future)
+    // Keep task alive, refreshing the signal strength for `Wifi::rssi`.
     loop {
-        Timer::after_secs(3600).await;
+        CLIENT_RSSI.store(control.get_rssi().await, Ordering::Relaxed);
+        Timer::after_secs(RSSI_INTERVAL_SECS).await;
     }
 }
 
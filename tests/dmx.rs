@@ -0,0 +1,253 @@
+//! Host-level tests for E1.31, Art-Net, and DDP parsing and DMX pixel mapping.
+#![cfg(feature = "host")]
+
+use std::net::UdpSocket;
+
+use device_kit::dmx::{DmxConfig, DmxMapper, DmxPacket, DmxProtocol};
+use device_kit::led_layout::LedLayout;
+use smart_leds::RGB8;
+
+fn e131_packet(universe: u16, channels: &[u8]) -> Vec<u8> {
+    let value_count = u16::try_from(channels.len() + 1).unwrap();
+    let mut packet = vec![0u8; 126];
+    packet[0..2].copy_from_slice(&0x0010u16.to_be_bytes());
+    packet[4..16].copy_from_slice(b"ASC-E1.17\0\0\0");
+    packet[18..22].copy_from_slice(&4u32.to_be_bytes());
+    packet[40..44].copy_from_slice(&2u32.to_be_bytes());
+    packet[44..52].copy_from_slice(b"test rig");
+    packet[108] = 100; // priority
+    packet[113..115].copy_from_slice(&universe.to_be_bytes());
+    packet[117] = 0x02;
+    packet[118] = 0xA1;
+    packet[121..123].copy_from_slice(&1u16.to_be_bytes());
+    packet[123..125].copy_from_slice(&value_count.to_be_bytes());
+    packet.extend_from_slice(channels);
+    packet
+}
+
+fn art_net_packet(universe: u16, channels: &[u8]) -> Vec<u8> {
+    let mut packet = b"Art-Net\0".to_vec();
+    packet.extend_from_slice(&0x5000u16.to_le_bytes());
+    packet.extend_from_slice(&14u16.to_be_bytes());
+    packet.extend_from_slice(&[0, 0]); // sequence, physical
+    packet.extend_from_slice(&universe.to_le_bytes());
+    packet.extend_from_slice(&u16::try_from(channels.len()).unwrap().to_be_bytes());
+    packet.extend_from_slice(channels);
+    packet
+}
+
+fn ddp_packet(offset: u32, data: &[u8], push: bool) -> Vec<u8> {
+    let mut packet = vec![0x40 | u8::from(push), 0, 0x0B, 1];
+    packet.extend_from_slice(&offset.to_be_bytes());
+    packet.extend_from_slice(&u16::try_from(data.len()).unwrap().to_be_bytes());
+    packet.extend_from_slice(data);
+    packet
+}
+
+fn gradient(pixels: usize) -> Vec<u8> {
+    (0..pixels)
+        .flat_map(|index| {
+            let value = u8::try_from(index % 256).unwrap();
+            [value, 0, 255 - value]
+        })
+        .collect()
+}
+
+#[test]
+fn e131_parses_universe_and_channels() {
+    let packet = e131_packet(7, &[1, 2, 3, 4]);
+    assert_eq!(
+        DmxPacket::parse_e131(&packet),
+        Some(DmxPacket::Universe {
+            universe: 7,
+            channels: &[1, 2, 3, 4]
+        })
+    );
+}
+
+#[test]
+fn e131_ignores_preview_and_alternate_start_codes() {
+    let mut preview = e131_packet(1, &[1, 2, 3]);
+    preview[112] = 0x40;
+    assert_eq!(DmxPacket::parse_e131(&preview), None);
+
+    let mut text = e131_packet(1, &[1, 2, 3]);
+    text[125] = 0x17;
+    assert_eq!(DmxPacket::parse_e131(&text), None);
+}
+
+#[test]
+fn art_net_parses_port_address() {
+    // Net 1, sub-net 2, universe 3.
+    let packet = art_net_packet(0x0123, &[9, 8, 7]);
+    assert_eq!(
+        DmxPacket::parse_art_net(&packet),
+        Some(DmxPacket::Universe {
+            universe: 0x0123,
+            channels: &[9, 8, 7]
+        })
+    );
+
+    let mut poll = packet;
+    poll[8..10].copy_from_slice(&0x2000u16.to_le_bytes());
+    assert_eq!(DmxPacket::parse_art_net(&poll), None);
+}
+
+#[test]
+fn ddp_parses_offset_and_push() {
+    let packet = ddp_packet(30, &[1, 2, 3], true);
+    assert_eq!(
+        DmxPacket::parse_ddp(&packet),
+        Some(DmxPacket::Pixels {
+            offset: 30,
+            data: &[1, 2, 3],
+            push: true
+        })
+    );
+
+    let mut query = packet;
+    query[0] |= 0x08;
+    assert_eq!(DmxPacket::parse_ddp(&query), None);
+}
+
+#[test]
+fn malformed_packets_are_rejected() {
+    for length in 0..126 {
+        let packet = e131_packet(1, &[]);
+        assert_eq!(DmxPacket::parse_e131(&packet[..length]), None);
+    }
+    assert_eq!(DmxPacket::parse_art_net(b"Art-Net"), None);
+    assert_eq!(DmxPacket::parse_ddp(&[0x41, 0, 0]), None);
+    assert_eq!(
+        DmxPacket::parse_ddp(&[0x81, 0, 0, 1, 0, 0, 0, 0, 0, 0]),
+        None
+    );
+}
+
+#[test]
+fn start_address_offsets_the_first_universe() {
+    let mut mapper = DmxMapper::<2>::linear(DmxConfig {
+        universe: 3,
+        start_address: 4,
+    });
+    let channels = [9, 9, 9, 10, 20, 30, 40, 50, 60];
+    assert!(!mapper.apply(&DmxPacket::Universe {
+        universe: 2,
+        channels: &channels
+    }));
+    assert!(mapper.apply(&DmxPacket::Universe {
+        universe: 3,
+        channels: &channels
+    }));
+    assert_eq!(
+        mapper.frame(),
+        &[RGB8::new(10, 20, 30), RGB8::new(40, 50, 60)]
+    );
+}
+
+#[test]
+fn pixels_continue_in_the_next_universe() {
+    const LEN: usize = 200;
+    let mut mapper = DmxMapper::<LEN>::linear(DmxConfig::DEFAULT);
+    assert_eq!(mapper.last_universe(), 2);
+
+    let channels = gradient(LEN);
+    let (first, rest) = channels.split_at(170 * 3);
+    assert!(!mapper.apply(&DmxPacket::Universe {
+        universe: 1,
+        channels: first
+    }));
+    assert!(mapper.apply(&DmxPacket::Universe {
+        universe: 2,
+        channels: rest
+    }));
+    assert_eq!(mapper.frame()[169], RGB8::new(169, 0, 86));
+    assert_eq!(mapper.frame()[170], RGB8::new(170, 0, 85));
+    assert_eq!(mapper.frame()[LEN - 1], RGB8::new(199, 0, 56));
+}
+
+#[test]
+fn last_universe_counts_pixels_after_the_start_address() {
+    let config = DmxConfig {
+        universe: 0,
+        start_address: 511,
+    };
+    assert_eq!(DmxMapper::<1>::linear(config).last_universe(), 1);
+    assert_eq!(
+        DmxMapper::<170>::linear(DmxConfig::DEFAULT).last_universe(),
+        1
+    );
+    assert_eq!(
+        DmxMapper::<171>::linear(DmxConfig::DEFAULT).last_universe(),
+        2
+    );
+}
+
+#[test]
+fn layout_places_row_major_pixels() {
+    // Serpentine 3x2: LED0 LED3 LED4 / LED1 LED2 LED5.
+    const LED_LAYOUT: LedLayout<6, 3, 2> = LedLayout::serpentine_column_major();
+    let mut mapper = DmxMapper::new(DmxConfig::DEFAULT, &LED_LAYOUT);
+    let channels: Vec<u8> = (0..6).flat_map(|pixel| [pixel, 0, 0]).collect();
+    assert!(mapper.apply(&DmxPacket::Universe {
+        universe: 1,
+        channels: &channels
+    }));
+    let reds: Vec<u8> = mapper.frame().iter().map(|color| color.r).collect();
+    assert_eq!(reds, [0, 3, 4, 1, 2, 5]);
+}
+
+#[test]
+fn ddp_offsets_may_split_pixels() {
+    let mut mapper = DmxMapper::<3>::linear(DmxConfig::DEFAULT);
+    assert!(!mapper.apply(&DmxPacket::Pixels {
+        offset: 0,
+        data: &[1, 2, 3, 4],
+        push: false
+    }));
+    assert!(mapper.apply(&DmxPacket::Pixels {
+        offset: 4,
+        data: &[5, 6, 7, 8, 9, 10, 11],
+        push: true
+    }));
+    assert_eq!(
+        mapper.frame(),
+        &[RGB8::new(1, 2, 3), RGB8::new(4, 5, 6), RGB8::new(7, 8, 9)]
+    );
+}
+
+#[test]
+#[should_panic(expected = "DMX start address must be 1-512")]
+fn start_address_zero_is_rejected() {
+    let _ = DmxMapper::<1>::linear(DmxConfig {
+        universe: 1,
+        start_address: 0,
+    });
+}
+
+/// Send each protocol over loopback UDP, as lighting software would, and map what arrives.
+#[test]
+fn frames_arrive_over_udp() {
+    const LEN: usize = 4;
+    let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let channels = gradient(LEN);
+    let sends = [
+        (DmxProtocol::E131, e131_packet(1, &channels)),
+        (DmxProtocol::ArtNet, art_net_packet(1, &channels)),
+        (DmxProtocol::Ddp, ddp_packet(0, &channels, true)),
+    ];
+
+    for (protocol, packet) in sends {
+        sender
+            .send_to(&packet, receiver.local_addr().unwrap())
+            .unwrap();
+        let mut buffer = [0u8; 1500];
+        let (len, _from) = receiver.recv_from(&mut buffer).unwrap();
+
+        let mut mapper = DmxMapper::<LEN>::linear(DmxConfig::DEFAULT);
+        let parsed = DmxPacket::parse(protocol, &buffer[..len]).unwrap();
+        assert!(mapper.apply(&parsed), "{protocol:?} frame should be ready");
+        assert_eq!(mapper.frame()[3], RGB8::new(3, 0, 252), "{protocol:?}");
+    }
+}
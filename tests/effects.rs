@@ -0,0 +1,137 @@
+//! Host-level tests for the built-in animation effects.
+#![cfg(feature = "host")]
+
+use device_kit::animation::AnimationSource;
+use device_kit::effects::{
+    Comet, Fire, Life, MatrixRain, RainbowCycle, TheaterChase, scale, wheel,
+};
+use device_kit::led2d::Frame;
+use embassy_time::Duration;
+use smart_leds::{RGB8, colors};
+
+const FRAME_DURATION: Duration = Duration::from_millis(20);
+
+#[test]
+fn wheel_hits_primaries_and_wraps() {
+    assert_eq!(wheel(0), RGB8::new(255, 0, 0));
+    assert_eq!(wheel(85), RGB8::new(0, 255, 0));
+    assert_eq!(wheel(170), RGB8::new(0, 0, 255));
+    assert_eq!(wheel(42), RGB8::new(129, 126, 0));
+    assert_eq!(wheel(255), wheel(0));
+}
+
+#[test]
+fn scale_is_proportional() {
+    assert_eq!(scale(RGB8::new(255, 128, 10), 255), RGB8::new(255, 128, 10));
+    assert_eq!(scale(RGB8::new(255, 128, 10), 0), RGB8::new(0, 0, 0));
+    assert_eq!(scale(RGB8::new(255, 128, 10), 128), RGB8::new(128, 64, 5));
+}
+
+#[test]
+fn rainbow_spreads_hues_and_rotates() {
+    let mut rainbow = RainbowCycle::new(FRAME_DURATION);
+    let mut frame = Frame::<3, 1>::new();
+    assert_eq!(rainbow.next_frame(&mut frame), FRAME_DURATION);
+    assert_eq!(frame.0, [[wheel(0), wheel(85), wheel(170)]]);
+    rainbow.next_frame(&mut frame);
+    assert_eq!(frame.0, [[wheel(1), wheel(86), wheel(171)]]);
+}
+
+#[test]
+fn theater_chase_marches_forward() {
+    const ON: RGB8 = colors::WHITE;
+    const OFF: RGB8 = colors::BLACK;
+    let mut chase = TheaterChase::new(ON, OFF, 3, FRAME_DURATION);
+    let mut frame = Frame::<6, 1>::new();
+    chase.next_frame(&mut frame);
+    assert_eq!(frame.0, [[ON, OFF, OFF, ON, OFF, OFF]]);
+    chase.next_frame(&mut frame);
+    assert_eq!(frame.0, [[OFF, ON, OFF, OFF, ON, OFF]]);
+}
+
+#[test]
+fn reversed_theater_chase_marches_backward() {
+    const ON: RGB8 = colors::WHITE;
+    const OFF: RGB8 = colors::BLACK;
+    let mut chase = TheaterChase::new(ON, OFF, 3, FRAME_DURATION).reversed();
+    let mut frame = Frame::<6, 1>::new();
+    chase.next_frame(&mut frame);
+    assert_eq!(frame.0, [[ON, OFF, OFF, ON, OFF, OFF]]);
+    chase.next_frame(&mut frame);
+    assert_eq!(frame.0, [[OFF, OFF, ON, OFF, OFF, ON]]);
+}
+
+#[test]
+fn comet_tail_fades_and_wraps() {
+    let mut comet = Comet::new(RGB8::new(255, 255, 255), 1, FRAME_DURATION);
+    let mut frame = Frame::<4, 1>::new();
+    comet.next_frame(&mut frame);
+    // The head is at 0; the tail wraps to the last pixel.
+    assert_eq!(
+        frame.0,
+        [[
+            RGB8::new(255, 255, 255),
+            RGB8::new(0, 0, 0),
+            RGB8::new(0, 0, 0),
+            RGB8::new(127, 127, 127),
+        ]]
+    );
+    comet.next_frame(&mut frame);
+    assert_eq!(frame[0][1], RGB8::new(255, 255, 255));
+    assert_eq!(frame[0][0], RGB8::new(127, 127, 127));
+    assert_eq!(frame[0][3], RGB8::new(0, 0, 0));
+}
+
+#[test]
+fn comet_renders_an_empty_frame() {
+    let mut comet = Comet::new(RGB8::new(255, 255, 255), 3, FRAME_DURATION);
+    let mut frame = Frame::<0, 1>::new();
+    assert_eq!(comet.next_frame(&mut frame), FRAME_DURATION);
+    assert_eq!(comet.next_frame(&mut frame), FRAME_DURATION);
+}
+
+#[test]
+fn fire_and_rain_render_empty_frames() {
+    let mut fire = Fire::new(55, 120, FRAME_DURATION);
+    let mut rain = MatrixRain::<4>::new(RGB8::new(0, 255, 0), 255, FRAME_DURATION);
+    let mut no_rows = Frame::<4, 0>::new();
+    assert_eq!(fire.next_frame(&mut no_rows), FRAME_DURATION);
+    assert_eq!(rain.next_frame(&mut no_rows), FRAME_DURATION);
+    let mut fire = Fire::<0, 4>::new(55, 120, FRAME_DURATION);
+    assert_eq!(fire.next_frame(&mut Frame::<0, 4>::new()), FRAME_DURATION);
+}
+
+#[test]
+fn life_blinker_oscillates() {
+    const ALIVE: RGB8 = colors::GREEN;
+    const DEAD: RGB8 = colors::BLACK;
+    let mut life = Life::new(ALIVE, FRAME_DURATION);
+    let mut frame = Frame::<5, 5>::new();
+    for column in 1..4 {
+        frame[2][column] = ALIVE;
+    }
+    let horizontal = frame;
+
+    life.next_frame(&mut frame);
+    for row in 0..5 {
+        for column in 0..5 {
+            let expected = if column == 2 && (1..4).contains(&row) {
+                ALIVE
+            } else {
+                DEAD
+            };
+            assert_eq!(frame[row][column], expected, "row {row}, column {column}");
+        }
+    }
+
+    life.next_frame(&mut frame);
+    assert_eq!(frame.0, horizontal.0);
+}
+
+#[test]
+fn life_reseeds_an_empty_board() {
+    let mut life = Life::new(colors::RED, FRAME_DURATION).with_seed(7);
+    let mut frame = Frame::<8, 8>::new();
+    life.next_frame(&mut frame);
+    assert!(frame.iter().flatten().any(|pixel| *pixel == colors::RED));
+}
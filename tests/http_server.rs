@@ -0,0 +1,310 @@
+//! Host-level tests for HTTP request parsing, routing, JSON bodies, and responses.
+#![cfg(feature = "host")]
+
+use core::convert::Infallible;
+
+use device_kit::http_server::json::{Object, ObjectWriter, Value};
+use device_kit::http_server::{
+    Handler, HttpError, MAX_REQUEST_LEN, Method, Request, Response, Status, respond,
+};
+use embassy_futures::block_on;
+use embedded_io_async::{ErrorType, Write};
+
+/// Collects everything written, like a socket that never fails.
+#[derive(Default)]
+struct Sink(Vec<u8>);
+
+impl ErrorType for Sink {
+    type Error = Infallible;
+}
+
+impl Write for Sink {
+    async fn write(&mut self, buf: &[u8]) -> Result<usize, Infallible> {
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+}
+
+fn parse(text: &str) -> Request<'_> {
+    Request::parse(text.as_bytes()).unwrap().unwrap()
+}
+
+#[test]
+fn request_line_and_headers_are_parsed() {
+    let request = parse(
+        "PUT /servo/2?angle=90&fast HTTP/1.1\r\nHost: pico\r\nContent-Type:  text/plain \r\nContent-Length: 5\r\n\r\nhello",
+    );
+    assert_eq!(request.method, Method::Put);
+    assert_eq!(request.path, "/servo/2");
+    assert_eq!(request.body, b"hello");
+    assert_eq!(request.header("content-TYPE"), Some("text/plain"));
+    assert_eq!(request.header("accept"), None);
+    assert_eq!(request.query.get("angle").unwrap().parse::<u16>(), Some(90));
+    assert_eq!(request.query.get("fast").unwrap().raw(), "");
+    assert_eq!(request.query.get("slow"), None);
+}
+
+#[test]
+fn incomplete_requests_wait_for_more() {
+    let full = "POST /text HTTP/1.1\r\nContent-Length: 4\r\n\r\nabcd";
+    for len in 0..full.len() {
+        assert_eq!(
+            Request::parse(&full.as_bytes()[..len]),
+            Ok(None),
+            "{len} bytes"
+        );
+    }
+    assert!(Request::parse(full.as_bytes()).unwrap().is_some());
+    // Bytes beyond Content-Length belong to no one and are ignored.
+    let extra = format!("{full}efgh");
+    assert_eq!(parse(&extra).body, b"abcd");
+}
+
+#[test]
+fn bad_requests_are_errors() {
+    let cases = [
+        ("GET /\r\n\r\n", HttpError::Malformed),
+        ("GET / HTTP/1.1 extra\r\n\r\n", HttpError::Malformed),
+        ("GET / SPDY/3\r\n\r\n", HttpError::Malformed),
+        ("GET index.html HTTP/1.1\r\n\r\n", HttpError::Malformed),
+        ("GET / HTTP/1.1\r\nNo colon\r\n\r\n", HttpError::Malformed),
+        (
+            "POST / HTTP/1.1\r\nContent-Length: lots\r\n\r\n",
+            HttpError::Malformed,
+        ),
+        ("BREW /pot HTTP/1.1\r\n\r\n", HttpError::Unsupported),
+        (
+            "POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n",
+            HttpError::Unsupported,
+        ),
+    ];
+    for (text, expected) in cases {
+        assert_eq!(Request::parse(text.as_bytes()), Err(expected), "{text:?}");
+    }
+}
+
+#[test]
+fn oversized_bodies_are_rejected_before_they_arrive() {
+    for len in [
+        "4294967295",
+        "18446744073709551615",
+        &MAX_REQUEST_LEN.to_string(),
+    ] {
+        let text = format!("POST / HTTP/1.1\r\nContent-Length: {len}\r\n\r\nabc");
+        assert_eq!(
+            Request::parse(text.as_bytes()),
+            Err(HttpError::TooLarge),
+            "{len}"
+        );
+    }
+    // A body that fits still waits for the rest.
+    let text = "POST / HTTP/1.1\r\nContent-Length: 1000\r\n\r\nabc";
+    assert_eq!(Request::parse(text.as_bytes()), Ok(None));
+}
+
+#[test]
+fn form_values_are_decoded() {
+    let request = parse(
+        "POST / HTTP/1.1\r\nContent-Length: 62\r\n\r\nssid=My+Home%21&password=p%25ss%3D&note=%zz&emoji=%F0%9F%98%80",
+    );
+    let form = request.form();
+    assert_eq!(
+        form.get("ssid").unwrap().decode::<32>().unwrap(),
+        "My Home!"
+    );
+    assert_eq!(
+        form.get("password").unwrap().decode::<32>().unwrap(),
+        "p%ss="
+    );
+    // A stray percent sign stands for itself.
+    assert_eq!(form.get("note").unwrap().decode::<32>().unwrap(), "%zz");
+    assert_eq!(form.get("emoji").unwrap().decode::<4>().unwrap(), "😀");
+    assert_eq!(
+        form.get("ssid").unwrap().decode::<4>(),
+        Err(HttpError::TooLarge)
+    );
+    let pairs: Vec<_> = form.iter().map(|(key, _value)| key.raw()).collect();
+    assert_eq!(pairs, ["ssid", "password", "note", "emoji"]);
+
+    let request = parse("GET /?bad=%FF HTTP/1.1\r\n\r\n");
+    assert_eq!(
+        request.query.get("bad").unwrap().decode::<4>(),
+        Err(HttpError::Malformed)
+    );
+}
+
+#[test]
+fn routes_match_whole_paths_and_capture_segments() {
+    let request = parse("POST /servo/left/angle HTTP/1.1\r\n\r\n");
+    let params = request.route(Method::Post, "/servo/{name}/angle").unwrap();
+    assert_eq!(params.get("name").unwrap().raw(), "left");
+    assert_eq!(params.get("angle"), None);
+    assert!(request.route(Method::Get, "/servo/{name}/angle").is_none());
+    assert!(request.route(Method::Post, "/servo/{name}").is_none());
+    assert!(
+        request
+            .route(Method::Post, "/servo/{name}/angle/{more}")
+            .is_none()
+    );
+    assert!(request.route(Method::Post, "/led/{name}/angle").is_none());
+
+    let root = parse("GET / HTTP/1.1\r\n\r\n");
+    assert!(root.route(Method::Get, "/").is_some());
+    assert!(root.route(Method::Get, "/{page}").is_none());
+}
+
+#[test]
+fn json_members_are_found() {
+    let object = Object::parse(
+        r#" { "on": true, "brightness": 128, "level": -2.5e1, "name": "Porch \"A\" é😀",
+             "color": {"r": 255, "g": [1, [2]], "b": null}, "on": false } "#,
+    )
+    .unwrap();
+    // The first of repeated keys wins.
+    assert_eq!(object.get("on").unwrap().as_bool(), Some(true));
+    assert_eq!(object.get("brightness").unwrap().as_u8(), Some(128));
+    assert_eq!(object.get("brightness").unwrap().as_i32(), Some(128));
+    assert_eq!(object.get("level").unwrap().as_f32(), Some(-25.0));
+    assert_eq!(object.get("level").unwrap().as_i32(), None);
+    assert_eq!(
+        object
+            .get("name")
+            .unwrap()
+            .as_str()
+            .unwrap()
+            .decode::<32>()
+            .unwrap(),
+        "Porch \"A\" é😀"
+    );
+    let color = object.get("color").unwrap().as_object().unwrap();
+    assert_eq!(color.get("r").unwrap().as_u8(), Some(255));
+    assert_eq!(color.get("g"), Some(Value::Array("[1, [2]]")));
+    assert_eq!(color.get("b"), Some(Value::Null));
+    assert_eq!(object.get("missing"), None);
+    assert_eq!(object.members().count(), 6);
+}
+
+#[test]
+fn malformed_json_is_rejected() {
+    let deep = format!("{{\"a\":{}1{}}}", "[".repeat(16), "]".repeat(16));
+    let cases = [
+        "",
+        "[]",
+        "{",
+        r#"{"a"}"#,
+        r#"{"a":1,}"#,
+        r#"{"a":01}"#,
+        r#"{"a":1.}"#,
+        r#"{"a":-}"#,
+        r#"{"a":tru}"#,
+        r#"{"a":"\x"}"#,
+        r#"{"a":"\ud83d"}"#,
+        "{\"a\":\"tab\there\"}",
+        r#"{"a":1} {}"#,
+        deep.as_str(),
+    ];
+    for text in cases {
+        assert_eq!(Object::parse(text), Err(HttpError::Malformed), "{text}");
+    }
+    let shallow = format!("{{\"a\":{}1{}}}", "[".repeat(15), "]".repeat(15));
+    assert!(Object::parse(&shallow).is_ok());
+}
+
+#[test]
+fn json_objects_are_written_and_read_back() {
+    let mut text = String::new();
+    let mut json = ObjectWriter::start(&mut text).unwrap();
+    json.string("name", format_args!("porch \"{}\"", 'A')).unwrap();
+    json.number("brightness", 128).unwrap();
+    json.null("color").unwrap();
+    json.strings("effects", &["rainbow", "back\\slash"]).unwrap();
+    json.object("device", |device| device.number("id", -1)).unwrap();
+    json.end().unwrap();
+    assert_eq!(
+        text,
+        r#"{"name":"porch \"A\"","brightness":128,"color":null,"effects":["rainbow","back\\slash"],"device":{"id":-1}}"#
+    );
+
+    let object = Object::parse(&text).unwrap();
+    let name = object.get("name").unwrap().as_str().unwrap();
+    assert_eq!(name.decode::<16>().unwrap(), "porch \"A\"");
+    assert_eq!(object.get("brightness").unwrap().as_u8(), Some(128));
+    assert_eq!(object.get("color"), Some(Value::Null));
+}
+
+struct Api;
+
+impl Handler for Api {
+    async fn handle<W: Write>(
+        &self,
+        request: &Request<'_>,
+        response: &mut Response<'_, W>,
+    ) -> device_kit::Result<()> {
+        if let Some(params) = request.route(Method::Post, "/echo/{word}") {
+            let word = params.get("word").unwrap().decode::<16>().unwrap();
+            let repeat = request
+                .json()
+                .ok()
+                .and_then(|json| json.get("repeat")?.as_u8())
+                .unwrap_or(1);
+            let body = word.repeat(usize::from(repeat));
+            response
+                .send(Status::Created, "text/plain", body.as_bytes())
+                .await;
+        } else if request.route(Method::Get, "/old").is_some() {
+            response.redirect("/new").await;
+            // A second answer is dropped.
+            response.send(Status::Ok, "text/plain", b"ignored").await;
+        }
+        Ok(())
+    }
+}
+
+fn serve(text: &str) -> String {
+    let mut sink = Sink::default();
+    block_on(respond(&Api, text.as_bytes(), &mut sink));
+    String::from_utf8(sink.0).unwrap()
+}
+
+#[test]
+fn handler_answers_are_written() {
+    assert_eq!(
+        serve(
+            "POST /echo/hi HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: 12\r\n\r\n{\"repeat\":3}"
+        ),
+        "HTTP/1.1 201 Created\r\nContent-Type: text/plain\r\nContent-Length: 6\r\nConnection: close\r\n\r\nhihihi"
+    );
+    assert_eq!(
+        serve("GET /old HTTP/1.1\r\n\r\n"),
+        "HTTP/1.1 303 See Other\r\nContent-Type: text/plain\r\nContent-Length: 0\r\nLocation: /new\r\nConnection: close\r\n\r\n"
+    );
+}
+
+#[test]
+fn unanswered_and_bad_requests_get_error_statuses() {
+    let cases = [
+        ("GET /missing HTTP/1.1\r\n\r\n", "404 Not Found"),
+        ("GET /echo/hi HTTP/1.1\r\n\r\n", "404 Not Found"),
+        ("GET nowhere HTTP/1.1\r\n\r\n", "400 Bad Request"),
+        ("BREW / HTTP/1.1\r\n\r\n", "501 Not Implemented"),
+        (
+            "POST /echo/hi HTTP/1.1\r\nContent-Length: 99\r\n\r\ncut short",
+            "413 Payload Too Large",
+        ),
+    ];
+    for (text, expected) in cases {
+        let response = serve(text);
+        let status_line = response.lines().next().unwrap();
+        assert_eq!(status_line, format!("HTTP/1.1 {expected}"), "{text:?}");
+        let reason = expected.split_once(' ').unwrap().1;
+        assert!(response.ends_with(&format!("\r\n\r\n{reason}")));
+    }
+}
+
+#[test]
+fn head_requests_get_headers_without_a_body() {
+    assert_eq!(
+        serve("HEAD /missing HTTP/1.1\r\n\r\n"),
+        "HTTP/1.1 404 Not Found\r\nContent-Type: text/plain\r\nContent-Length: 9\r\nConnection: close\r\n\r\n"
+    );
+}
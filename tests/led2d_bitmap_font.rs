@@ -0,0 +1,139 @@
+//! Host-level tests for proportional `led2d::bitmap_font` fonts.
+#![cfg(feature = "host")]
+
+use device_kit::led2d::bitmap_font::BitmapFont;
+use device_kit::led2d::scroll::{ScrollDirection, ScrollMode, ScrollOptions, TextScroll};
+use device_kit::led2d::{Frame, TextFont, render_text};
+use smart_leds::{RGB8, colors};
+
+// 3 pixels high, 1 column of spacing, 3 glyphs, 1 kerning pair.
+static FONT: BitmapFont = BitmapFont::new(&[
+    b'D',
+    b'K',
+    b'F',
+    b'1',
+    3,
+    1,
+    3,
+    0,
+    1,
+    0, //
+    b'?',
+    0,
+    0,
+    0,
+    2,
+    3,
+    0, // '?' is 2 wide, bitmap at 3
+    b'T',
+    0,
+    0,
+    0,
+    3,
+    0,
+    0, // 'T' is 3 wide, bitmap at 0
+    b'i',
+    0,
+    0,
+    0,
+    1,
+    2,
+    0, // 'i' is 1 wide, bitmap at 2
+    1,
+    0,
+    2,
+    0,
+    0xFF, // 'T' then 'i': one column closer
+    0b1110_1001,
+    0b0000_0000, // 'T': ###, .#., .#.
+    0b1010_0000, // 'i': #, ., #
+    0b1101_1000, // '?': ##, .#, #.
+]);
+
+fn lit_rows<const W: usize, const H: usize>(frame: &Frame<W, H>) -> Vec<String> {
+    frame
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|pixel| if *pixel == colors::BLACK { '.' } else { '#' })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn header_matches_expected() {
+    assert_eq!(FONT.height(), 3);
+    assert_eq!(FONT.spacing(), 1);
+    assert_eq!(FONT.glyph_count(), 3);
+    assert_eq!(FONT.kerning_count(), 1);
+}
+
+#[test]
+fn glyphs_have_their_own_widths() {
+    let t = FONT.glyph('T').expect("font has T");
+    let i = FONT.glyph('i').expect("font has i");
+    assert_eq!((t.width(), i.width()), (3, 1));
+    assert!(t.pixel(1, 2) && !t.pixel(0, 2));
+    assert!(i.pixel(0, 0) && !i.pixel(0, 1));
+}
+
+#[test]
+fn missing_characters_fall_back_to_question_mark() {
+    assert_eq!(FONT.glyph('x').expect("falls back").width(), 2);
+}
+
+#[test]
+fn kerning_applies_only_to_listed_pairs() {
+    assert_eq!(FONT.kerning('T', 'i'), -1);
+    assert_eq!(FONT.kerning('i', 'T'), 0);
+    // 3 + 1 spacing - 1 kerning, then 1
+    assert_eq!(FONT.line_width("Ti"), 4);
+    // 1 + 1 spacing, then 3
+    assert_eq!(FONT.line_width("iT"), 5);
+}
+
+#[test]
+fn text_font_line_width_excludes_trailing_spacing() {
+    // 3 + 1 spacing - 1 kerning, then 1 and no spacing after it.
+    assert_eq!(TextFont::from(&FONT).line_width("Ti"), 4);
+    assert_eq!(TextFont::from(&FONT).line_width(""), 0);
+}
+
+#[test]
+fn render_text_draws_proportional_glyphs_with_kerning() {
+    let mut frame = Frame::<8, 3>::new();
+    render_text(&mut frame, TextFont::from(&FONT), "Tii", &[]).expect("render must succeed");
+    assert_eq!(lit_rows(&frame), ["####.#..", ".#......", ".#.#.#.."]);
+}
+
+#[test]
+fn render_text_clips_at_width_and_cycles_colors() {
+    let mut frame = Frame::<5, 3>::new();
+    let colors = [colors::RED, colors::GREEN];
+    render_text(&mut frame, TextFont::from(&FONT), "iTi", &colors).expect("render must succeed");
+    // The last 'i' would start at column 5, past the edge.
+    assert_eq!(lit_rows(&frame), ["#.###", "...#.", "#..#."]);
+    assert_eq!(frame[0][0], colors[0]);
+    assert_eq!(frame[0][2], colors[1]);
+}
+
+#[test]
+fn scroll_uses_proportional_widths() {
+    let options = ScrollOptions {
+        direction: ScrollDirection::Left,
+        pixels_per_second: 10,
+        mode: ScrollMode::Once,
+    };
+    let mut scroll = TextScroll::new("Ti", &[RGB8::new(0, 0, 255)], &FONT, options);
+    assert_eq!(scroll.text_size().width, 4);
+    let mut frame = Frame::<4, 3>::new();
+    let mut frame_count = 0;
+    while scroll.render_next(&mut frame).is_some() {
+        frame_count += 1;
+        if frame_count == 4 {
+            assert_eq!(lit_rows(&frame), ["####", ".#..", ".#.#"]);
+        }
+    }
+    assert_eq!(frame_count, 4 + 4);
+}
@@ -0,0 +1,181 @@
+//! Host-level tests for `led2d::compose` blending, layers, sprites, and transitions.
+#![cfg(feature = "host")]
+
+use device_kit::led2d::Frame;
+use device_kit::led2d::compose::{
+    Alpha, BlendMode, Direction, Layer, Transition, blend, composite, transition,
+};
+use embassy_time::Duration;
+use embedded_graphics::prelude::Point;
+use smart_leds::{RGB8, colors};
+
+/// A 4x3 frame whose red channel is the pixel's row-major index.
+fn gradient() -> Frame<4, 3> {
+    let mut frame = Frame::new();
+    for (row_index, row) in frame.iter_mut().enumerate() {
+        for (column_index, pixel) in row.iter_mut().enumerate() {
+            *pixel = RGB8::new((row_index * 4 + column_index) as u8, 0, 0);
+        }
+    }
+    frame
+}
+
+const TARGET: RGB8 = RGB8::new(99, 0, 0);
+
+#[test]
+fn blend_modes_combine_channels() {
+    let bottom = RGB8::new(200, 100, 0);
+    let top = RGB8::new(100, 255, 10);
+    assert_eq!(BlendMode::Normal.apply(bottom, top), top);
+    assert_eq!(BlendMode::Add.apply(bottom, top), RGB8::new(255, 255, 10));
+    assert_eq!(
+        BlendMode::Multiply.apply(bottom, top),
+        RGB8::new(78, 100, 0)
+    );
+    assert_eq!(
+        BlendMode::Screen.apply(bottom, top),
+        RGB8::new(222, 255, 10)
+    );
+}
+
+#[test]
+fn blend_alpha_mixes_with_bottom() {
+    let bottom = RGB8::new(200, 100, 0);
+    let top = RGB8::new(100, 255, 10);
+    assert_eq!(blend(bottom, top, BlendMode::Normal, 0), bottom);
+    assert_eq!(blend(bottom, top, BlendMode::Normal, 255), top);
+    assert_eq!(
+        blend(colors::BLACK, colors::WHITE, BlendMode::Normal, 128),
+        RGB8::new(128, 128, 128)
+    );
+}
+
+#[test]
+fn composite_applies_masks_and_black_transparency() {
+    let background = Frame::<4, 3>::filled(RGB8::new(10, 0, 0));
+    let top = gradient();
+    let mask = [[255; 4], [0; 4], [128; 4]];
+    let masked = composite(&[
+        Layer::new(&background),
+        Layer::new(&top).with_alpha(Alpha::Mask(&mask)),
+    ]);
+    assert_eq!(masked[0][2], RGB8::new(2, 0, 0));
+    assert_eq!(masked[1][2], RGB8::new(10, 0, 0));
+    assert_eq!(masked[2][2], RGB8::new(10, 0, 0));
+
+    let overlaid = composite(&[
+        Layer::new(&background),
+        Layer::new(&top).with_alpha(Alpha::BlackIsTransparent),
+    ]);
+    assert_eq!(overlaid[0][0], RGB8::new(10, 0, 0));
+    assert_eq!(overlaid[0][1], RGB8::new(1, 0, 0));
+
+    let added = composite(&[
+        Layer::new(&background),
+        Layer::new(&top).with_mode(BlendMode::Add),
+    ]);
+    assert_eq!(added[2][3], RGB8::new(21, 0, 0));
+}
+
+#[test]
+fn blit_clips_and_skips_transparent_pixels() {
+    let mut frame = Frame::<4, 3>::new();
+    let sprite = Frame::<2, 2>::from([[colors::RED, colors::BLACK], [colors::GREEN, colors::BLUE]]);
+    // Only the sprite's bottom-left pixel lands inside the frame.
+    frame.blit(&sprite, Point::new(3, -1), Some(colors::BLACK));
+    assert_eq!(frame[0][3], colors::GREEN);
+    assert_eq!(
+        frame
+            .iter()
+            .flatten()
+            .filter(|pixel| **pixel != colors::BLACK)
+            .count(),
+        1
+    );
+
+    let mut frame = Frame::<4, 3>::filled(colors::WHITE);
+    frame.blit(&sprite, Point::new(1, 1), None);
+    assert_eq!(frame[1][2], colors::BLACK);
+    assert_eq!(frame[2][2], colors::BLUE);
+}
+
+#[test]
+fn every_transition_ends_on_target() {
+    let from = gradient();
+    let to = Frame::<4, 3>::filled(TARGET);
+    let directions = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
+    ];
+    let kinds = directions
+        .iter()
+        .flat_map(|direction| [Transition::Wipe(*direction), Transition::Slide(*direction)])
+        .chain([Transition::Crossfade, Transition::Dissolve { seed: 7 }]);
+    for kind in kinds {
+        let frames = transition(&from, &to, kind, 4, Duration::from_millis(40));
+        assert_eq!(frames.len(), 4);
+        let frames: Vec<_> = frames.collect();
+        assert!(
+            frames
+                .iter()
+                .all(|(_, duration)| *duration == Duration::from_millis(10))
+        );
+        assert_eq!(frames[3].0.0, to.0, "{kind:?}");
+        assert_eq!(kind.render(&from, &to, 0, 4).0, from.0, "{kind:?}");
+    }
+}
+
+#[test]
+fn slide_moves_both_frames() {
+    let from = gradient();
+    let to = Frame::<4, 3>::filled(TARGET);
+    let left = Transition::Slide(Direction::Left).render(&from, &to, 1, 4);
+    assert_eq!(left[0], [from[0][1], from[0][2], from[0][3], TARGET]);
+    let right = Transition::Slide(Direction::Right).render(&from, &to, 1, 4);
+    assert_eq!(right[0], [TARGET, from[0][0], from[0][1], from[0][2]]);
+    let up = Transition::Slide(Direction::Up).render(&from, &to, 1, 3);
+    assert_eq!(up[0], from[1]);
+    assert_eq!(up[2], [TARGET; 4]);
+}
+
+#[test]
+fn wipe_reveals_without_moving() {
+    let from = gradient();
+    let to = Frame::<4, 3>::filled(TARGET);
+    let wipe = Transition::Wipe(Direction::Left).render(&from, &to, 1, 4);
+    assert_eq!(wipe[0], [from[0][0], from[0][1], from[0][2], TARGET]);
+    let wipe = Transition::Wipe(Direction::Down).render(&from, &to, 1, 3);
+    assert_eq!(wipe[0], [TARGET; 4]);
+    assert_eq!(wipe[1], from[1]);
+}
+
+#[test]
+fn dissolve_switches_about_half_halfway() {
+    let from = Frame::<16, 16>::new();
+    let to = Frame::<16, 16>::filled(colors::WHITE);
+    let halfway = Transition::Dissolve { seed: 7 }.render(&from, &to, 1, 2);
+    let switched = halfway
+        .iter()
+        .flatten()
+        .filter(|pixel| **pixel == colors::WHITE)
+        .count();
+    assert!((90..166).contains(&switched), "switched {switched} pixels");
+
+    let other_seed = Transition::Dissolve { seed: 8 }.render(&from, &to, 1, 2);
+    assert_ne!(halfway.0, other_seed.0);
+}
+
+#[test]
+#[should_panic(expected = "transition steps must be positive")]
+fn zero_steps_panics() {
+    let frame = Frame::<4, 3>::new();
+    let _ = transition(
+        &frame,
+        &frame,
+        Transition::Crossfade,
+        0,
+        Duration::from_millis(40),
+    );
+}
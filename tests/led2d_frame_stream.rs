@@ -0,0 +1,236 @@
+//! Host-level tests for the `led2d::frame_stream` packet format and receiver.
+#![cfg(feature = "host")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, UdpSocket};
+
+use device_kit::led2d::Frame;
+use device_kit::led2d::frame_stream::{
+    Encoding, FrameDecoder, FrameReceiver, HEADER_LEN, MAGIC, StreamError, StreamHeader,
+};
+use smart_leds::RGB8;
+
+const RED: RGB8 = RGB8::new(255, 0, 0);
+const BLUE: RGB8 = RGB8::new(0, 0, 255);
+
+fn packet(sequence: u32, width: u8, height: u8, encoding: Encoding, payload: &[u8]) -> Vec<u8> {
+    let mut packet = MAGIC.to_vec();
+    packet.extend_from_slice(&sequence.to_le_bytes());
+    packet.extend_from_slice(&[width, height, encoding as u8, 0]);
+    packet.extend_from_slice(&u16::try_from(payload.len()).unwrap().to_le_bytes());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// A 3x2 frame: red top row, blue bottom row, raw.
+fn raw_packet(sequence: u32) -> Vec<u8> {
+    let payload: Vec<u8> = [RED, RED, RED, BLUE, BLUE, BLUE]
+        .iter()
+        .flat_map(|color| [color.r, color.g, color.b])
+        .collect();
+    packet(sequence, 3, 2, Encoding::Raw, &payload)
+}
+
+fn red_over_blue() -> Frame<3, 2> {
+    Frame([[RED; 3], [BLUE; 3]])
+}
+
+#[test]
+fn header_round_trips_fields() {
+    let packet = packet(0x0102_0304, 12, 8, Encoding::RunLength, &[0; 40]);
+    let header = StreamHeader::parse(packet[..HEADER_LEN].try_into().unwrap()).unwrap();
+    assert_eq!(
+        header,
+        StreamHeader {
+            sequence: 0x0102_0304,
+            width: 12,
+            height: 8,
+            encoding: Encoding::RunLength,
+            payload_len: 40,
+        }
+    );
+}
+
+#[test]
+fn raw_and_run_length_decode_the_same_frame() {
+    let mut raw = FrameReceiver::<3, 2>::new();
+    let raw_frame = *raw.receive_packet(&raw_packet(1)).unwrap().unwrap();
+
+    let mut run_length = FrameReceiver::<3, 2>::new();
+    let compressed = packet(1, 3, 2, Encoding::RunLength, &[3, 255, 0, 0, 3, 0, 0, 255]);
+    let run_length_frame = *run_length.receive_packet(&compressed).unwrap().unwrap();
+
+    assert_eq!(raw_frame.0, red_over_blue().0);
+    assert_eq!(run_length_frame.0, red_over_blue().0);
+}
+
+#[test]
+fn older_and_repeated_frames_are_dropped() {
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    assert!(receiver.receive_packet(&raw_packet(10)).unwrap().is_some());
+    assert!(receiver.receive_packet(&raw_packet(10)).unwrap().is_none());
+    assert!(receiver.receive_packet(&raw_packet(9)).unwrap().is_none());
+    assert!(receiver.receive_packet(&raw_packet(11)).unwrap().is_some());
+}
+
+#[test]
+fn sequence_numbers_wrap() {
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    assert!(
+        receiver
+            .receive_packet(&raw_packet(u32::MAX))
+            .unwrap()
+            .is_some()
+    );
+    assert!(receiver.receive_packet(&raw_packet(0)).unwrap().is_some());
+    assert!(
+        receiver
+            .receive_packet(&raw_packet(u32::MAX))
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn reset_accepts_a_restarted_sender() {
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    assert!(receiver.receive_packet(&raw_packet(500)).unwrap().is_some());
+    assert!(receiver.receive_packet(&raw_packet(0)).unwrap().is_none());
+    receiver.reset();
+    assert!(receiver.receive_packet(&raw_packet(0)).unwrap().is_some());
+}
+
+#[test]
+fn rejected_packets_leave_the_frame_unchanged() {
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    receiver.receive_packet(&raw_packet(1)).unwrap();
+
+    let mut bad_magic = raw_packet(2);
+    bad_magic[0] = b'X';
+    let cases = [
+        (bad_magic, StreamError::BadMagic),
+        (
+            packet(2, 3, 2, Encoding::RunLength, &[7, 0, 0, 0]),
+            StreamError::BadPixelData,
+        ),
+        (
+            packet(2, 3, 2, Encoding::RunLength, &[0, 0, 0, 0]),
+            StreamError::BadPixelData,
+        ),
+        (
+            packet(2, 3, 2, Encoding::RunLength, &[5, 0, 0, 0]),
+            StreamError::BadPixelData,
+        ),
+        (
+            packet(2, 3, 2, Encoding::Raw, &[0; 17]),
+            StreamError::BadPixelData,
+        ),
+        (
+            packet(2, 2, 3, Encoding::Raw, &[0; 18]),
+            StreamError::SizeMismatch {
+                width: 2,
+                height: 3,
+                expected_width: 3,
+                expected_height: 2,
+            },
+        ),
+        (raw_packet(2)[..20].to_vec(), StreamError::LengthMismatch),
+    ];
+    for (packet, expected) in cases {
+        assert_eq!(receiver.receive_packet(&packet).unwrap_err(), expected);
+        assert_eq!(receiver.frame().0, red_over_blue().0);
+    }
+
+    let mut unknown_encoding = raw_packet(2);
+    unknown_encoding[10] = 9;
+    assert_eq!(
+        receiver.receive_packet(&unknown_encoding).unwrap_err(),
+        StreamError::UnknownEncoding(9)
+    );
+}
+
+#[test]
+fn payload_may_arrive_in_pieces() {
+    let packet = packet(1, 3, 2, Encoding::RunLength, &[2, 255, 0, 0, 4, 0, 0, 255]);
+    let header = StreamHeader::parse(packet[..HEADER_LEN].try_into().unwrap()).unwrap();
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    receiver.begin(&header).unwrap();
+    for byte in &packet[HEADER_LEN..] {
+        receiver.payload(std::slice::from_ref(byte)).unwrap();
+    }
+    let frame = receiver.finish().unwrap().unwrap();
+    assert_eq!(frame.0, [[RED, RED, BLUE], [BLUE; 3]]);
+}
+
+#[test]
+fn finish_requires_the_whole_payload() {
+    let packet = raw_packet(1);
+    let header = StreamHeader::parse(packet[..HEADER_LEN].try_into().unwrap()).unwrap();
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    receiver.begin(&header).unwrap();
+    receiver
+        .payload(&packet[HEADER_LEN..HEADER_LEN + 4])
+        .unwrap();
+    assert_eq!(receiver.finish().unwrap_err(), StreamError::LengthMismatch);
+}
+
+#[test]
+fn udp_packets_do_not_disturb_a_tcp_frame_in_progress() {
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    let mut tcp_decoder = FrameDecoder::<3, 2>::new();
+    let tcp_packet = packet(1, 3, 2, Encoding::RunLength, &[2, 255, 0, 0, 4, 0, 0, 255]);
+    let header = StreamHeader::parse(tcp_packet[..HEADER_LEN].try_into().unwrap()).unwrap();
+    let (first_half, second_half) = tcp_packet[HEADER_LEN..].split_at(4);
+
+    tcp_decoder.begin(&header).unwrap();
+    tcp_decoder.payload(first_half).unwrap();
+    // A UDP frame and a lost-sender reset land while the TCP frame is half read.
+    assert!(receiver.receive_packet(&raw_packet(5)).unwrap().is_some());
+    receiver.reset();
+    tcp_decoder.payload(second_half).unwrap();
+
+    let (sequence, frame) = tcp_decoder.finish().unwrap();
+    assert_eq!(sequence, 1);
+    let shown = receiver.accept(sequence, frame).unwrap();
+    assert_eq!(shown.0, [[RED, RED, BLUE], [BLUE; 3]]);
+    // The shared sequence number still drops stale frames from either transport.
+    assert!(receiver.receive_packet(&raw_packet(1)).unwrap().is_none());
+}
+
+/// Stream frames over loopback UDP and TCP, as `cargo xtask stream-frames` would.
+#[test]
+fn frames_arrive_over_udp_and_tcp() {
+    let receiver_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sender = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let mut receiver = FrameReceiver::<3, 2>::new();
+    for sequence in [1, 2] {
+        sender
+            .send_to(&raw_packet(sequence), receiver_socket.local_addr().unwrap())
+            .unwrap();
+        let mut datagram = [0u8; 1472];
+        let (len, _from) = receiver_socket.recv_from(&mut datagram).unwrap();
+        let frame = receiver.receive_packet(&datagram[..len]).unwrap().unwrap();
+        assert_eq!(frame.0, red_over_blue().0);
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let sending = std::thread::spawn(move || {
+        let mut stream = TcpStream::connect(address).unwrap();
+        for sequence in [1, 2, 3] {
+            stream.write_all(&raw_packet(sequence)).unwrap();
+        }
+    });
+    let (mut stream, _from) = listener.accept().unwrap();
+    let mut bytes = Vec::new();
+    stream.read_to_end(&mut bytes).unwrap();
+    sending.join().unwrap();
+
+    receiver.reset();
+    let mut frame_count = 0;
+    for packet in bytes.chunks(raw_packet(0).len()) {
+        assert!(receiver.receive_packet(packet).unwrap().is_some());
+        frame_count += 1;
+    }
+    assert_eq!(frame_count, 3);
+}
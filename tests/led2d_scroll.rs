@@ -0,0 +1,181 @@
+//! Host-level tests for `led2d::scroll` marquee text.
+#![cfg(feature = "host")]
+
+use device_kit::led2d::scroll::{ScrollDirection, ScrollMode, ScrollOptions, TextScroll};
+use device_kit::led2d::{Frame, Led2dFont, render_text_to_frame};
+use embassy_time::Duration;
+use smart_leds::{RGB8, colors};
+
+const FONT: Led2dFont = Led2dFont::Font3x4Trim;
+const COLORS: [RGB8; 2] = [colors::RED, colors::GREEN];
+
+fn options(direction: ScrollDirection, mode: ScrollMode) -> ScrollOptions {
+    ScrollOptions {
+        direction,
+        pixels_per_second: 20,
+        mode,
+    }
+}
+
+/// Render every frame of a one-shot scroll.
+fn all_frames<const W: usize, const H: usize>(scroll: &mut TextScroll) -> Vec<Frame<W, H>> {
+    let mut frames = Vec::new();
+    let mut frame = Frame::<W, H>::new();
+    while scroll.render_next(&mut frame).is_some() {
+        frames.push(frame);
+        assert!(frames.len() < 10_000, "one-shot scroll must finish");
+    }
+    frames
+}
+
+fn is_blank<const W: usize, const H: usize>(frame: &Frame<W, H>) -> bool {
+    frame.iter().flatten().all(|pixel| *pixel == colors::BLACK)
+}
+
+#[test]
+fn text_size_covers_longest_line_and_all_lines() {
+    let scroll = TextScroll::new("HI!", &[], FONT, ScrollOptions::DEFAULT);
+    assert_eq!(
+        (scroll.text_size().width, scroll.text_size().height),
+        (9, 4)
+    );
+    let scroll = TextScroll::new("AB\nC", &[], FONT, ScrollOptions::DEFAULT);
+    assert_eq!(
+        (scroll.text_size().width, scroll.text_size().height),
+        (6, 8)
+    );
+}
+
+#[test]
+fn frame_duration_follows_speed() {
+    let scroll = TextScroll::new("HI", &[], FONT, ScrollOptions::DEFAULT);
+    assert_eq!(scroll.frame_duration(), Duration::from_millis(100));
+    let scroll = TextScroll::new(
+        "HI",
+        &[],
+        FONT,
+        options(ScrollDirection::Left, ScrollMode::Once),
+    );
+    assert_eq!(scroll.frame_duration(), Duration::from_millis(50));
+}
+
+#[test]
+fn left_once_enters_right_edge_and_ends_blank() {
+    let mut scroll = TextScroll::new(
+        "HELLO, WORLD",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Left, ScrollMode::Once),
+    );
+    let frames = all_frames::<12, 4>(&mut scroll);
+
+    // Display width plus text width, one pixel per frame.
+    assert_eq!(frames.len(), 12 + 36);
+    let first = frames.first().expect("scroll has frames");
+    assert!(
+        first
+            .iter()
+            .all(|row| row[..11].iter().all(|pixel| *pixel == colors::BLACK))
+    );
+    assert!(!is_blank(first));
+    assert!(is_blank(frames.last().expect("scroll has frames")));
+    assert!(scroll.render_next(&mut Frame::<12, 4>::new()).is_none());
+
+    scroll.restart();
+    assert!(scroll.render_next(&mut Frame::<12, 4>::new()).is_some());
+}
+
+#[test]
+fn left_scroll_matches_static_text_when_aligned() {
+    let mut scroll = TextScroll::new(
+        "RUST",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Left, ScrollMode::Once),
+    );
+    let frames = all_frames::<12, 4>(&mut scroll);
+
+    // After moving the display width, the text starts at column 0.
+    let mut expected = Frame::<12, 4>::new();
+    render_text_to_frame(&mut expected, &FONT.to_font(), "RUST", &COLORS, (0, 0))
+        .expect("render must succeed");
+    assert_eq!(frames[11].0, expected.0);
+}
+
+#[test]
+fn right_scroll_mirrors_left_scroll_positions() {
+    let mut left = TextScroll::new(
+        "RUST",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Left, ScrollMode::Once),
+    );
+    let mut right = TextScroll::new(
+        "RUST",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Right, ScrollMode::Once),
+    );
+    let left_frames = all_frames::<12, 4>(&mut left);
+    let right_frames = all_frames::<12, 4>(&mut right);
+    assert_eq!(left_frames.len(), right_frames.len());
+    // Text at column 0 is reached after 12 steps moving left and 12 steps moving right.
+    assert_eq!(left_frames[11].0, right_frames[11].0);
+}
+
+#[test]
+fn up_scroll_moves_lines_through_display() {
+    let mut scroll = TextScroll::new(
+        "HI\nYO",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Up, ScrollMode::Once),
+    );
+    let frames = all_frames::<12, 4>(&mut scroll);
+    assert_eq!(frames.len(), 4 + 8);
+
+    // Each line is aligned with the display after 4 and 8 steps.
+    let mut first_line = Frame::<12, 4>::new();
+    render_text_to_frame(&mut first_line, &FONT.to_font(), "HI", &COLORS, (0, 0))
+        .expect("render must succeed");
+    assert_eq!(frames[3].0, first_line.0);
+
+    // Colors cycle across lines; with two colors and two-letter lines they restart on each.
+    let mut second_line = Frame::<12, 4>::new();
+    render_text_to_frame(&mut second_line, &FONT.to_font(), "YO", &COLORS, (0, 0))
+        .expect("render must succeed");
+    assert_eq!(frames[7].0, second_line.0);
+    assert!(is_blank(frames.last().expect("scroll has frames")));
+}
+
+#[test]
+fn loop_mode_repeats_forever() {
+    let mut scroll = TextScroll::new(
+        "HI",
+        &COLORS,
+        FONT,
+        options(ScrollDirection::Left, ScrollMode::Loop),
+    );
+    let mut frame = Frame::<12, 4>::new();
+    let mut first_pass = Vec::new();
+    for _ in 0..(12 + 6) {
+        scroll
+            .render_next(&mut frame)
+            .expect("looping scroll continues");
+        first_pass.push(frame);
+    }
+    for expected in &first_pass {
+        scroll
+            .render_next(&mut frame)
+            .expect("looping scroll continues");
+        assert_eq!(frame.0, expected.0);
+    }
+}
+
+#[test]
+#[should_panic(expected = "scroll speed must be positive")]
+fn zero_speed_panics() {
+    let mut scroll_options = ScrollOptions::DEFAULT;
+    scroll_options.pixels_per_second = 0;
+    let _ = TextScroll::new("HI", &[], FONT, scroll_options);
+}
@@ -0,0 +1,135 @@
+//! Host-level tests for `led2d::text_layout` alignment and wrapping.
+#![cfg(feature = "host")]
+
+use device_kit::led2d::text_layout::{HorizontalAlign, TextLayout, VerticalAlign, wrap_lines};
+use device_kit::led2d::{Frame, Led2dFont, TextFont, render_text};
+use smart_leds::{RGB8, colors};
+
+// 3x4 glyph cells with no spacing reduction.
+const FONT: Led2dFont = Led2dFont::Font3x4Trim;
+
+fn lines(text: &str, max_width: Option<u32>) -> Vec<&str> {
+    wrap_lines(text, FONT, max_width).collect()
+}
+
+/// `text` drawn by `render_text` at the top-left, then moved by `(dx, dy)` onto `frame`.
+fn stamp<const W: usize, const H: usize>(
+    frame: &mut Frame<W, H>,
+    text: &str,
+    colors: &[RGB8],
+    (dx, dy): (usize, usize),
+) {
+    let mut source = Frame::<W, H>::new();
+    render_text(&mut source, FONT.into(), text, colors).expect("render must succeed");
+    for row in 0..H - dy {
+        for col in 0..W - dx {
+            if source[row][col] != colors::BLACK {
+                frame[row + dy][col + dx] = source[row][col];
+            }
+        }
+    }
+}
+
+fn layout(horizontal: HorizontalAlign, vertical: VerticalAlign) -> TextLayout {
+    TextLayout {
+        horizontal,
+        vertical,
+        ..TextLayout::DEFAULT
+    }
+}
+
+#[test]
+fn wrap_breaks_at_spaces_and_newlines() {
+    assert_eq!(lines("AB CD EF", Some(12)), ["AB", "CD", "EF"]);
+    assert_eq!(lines("AB CD EF", Some(15)), ["AB CD", "EF"]);
+    assert_eq!(lines("12:45\nRain 60%", Some(24)), ["12:45", "Rain 60%"]);
+    assert_eq!(lines("ABCD    EF", Some(12)), ["ABCD", "EF"]);
+    assert_eq!(lines("A\n", None), ["A", ""]);
+}
+
+#[test]
+fn wrap_breaks_long_words_between_characters() {
+    assert_eq!(
+        lines("ABCDEFGHIJKLMNO", Some(12)),
+        ["ABCD", "EFGH", "IJKL", "MNO"]
+    );
+    assert_eq!(lines("AB", Some(1)), ["A", "B"]);
+}
+
+#[test]
+fn no_wrap_keeps_paragraphs_whole() {
+    assert_eq!(lines("AB CD EF GH", None), ["AB CD EF GH"]);
+}
+
+#[test]
+fn mono_line_width_excludes_trailing_spacing() {
+    // 5-pixel cells whose last column is blank: two advances, less that column.
+    assert_eq!(TextFont::from(Led2dFont::Font5x7).line_width("AB"), 9);
+    // The same glyphs trimmed to a 4-pixel advance have no blank column to drop.
+    assert_eq!(TextFont::from(Led2dFont::Font4x6Trim).line_width("AB"), 8);
+    assert_eq!(TextFont::from(FONT).line_width("AB"), 6);
+    assert_eq!(TextFont::from(Led2dFont::Font5x7).line_width(""), 0);
+}
+
+#[test]
+fn horizontal_alignment_offsets_each_line() {
+    for (horizontal, dx) in [
+        (HorizontalAlign::Left, 0),
+        (HorizontalAlign::Center, 3),
+        (HorizontalAlign::Right, 6),
+    ] {
+        let mut frame = Frame::<12, 4>::new();
+        layout(horizontal, VerticalAlign::Top).draw(&mut frame, FONT, "HI", &[colors::RED]);
+        let mut expected = Frame::<12, 4>::new();
+        stamp(&mut expected, "HI", &[colors::RED], (dx, 0));
+        assert_eq!(frame.0, expected.0, "{horizontal:?}");
+    }
+}
+
+#[test]
+fn vertical_alignment_offsets_the_block() {
+    for (vertical, dy) in [
+        (VerticalAlign::Top, 0),
+        (VerticalAlign::Middle, 2),
+        (VerticalAlign::Bottom, 4),
+    ] {
+        let mut frame = Frame::<12, 8>::new();
+        layout(HorizontalAlign::Left, vertical).draw(&mut frame, FONT, "HI", &[colors::RED]);
+        let mut expected = Frame::<12, 8>::new();
+        stamp(&mut expected, "HI", &[colors::RED], (0, dy));
+        assert_eq!(frame.0, expected.0, "{vertical:?}");
+    }
+}
+
+#[test]
+fn centered_weather_message_with_line_spacing() {
+    let colors = [colors::WHITE, colors::CYAN];
+    let weather = TextLayout {
+        line_spacing: 1,
+        ..layout(HorizontalAlign::Center, VerticalAlign::Middle)
+    };
+    let mut frame = Frame::<24, 16>::new();
+    weather.draw(&mut frame, FONT, "12:45\nRain 60%", &colors);
+
+    // Block is 4 + 1 + 4 rows, so it starts at row 3. "12:45" is 15 wide, so column 4.
+    // Colors continue across lines: the sixth character starts on the second color.
+    let mut expected = Frame::<24, 16>::new();
+    stamp(&mut expected, "12:45", &colors, (4, 3));
+    stamp(&mut expected, "Rain 60%", &[colors[1], colors[0]], (0, 8));
+    assert_eq!(frame.0, expected.0);
+}
+
+#[test]
+fn wrapped_lines_are_aligned_individually() {
+    let mut frame = Frame::<12, 8>::new();
+    layout(HorizontalAlign::Right, VerticalAlign::Top).draw(
+        &mut frame,
+        FONT,
+        "ABC DE",
+        &[colors::RED],
+    );
+    let mut expected = Frame::<12, 8>::new();
+    stamp(&mut expected, "ABC", &[colors::RED], (3, 0));
+    stamp(&mut expected, "DE", &[colors::RED], (6, 4));
+    assert_eq!(frame.0, expected.0);
+}
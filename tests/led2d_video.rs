@@ -0,0 +1,98 @@
+//! Host-level tests for the compressed `led2d::video` format.
+#![cfg(feature = "host")]
+
+use device_kit::animation::AnimationSource;
+use device_kit::led2d::Frame;
+use device_kit::led2d::video::{KEEP_INDEX, Video};
+use embassy_time::Duration;
+use smart_leds::RGB8;
+
+const RED: RGB8 = RGB8::new(255, 0, 0);
+const GREEN: RGB8 = RGB8::new(0, 128, 0);
+const BLUE: RGB8 = RGB8::new(0, 0, 255);
+
+// 3x2 pixels, 3 frames, palette: red, green, blue.
+const VIDEO_BYTES: [u8; 34] = [
+    b'D', b'K', b'V', b'1', 3, 2, 3, 0, 3, //
+    255, 0, 0, 0, 128, 0, 0, 0, 255, //
+    100, 0, 6, 0, // 100 ms: all red
+    44, 1, 2, KEEP_INDEX, 4, 1, // 300 ms: keep two, then green
+    100, 0, 5, KEEP_INDEX, 1, 2, // 100 ms: keep five, then blue
+];
+static VIDEO: Video = Video::new(&VIDEO_BYTES);
+
+#[test]
+fn header_matches_expected() {
+    assert_eq!(VIDEO.width(), 3);
+    assert_eq!(VIDEO.height(), 2);
+    assert_eq!(VIDEO.frame_count(), 3);
+    assert_eq!(VIDEO.len_bytes(), VIDEO_BYTES.len());
+}
+
+#[test]
+fn frames_apply_keep_runs() {
+    let frames: Vec<_> = VIDEO.frames::<3, 2>().collect();
+    assert_eq!(frames.len(), 3);
+
+    assert_eq!(frames[0].0.0, [[RED; 3]; 2]);
+    assert_eq!(frames[0].1, Duration::from_millis(100));
+
+    assert_eq!(frames[1].0.0, [[RED, RED, GREEN], [GREEN; 3]]);
+    assert_eq!(frames[1].1, Duration::from_millis(300));
+
+    assert_eq!(frames[2].0.0, [[RED, RED, GREEN], [GREEN, GREEN, BLUE]]);
+    assert_eq!(frames[2].1, Duration::from_millis(100));
+}
+
+#[test]
+fn player_loops_and_reports_only_set_pixels() {
+    let mut player = VIDEO.player();
+    let mut frame = [RGB8::default(); 6];
+    let mut set_counts = Vec::new();
+    for _ in 0..4 {
+        let mut set_count = 0;
+        player.next_frame(|pixel_index, color| {
+            frame[pixel_index] = color;
+            set_count += 1;
+        });
+        set_counts.push(set_count);
+    }
+    // The fourth frame is the first frame again.
+    assert_eq!(set_counts, [6, 4, 1, 6]);
+    assert_eq!(frame, [RED; 6]);
+}
+
+#[test]
+#[should_panic(expected = "not a DKV1 video")]
+fn new_panics_on_bad_magic() {
+    let _ = Video::new(&[b'X', b'K', b'V', b'1', 1, 1, 1, 0, 1, 0, 0, 0]);
+}
+
+#[test]
+#[should_panic(expected = "first frame cannot keep pixels")]
+fn player_panics_on_keep_in_first_frame() {
+    let video = Video::new(&[
+        b'D', b'K', b'V', b'1', 1, 1, 1, 0, 1, 0, 0, 0, 100, 0, 1, KEEP_INDEX,
+    ]);
+    video.player().next_frame(|_, _| {});
+}
+
+#[test]
+#[should_panic(expected = "frame size must match video size")]
+fn frames_panics_on_size_mismatch() {
+    let _ = VIDEO.frames::<2, 3>();
+}
+
+#[test]
+fn player_as_animation_source_updates_frame_in_place() {
+    let mut player = VIDEO.player();
+    let mut frame = Frame::<3, 2>::new();
+    let durations: Vec<_> = (0..3)
+        .map(|_| AnimationSource::next_frame(&mut player, &mut frame))
+        .collect();
+    assert_eq!(
+        durations,
+        [100, 300, 100].map(Duration::from_millis).to_vec()
+    );
+    assert_eq!(frame.0, [[RED, RED, GREEN], [GREEN, GREEN, BLUE]]);
+}
@@ -0,0 +1,313 @@
+//! Host-level tests for MQTT packet encoding and decoding and reconnection backoff.
+#![cfg(feature = "host")]
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+
+use device_kit::mqtt::packet::{
+    Connect, DISCONNECT, PINGREQ, Packet, PacketError, Publish, encode_puback, encode_subscribe,
+};
+use device_kit::mqtt::{Backoff, LastWill, MqttConfig, QoS, Subscription};
+use embassy_time::Duration;
+
+/// Read one whole packet from `stream`.
+fn read_packet(stream: &mut TcpStream) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    loop {
+        if Packet::total_len(&bytes).unwrap() == Some(bytes.len()) {
+            return bytes;
+        }
+        let mut byte = [0];
+        stream.read_exact(&mut byte).unwrap();
+        bytes.push(byte[0]);
+    }
+}
+
+fn encode(encode: impl FnOnce(&mut [u8]) -> Result<usize, PacketError>) -> Vec<u8> {
+    let mut buffer = [0; 512];
+    let len = encode(&mut buffer).unwrap();
+    buffer[..len].to_vec()
+}
+
+#[test]
+fn connect_encodes_every_field() {
+    let connect = Connect {
+        client_id: "porch",
+        username: Some("user"),
+        password: Some(b"pw"),
+        keep_alive_secs: 60,
+        will: Some(LastWill {
+            topic: "porch/status",
+            payload: b"offline",
+            qos: QoS::AtLeastOnce,
+            retain: true,
+        }),
+    };
+    let mut expected = vec![0x10, 50];
+    expected.extend_from_slice(b"\x00\x04MQTT\x04");
+    // user name, password, will retain, will QoS 1, will, clean session
+    expected.push(0b1110_1110);
+    expected.extend_from_slice(&[0, 60]);
+    expected.extend_from_slice(b"\x00\x05porch");
+    expected.extend_from_slice(b"\x00\x0Cporch/status");
+    expected.extend_from_slice(b"\x00\x07offline");
+    expected.extend_from_slice(b"\x00\x04user");
+    expected.extend_from_slice(b"\x00\x02pw");
+    assert_eq!(encode(|buffer| connect.encode(buffer)), expected);
+}
+
+#[test]
+fn connect_sends_password_only_with_user_name() {
+    let connect = Connect {
+        client_id: "c",
+        username: None,
+        password: Some(b"secret"),
+        keep_alive_secs: 0,
+        will: None,
+    };
+    assert_eq!(
+        encode(|buffer| connect.encode(buffer)),
+        b"\x10\x0D\x00\x04MQTT\x04\x02\x00\x00\x00\x01c"
+    );
+}
+
+#[test]
+fn publish_round_trips_at_each_qos() {
+    for (qos, packet_id) in [(QoS::AtMostOnce, None), (QoS::AtLeastOnce, Some(7))] {
+        let publish = Publish {
+            topic: "porch/ir",
+            payload: b"00FF:16",
+            qos,
+            retain: true,
+            packet_id,
+        };
+        let bytes = encode(|buffer| publish.encode(buffer));
+        assert_eq!(
+            Packet::parse(&bytes).unwrap(),
+            Some((Packet::Publish(publish), bytes.len()))
+        );
+    }
+}
+
+#[test]
+fn publish_requires_packet_id_exactly_for_qos_1() {
+    let mut publish = Publish {
+        topic: "t",
+        payload: b"",
+        qos: QoS::AtLeastOnce,
+        retain: false,
+        packet_id: None,
+    };
+    let mut buffer = [0; 16];
+    assert_eq!(publish.encode(&mut buffer), Err(PacketError::Malformed));
+    publish.qos = QoS::AtMostOnce;
+    publish.packet_id = Some(1);
+    assert_eq!(publish.encode(&mut buffer), Err(PacketError::Malformed));
+}
+
+#[test]
+fn subscribe_lists_each_filter() {
+    let subscriptions = [
+        Subscription {
+            topic_filter: "a/+",
+            qos: QoS::AtMostOnce,
+        },
+        Subscription {
+            topic_filter: "b/#",
+            qos: QoS::AtLeastOnce,
+        },
+    ];
+    assert_eq!(
+        encode(|buffer| encode_subscribe(0x0102, &subscriptions, buffer)),
+        b"\x82\x0E\x01\x02\x00\x03a/+\x00\x00\x03b/#\x01"
+    );
+    let mut buffer = [0; 16];
+    assert_eq!(
+        encode_subscribe(1, &[], &mut buffer),
+        Err(PacketError::Malformed)
+    );
+}
+
+#[test]
+fn long_packets_use_multi_byte_lengths() {
+    let payload = [0x5A; 300];
+    let publish = Publish {
+        topic: "big",
+        payload: &payload,
+        qos: QoS::AtMostOnce,
+        retain: false,
+        packet_id: None,
+    };
+    let bytes = encode(|buffer| publish.encode(buffer));
+    // 305 = 0b10_0110001: low 7 bits with the continuation bit, then 2.
+    assert_eq!(&bytes[..3], &[0x30, 0xB1, 0x02]);
+    assert_eq!(Packet::total_len(&bytes[..3]), Ok(Some(bytes.len())));
+    for len in 0..bytes.len() {
+        assert_eq!(
+            Packet::parse(&bytes[..len]),
+            Ok(None),
+            "prefix of {len} bytes"
+        );
+    }
+    assert!(Packet::parse(&bytes).unwrap().is_some());
+}
+
+#[test]
+fn malformed_packets_are_rejected() {
+    assert_eq!(
+        Packet::total_len(&[0x30, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+        Err(PacketError::Malformed)
+    );
+    assert_eq!(
+        Packet::parse(&[0x20, 0x01, 0x00]),
+        Err(PacketError::Malformed)
+    );
+    // Topic length runs past the end of the packet.
+    assert_eq!(
+        Packet::parse(&[0x30, 0x03, 0x00, 0x09, b'a']),
+        Err(PacketError::Malformed)
+    );
+    // QoS 2 is never requested.
+    assert_eq!(
+        Packet::parse(&[0x34, 0x05, 0x00, 0x01, b'a', 0x00, 0x01]),
+        Err(PacketError::Malformed)
+    );
+}
+
+#[test]
+fn small_buffers_are_reported() {
+    let mut buffer = [0; 4];
+    assert_eq!(
+        encode_puback(1, &mut buffer[..3]),
+        Err(PacketError::BufferTooSmall)
+    );
+    assert_eq!(encode_puback(1, &mut buffer), Ok(4));
+    assert_eq!(buffer, [0x40, 0x02, 0x00, 0x01]);
+}
+
+#[test]
+fn backoff_doubles_up_to_max() {
+    let backoff = Backoff {
+        min: Duration::from_millis(500),
+        max: Duration::from_secs(5),
+    };
+    let delays: Vec<u64> = (0..6)
+        .map(|attempt| backoff.delay(attempt).as_millis())
+        .collect();
+    assert_eq!(delays, [500, 1000, 2000, 4000, 5000, 5000]);
+    assert_eq!(backoff.delay(u32::MAX), Duration::from_secs(5));
+    assert_eq!(
+        MqttConfig::new("broker.local", "porch").backoff,
+        Backoff::DEFAULT
+    );
+}
+
+/// Play both sides of a session over loopback TCP: a broker stand-in thread and a client
+/// using the packet functions the device task uses.
+#[test]
+fn session_with_broker_stand_in() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let broker = thread::spawn(move || {
+        let (mut stream, _from) = listener.accept().unwrap();
+
+        let connect = read_packet(&mut stream);
+        let (packet, _len) = Packet::parse(&connect).unwrap().unwrap();
+        let Packet::Other {
+            packet_type: 1,
+            body,
+            ..
+        } = packet
+        else {
+            panic!("expected CONNECT, got {packet:?}");
+        };
+        assert!(body.ends_with(b"\x00\x05porch"));
+        stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+        let subscribe = read_packet(&mut stream);
+        assert_eq!(subscribe[0], 0x82);
+        let packet_id = [subscribe[2], subscribe[3]];
+        stream
+            .write_all(&[0x90, 0x03, packet_id[0], packet_id[1], 0x01])
+            .unwrap();
+
+        let publish = Publish {
+            topic: "porch/display",
+            payload: b"HELLO",
+            qos: QoS::AtLeastOnce,
+            retain: false,
+            packet_id: Some(42),
+        };
+        stream
+            .write_all(&encode(|buffer| publish.encode(buffer)))
+            .unwrap();
+        let puback = read_packet(&mut stream);
+        assert_eq!(
+            Packet::parse(&puback).unwrap().unwrap().0,
+            Packet::PubAck { packet_id: 42 }
+        );
+
+        assert_eq!(read_packet(&mut stream), PINGREQ);
+        stream.write_all(&[0xD0, 0x00]).unwrap();
+        assert_eq!(read_packet(&mut stream), DISCONNECT);
+    });
+
+    let mut stream = TcpStream::connect(address).unwrap();
+    let connect = Connect {
+        client_id: "porch",
+        username: None,
+        password: None,
+        keep_alive_secs: 30,
+        will: None,
+    };
+    stream
+        .write_all(&encode(|buffer| connect.encode(buffer)))
+        .unwrap();
+    assert_eq!(
+        Packet::parse(&read_packet(&mut stream)).unwrap().unwrap().0,
+        Packet::ConnAck {
+            session_present: false,
+            return_code: 0
+        }
+    );
+
+    let subscriptions = [Subscription {
+        topic_filter: "porch/display",
+        qos: QoS::AtLeastOnce,
+    }];
+    stream
+        .write_all(&encode(|buffer| {
+            encode_subscribe(1, &subscriptions, buffer)
+        }))
+        .unwrap();
+    assert_eq!(
+        Packet::parse(&read_packet(&mut stream)).unwrap().unwrap().0,
+        Packet::SubAck {
+            packet_id: 1,
+            return_codes: &[1]
+        }
+    );
+
+    let bytes = read_packet(&mut stream);
+    let Packet::Publish(publish) = Packet::parse(&bytes).unwrap().unwrap().0 else {
+        panic!("expected PUBLISH");
+    };
+    assert_eq!(
+        (publish.topic, publish.payload),
+        ("porch/display", &b"HELLO"[..])
+    );
+    let packet_id = publish.packet_id.unwrap();
+    stream
+        .write_all(&encode(|buffer| encode_puback(packet_id, buffer)))
+        .unwrap();
+
+    stream.write_all(&PINGREQ).unwrap();
+    assert_eq!(
+        Packet::parse(&read_packet(&mut stream)).unwrap().unwrap().0,
+        Packet::PingResp
+    );
+    stream.write_all(&DISCONNECT).unwrap();
+    broker.join().unwrap();
+}
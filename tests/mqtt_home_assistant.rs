@@ -0,0 +1,260 @@
+//! Host-level tests for Home Assistant MQTT discovery messages, states, and commands.
+#![cfg(feature = "host")]
+
+use device_kit::mqtt::MqttMessage;
+use device_kit::mqtt::home_assistant::{
+    Device, Entity, EntityKind, HomeAssistant, Incoming, Value,
+};
+use smart_leds::RGB8;
+
+static ENTITIES: [Entity; 5] = [
+    Entity {
+        object_id: "strip",
+        name: "Strip",
+        kind: EntityKind::Light {
+            brightness: true,
+            color: true,
+            effects: &["rainbow", "comet"],
+        },
+    },
+    Entity {
+        object_id: "arm",
+        name: "Arm",
+        kind: EntityKind::SERVO,
+    },
+    Entity {
+        object_id: "button",
+        name: "Button",
+        kind: EntityKind::BUTTON,
+    },
+    Entity {
+        object_id: "card",
+        name: "Last card",
+        kind: EntityKind::Sensor { unit: None },
+    },
+    Entity {
+        object_id: "digits",
+        name: "Digits",
+        kind: EntityKind::Text { max_len: 4 },
+    },
+];
+
+static HOME_ASSISTANT: HomeAssistant = HomeAssistant::new(
+    Device {
+        model: Some("Pico 2 W"),
+        ..Device::new("porch", "Porch")
+    },
+    &ENTITIES,
+);
+
+fn message(topic: &str, payload: &str) -> MqttMessage {
+    MqttMessage {
+        topic: heapless::String::try_from(topic).unwrap(),
+        payload: heapless::Vec::from_slice(payload.as_bytes()).unwrap(),
+        retain: false,
+    }
+}
+
+fn command(topic: &str, payload: &str) -> Option<(&'static str, Value<'static>)> {
+    // Leak so the borrowed value can outlive this helper.
+    let message = Box::leak(Box::new(message(topic, payload)));
+    match HOME_ASSISTANT.parse(message)? {
+        Incoming::Command { entity, value } => Some((entity.object_id, value)),
+        Incoming::HomeAssistantOnline => panic!("unexpected status message"),
+    }
+}
+
+const DEVICE_JSON: &str = r#""availability_topic":"porch/status","device":{"identifiers":["porch"],"name":"Porch","model":"Pico 2 W"}"#;
+
+#[test]
+fn light_discovery_lists_every_feature() {
+    let (topic, payload) = HOME_ASSISTANT.discovery(&ENTITIES[0]).unwrap();
+    assert_eq!(topic.as_str(), "homeassistant/light/porch/strip/config");
+    assert_eq!(
+        payload.as_str(),
+        format!(
+            concat!(
+                r#"{{"~":"porch/strip","name":"Strip","unique_id":"porch_strip",{}"#,
+                r#","command_topic":"~/set","state_topic":"~/state""#,
+                r#","brightness_command_topic":"~/brightness/set","brightness_state_topic":"~/brightness/state""#,
+                r#","rgb_command_topic":"~/rgb/set","rgb_state_topic":"~/rgb/state""#,
+                r#","effect_command_topic":"~/effect/set","effect_state_topic":"~/effect/state""#,
+                r#","effect_list":["rainbow","comet"]}}"#,
+            ),
+            DEVICE_JSON
+        )
+    );
+}
+
+#[test]
+fn other_kinds_use_their_components() {
+    let expected = [
+        (
+            "homeassistant/number/porch/arm/config",
+            r#","command_topic":"~/set","state_topic":"~/state","min":0,"max":180,"step":1,"unit_of_measurement":"°","mode":"slider"}"#,
+        ),
+        (
+            "homeassistant/event/porch/button/config",
+            r#","state_topic":"~/state","event_types":["short","long"]}"#,
+        ),
+        (
+            "homeassistant/sensor/porch/card/config",
+            r#","state_topic":"~/state"}"#,
+        ),
+        (
+            "homeassistant/text/porch/digits/config",
+            r#","command_topic":"~/set","state_topic":"~/state","max":4}"#,
+        ),
+    ];
+    for (entity, (expected_topic, expected_tail)) in ENTITIES[1..].iter().zip(expected) {
+        let (topic, payload) = HOME_ASSISTANT.discovery(entity).unwrap();
+        assert_eq!(topic.as_str(), expected_topic);
+        let tail = payload.split_once(DEVICE_JSON).unwrap().1;
+        assert_eq!(tail, expected_tail);
+    }
+}
+
+#[test]
+fn names_are_escaped() {
+    static QUOTED: HomeAssistant = HomeAssistant::new(
+        Device {
+            manufacturer: Some("Back\\slash"),
+            ..Device::new("n", "Say \"hi\"\n")
+        },
+        &[],
+    );
+    let entity = Entity {
+        object_id: "t",
+        name: "Tab\there",
+        kind: EntityKind::Sensor { unit: Some("%") },
+    };
+    let (_topic, payload) = QUOTED.discovery(&entity).unwrap();
+    assert!(payload.contains(r#""name":"Tab\u0009here""#));
+    assert!(payload.contains(r#""name":"Say \"hi\"\u000a""#));
+    assert!(payload.contains(r#""manufacturer":"Back\\slash""#));
+    assert!(payload.ends_with(r#""unit_of_measurement":"%"}"#));
+}
+
+#[test]
+fn oversized_discovery_is_an_error() {
+    let entity = Entity {
+        object_id: "long",
+        name: "x".repeat(1100).leak(),
+        kind: EntityKind::Sensor { unit: None },
+    };
+    assert!(HOME_ASSISTANT.discovery(&entity).is_err());
+}
+
+#[test]
+fn states_go_to_matching_topics() {
+    let cases = [
+        ("strip", Value::Power(true), "porch/strip/state", "ON"),
+        ("strip", Value::Power(false), "porch/strip/state", "OFF"),
+        (
+            "strip",
+            Value::Brightness(128),
+            "porch/strip/brightness/state",
+            "128",
+        ),
+        (
+            "strip",
+            Value::Color(RGB8::new(255, 0, 64)),
+            "porch/strip/rgb/state",
+            "255,0,64",
+        ),
+        (
+            "strip",
+            Value::Effect("comet"),
+            "porch/strip/effect/state",
+            "comet",
+        ),
+        ("arm", Value::Number(-5), "porch/arm/state", "-5"),
+        (
+            "button",
+            Value::Event("long"),
+            "porch/button/state",
+            r#"{"event_type":"long"}"#,
+        ),
+        (
+            "card",
+            Value::Text("04A1B2C3"),
+            "porch/card/state",
+            "04A1B2C3",
+        ),
+    ];
+    for (object_id, value, expected_topic, expected_payload) in cases {
+        let (topic, payload) = HOME_ASSISTANT.state(object_id, value).unwrap();
+        assert_eq!(
+            (topic.as_str(), payload.as_str()),
+            (expected_topic, expected_payload)
+        );
+    }
+    assert_eq!(
+        HOME_ASSISTANT.availability_topic().unwrap().as_str(),
+        "porch/status"
+    );
+}
+
+#[test]
+fn commands_are_decoded() {
+    let cases = [
+        ("porch/strip/set", "ON", Value::Power(true)),
+        ("porch/strip/set", "OFF", Value::Power(false)),
+        ("porch/strip/brightness/set", "200", Value::Brightness(200)),
+        (
+            "porch/strip/rgb/set",
+            "10, 20,30",
+            Value::Color(RGB8::new(10, 20, 30)),
+        ),
+        (
+            "porch/strip/effect/set",
+            "rainbow",
+            Value::Effect("rainbow"),
+        ),
+        ("porch/arm/set", "135", Value::Number(135)),
+        ("porch/digits/set", "12:3", Value::Text("12:3")),
+    ];
+    for (topic, payload, expected) in cases {
+        let (object_id, value) = command(topic, payload).unwrap();
+        assert_eq!(value, expected, "{topic} {payload}");
+        assert_eq!(object_id, topic.split('/').nth(1).unwrap());
+    }
+}
+
+#[test]
+fn unusable_messages_are_ignored() {
+    let cases = [
+        // Not for this device, or not a command.
+        ("garage/strip/set", "ON"),
+        ("porchlight/strip/set", "ON"),
+        ("porch/strip/state", "ON"),
+        ("porch/lamp/set", "ON"),
+        // Values the entity doesn't accept.
+        ("porch/strip/set", "on"),
+        ("porch/strip/brightness/set", "256"),
+        ("porch/strip/rgb/set", "1,2"),
+        ("porch/strip/rgb/set", "1,2,3,4"),
+        ("porch/strip/effect/set", "fire"),
+        ("porch/arm/set", "181"),
+        ("porch/arm/set", "90.5"),
+        ("porch/digits/set", "12345"),
+        ("porch/button/set", "short"),
+        ("porch/card/set", "x"),
+        ("porch/arm/brightness/set", "1"),
+    ];
+    for (topic, payload) in cases {
+        assert_eq!(command(topic, payload), None, "{topic} {payload}");
+    }
+}
+
+#[test]
+fn home_assistant_restart_is_reported() {
+    assert_eq!(
+        HOME_ASSISTANT.parse(&message("homeassistant/status", "online")),
+        Some(Incoming::HomeAssistantOnline)
+    );
+    assert_eq!(
+        HOME_ASSISTANT.parse(&message("homeassistant/status", "offline")),
+        None
+    );
+}
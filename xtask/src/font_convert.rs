@@ -0,0 +1,619 @@
+//! Convert BDF or PCF bitmap fonts into the compact DKF1 format drawn by
+//! `device_kit::led2d::bitmap_font`.
+//!
+//! Glyphs are rasterized into cells one line tall and as wide as each glyph's advance, so
+//! proportional fonts stay proportional. `--trim` crops every glyph to its inked columns and
+//! relies on the font's spacing instead, which packs text tighter still. BDF and PCF carry no
+//! kerning, so `--auto-kern` derives pairs from the glyph shapes. See
+//! `src/led2d/bitmap_font.rs` for the format.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"DKF1";
+const MAX_HEIGHT: usize = 32;
+const MAX_AUTO_KERN: i32 = 2;
+
+/// Options for [`convert`].
+pub struct ConvertOptions {
+    /// First code point to include.
+    pub first: u32,
+    /// Last code point to include.
+    pub last: u32,
+    /// Crop glyphs to their inked columns.
+    pub trim: bool,
+    /// Blank columns after every glyph (default: 1 when trimming, otherwise 0).
+    pub spacing: Option<u8>,
+    /// Add kerning pairs that close gaps between glyph shapes.
+    pub auto_kern: bool,
+}
+
+/// A glyph as read from a BDF or PCF file.
+struct SourceGlyph {
+    code_point: u32,
+    /// Horizontal advance (`DWIDTH`).
+    advance: i32,
+    /// Left edge of the bitmap relative to the origin.
+    x_offset: i32,
+    /// Bottom edge of the bitmap relative to the baseline.
+    y_offset: i32,
+    /// Lit pixels, top row first; every row has the same length.
+    rows: Vec<Vec<bool>>,
+}
+
+struct SourceFont {
+    ascent: i32,
+    descent: i32,
+    glyphs: Vec<SourceGlyph>,
+}
+
+/// A glyph rasterized into its cell: `height` rows of `width` pixels.
+struct CellGlyph {
+    code_point: u32,
+    width: usize,
+    pixels: Vec<Vec<bool>>,
+}
+
+/// Read the BDF or PCF font at `input`, convert it, and write the result to `output`.
+///
+/// The format is detected from the file contents, not its extension.
+pub fn convert(
+    input: &Path,
+    output: &Path,
+    options: &ConvertOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if options.first > options.last {
+        return Err("--first must not be greater than --last".into());
+    }
+    let bytes = fs::read(input)?;
+    let source = if bytes.starts_with(b"\x01fcp") {
+        parse_pcf(&bytes)?
+    } else if bytes.starts_with(b"STARTFONT") {
+        parse_bdf(std::str::from_utf8(&bytes)?)?
+    } else {
+        return Err(format!("{} is not a BDF or PCF font", input.display()).into());
+    };
+
+    let height = source.ascent + source.descent;
+    if height <= 0 || height as usize > MAX_HEIGHT {
+        return Err(format!("font height {} must be 1..={}", height, MAX_HEIGHT).into());
+    }
+    let spacing = options.spacing.unwrap_or(u8::from(options.trim));
+    let mut glyphs: Vec<CellGlyph> = source
+        .glyphs
+        .iter()
+        .filter(|glyph| (options.first..=options.last).contains(&glyph.code_point))
+        .map(|glyph| rasterize(glyph, source.ascent, height as usize, options.trim))
+        .collect();
+    glyphs.sort_by_key(|glyph| glyph.code_point);
+    glyphs.dedup_by_key(|glyph| glyph.code_point);
+    if glyphs.is_empty() {
+        return Err(format!(
+            "font has no glyphs in U+{:04X}..=U+{:04X}",
+            options.first, options.last
+        )
+        .into());
+    }
+
+    let kerning = if options.auto_kern {
+        auto_kern(&glyphs, spacing)
+    } else {
+        Vec::new()
+    };
+    let encoded = encode(&glyphs, height as usize, spacing, &kerning)?;
+    fs::write(output, &encoded)?;
+
+    let widths: Vec<usize> = glyphs.iter().map(|glyph| glyph.width).collect();
+    eprintln!(
+        "Wrote {} ({} glyphs, {} px high, {}..={} px wide, {} kerning pairs, {} bytes)",
+        output.display(),
+        glyphs.len(),
+        height,
+        widths.iter().min().copied().unwrap_or(0),
+        widths.iter().max().copied().unwrap_or(0),
+        kerning.len(),
+        encoded.len()
+    );
+    Ok(())
+}
+
+/// Place a source glyph in a cell one line tall with the baseline `ascent` rows down.
+fn rasterize(glyph: &SourceGlyph, ascent: i32, height: usize, trim: bool) -> CellGlyph {
+    let bitmap_width = glyph.rows.first().map_or(0, Vec::len) as i32;
+    let left = glyph.x_offset.min(0);
+    let right = glyph.advance.max(glyph.x_offset + bitmap_width);
+    let mut pixels = vec![vec![false; (right - left).max(0) as usize]; height];
+    let top = ascent - (glyph.y_offset + glyph.rows.len() as i32);
+    for (row_index, row) in glyph.rows.iter().enumerate() {
+        let y = top + row_index as i32;
+        if y < 0 || y >= height as i32 {
+            continue;
+        }
+        for (column_index, lit) in row.iter().enumerate() {
+            if *lit {
+                let x = glyph.x_offset - left + column_index as i32;
+                pixels[y as usize][x as usize] = true;
+            }
+        }
+    }
+
+    if trim {
+        let inked = |x: usize| pixels.iter().any(|row| row[x]);
+        let width = pixels.first().map_or(0, Vec::len);
+        // Blank glyphs such as space keep their advance.
+        if let (Some(first), Some(last)) = (
+            (0..width).find(|x| inked(*x)),
+            (0..width).rfind(|x| inked(*x)),
+        ) {
+            for row in &mut pixels {
+                *row = row[first..=last].to_vec();
+            }
+        }
+    }
+    // Zero-width cells can't be drawn; give them one blank column.
+    if pixels.first().map_or(0, Vec::len) == 0 {
+        for row in &mut pixels {
+            *row = vec![false];
+        }
+    }
+
+    CellGlyph {
+        code_point: glyph.code_point,
+        width: pixels[0].len(),
+        pixels,
+    }
+}
+
+/// Find pairs whose facing edges are further apart than one blank column on every row
+/// (counting diagonal neighbors), and kern them closer by up to [`MAX_AUTO_KERN`] pixels.
+///
+/// Returns `(left index, right index, adjustment)` sorted by index pair.
+fn auto_kern(glyphs: &[CellGlyph], spacing: u8) -> Vec<(u16, u16, i8)> {
+    // For each row, the blank columns after the last lit pixel and before the first.
+    let edges: Vec<Vec<Option<(usize, usize)>>> = glyphs
+        .iter()
+        .map(|glyph| {
+            glyph
+                .pixels
+                .iter()
+                .map(|row| {
+                    let first = row.iter().position(|lit| *lit)?;
+                    let last = row.iter().rposition(|lit| *lit)?;
+                    Some((row.len() - 1 - last, first))
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (left_index, left_edges) in edges.iter().enumerate() {
+        for (right_index, right_edges) in edges.iter().enumerate() {
+            let mut min_gap: Option<usize> = None;
+            for (row, left_edge) in left_edges.iter().enumerate() {
+                let Some((right_blank, _)) = left_edge else {
+                    continue;
+                };
+                let neighbors = row.saturating_sub(1)..=(row + 1).min(right_edges.len() - 1);
+                for (_, left_blank) in right_edges[neighbors].iter().flatten() {
+                    let gap = right_blank + usize::from(spacing) + left_blank;
+                    min_gap = Some(min_gap.map_or(gap, |min_gap: usize| min_gap.min(gap)));
+                }
+            }
+            let Some(min_gap) = min_gap else {
+                continue;
+            };
+            let adjustment = (min_gap as i32 - 1).min(MAX_AUTO_KERN);
+            if adjustment > 0 {
+                pairs.push((left_index as u16, right_index as u16, -adjustment as i8));
+            }
+        }
+    }
+    pairs
+}
+
+fn encode(
+    glyphs: &[CellGlyph],
+    height: usize,
+    spacing: u8,
+    kerning: &[(u16, u16, i8)],
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let glyph_count = u16::try_from(glyphs.len()).map_err(|_| "too many glyphs (max 65535)")?;
+    let kerning_count =
+        u16::try_from(kerning.len()).map_err(|_| "too many kerning pairs (max 65535)")?;
+
+    // Pack bitmaps, sharing storage between identical glyphs.
+    let mut bitmaps = Vec::new();
+    let mut offset_by_bitmap: HashMap<Vec<u8>, u16> = HashMap::new();
+    let mut entries = Vec::new();
+    for glyph in glyphs {
+        let width = u8::try_from(glyph.width)
+            .map_err(|_| format!("glyph U+{:04X} is wider than 255", glyph.code_point))?;
+        let bits: Vec<bool> = glyph.pixels.iter().flatten().copied().collect();
+        let packed: Vec<u8> = bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (bit, lit)| byte | (u8::from(*lit) << (7 - bit)))
+            })
+            .collect();
+        let offset = match offset_by_bitmap.get(&packed) {
+            Some(offset) => *offset,
+            None => {
+                let offset = u16::try_from(bitmaps.len())
+                    .map_err(|_| "glyph bitmaps exceed 64 KiB; narrow --first/--last")?;
+                bitmaps.extend_from_slice(&packed);
+                offset_by_bitmap.insert(packed, offset);
+                offset
+            }
+        };
+        entries.push((glyph.code_point, width, offset));
+    }
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(height as u8);
+    out.push(spacing);
+    out.extend_from_slice(&glyph_count.to_le_bytes());
+    out.extend_from_slice(&kerning_count.to_le_bytes());
+    for (code_point, width, offset) in entries {
+        out.extend_from_slice(&code_point.to_le_bytes());
+        out.push(width);
+        out.extend_from_slice(&offset.to_le_bytes());
+    }
+    for (left, right, adjustment) in kerning {
+        out.extend_from_slice(&left.to_le_bytes());
+        out.extend_from_slice(&right.to_le_bytes());
+        out.push(*adjustment as u8);
+    }
+    out.extend_from_slice(&bitmaps);
+    Ok(out)
+}
+
+// ============================================================================
+// BDF
+// ============================================================================
+
+fn parse_bdf(text: &str) -> Result<SourceFont, Box<dyn std::error::Error>> {
+    let mut ascent = None;
+    let mut descent = None;
+    let mut bounding_box = None;
+    let mut glyphs = Vec::new();
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("FONT_ASCENT") => ascent = Some(parse_number(words.next(), line)?),
+            Some("FONT_DESCENT") => descent = Some(parse_number(words.next(), line)?),
+            Some("FONTBOUNDINGBOX") => {
+                let numbers = parse_numbers::<4>(words, line)?;
+                bounding_box = Some(numbers);
+            }
+            Some("STARTCHAR") => {
+                if let Some(glyph) = parse_bdf_glyph(&mut lines)? {
+                    glyphs.push(glyph);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Fall back to the bounding box when the ascent and descent properties are missing.
+    let [_, box_height, _, box_y_offset] = bounding_box.ok_or("BDF font has no FONTBOUNDINGBOX")?;
+    Ok(SourceFont {
+        ascent: ascent.unwrap_or(box_height + box_y_offset),
+        descent: descent.unwrap_or(-box_y_offset),
+        glyphs,
+    })
+}
+
+/// Parse one glyph after its `STARTCHAR` line. Returns `None` for unencoded glyphs.
+fn parse_bdf_glyph<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+) -> Result<Option<SourceGlyph>, Box<dyn std::error::Error>> {
+    let mut code_point = None;
+    let mut advance = 0;
+    let mut bbx = None;
+    while let Some(line) = lines.next() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("ENCODING") => {
+                let encoding: i32 = parse_number(words.next(), line)?;
+                code_point = u32::try_from(encoding).ok();
+            }
+            Some("DWIDTH") => advance = parse_number(words.next(), line)?,
+            Some("BBX") => bbx = Some(parse_numbers::<4>(words, line)?),
+            Some("BITMAP") => {
+                let [width, height, x_offset, y_offset] =
+                    bbx.ok_or("BDF glyph has BITMAP before BBX")?;
+                let mut rows = Vec::with_capacity(height.max(0) as usize);
+                for _ in 0..height {
+                    let hex = lines.next().ok_or("BDF bitmap truncated")?.trim();
+                    let bytes = (0..hex.len())
+                        .step_by(2)
+                        .map(|start| {
+                            u8::from_str_radix(&hex[start..(start + 2).min(hex.len())], 16)
+                        })
+                        .collect::<Result<Vec<u8>, _>>()?;
+                    let row = (0..width.max(0) as usize)
+                        .map(|x| {
+                            bytes
+                                .get(x / 8)
+                                .is_some_and(|byte| byte & (0x80 >> (x % 8)) != 0)
+                        })
+                        .collect();
+                    rows.push(row);
+                }
+                let end = lines.next().map(str::trim);
+                if end != Some("ENDCHAR") {
+                    return Err("BDF glyph bitmap not followed by ENDCHAR".into());
+                }
+                return Ok(code_point.map(|code_point| SourceGlyph {
+                    code_point,
+                    advance,
+                    x_offset,
+                    y_offset,
+                    rows,
+                }));
+            }
+            Some("ENDCHAR") => return Ok(None),
+            _ => {}
+        }
+    }
+    Err("BDF glyph missing ENDCHAR".into())
+}
+
+fn parse_number<T: std::str::FromStr>(
+    word: Option<&str>,
+    line: &str,
+) -> Result<T, Box<dyn std::error::Error>> {
+    word.and_then(|word| word.parse().ok())
+        .ok_or_else(|| format!("bad number in BDF line: {}", line).into())
+}
+
+fn parse_numbers<'a, const N: usize>(
+    mut words: impl Iterator<Item = &'a str>,
+    line: &str,
+) -> Result<[i32; N], Box<dyn std::error::Error>> {
+    let mut numbers = [0; N];
+    for number in &mut numbers {
+        *number = parse_number(words.next(), line)?;
+    }
+    Ok(numbers)
+}
+
+// ============================================================================
+// PCF
+// ============================================================================
+
+const PCF_ACCELERATORS: u32 = 1 << 1;
+const PCF_METRICS: u32 = 1 << 2;
+const PCF_BITMAPS: u32 = 1 << 3;
+const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+const PCF_BDF_ACCELERATORS: u32 = 1 << 8;
+
+const PCF_COMPRESSED_METRICS: u32 = 0x100;
+const PCF_BYTE_MSB_FIRST: u32 = 1 << 2;
+const PCF_BIT_MSB_FIRST: u32 = 1 << 3;
+
+/// Reads the values in one PCF table, honoring the table's byte order.
+struct PcfTable<'a> {
+    data: &'a [u8],
+    format: u32,
+    position: usize,
+}
+
+impl<'a> PcfTable<'a> {
+    fn find(bytes: &'a [u8], table_type: u32) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let table_count = u32_le(bytes, 4)? as usize;
+        for index in 0..table_count {
+            let entry = 8 + index * 16;
+            if u32_le(bytes, entry)? != table_type {
+                continue;
+            }
+            let size = u32_le(bytes, entry + 8)? as usize;
+            let offset = u32_le(bytes, entry + 12)? as usize;
+            let data = bytes
+                .get(offset..offset + size)
+                .ok_or("PCF table extends past end of file")?;
+            // The format is always little-endian; everything after it uses the table's order.
+            let format = u32_le(data, 0)?;
+            return Ok(Some(Self {
+                data,
+                format,
+                position: 4,
+            }));
+        }
+        Ok(None)
+    }
+
+    fn big_endian(&self) -> bool {
+        self.format & PCF_BYTE_MSB_FIRST != 0
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or("PCF table truncated")?;
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Box<dyn std::error::Error>> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Box<dyn std::error::Error>> {
+        let bytes: [u8; 2] = self.bytes(2)?.try_into()?;
+        Ok(if self.big_endian() {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&mut self) -> Result<u32, Box<dyn std::error::Error>> {
+        let bytes: [u8; 4] = self.bytes(4)?.try_into()?;
+        Ok(if self.big_endian() {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+}
+
+fn u32_le(bytes: &[u8], offset: usize) -> Result<u32, Box<dyn std::error::Error>> {
+    let bytes: [u8; 4] = bytes
+        .get(offset..offset + 4)
+        .ok_or("PCF file truncated")?
+        .try_into()?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Per-glyph metrics from the PCF metrics table.
+struct PcfMetrics {
+    left_bearing: i32,
+    right_bearing: i32,
+    advance: i32,
+    ascent: i32,
+    descent: i32,
+}
+
+fn parse_pcf(bytes: &[u8]) -> Result<SourceFont, Box<dyn std::error::Error>> {
+    let mut metrics_table = PcfTable::find(bytes, PCF_METRICS)?.ok_or("PCF has no metrics")?;
+    let compressed = metrics_table.format & PCF_COMPRESSED_METRICS != 0;
+    let metrics_count = if compressed {
+        usize::from(metrics_table.u16()?)
+    } else {
+        metrics_table.u32()? as usize
+    };
+    let mut metrics = Vec::with_capacity(metrics_count);
+    for _ in 0..metrics_count {
+        metrics.push(if compressed {
+            let mut next = || -> Result<i32, Box<dyn std::error::Error>> {
+                Ok(i32::from(metrics_table.u8()?) - 0x80)
+            };
+            PcfMetrics {
+                left_bearing: next()?,
+                right_bearing: next()?,
+                advance: next()?,
+                ascent: next()?,
+                descent: next()?,
+            }
+        } else {
+            let mut next = || -> Result<i32, Box<dyn std::error::Error>> {
+                Ok(i32::from(metrics_table.u16()? as i16))
+            };
+            let glyph_metrics = PcfMetrics {
+                left_bearing: next()?,
+                right_bearing: next()?,
+                advance: next()?,
+                ascent: next()?,
+                descent: next()?,
+            };
+            next()?; // attributes
+            glyph_metrics
+        });
+    }
+
+    let mut bitmap_table = PcfTable::find(bytes, PCF_BITMAPS)?.ok_or("PCF has no bitmaps")?;
+    let bitmap_count = bitmap_table.u32()? as usize;
+    if bitmap_count != metrics_count {
+        return Err("PCF bitmap and metrics counts differ".into());
+    }
+    let mut bitmap_offsets = Vec::with_capacity(bitmap_count);
+    for _ in 0..bitmap_count {
+        bitmap_offsets.push(bitmap_table.u32()? as usize);
+    }
+    let pad_index = (bitmap_table.format & 3) as usize;
+    let mut bitmap_sizes = [0usize; 4];
+    for size in &mut bitmap_sizes {
+        *size = bitmap_table.u32()? as usize;
+    }
+    let bitmap_data = bitmap_table.bytes(bitmap_sizes[pad_index])?;
+    let row_pad = 1 << pad_index;
+    let scan_unit = 1 << ((bitmap_table.format >> 4) & 3);
+    let bit_msb_first = bitmap_table.format & PCF_BIT_MSB_FIRST != 0;
+    let byte_msb_first = bitmap_table.big_endian();
+
+    let mut encoding_table =
+        PcfTable::find(bytes, PCF_BDF_ENCODINGS)?.ok_or("PCF has no encodings")?;
+    let min_byte2 = u32::from(encoding_table.u16()?);
+    let max_byte2 = u32::from(encoding_table.u16()?);
+    let min_byte1 = u32::from(encoding_table.u16()?);
+    let max_byte1 = u32::from(encoding_table.u16()?);
+    let _default_char = encoding_table.u16()?;
+
+    let mut glyphs = Vec::new();
+    for byte1 in min_byte1..=max_byte1 {
+        for byte2 in min_byte2..=max_byte2 {
+            let glyph_index = usize::from(encoding_table.u16()?);
+            if glyph_index == 0xFFFF {
+                continue;
+            }
+            let glyph_metrics = metrics
+                .get(glyph_index)
+                .ok_or("PCF encoding refers to a missing glyph")?;
+            let width = (glyph_metrics.right_bearing - glyph_metrics.left_bearing).max(0) as usize;
+            let height = (glyph_metrics.ascent + glyph_metrics.descent).max(0) as usize;
+            let row_len = width.div_ceil(8).div_ceil(row_pad) * row_pad;
+            let start = bitmap_offsets[glyph_index];
+            let mut rows = Vec::with_capacity(height);
+            for row_index in 0..height {
+                let row_start = start + row_index * row_len;
+                let mut row_bytes = bitmap_data
+                    .get(row_start..row_start + row_len)
+                    .ok_or("PCF bitmap truncated")?
+                    .to_vec();
+                // Normalize to most significant bit and byte first, as X11 does.
+                if byte_msb_first != bit_msb_first && scan_unit > 1 {
+                    for unit in row_bytes.chunks_mut(scan_unit) {
+                        unit.reverse();
+                    }
+                }
+                if !bit_msb_first {
+                    for byte in &mut row_bytes {
+                        *byte = byte.reverse_bits();
+                    }
+                }
+                rows.push(
+                    (0..width)
+                        .map(|x| row_bytes[x / 8] & (0x80 >> (x % 8)) != 0)
+                        .collect(),
+                );
+            }
+            glyphs.push(SourceGlyph {
+                code_point: byte1 * 256 + byte2,
+                advance: glyph_metrics.advance,
+                x_offset: glyph_metrics.left_bearing,
+                y_offset: -glyph_metrics.descent,
+                rows,
+            });
+        }
+    }
+
+    // Accelerators hold the font-wide ascent and descent; otherwise use the tallest glyphs.
+    let accelerators = match PcfTable::find(bytes, PCF_BDF_ACCELERATORS)? {
+        Some(table) => Some(table),
+        None => PcfTable::find(bytes, PCF_ACCELERATORS)?,
+    };
+    let (ascent, descent) = match accelerators {
+        Some(mut table) => {
+            table.bytes(8)?; // flags
+            (table.u32()? as i32, table.u32()? as i32)
+        }
+        None => (
+            metrics.iter().map(|m| m.ascent).max().unwrap_or(0),
+            metrics.iter().map(|m| m.descent).max().unwrap_or(0),
+        ),
+    };
+    Ok(SourceFont {
+        ascent,
+        descent,
+        glyphs,
+    })
+}
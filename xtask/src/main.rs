@@ -2,6 +2,9 @@
 //!
 //! Run with: `cargo xtask <command>`
 
+mod font_convert;
+mod stream_frames;
+mod video_compress;
 mod video_frames_gen;
 
 use clap::{Parser, Subcommand};
@@ -34,6 +37,70 @@ enum Commands {
     HandFramesGen,
     /// Generate clock video frames from video file
     ClockFramesGen,
+    /// Compress PNG frames or a video file into a DKV1 video for `led2d::video`
+    VideoCompress {
+        /// Directory of PNG frames (read in file-name order) or a video file (needs ffmpeg)
+        input: PathBuf,
+        /// Output file (e.g., cat.dkv), for use with `include_bytes!`
+        output: PathBuf,
+        #[arg(long, default_value_t = 12)]
+        width: u32,
+        #[arg(long, default_value_t = 8)]
+        height: u32,
+        /// Frames per second to sample video files at, and to play back at
+        #[arg(long, default_value_t = 10)]
+        fps: u32,
+        /// Flip frames vertically (for displays mounted upside down)
+        #[arg(long)]
+        flip_v: bool,
+    },
+    /// Convert a BDF or PCF font into a DKF1 font for `led2d::bitmap_font`
+    FontConvert {
+        /// BDF or PCF font file
+        input: PathBuf,
+        /// Output file (e.g., thumb.dkf), for use with `include_bytes!`
+        output: PathBuf,
+        /// First code point to include
+        #[arg(long, default_value_t = 0x20)]
+        first: u32,
+        /// Last code point to include
+        #[arg(long, default_value_t = 0x7E)]
+        last: u32,
+        /// Crop each glyph to its inked columns
+        #[arg(long)]
+        trim: bool,
+        /// Blank columns after every glyph [default: 1 with --trim, otherwise 0]
+        #[arg(long)]
+        spacing: Option<u8>,
+        /// Add kerning pairs that close gaps between glyph shapes
+        #[arg(long)]
+        auto_kern: bool,
+    },
+    /// Stream frames live to a device running `led2d::frame_stream`
+    StreamFrames {
+        /// Device address, as `host` or `host:port`
+        target: String,
+        /// Directory of PNG frames or a video file (needs ffmpeg) [default: a test pattern]
+        input: Option<PathBuf>,
+        #[arg(long, default_value_t = 12)]
+        width: u32,
+        #[arg(long, default_value_t = 8)]
+        height: u32,
+        #[arg(long, default_value_t = 30)]
+        fps: u32,
+        /// Send over UDP instead of TCP
+        #[arg(long)]
+        udp: bool,
+        /// Run-length encode frames when that makes them smaller
+        #[arg(long)]
+        compress: bool,
+        /// Stop after one pass instead of looping
+        #[arg(long)]
+        once: bool,
+        /// Flip frames vertically (for displays mounted upside down)
+        #[arg(long)]
+        flip_v: bool,
+    },
     /// Build library with specified features
     Build {
         #[arg(long, default_value = "pico1")]
@@ -146,6 +213,77 @@ fn main() -> ExitCode {
                 ExitCode::SUCCESS
             }
         }
+        Commands::VideoCompress {
+            input,
+            output,
+            width,
+            height,
+            fps,
+            flip_v,
+        } => {
+            let options = video_compress::CompressOptions {
+                width,
+                height,
+                fps,
+                flip_v,
+            };
+            if let Err(e) = video_compress::compress(&input, &output, &options) {
+                eprintln!("Error compressing video: {}", e);
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Commands::FontConvert {
+            input,
+            output,
+            first,
+            last,
+            trim,
+            spacing,
+            auto_kern,
+        } => {
+            let options = font_convert::ConvertOptions {
+                first,
+                last,
+                trim,
+                spacing,
+                auto_kern,
+            };
+            if let Err(e) = font_convert::convert(&input, &output, &options) {
+                eprintln!("Error converting font: {}", e);
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
+        Commands::StreamFrames {
+            target,
+            input,
+            width,
+            height,
+            fps,
+            udp,
+            compress,
+            once,
+            flip_v,
+        } => {
+            let options = stream_frames::StreamOptions {
+                width,
+                height,
+                fps,
+                udp,
+                compress,
+                once,
+                flip_v,
+            };
+            if let Err(e) = stream_frames::stream(&target, input.as_deref(), &options) {
+                eprintln!("Error streaming frames: {}", e);
+                ExitCode::FAILURE
+            } else {
+                ExitCode::SUCCESS
+            }
+        }
         Commands::Build { board, arch, wifi } => build_lib(board, arch, wifi),
         Commands::Example {
             name,
@@ -0,0 +1,228 @@
+//! Stream frames live to a device running `device_kit::led2d::frame_stream`.
+//!
+//! Frames come from a directory of PNGs, a video file (resampled with `ffmpeg`), or a built-in
+//! test pattern, and are sent as DKS1 packets over TCP or UDP. See
+//! `src/led2d/frame_stream.rs` for the packet format.
+
+use std::io::Write;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::video_compress::{self, CompressOptions};
+
+const MAGIC: &[u8; 4] = b"DKS1";
+const DEFAULT_PORT: u16 = 6868;
+const MAX_DATAGRAM_LEN: usize = 1472;
+const ENCODING_RAW: u8 = 0;
+const ENCODING_RUN_LENGTH: u8 = 1;
+const MAX_RUN: usize = 255;
+
+type Rgb = [u8; 3];
+
+/// Options for [`stream`].
+pub struct StreamOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub udp: bool,
+    pub compress: bool,
+    pub once: bool,
+    pub flip_v: bool,
+}
+
+/// Send frames from `input` (or a test pattern if `None`) to `target` (`host` or
+/// `host:port`) at `options.fps` until interrupted, or once through with `options.once`.
+pub fn stream(
+    target: &str,
+    input: Option<&Path>,
+    options: &StreamOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if options.width == 0 || options.width > 255 || options.height == 0 || options.height > 255 {
+        return Err("width and height must be 1..=255".into());
+    }
+    if options.fps == 0 || options.fps > 1000 {
+        return Err("fps must be 1..=1000".into());
+    }
+
+    let frames = match input {
+        Some(input) => load_frames(input, options)?,
+        None => test_pattern(options.width as usize, options.height as usize),
+    };
+    let address = resolve(target)?;
+    let mut transport = if options.udp {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))?;
+        socket.connect(address)?;
+        Transport::Udp(socket)
+    } else {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Transport::Tcp(stream)
+    };
+    eprintln!(
+        "Streaming {} frames of {}x{} to {} over {} at {} fps{}",
+        frames.len(),
+        options.width,
+        options.height,
+        address,
+        if options.udp { "UDP" } else { "TCP" },
+        options.fps,
+        if options.once {
+            ""
+        } else {
+            " (Ctrl-C to stop)"
+        }
+    );
+
+    let frame_duration = Duration::from_secs(1) / options.fps;
+    let mut next_send = Instant::now();
+    let mut sequence = 0u32;
+    loop {
+        for frame in &frames {
+            let packet = encode_packet(
+                frame,
+                options.width as u8,
+                options.height as u8,
+                sequence,
+                options.compress,
+            )?;
+            transport.send(&packet)?;
+            sequence = sequence.wrapping_add(1);
+
+            next_send += frame_duration;
+            let now = Instant::now();
+            if next_send > now {
+                thread::sleep(next_send - now);
+            } else {
+                // Running behind; don't try to catch up with a burst.
+                next_send = now;
+            }
+        }
+        if options.once {
+            return Ok(());
+        }
+    }
+}
+
+enum Transport {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+}
+
+impl Transport {
+    fn send(&mut self, packet: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            Self::Tcp(stream) => stream.write_all(packet)?,
+            Self::Udp(socket) => {
+                if packet.len() > MAX_DATAGRAM_LEN {
+                    return Err(format!(
+                        "a {}-byte frame does not fit in a UDP datagram; use --compress or TCP",
+                        packet.len()
+                    )
+                    .into());
+                }
+                socket.send(packet)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolve `host` or `host:port`, defaulting to the receiver's port.
+fn resolve(target: &str) -> Result<SocketAddr, Box<dyn std::error::Error>> {
+    let with_port = if target.contains(':') {
+        target.to_string()
+    } else {
+        format!("{target}:{DEFAULT_PORT}")
+    };
+    with_port
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| format!("could not resolve {target}").into())
+}
+
+fn load_frames(
+    input: &Path,
+    options: &StreamOptions,
+) -> Result<Vec<Vec<Rgb>>, Box<dyn std::error::Error>> {
+    let compress_options = CompressOptions {
+        width: options.width,
+        height: options.height,
+        fps: options.fps,
+        flip_v: options.flip_v,
+    };
+    let frames_dir = if input.is_dir() {
+        input.to_path_buf()
+    } else {
+        video_compress::extract_frames(input, &compress_options)?
+    };
+    let frames = video_compress::read_frames(&frames_dir, &compress_options)?;
+    if frames.is_empty() {
+        return Err(format!("no PNG frames found in {}", frames_dir.display()).into());
+    }
+    Ok(frames)
+}
+
+/// A rainbow that scrolls one column per frame, for checking a display's wiring and timing.
+fn test_pattern(width: usize, height: usize) -> Vec<Vec<Rgb>> {
+    (0..width)
+        .map(|shift| {
+            (0..width * height)
+                .map(|pixel_index| {
+                    let column = (pixel_index % width + shift) % width;
+                    hue_to_rgb((column * 256 / width) as u8)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn hue_to_rgb(hue: u8) -> Rgb {
+    let rising = (hue % 85) * 3;
+    let falling = 255 - rising;
+    match hue / 85 {
+        0 => [falling, rising, 0],
+        1 => [0, falling, rising],
+        _ => [rising, 0, falling],
+    }
+}
+
+/// Build one DKS1 packet. With `compress`, the frame is run-length encoded when that is
+/// smaller than sending it raw.
+fn encode_packet(
+    frame: &[Rgb],
+    width: u8,
+    height: u8,
+    sequence: u32,
+    compress: bool,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let raw: Vec<u8> = frame.iter().flatten().copied().collect();
+    let (encoding, payload) = match compress.then(|| run_length(frame)) {
+        Some(runs) if runs.len() < raw.len() => (ENCODING_RUN_LENGTH, runs),
+        _ => (ENCODING_RAW, raw),
+    };
+    let payload_len = u16::try_from(payload.len()).map_err(|_| "frame too large for one packet")?;
+
+    let mut packet = Vec::with_capacity(14 + payload.len());
+    packet.extend_from_slice(MAGIC);
+    packet.extend_from_slice(&sequence.to_le_bytes());
+    packet.extend_from_slice(&[width, height, encoding, 0]);
+    packet.extend_from_slice(&payload_len.to_le_bytes());
+    packet.extend_from_slice(&payload);
+    Ok(packet)
+}
+
+fn run_length(frame: &[Rgb]) -> Vec<u8> {
+    let mut runs = Vec::new();
+    let mut pixels = frame.iter().peekable();
+    while let Some(rgb) = pixels.next() {
+        let mut run = 1;
+        while run < MAX_RUN && pixels.next_if_eq(&rgb).is_some() {
+            run += 1;
+        }
+        runs.push(run as u8);
+        runs.extend_from_slice(rgb);
+    }
+    runs
+}
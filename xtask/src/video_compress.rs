@@ -0,0 +1,280 @@
+//! Encode PNG frames or a video file into the compact DKV1 format played by
+//! `device_kit::led2d::video`.
+//!
+//! The format is a shared palette followed by run-length encoded palette indices per frame.
+//! Index 255 means "keep the previous frame's pixel". See `src/led2d/video.rs` for details.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const MAGIC: &[u8; 4] = b"DKV1";
+const KEEP_INDEX: u8 = 255;
+const MAX_PALETTE_LEN: usize = 255;
+const MAX_RUN: usize = 255;
+
+type Rgb = [u8; 3];
+
+/// Options for [`compress`].
+pub struct CompressOptions {
+    pub width: u32,
+    pub height: u32,
+    pub fps: u32,
+    pub flip_v: bool,
+}
+
+/// Encode `input` (a directory of PNG frames or a video file) and write the result to `output`.
+///
+/// PNG frames are read in file-name order and must already be `width` x `height`. Video files
+/// are resampled with `ffmpeg`, which must be on the `PATH`.
+pub fn compress(
+    input: &Path,
+    output: &Path,
+    options: &CompressOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if options.width == 0 || options.width > 255 || options.height == 0 || options.height > 255 {
+        return Err("width and height must be 1..=255".into());
+    }
+    if options.fps == 0 || options.fps > 1000 {
+        return Err("fps must be 1..=1000".into());
+    }
+
+    let frames_dir = if input.is_dir() {
+        input.to_path_buf()
+    } else {
+        extract_frames(input, options)?
+    };
+    let mut frames = read_frames(&frames_dir, options)?;
+    if frames.is_empty() {
+        return Err(format!("no PNG frames found in {}", frames_dir.display()).into());
+    }
+
+    let shift = quantize(&mut frames);
+    let encoded = encode(
+        &frames,
+        options.width,
+        options.height,
+        (1000 / options.fps) as u16,
+    )?;
+    fs::write(output, &encoded)?;
+
+    let raw_len = frames.len() * (options.width * options.height * 3) as usize;
+    eprintln!(
+        "Wrote {} ({} frames, {} bytes, {:.1}% of {} raw bytes{})",
+        output.display(),
+        frames.len(),
+        encoded.len(),
+        100.0 * encoded.len() as f64 / raw_len as f64,
+        raw_len,
+        if shift > 0 {
+            format!(", colors reduced to {} bits per channel", 8 - shift)
+        } else {
+            String::new()
+        }
+    );
+    Ok(())
+}
+
+/// Use `ffmpeg` to resample a video into numbered PNG frames in a temporary directory.
+pub(crate) fn extract_frames(
+    video_path: &Path,
+    options: &CompressOptions,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let temp_dir = std::env::temp_dir().join(format!(
+        "video_compress_{}x{}",
+        options.width, options.height
+    ));
+    if temp_dir.exists() {
+        fs::remove_dir_all(&temp_dir)?;
+    }
+    fs::create_dir_all(&temp_dir)?;
+
+    eprintln!("Extracting frames from video: {}", video_path.display());
+    let status = Command::new("ffmpeg")
+        .arg("-i")
+        .arg(video_path)
+        .args([
+            "-vf",
+            &format!(
+                "fps={},scale={}:{}:flags=lanczos",
+                options.fps, options.width, options.height
+            ),
+            "-q:v",
+            "2",
+            &format!("{}/frame_%06d.png", temp_dir.display()),
+        ])
+        .status()?;
+    if !status.success() {
+        return Err("ffmpeg failed to extract frames".into());
+    }
+    Ok(temp_dir)
+}
+
+/// Read every PNG in `frames_dir`, sorted by file name, as row-major RGB pixels.
+pub(crate) fn read_frames(
+    frames_dir: &Path,
+    options: &CompressOptions,
+) -> Result<Vec<Vec<Rgb>>, Box<dyn std::error::Error>> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(frames_dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "png"));
+    paths.sort();
+
+    let (width, height) = (options.width as usize, options.height as usize);
+    let mut frames = Vec::with_capacity(paths.len());
+    for path in paths {
+        let decoder = png::Decoder::new(File::open(&path)?);
+        let mut reader = decoder.read_info()?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf)?;
+        if info.width as usize != width || info.height as usize != height {
+            return Err(format!(
+                "{} is {}x{}, expected {}x{}",
+                path.display(),
+                info.width,
+                info.height,
+                width,
+                height
+            )
+            .into());
+        }
+        let bytes_per_pixel = match info.color_type {
+            png::ColorType::Rgb => 3,
+            png::ColorType::Rgba => 4,
+            color_type => return Err(format!("unsupported color type: {:?}", color_type).into()),
+        };
+
+        let mut frame = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let source_row = if options.flip_v {
+                height - 1 - row
+            } else {
+                row
+            };
+            for col in 0..width {
+                let pixel_index = (source_row * width + col) * bytes_per_pixel;
+                frame.push([buf[pixel_index], buf[pixel_index + 1], buf[pixel_index + 2]]);
+            }
+        }
+        frames.push(frame);
+    }
+    Ok(frames)
+}
+
+/// Drop low bits from every channel until the video uses at most [`MAX_PALETTE_LEN`] colors.
+/// Returns the number of bits dropped.
+fn quantize(frames: &mut [Vec<Rgb>]) -> u32 {
+    let mut shift = 0;
+    loop {
+        let mask = 0xFFu8 << shift;
+        let colors: BTreeSet<Rgb> = frames
+            .iter()
+            .flatten()
+            .map(|rgb| rgb.map(|channel| channel & mask))
+            .collect();
+        if colors.len() <= MAX_PALETTE_LEN {
+            for rgb in frames.iter_mut().flatten() {
+                *rgb = rgb.map(|channel| channel & mask);
+            }
+            return shift;
+        }
+        shift += 1;
+    }
+}
+
+fn encode(
+    frames: &[Vec<Rgb>],
+    width: u32,
+    height: u32,
+    frame_duration_ms: u16,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let palette: Vec<Rgb> = frames
+        .iter()
+        .flatten()
+        .copied()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    let index_by_color: HashMap<Rgb, u8> = palette
+        .iter()
+        .enumerate()
+        .map(|(index, rgb)| (*rgb, index as u8))
+        .collect();
+
+    // Merge identical consecutive frames into one longer frame.
+    let mut shots: Vec<(&Vec<Rgb>, u16)> = Vec::new();
+    for frame in frames {
+        match shots.last_mut() {
+            Some((previous, duration))
+                if *previous == frame && *duration <= u16::MAX - frame_duration_ms =>
+            {
+                *duration += frame_duration_ms;
+            }
+            _ => shots.push((frame, frame_duration_ms)),
+        }
+    }
+    let frame_count = u16::try_from(shots.len()).map_err(|_| "too many frames (max 65535)")?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.push(width as u8);
+    out.push(height as u8);
+    out.extend_from_slice(&frame_count.to_le_bytes());
+    out.push(palette.len() as u8);
+    for rgb in &palette {
+        out.extend_from_slice(rgb);
+    }
+
+    let mut previous: Option<&Vec<Rgb>> = None;
+    for (frame, duration) in &shots {
+        out.extend_from_slice(&duration.to_le_bytes());
+        let indices: Vec<u8> = frame.iter().map(|rgb| index_by_color[rgb]).collect();
+        let full = run_length(&indices);
+        let encoded = match previous {
+            Some(previous) => {
+                let delta: Vec<u8> = frame
+                    .iter()
+                    .zip(previous.iter())
+                    .zip(&indices)
+                    .map(|((rgb, previous_rgb), index)| {
+                        if rgb == previous_rgb {
+                            KEEP_INDEX
+                        } else {
+                            *index
+                        }
+                    })
+                    .collect();
+                let delta = run_length(&delta);
+                if delta.len() < full.len() {
+                    delta
+                } else {
+                    full
+                }
+            }
+            None => full,
+        };
+        out.extend_from_slice(&encoded);
+        previous = Some(frame);
+    }
+    Ok(out)
+}
+
+/// Encode indices as `(run, index)` byte pairs with runs of at most [`MAX_RUN`].
+fn run_length(indices: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    while start < indices.len() {
+        let index = indices[start];
+        let run = indices[start..]
+            .iter()
+            .take(MAX_RUN)
+            .take_while(|other| **other == index)
+            .count();
+        out.push(run as u8);
+        out.push(index);
+        start += run;
+    }
+    out
+}